@@ -18,26 +18,51 @@ use crate::card::Card;
 /// Finally, if P1 has a balance of $1000 and they go all-in, then the corresponding
 /// action should be AllIn(1000).
 /// 
+/// Ante is dead money paid by everyone before the cards are dealt (see Pot::add_ante), while Blind is a forced bet
+/// (a blind, straddle, kill blind or bring-in) that counts towards calling.
+///
+/// Muck is played at the showdown by a beaten player who chooses not to show their cards.
+/// 
 /// Win and Lose actions are for book keeping and will be added onto the pot history
 /// after dividing the winnings for a particular round as turns in a separte phase.
+/// 
+/// Actions are (de)serialized with the same lowercase tags as ActionOption,
+/// e.g. "call" or {"raise": 10}. Turns saved before then used the variant names
+/// (e.g. "Call" or {"Raise": 10}), which are still accepted when deserializing.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
 pub enum Action {
+    #[serde(alias = "Ante")]
     Ante(usize),
+    Blind(usize),
+    #[serde(alias = "Call")]
     Call,
+    #[serde(alias = "Bet")]
     Bet(usize),
+    #[serde(alias = "Raise")]
     Raise(usize),
+    #[serde(alias = "Check")]
     Check,
+    #[serde(alias = "AllIn")]
     AllIn(usize),
+    #[serde(alias = "Fold")]
     Fold,
+    #[serde(alias = "Replace")]
     Replace(Vec<Box<Card>>),
+    #[serde(alias = "Win")]
     Win(usize),
+    #[serde(alias = "Lose")]
     Lose(usize),
+    /// hide a losing hand at the showdown instead of showing it,
+    /// the player's stake stays in the pot and they are not folded
+    Muck,
 }
 
 impl PartialEq for Action {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Ante(l0), Self::Ante(r0)) => l0 == r0,
+            (Self::Blind(l0), Self::Blind(r0)) => l0 == r0,
             (Self::Bet(l0), Self::Bet(r0)) => l0 == r0,
             (Self::Raise(l0), Self::Raise(r0)) => l0 == r0,
             (Self::AllIn(l0), Self::AllIn(r0)) => l0 == r0,
@@ -48,3 +73,84 @@ impl PartialEq for Action {
         }
     }
 }
+
+impl Action {
+    /// describe the action so that it can be shown to every player,
+    /// which leaves out the cards that were replaced, as only the player who replaced them has seen them
+    pub fn public_description(&self) -> String {
+        return match self {
+            Action::Replace(cards) => format!("replace {} cards", cards.len()),
+            action => action.to_string(),
+        };
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Ante(amount) => write!(f, "ante {amount}"),
+            Action::Blind(amount) => write!(f, "blind {amount}"),
+            Action::Call => write!(f, "call"),
+            Action::Bet(amount) => write!(f, "bet {amount}"),
+            Action::Raise(amount) => write!(f, "raise {amount}"),
+            Action::Check => write!(f, "check"),
+            Action::AllIn(amount) => write!(f, "all_in {amount}"),
+            Action::Fold => write!(f, "fold"),
+            Action::Replace(cards) => write!(f, "replace {}", cards.iter().map(|card| card.to_string()).collect::<Vec<String>>().join(" ")),
+            Action::Win(amount) => write!(f, "win {amount}"),
+            Action::Lose(amount) => write!(f, "lose {amount}"),
+            Action::Muck => write!(f, "muck"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let actions = vec![
+            Action::Ante(2),
+            Action::Blind(2),
+            Action::Call,
+            Action::Bet(5),
+            Action::Raise(10),
+            Action::Check,
+            Action::AllIn(1000),
+            Action::Fold,
+            Action::Replace(vec![Box::new("Ah".parse().unwrap()), Box::new("2c".parse().unwrap())]),
+            Action::Win(30),
+            Action::Lose(10),
+            Action::Muck,
+        ];
+        for action in actions {
+            let json = serde_json::to_string(&action).unwrap();
+            assert_eq!(serde_json::from_str::<Action>(&json).unwrap(), action);
+        }
+    }
+
+    #[test]
+    fn legacy_tags() {
+        assert_eq!(serde_json::from_str::<Action>("\"Call\"").unwrap(), Action::Call);
+        assert_eq!(serde_json::from_str::<Action>("{\"Win\":30}").unwrap(), Action::Win(30));
+        assert_eq!(serde_json::from_str::<Action>("{\"AllIn\":500}").unwrap(), Action::AllIn(500));
+        let turn = bson::to_bson(&serde_json::json!({"Raise": 10})).unwrap();
+        assert_eq!(bson::from_bson::<Action>(turn).unwrap(), Action::Raise(10));
+    }
+
+    #[test]
+    fn stable_tags() {
+        assert_eq!(serde_json::to_string(&Action::Call).unwrap(), "\"call\"");
+        assert_eq!(serde_json::to_string(&Action::Raise(10)).unwrap(), "{\"raise\":10}");
+        assert_eq!(serde_json::to_string(&Action::Blind(2)).unwrap(), "{\"blind\":2}");
+        assert_eq!(serde_json::from_str::<Action>("{\"all_in\":500}").unwrap(), Action::AllIn(500));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Action::Call.to_string(), "call");
+        assert_eq!(Action::Raise(10).to_string(), "raise 10");
+        assert_eq!(Action::AllIn(500).to_string(), "all_in 500");
+    }
+}
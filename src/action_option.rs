@@ -1,9 +1,15 @@
-#[derive(Debug, Clone, Copy)]
+use serde::{ Deserialize, Serialize };
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
 /// The ActionOption enum is the set of possible actions that can be performed by a user
 /// The intended way to use this enum is to take a subset of the enum
 /// (a vector of specific variants), and pass it to an implementation of the Input trait
 /// to ask the user to pick one of the actions to perform, after which it will be converted
 /// to its corresponding Action variant (Action enum)
+/// 
+/// Options are (de)serialized as stable lowercase tags (e.g. "call", "all_in"),
+/// which are also what Display writes, so that they can be sent to and from the web client.
 pub enum ActionOption {
     Ante,
     Call,
@@ -17,4 +23,78 @@ pub enum ActionOption {
     Lose,
 }
 
+impl ActionOption {
+    /// the position of this option when options are shown to a player, following the poker convention
+    /// of passive actions first and folding last, lower values are shown first.
+    /// Bet and Raise are never offered together, so they share a position,
+    /// and options that are never offered to players are shown last
+    pub fn display_priority(&self) -> u8 {
+        return match self {
+            ActionOption::Check => 0,
+            ActionOption::Call => 1,
+            ActionOption::Bet | ActionOption::Raise => 2,
+            ActionOption::AllIn => 3,
+            ActionOption::Replace => 4,
+            ActionOption::Fold => 5,
+            ActionOption::Ante | ActionOption::Win | ActionOption::Lose => 6,
+        };
+    }
+}
+
+impl std::fmt::Display for ActionOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionOption::Ante => write!(f, "ante"),
+            ActionOption::Call => write!(f, "call"),
+            ActionOption::Bet => write!(f, "bet"),
+            ActionOption::Raise => write!(f, "raise"),
+            ActionOption::Check => write!(f, "check"),
+            ActionOption::AllIn => write!(f, "all_in"),
+            ActionOption::Fold => write!(f, "fold"),
+            ActionOption::Replace => write!(f, "replace"),
+            ActionOption::Win => write!(f, "win"),
+            ActionOption::Lose => write!(f, "lose"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip_matches_display() {
+        let action_options = vec![
+            ActionOption::Ante,
+            ActionOption::Call,
+            ActionOption::Bet,
+            ActionOption::Raise,
+            ActionOption::Check,
+            ActionOption::AllIn,
+            ActionOption::Fold,
+            ActionOption::Replace,
+            ActionOption::Win,
+            ActionOption::Lose,
+        ];
+        for action_option in action_options {
+            let json = serde_json::to_string(&action_option).unwrap();
+            assert_eq!(json, format!("\"{action_option}\""));
+            assert_eq!(serde_json::from_str::<ActionOption>(&json).unwrap(), action_option);
+        }
+    }
 
+    #[test]
+    fn display_priority_order() {
+        let mut action_options = vec![ActionOption::Fold, ActionOption::Replace, ActionOption::AllIn, ActionOption::Raise, ActionOption::Call, ActionOption::Check];
+        action_options.sort_by_key(|action_option| action_option.display_priority());
+        assert_eq!(action_options, vec![ActionOption::Check, ActionOption::Call, ActionOption::Raise, ActionOption::AllIn, ActionOption::Replace, ActionOption::Fold]);
+    }
+
+    #[test]
+    fn stable_tags() {
+        assert_eq!(serde_json::to_string(&ActionOption::Raise).unwrap(), "\"raise\"");
+        assert_eq!(serde_json::to_string(&ActionOption::AllIn).unwrap(), "\"all_in\"");
+        assert_eq!(serde_json::from_str::<ActionOption>("\"call\"").unwrap(), ActionOption::Call);
+        assert!(serde_json::from_str::<ActionOption>("\"Call\"").is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::card::{Card, Rank};
+use std::cmp::Ordering;
+
+#[derive(Debug, PartialEq, Eq)]
+/// the value of a hand in badugi, a lowball game played with four cards.
+/// A hand is scored by the largest set of its cards that are all of different ranks and different suits,
+/// a complete badugi uses all four cards, and beats any "incomplete" badugi of three cards or fewer.
+/// Between two hands with the same number of cards, the one with the lower highest card wins,
+/// then the lower second highest card, and so on. Aces are always low.
+/// Like HandRank, a greater BadugiHand is a better hand.
+/// usage example:
+/// ```
+/// use poker_project_rustaceans::badugi_hand::BadugiHand;
+/// use poker_project_rustaceans::card::Card;
+/// let cards: Vec<Card> = ["4s", "3h", "2d", "Ac"].iter().map(|notation| notation.parse().unwrap()).collect();
+/// let hand = BadugiHand::new(&cards);
+/// assert!(hand.is_badugi());
+/// ```
+pub struct BadugiHand {
+    /// the ace low values of the cards used, from highest to lowest
+    values: Vec<u8>
+}
+
+impl BadugiHand {
+    /// score a hand of badugi, using the best set of cards with different ranks and different suits
+    pub fn new(cards: &[Card]) -> BadugiHand {
+        return Self::best_subset(cards).0;
+    }
+
+    /// the cards that make up the best hand, in the order they are given,
+    /// the rest of the cards are the ones worth replacing in a draw
+    pub fn best_cards(cards: &[Card]) -> Vec<&Card> {
+        let (_, best_subset) = Self::best_subset(cards);
+        return cards.iter().enumerate()
+            .filter(|(card_index, _)| best_subset & (1 << card_index) != 0)
+            .map(|(_, card)| card)
+            .collect();
+    }
+
+    /// the best hand that can be made from the cards, along with the subset of the cards it uses (as a bit for each card)
+    fn best_subset(cards: &[Card]) -> (BadugiHand, u32) {
+        let mut best_hand = BadugiHand { values: Vec::new() };
+        let mut best_subset = 0;
+        // try every subset of the cards, there are only 16 subsets of a four card hand
+        for subset in 0..(1_u32 << cards.len()) {
+            let subset_cards: Vec<&Card> = cards.iter().enumerate()
+                .filter(|(card_index, _)| subset & (1 << card_index) != 0)
+                .map(|(_, card)| card)
+                .collect();
+            let all_different = subset_cards.iter().enumerate().all(|(card_index, card)| {
+                subset_cards[card_index+1..].iter().all(|other_card| card.rank() != other_card.rank() && card.suit() != other_card.suit())
+            });
+            if !all_different {
+                continue;
+            }
+            let mut values: Vec<u8> = subset_cards.iter().map(|card| Self::ace_low_value(card.rank())).collect();
+            values.sort();
+            values.reverse();
+            let hand = BadugiHand { values };
+            if hand > best_hand {
+                best_hand = hand;
+                best_subset = subset;
+            }
+        }
+        return (best_hand, best_subset);
+    }
+
+    /// the value of a rank in badugi, where an ace is the lowest card
+    fn ace_low_value(rank: &Rank) -> u8 {
+        return match rank {
+            Rank::Ace => 1,
+            rank => rank.to_u8(),
+        };
+    }
+
+    /// the number of cards that make up the hand
+    pub fn number_of_cards(&self) -> usize {
+        return self.values.len();
+    }
+
+    /// true if all four cards make up the hand (a complete badugi)
+    pub fn is_badugi(&self) -> bool {
+        return self.number_of_cards() == 4;
+    }
+
+    /// the ace low value of the highest card in the hand (an ace is 1, a king is 13), None if the hand has no cards
+    pub fn highest_card_value(&self) -> Option<u8> {
+        return self.values.first().copied();
+    }
+}
+
+impl std::fmt::Display for BadugiHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranks: Vec<String> = self.values.iter()
+            .map(|&value| match value {
+                1 => Rank::Ace.to_string(),
+                value => Rank::to_rank(value).to_string(),
+            })
+            .collect();
+        write!(f, "{}-card badugi ({})", self.number_of_cards(), ranks.join(", "))
+    }
+}
+
+impl PartialOrd for BadugiHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BadugiHand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // more cards is better, then lower cards are better
+        self.number_of_cards().cmp(&other.number_of_cards())
+            .then_with(|| other.values.cmp(&self.values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::cards;
+
+    #[test]
+    fn four_card_badugi() {
+        let hand = BadugiHand::new(&cards("Kc Qd 7h 2s"));
+        assert!(hand.is_badugi());
+        assert_eq!(hand.number_of_cards(), 4);
+    }
+
+    #[test]
+    fn four_card_badugi_beats_three_card_badugi() {
+        // the king high badugi beats the best possible three card hand
+        let four_card_hand = BadugiHand::new(&cards("Kc Qd Jh Ts"));
+        let three_card_hand = BadugiHand::new(&cards("Ac 2d 3h 4h"));
+        assert_eq!(three_card_hand.number_of_cards(), 3);
+        assert!(!three_card_hand.is_badugi());
+        assert!(four_card_hand > three_card_hand);
+    }
+
+    #[test]
+    fn lower_badugi_wins() {
+        let wheel = BadugiHand::new(&cards("4s 3h 2d Ac"));
+        let five_high = BadugiHand::new(&cards("5s 3h 2d Ac"));
+        let five_four = BadugiHand::new(&cards("5s 4h 2d Ac"));
+        assert!(wheel > five_high);
+        assert!(five_high > five_four);
+    }
+
+    #[test]
+    fn ace_is_low() {
+        let ace_low = BadugiHand::new(&cards("Ac 3d 5h 7s"));
+        let two_low = BadugiHand::new(&cards("2c 3d 5h 7s"));
+        assert!(ace_low > two_low);
+    }
+
+    #[test]
+    fn pairs_and_suits_are_discarded() {
+        // the pair of threes and the two hearts each cost a card, so this is a two card hand
+        let hand = BadugiHand::new(&cards("3h 3s 5h 8h"));
+        assert_eq!(hand.number_of_cards(), 2);
+        // the best two cards are the 3s and the 5h
+        assert_eq!(hand, BadugiHand::new(&cards("3s 5h")));
+    }
+
+    #[test]
+    fn chooses_lowest_three_card_hand() {
+        // the Kd and 2d share a suit, so the best three cards are 4h 3c 2d
+        let hand = BadugiHand::new(&cards("Kd 4h 3c 2d"));
+        assert_eq!(hand, BadugiHand::new(&cards("4h 3c 2d")));
+        assert_eq!(hand.to_string(), "3-card badugi (Four, Three, Two)");
+    }
+
+    #[test]
+    fn equal_hands() {
+        let hand = BadugiHand::new(&cards("4s 3h 2d Ac"));
+        let other_hand = BadugiHand::new(&cards("4h 3s 2c Ad"));
+        assert_eq!(hand.cmp(&other_hand), Ordering::Equal);
+    }
+
+    #[test]
+    fn best_cards() {
+        let hand_cards = cards("Kd 4h 3c 2d");
+        let best_cards: Vec<Card> = BadugiHand::best_cards(&hand_cards).into_iter().cloned().collect();
+        assert_eq!(best_cards, cards("4h 3c 2d"));
+        assert_eq!(BadugiHand::new(&hand_cards).highest_card_value(), Some(4));
+        assert_eq!(BadugiHand::new(&[]).highest_card_value(), None);
+    }
+}
@@ -1,18 +1,19 @@
 use serde::{ Deserialize, Serialize };
 
 use std::cmp::Ordering;
+use std::str::FromStr;
 
 mod rank;
-pub use rank::Rank;
+pub use rank::{Rank, ParseRankError};
 mod suit;
-pub use suit::Suit;
+pub use suit::{Suit, SuitColor, ParseSuitError};
 
 /// Card class, containing a rank and a suit.
-/// Create a new card with Card::new(),
+/// Create a new face down card with Card::new(), or a face up card with Card::face_up(),
 /// Example:
 /// ```
 /// use poker_project_rustaceans::card::{Card, Rank, Suit};
-/// let card = Card::new(Rank::Ace, Suit::Spades, true);
+/// let card = Card::face_up(Rank::Ace, Suit::Spades);
 /// ```
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Card {
@@ -22,21 +23,35 @@ pub struct Card {
 }
 
 impl Card {
-    /// Constructor for Card.
+    /// Constructor for Card, the card starts face down.
     /// Example:
     /// ```
     /// use poker_project_rustaceans::card::{Card, Rank, Suit};
-    /// let card = Card::new(Rank::Ace, Suit::Spades, true);
+    /// let card = Card::new(Rank::Ace, Suit::Spades);
+    /// assert!(!card.is_face_up());
     /// ```
-    pub fn new(rank: Rank, suit: Suit, is_face_up: bool) -> Card {
+    pub fn new(rank: Rank, suit: Suit) -> Card {
         let card = Card {
             rank,
             suit,
-            is_face_up
+            is_face_up: false
         };
         return card;
     }
 
+    /// Constructor for a Card that starts face up.
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::card::{Card, Rank, Suit};
+    /// let card = Card::face_up(Rank::Ace, Suit::Spades);
+    /// assert!(card.is_face_up());
+    /// ```
+    pub fn face_up(rank: Rank, suit: Suit) -> Card {
+        let mut card = Card::new(rank, suit);
+        card.set_face_up(true);
+        return card;
+    }
+
     /// Get the Rank of this Card
     pub fn rank(&self) -> &Rank {
         return &self.rank;
@@ -67,6 +82,11 @@ impl Card {
         return self.suit.is_red();
     }
 
+    /// Get the colour of this Card's Suit
+    pub fn color(&self) -> SuitColor {
+        return self.suit.color();
+    }
+
     /// true if Card is face up
     pub fn is_face_up(&self) -> bool {
         return self.is_face_up;
@@ -76,6 +96,87 @@ impl Card {
     pub fn set_face_up(&mut self, is_face_up: bool) {
         self.is_face_up = is_face_up;
     }
+
+    /// Get the standard poker notation for this Card,
+    /// which is the rank (2-10, J, Q, K, A) followed by the suit (h, d, c, s).
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::card::{Card, Rank, Suit};
+    /// let card = Card::face_up(Rank::Ten, Suit::Hearts);
+    /// assert_eq!(card.to_notation(), "10h");
+    /// ```
+    pub fn to_notation(&self) -> String {
+        let rank = self.rank.rank_for_display();
+        let suit = match self.suit {
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+        };
+        return format!("{rank}{suit}");
+    }
+}
+
+/// Error returned when a Card cannot be parsed from a string
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseCardError {
+    /// the string was not a rank followed by a suit (two characters, or three for a ten written as "10")
+    InvalidLength(String),
+    /// the first character was not a valid rank
+    InvalidRank(char),
+    /// the second character was not a valid suit
+    InvalidSuit(char),
+}
+
+impl std::fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseCardError::InvalidLength(input) => write!(f, "\"{input}\" is not a card, expected a rank followed by a suit, like \"Ah\" or \"10h\""),
+            ParseCardError::InvalidRank(rank) => write!(f, "'{rank}' is not a valid rank, expected one of 2-10, T, J, Q, K, A"),
+            ParseCardError::InvalidSuit(suit) => write!(f, "'{suit}' is not a valid suit, expected one of h, d, c, s"),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl From<ParseRankError> for ParseCardError {
+    fn from(error: ParseRankError) -> Self {
+        return ParseCardError::InvalidRank(error.0);
+    }
+}
+
+impl From<ParseSuitError> for ParseCardError {
+    fn from(error: ParseSuitError) -> Self {
+        return ParseCardError::InvalidSuit(error.0);
+    }
+}
+
+/// Parse a Card from standard poker notation, such as "Ah", "Kd" or "2c" (case-insensitive).
+/// Parsed cards are face down.
+/// Example:
+/// ```
+/// use poker_project_rustaceans::card::{Card, Rank, Suit};
+/// let card: Card = "Ah".parse().unwrap();
+/// assert_eq!(card, Card::new(Rank::Ace, Suit::Hearts));
+/// ```
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut characters: Vec<char> = s.trim().chars().collect();
+        // a ten can be written as "10" as well as "T", which is how to_notation writes it
+        if characters.len() == 3 && characters[0] == '1' && characters[1] == '0' {
+            characters.remove(1);
+            characters[0] = 'T';
+        }
+        if characters.len() != 2 {
+            return Err(ParseCardError::InvalidLength(s.to_string()));
+        }
+        let rank = Rank::from_char(characters[0])?;
+        let suit = Suit::from_char(characters[1])?;
+        return Ok(Card::new(rank, suit));
+    }
 }
 
 impl PartialEq for Card {
@@ -88,14 +189,16 @@ impl Eq for Card {}
 
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.rank.partial_cmp(&other.rank)
+        Some(self.cmp(other))
     }
 }
 
 // converted rank to number because of rank iterator error....
+/// Cards are ordered by rank, and then by suit (see Suit::to_u8), so only equal cards are ordered equally
 impl Ord for Card {
     fn cmp(&self, other: &Self) -> Ordering {
         self.rank().to_u8().cmp(&other.rank().to_u8())
+            .then(self.suit().to_u8().cmp(&other.suit().to_u8()))
     }
 }
 
@@ -105,23 +208,31 @@ impl Clone for Card {
     }
 }
 
+/// Displays the Card as its rank followed by its suit symbol, like "A♠" or "10♥",
+/// with " [face down]" after it when the card is face down.
 impl std::fmt::Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let face_up_string = match self.is_face_up {
-            true => "face up",
-            false => "face down",
-        };
-        write!(f, "{} of {} ({})", self.rank, self.suit, face_up_string)
+        write!(f, "{}{}", self.rank.rank_for_display(), self.suit.to_symbol())?;
+        if !self.is_face_up {
+            write!(f, " [face down]")?;
+        }
+        return Ok(());
     }
 }
 
+/// build a list of face down cards from space separated poker notation, like "Ah Kd 2c", for use in tests
+#[cfg(test)]
+pub(crate) fn cards(notation: &str) -> Vec<Card> {
+    return notation.split_whitespace().map(|card| card.parse().unwrap()).collect();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn card_constructor() {
-        let card = Card::new(Rank::Ace, Suit::Clubs, false);
+        let card = Card::new(Rank::Ace, Suit::Clubs);
         assert_eq!(*card.rank(), Rank::Ace);
         assert_eq!(*card.suit(), Suit::Clubs);
         assert_ne!(*card.rank(), Rank::King);
@@ -130,48 +241,114 @@ mod tests {
 
     #[test]
     fn is_number() {
-        let card = Card::new(Rank::Queen, Suit::Clubs, false);
+        let card = Card::new(Rank::Queen, Suit::Clubs);
         assert!(!card.is_number());
-        let card = Card::new(Rank::Two, Suit::Clubs, false);
+        let card = Card::new(Rank::Two, Suit::Clubs);
         assert!(card.is_number());
-        let card = Card::new(Rank::Ace, Suit::Diamonds, false);
+        let card = Card::new(Rank::Ace, Suit::Diamonds);
         assert!(card.is_number());
-        let card = Card::new(Rank::Ten, Suit::Spades, false);
+        let card = Card::new(Rank::Ten, Suit::Spades);
         assert!(card.is_number());
-        let card = Card::new(Rank::Jack, Suit::Hearts, false);
+        let card = Card::new(Rank::Jack, Suit::Hearts);
         assert!(!card.is_number());
     }
 
     #[test]
     fn is_face() {
-        let card = Card::new(Rank::Queen, Suit::Clubs, false);
+        let card = Card::new(Rank::Queen, Suit::Clubs);
         assert!(card.is_face());
-        let card = Card::new(Rank::Two, Suit::Clubs, false);
+        let card = Card::new(Rank::Two, Suit::Clubs);
         assert!(!card.is_face());
-        let card = Card::new(Rank::Ace, Suit::Diamonds, false);
+        let card = Card::new(Rank::Ace, Suit::Diamonds);
         assert!(!card.is_face());
-        let card = Card::new(Rank::Ten, Suit::Spades, false);
+        let card = Card::new(Rank::Ten, Suit::Spades);
         assert!(!card.is_face());
-        let card = Card::new(Rank::Jack, Suit::Hearts, false);
+        let card = Card::new(Rank::Jack, Suit::Hearts);
         assert!(card.is_face());
     }
 
+    #[test]
+    fn display() {
+        assert_eq!(Card::face_up(Rank::Ace, Suit::Spades).to_string(), "A♠");
+        assert_eq!(Card::face_up(Rank::Ten, Suit::Hearts).to_string(), "10♥");
+        assert_eq!(Card::new(Rank::Two, Suit::Diamonds).to_string(), "2♦ [face down]");
+    }
+
+    #[test]
+    fn color() {
+        assert_eq!(Card::face_up(Rank::Ace, Suit::Hearts).color(), SuitColor::Red);
+        assert_eq!(Card::face_up(Rank::Ace, Suit::Diamonds).color(), SuitColor::Red);
+        assert_eq!(Card::face_up(Rank::Ace, Suit::Clubs).color(), SuitColor::Black);
+        assert_eq!(Card::face_up(Rank::Ace, Suit::Spades).color(), SuitColor::Black);
+    }
+
     #[test]
     fn is_equal() {
-        let ace_of_clubs = Card::new(Rank::Ace, Suit::Clubs, false);
-        let ace_of_clubs_2 = Card::new(Rank::Ace, Suit::Clubs, false);
+        let ace_of_clubs = Card::new(Rank::Ace, Suit::Clubs);
+        let ace_of_clubs_2 = Card::new(Rank::Ace, Suit::Clubs);
         assert_eq!(ace_of_clubs, ace_of_clubs_2);
-        let ace_of_spades = Card::new(Rank::Ace, Suit::Spades, false);
+        let ace_of_spades = Card::new(Rank::Ace, Suit::Spades);
         assert_ne!(ace_of_clubs, ace_of_spades);
-        let two_of_clubs = Card::new(Rank::Two, Suit::Clubs, false);
+        let two_of_clubs = Card::new(Rank::Two, Suit::Clubs);
         assert_ne!(ace_of_clubs, two_of_clubs);
     }
 
+    #[test]
+    fn ordering() {
+        let ace_of_spades = Card::new(Rank::Ace, Suit::Spades);
+        let ace_of_hearts = Card::new(Rank::Ace, Suit::Hearts);
+        let ace_of_diamonds = Card::new(Rank::Ace, Suit::Diamonds);
+        let ace_of_clubs = Card::new(Rank::Ace, Suit::Clubs);
+        assert!(ace_of_spades > ace_of_hearts);
+        assert!(ace_of_hearts > ace_of_diamonds);
+        assert!(ace_of_diamonds > ace_of_clubs);
+        // the rank comes before the suit
+        assert!(Card::new(Rank::King, Suit::Spades) < ace_of_clubs);
+        assert_eq!(ace_of_spades.cmp(&Card::face_up(Rank::Ace, Suit::Spades)), Ordering::Equal);
+    }
+
     #[test]
     fn is_face_up() {
-        let mut card = Card::new(Rank::Ace, Suit::Clubs, false);
+        let mut card = Card::new(Rank::Ace, Suit::Clubs);
         assert!(!card.is_face_up());
         card.set_face_up(true);
         assert!(card.is_face_up());
     }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("Ah".parse::<Card>().unwrap(), Card::new(Rank::Ace, Suit::Hearts));
+        assert_eq!("Kd".parse::<Card>().unwrap(), Card::new(Rank::King, Suit::Diamonds));
+        assert_eq!("2c".parse::<Card>().unwrap(), Card::new(Rank::Two, Suit::Clubs));
+        assert_eq!("Ts".parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Spades));
+        assert_eq!("10s".parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Spades));
+        assert_eq!(Card::new(Rank::Ten, Suit::Spades).to_notation().parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Spades));
+        assert!(!"Ah".parse::<Card>().unwrap().is_face_up());
+    }
+
+    #[test]
+    fn from_str_case_insensitive() {
+        assert_eq!("aH".parse::<Card>().unwrap(), Card::new(Rank::Ace, Suit::Hearts));
+        assert_eq!("tD".parse::<Card>().unwrap(), Card::new(Rank::Ten, Suit::Diamonds));
+        assert_eq!("qS".parse::<Card>().unwrap(), Card::new(Rank::Queen, Suit::Spades));
+    }
+
+    #[test]
+    fn from_str_invalid() {
+        assert_eq!("".parse::<Card>(), Err(ParseCardError::InvalidLength("".to_string())));
+        assert_eq!("100h".parse::<Card>(), Err(ParseCardError::InvalidLength("100h".to_string())));
+        assert_eq!("11h".parse::<Card>(), Err(ParseCardError::InvalidLength("11h".to_string())));
+        assert_eq!("1h".parse::<Card>(), Err(ParseCardError::InvalidRank('1')));
+        assert_eq!("Ax".parse::<Card>(), Err(ParseCardError::InvalidSuit('x')));
+    }
+
+    #[test]
+    fn to_notation() {
+        assert_eq!(Card::new(Rank::Ace, Suit::Hearts).to_notation(), "Ah");
+        assert_eq!(Card::face_up(Rank::Ten, Suit::Clubs).to_notation(), "10c");
+        assert_eq!(Card::new(Rank::Two, Suit::Spades).to_notation(), "2s");
+        for notation in ["Kd", "9h", "Jc", "Qs"] {
+            assert_eq!(notation.parse::<Card>().unwrap().to_notation(), notation);
+        }
+    }
 }
@@ -47,6 +47,25 @@ impl Rank {
         return faces.contains(self);
     }
 
+    /// the short form of this Rank shown on cards: "2" to "10", then "J", "Q", "K" and "A"
+    pub fn rank_for_display(&self) -> &str {
+        return match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        };
+    }
+
     // convert ranks to numbers for easy comparing
     pub fn to_u8(&self) -> u8 {
         match self {
@@ -86,9 +105,52 @@ impl Rank {
         }
     }
 
+    /// parse a Rank from the character it is written as in poker notation (case-insensitive):
+    /// '2' to '9', then 'T' for a Ten, 'J', 'Q', 'K' and 'A'
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::card::Rank;
+    /// assert_eq!(Rank::from_char('t'), Ok(Rank::Ten));
+    /// assert!(Rank::from_char('1').is_err());
+    /// ```
+    pub fn from_char(c: char) -> Result<Rank, ParseRankError> {
+        return match c.to_ascii_uppercase() {
+            '2' => Ok(Rank::Two),
+            '3' => Ok(Rank::Three),
+            '4' => Ok(Rank::Four),
+            '5' => Ok(Rank::Five),
+            '6' => Ok(Rank::Six),
+            '7' => Ok(Rank::Seven),
+            '8' => Ok(Rank::Eight),
+            '9' => Ok(Rank::Nine),
+            'T' => Ok(Rank::Ten),
+            'J' => Ok(Rank::Jack),
+            'Q' => Ok(Rank::Queen),
+            'K' => Ok(Rank::King),
+            'A' => Ok(Rank::Ace),
+            _ => Err(ParseRankError(c)),
+        };
+    }
 
+    /// the absolute difference between two ranks, as numbers obtained with to_u8()
+    /// (so an Ace is always high, and is 12 away from a Two)
+    pub fn distance(a: &Rank, b: &Rank) -> u8 {
+        return a.to_u8().abs_diff(b.to_u8());
+    }
 }
 
+/// Error returned when a Rank cannot be parsed from a character, holding the character
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseRankError(pub char);
+
+impl std::fmt::Display for ParseRankError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "'{}' is not a valid rank, expected one of 2-9, T, J, Q, K, A", self.0);
+    }
+}
+
+impl std::error::Error for ParseRankError {}
+
 impl PartialEq for Rank {
     fn eq(&self, other: &Self) -> bool {
         return core::mem::discriminant(self) == core::mem::discriminant(other);
@@ -278,6 +340,7 @@ impl std::fmt::Display for Rank {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use strum::IntoEnumIterator;
     #[test]
     fn ordering() {
         let ace = Rank::Ace;
@@ -294,4 +357,38 @@ mod tests {
         assert_eq!(ace, ace_2);
         assert_ne!(ace, king);
     }
+
+    #[test]
+    fn distance() {
+        assert_eq!(Rank::distance(&Rank::Two, &Rank::Two), 0);
+        assert_eq!(Rank::distance(&Rank::Five, &Rank::Nine), 4);
+        assert_eq!(Rank::distance(&Rank::Nine, &Rank::Five), 4);
+        assert_eq!(Rank::distance(&Rank::Two, &Rank::Ace), 12);
+    }
+
+    #[test]
+    fn rank_for_display() {
+        assert_eq!(Rank::Ten.rank_for_display(), "10");
+        assert_eq!(Rank::Two.rank_for_display(), "2");
+        assert_eq!(Rank::Ace.rank_for_display(), "A");
+        for rank in Rank::iter().filter(|rank| *rank != Rank::Ten) {
+            assert_eq!(rank.rank_for_display().chars().count(), 1, "{rank} should be shown as a single character");
+        }
+    }
+
+    #[test]
+    fn from_char() {
+        let characters = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+        for (character, rank) in characters.into_iter().zip(Rank::iter()) {
+            assert_eq!(Rank::from_char(character), Ok(rank.clone()));
+            assert_eq!(Rank::from_char(character.to_ascii_lowercase()), Ok(rank));
+        }
+    }
+
+    #[test]
+    fn from_char_invalid() {
+        for character in ['0', '1', 'B', 'x', 'Z', ' ', '*', '♠'] {
+            assert_eq!(Rank::from_char(character), Err(ParseRankError(character)));
+        }
+    }
 }
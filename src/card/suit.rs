@@ -1,7 +1,14 @@
 use serde::{ Serialize, Deserialize };
 use strum_macros::EnumIter;
 
-#[derive(Debug, EnumIter, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// the colour of a Suit, red for Hearts and Diamonds, black for Clubs and Spades
+pub enum SuitColor {
+    Red,
+    Black
+}
+
+#[derive(Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize, Hash)]
 /// Suit class, representing the suit of a Card (shape + colour)
 pub enum Suit {
     Clubs,
@@ -28,14 +35,67 @@ impl Suit {
         ];
         return reds.contains(self);
     }
+
+    /// get the colour of this Suit
+    pub fn color(&self) -> SuitColor {
+        return match self {
+            Suit::Clubs | Suit::Spades => SuitColor::Black,
+            Suit::Hearts | Suit::Diamonds => SuitColor::Red,
+        };
+    }
+
+    /// convert suits to numbers for breaking ties between cards of the same rank,
+    /// from 4 for Spades down to 1 for Clubs (Spades > Hearts > Diamonds > Clubs)
+    pub fn to_u8(&self) -> u8 {
+        return match self {
+            Suit::Clubs => 1,
+            Suit::Diamonds => 2,
+            Suit::Hearts => 3,
+            Suit::Spades => 4,
+        };
+    }
+
+    /// parse a Suit from the character it is written as in poker notation (case-insensitive):
+    /// 'h' for Hearts, 'd' for Diamonds, 'c' for Clubs and 's' for Spades
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::card::Suit;
+    /// assert_eq!(Suit::from_char('S'), Ok(Suit::Spades));
+    /// assert!(Suit::from_char('x').is_err());
+    /// ```
+    pub fn from_char(c: char) -> Result<Suit, ParseSuitError> {
+        return match c.to_ascii_lowercase() {
+            'h' => Ok(Suit::Hearts),
+            'd' => Ok(Suit::Diamonds),
+            'c' => Ok(Suit::Clubs),
+            's' => Ok(Suit::Spades),
+            _ => Err(ParseSuitError(c)),
+        };
+    }
+
+    /// get the symbol used when displaying a card of this suit
+    pub fn to_symbol(&self) -> char {
+        return match self {
+            Suit::Clubs => '♣',
+            Suit::Spades => '♠',
+            Suit::Hearts => '♥',
+            Suit::Diamonds => '♦',
+        };
+    }
 }
 
-impl PartialEq for Suit {
-    fn eq(&self, other: &Self) -> bool {
-        return core::mem::discriminant(self) == core::mem::discriminant(other);
+/// Error returned when a Suit cannot be parsed from a character, holding the character
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseSuitError(pub char);
+
+impl std::fmt::Display for ParseSuitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "'{}' is not a valid suit, expected one of h, d, c, s", self.0);
     }
 }
 
+impl std::error::Error for ParseSuitError {}
+
 impl Clone for Suit {
     fn clone(&self) -> Self {
         match self {
@@ -73,4 +133,37 @@ mod tests {
         assert_ne!(clubs, diamonds);
         assert_ne!(spades, diamonds);
     }
+
+    #[test]
+    fn color() {
+        assert_eq!(Suit::Hearts.color(), SuitColor::Red);
+        assert_eq!(Suit::Diamonds.color(), SuitColor::Red);
+        assert_eq!(Suit::Clubs.color(), SuitColor::Black);
+        assert_eq!(Suit::Spades.color(), SuitColor::Black);
+        for suit in [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds] {
+            assert_eq!(suit.color() == SuitColor::Red, suit.is_red());
+        }
+    }
+
+    #[test]
+    fn to_u8_order() {
+        assert!(Suit::Spades.to_u8() > Suit::Hearts.to_u8());
+        assert!(Suit::Hearts.to_u8() > Suit::Diamonds.to_u8());
+        assert!(Suit::Diamonds.to_u8() > Suit::Clubs.to_u8());
+    }
+
+    #[test]
+    fn from_char() {
+        for (character, suit) in [('h', Suit::Hearts), ('d', Suit::Diamonds), ('c', Suit::Clubs), ('s', Suit::Spades)] {
+            assert_eq!(Suit::from_char(character), Ok(suit.clone()));
+            assert_eq!(Suit::from_char(character.to_ascii_uppercase()), Ok(suit));
+        }
+    }
+
+    #[test]
+    fn from_char_invalid() {
+        for character in ['x', 'a', 'b', 'X', '1', ' ', '♠'] {
+            assert_eq!(Suit::from_char(character), Err(ParseSuitError(character)));
+        }
+    }
 }
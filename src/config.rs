@@ -0,0 +1,104 @@
+use std::env;
+
+use crate::database::db_handler::DbHandler;
+
+/// the balance that newly registered players start with, unless it is configured otherwise
+pub const DEFAULT_STARTING_BALANCE: u32 = 1000;
+
+/// the environment variable that sets the starting balance of newly registered players
+pub const STARTING_BALANCE_VARIABLE: &str = "POKER_STARTING_BALANCE";
+
+/// the database that rounds, accounts and lobbies are saved to, unless it is configured otherwise
+pub const DEFAULT_DATABASE_URL: &str = "mongodb://localhost:27017/";
+pub const DEFAULT_DATABASE_NAME: &str = "test";
+
+/// the environment variables that set the database's connection string and name
+pub const DATABASE_URL_VARIABLE: &str = "POKER_DATABASE_URL";
+pub const DATABASE_NAME_VARIABLE: &str = "POKER_DATABASE_NAME";
+
+/// Config struct
+///
+/// Settings that are loaded from the environment when the program starts,
+/// and passed to the menus and the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// the balance that newly registered players start with
+    pub starting_balance: u32,
+    /// the connection string of the database that rounds, accounts and lobbies are saved to
+    pub database_url: String,
+    /// the name of that database
+    pub database_name: String,
+}
+
+impl Config {
+    /// load the config from the environment variables,
+    /// any setting that isn't set (or can't be read) is left as its default
+    pub fn from_env() -> Config {
+        return Config::from_values(
+            env::var(STARTING_BALANCE_VARIABLE).ok().as_deref(),
+            env::var(DATABASE_URL_VARIABLE).ok().as_deref(),
+            env::var(DATABASE_NAME_VARIABLE).ok().as_deref(),
+        );
+    }
+
+    /// connect to the configured database, falling back to a dummy handler (which saves nothing)
+    /// if the database can't be reached
+    pub async fn connect_db_handler(&self) -> DbHandler {
+        return match DbHandler::new(self.database_url.clone(), self.database_name.clone()).await {
+            Ok(db_handler) => db_handler,
+            Err(e) => {
+                println!("Using dummy DbHandler due to error while connecting to the database: {}", e);
+                DbHandler::new_dummy()
+            },
+        };
+    }
+
+    /// build the config from the values of the environment variables
+    fn from_values(starting_balance: Option<&str>, database_url: Option<&str>, database_name: Option<&str>) -> Config {
+        let starting_balance = match starting_balance.map(|value| value.trim().parse::<u32>()) {
+            Some(Ok(starting_balance)) if starting_balance > 0 => starting_balance,
+            Some(_) => {
+                println!("{} must be a positive whole number, using the default starting balance of {}", STARTING_BALANCE_VARIABLE, DEFAULT_STARTING_BALANCE);
+                DEFAULT_STARTING_BALANCE
+            },
+            None => DEFAULT_STARTING_BALANCE,
+        };
+        let non_blank = |value: Option<&str>, default: &str| match value.map(str::trim) {
+            Some(value) if !value.is_empty() => value.to_string(),
+            _ => default.to_string(),
+        };
+        return Config {
+            starting_balance,
+            database_url: non_blank(database_url, DEFAULT_DATABASE_URL),
+            database_name: non_blank(database_name, DEFAULT_DATABASE_NAME),
+        };
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        return Config {
+            starting_balance: DEFAULT_STARTING_BALANCE,
+            database_url: DEFAULT_DATABASE_URL.to_string(),
+            database_name: DEFAULT_DATABASE_NAME.to_string(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_values() {
+        assert_eq!(Config::from_values(None, None, None), Config::default());
+        assert_eq!(Config::from_values(Some(" 2500 "), None, None).starting_balance, 2500);
+        // values that aren't positive whole numbers are ignored
+        assert_eq!(Config::from_values(Some("0"), None, None).starting_balance, DEFAULT_STARTING_BALANCE);
+        assert_eq!(Config::from_values(Some("lots"), None, None).starting_balance, DEFAULT_STARTING_BALANCE);
+
+        let config = Config::from_values(None, Some("mongodb://db.example:27017/"), Some(" "));
+        assert_eq!(config.database_url, "mongodb://db.example:27017/");
+        assert_eq!(config.database_name, DEFAULT_DATABASE_NAME);
+    }
+}
@@ -11,10 +11,13 @@
 /// dedicated to storing document IDs to create connections between documents.
 
 
-use mongodb::{ action::CountDocuments, bson::{ doc, Document}, options::{ ClientOptions, ServerApi, ServerApiVersion }, results::{ DeleteResult, InsertManyResult, InsertOneResult, UpdateResult }, Client, Collection, Cursor};
+use mongodb::{ action::CountDocuments, bson::{ doc, Document}, error::{ Error, ErrorKind, WriteFailure }, options::{ ClientOptions, IndexOptions, ServerApi, ServerApiVersion }, results::{ CreateIndexResult, DeleteResult, InsertManyResult, InsertOneResult, UpdateResult }, Client, Collection, Cursor, IndexModel };
 use serde::{ de::DeserializeOwned, Serialize };
+use futures::TryStreamExt;
 use uuid::Uuid;
 
+use crate::database::db_structs::{ LeaderboardEntry, Round, Turn };
+
 
 extern crate bson;
 
@@ -74,6 +77,36 @@ impl DbHandler {
         }
     }
 
+    /// Makes a field unique across all documents in a collection,
+    /// documents that do not have the field are not indexed, so the field can still be left out.
+    pub async fn create_unique_index<T>(&self, field: &str, collection_name: &str) -> Option<mongodb::error::Result<CreateIndexResult>>
+    where
+        T: Send + Sync
+    {
+        match &self.client {
+            DbClient::RealClient(client) => {
+                let collection: Collection<T> = client.database(&self.database_name).collection(collection_name);
+                let mut keys = Document::new();
+                keys.insert(field, 1);
+                let index = IndexModel::builder()
+                    .keys(keys)
+                    .options(IndexOptions::builder().unique(true).sparse(true).build())
+                    .build();
+                Some(collection.create_index(index).await)
+            },
+            DbClient::Dummy => None,
+        }
+    }
+
+    /// Returns true iff the error is caused by adding a document which has the same value
+    /// for a unique field as another document in the collection.
+    pub fn is_duplicate_key_error(error: &Error) -> bool {
+        match error.kind.as_ref() {
+            ErrorKind::Write(WriteFailure::WriteError(write_error)) => write_error.code == 11000,
+            _ => false,
+        }
+    }
+
     /// Adds one document to collection.
     pub async fn add_document<T>(&self, doc: T, collection_name: &str) -> Option<mongodb::error::Result<InsertOneResult>>
     where
@@ -156,6 +189,83 @@ impl DbHandler {
         }
     }
 
+    /// Deletes all documents that match filter.
+    pub async fn delete_documents<T>(&self, filter: Document, collection_name: &str) -> Option<mongodb::error::Result<DeleteResult>>
+    where
+        T: Send + Sync
+    {
+        match &self.client {
+            DbClient::RealClient(client) => {
+                let collection: Collection<T> = client.database(&self.database_name).collection(collection_name);
+                Some(collection.delete_many(filter).await)
+            },
+            DbClient::Dummy => None,
+        }
+    }
+
+    /// Deletes all Round documents of a game, and then all Turn documents of those rounds.
+    /// The result of deleting the rounds is returned.
+    pub async fn delete_lobby_data(&self, game_id: Uuid) -> Option<mongodb::error::Result<DeleteResult>> {
+        let game_filter = doc! { "game_id": game_id.simple().to_string() };
+        let round_documents: Vec<Document> = match self.get_documents::<Document>(game_filter.clone(), "Rounds").await? {
+            Ok(cursor) => match cursor.try_collect().await {
+                Ok(round_documents) => round_documents,
+                Err(e) => return Some(Err(e)),
+            },
+            Err(e) => return Some(Err(e)),
+        };
+        let round_ids: Vec<&str> = round_documents.iter()
+            .filter_map(|round_document| round_document.get_str("_id").ok())
+            .collect();
+
+        let rounds_result = self.delete_documents::<Round>(game_filter, "Rounds").await?;
+        if rounds_result.is_err() {
+            return Some(rounds_result);
+        }
+        match self.delete_documents::<Turn>(doc! { "round_id": { "$in": round_ids } }, "Turns").await? {
+            Ok(_) => Some(rounds_result),
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    /// Returns the top_n players with the most winnings, adding up the Win turns of each player.
+    /// A dummy DbHandler has no turns, so its leaderboard is empty.
+    pub async fn get_leaderboard(&self, top_n: u32) -> mongodb::error::Result<Vec<LeaderboardEntry>> {
+        let client = match &self.client {
+            DbClient::RealClient(client) => client,
+            DbClient::Dummy => return Ok(Vec::new()),
+        };
+        let collection: Collection<Turn> = client.database(&self.database_name).collection("Turns");
+        let pipeline = vec![
+            // the amount of a Win turn is stored as { "win": amount }, or { "Win": amount } in turns saved before the tags were lowercased,
+            // other turns count for nothing
+            doc! { "$group": {
+                "_id": "$acting_player_id",
+                "total_winnings": { "$sum": { "$ifNull": ["$action.win", { "$ifNull": ["$action.Win", 0] }] } },
+                "round_ids": { "$addToSet": "$round_id" },
+            } },
+            doc! { "$project": { "total_winnings": 1, "rounds_played": { "$size": "$round_ids" } } },
+            doc! { "$sort": { "total_winnings": -1, "_id": 1 } },
+            doc! { "$limit": top_n as i64 },
+        ];
+        let cursor = collection.aggregate(pipeline).with_type::<LeaderboardEntry>().await?;
+        return cursor.try_collect().await;
+    }
+
+    /// Replaces the document that matches id, adding it if there is no such document.
+    pub async fn replace_document_by_id<T>(&self, id: Uuid, doc: T, collection_name: &str) -> Option<mongodb::error::Result<UpdateResult>>
+    where
+        T: Serialize + Send + Sync
+    {
+        match &self.client {
+            DbClient::RealClient(client) => {
+                let collection: Collection<T> = client.database(&self.database_name).collection(collection_name);
+                Some(collection.replace_one(doc! { "_id": id.simple().to_string() }, doc).upsert(true).await)
+            },
+            DbClient::Dummy => None,
+        }
+    }
+
     /// Updates certain fields in a document.
     pub async fn update_document_by_id<T>(&self, id: Uuid, update_fields: Document, collection_name: &str) -> Option<mongodb::error::Result<UpdateResult>>
     where
@@ -178,7 +288,9 @@ mod tests {
     use test_context::{ test_context, AsyncTestContext };
 
     use super::*;
+    use crate::action::Action;
     use crate::database::db_structs::Account;
+    use crate::config::DEFAULT_STARTING_BALANCE;
 
 
     struct Context {
@@ -205,6 +317,8 @@ mod tests {
         let new_id = Uuid::now_v7();
         let dummy_account = Account {
             _id: new_id,
+            username: None,
+            starting_balance: DEFAULT_STARTING_BALANCE,
         };
         let _ = ctx.db.add_document(dummy_account, &ctx.test_collection).await;
         match ctx.db.delete_document_by_id::<Account>(new_id, &ctx.test_collection).await.unwrap() {
@@ -220,6 +334,8 @@ mod tests {
         let new_id = Uuid::now_v7();
         let dummy_account = Account {
             _id: new_id,
+            username: None,
+            starting_balance: DEFAULT_STARTING_BALANCE,
         };
         let res = ctx.db.add_document(dummy_account, &ctx.test_collection).await.unwrap().unwrap();
         let _ = ctx.db.delete_document_by_id::<Account>(new_id, &ctx.test_collection).await;
@@ -233,10 +349,97 @@ mod tests {
         let new_id = Uuid::now_v7();
         let dummy_account = Account {
             _id: new_id,
+            username: None,
+            starting_balance: DEFAULT_STARTING_BALANCE,
         };
         let _ = ctx.db.add_document(dummy_account, &ctx.test_collection).await;
         let doc: Account = ctx.db.get_document_by_id(new_id, &ctx.test_collection).await.unwrap().unwrap().unwrap();
         let _ = ctx.db.delete_document_by_id::<Account>(new_id, &ctx.test_collection).await;
         assert_eq!(doc._id, new_id);
     }
-}
\ No newline at end of file
+
+    #[test_context(Context)]
+    #[tokio::test]
+    #[ignore]
+    async fn test_delete_lobby_data(ctx: &mut Context) {
+        let game_id = Uuid::now_v7();
+        let mut round_ids = Vec::new();
+        for _ in 0..2 {
+            let round_id = Uuid::now_v7();
+            round_ids.push(round_id.simple().to_string());
+            let mut turn_ids = Vec::new();
+            for _ in 0..3 {
+                let turn_id = Uuid::now_v7();
+                let _ = ctx.db.add_document(Turn {
+                    _id: turn_id,
+                    round_id,
+                    phase_num: 0,
+                    acting_player_id: Uuid::now_v7(),
+                    hand: Vec::new(),
+                    action: Action::Check,
+                }, "Turns").await;
+                turn_ids.push(turn_id);
+            }
+            let _ = ctx.db.add_document(Round {
+                _id: round_id,
+                game_id,
+                game_round_number: 0,
+                turn_ids,
+                player_ids: Vec::new(),
+                final_stakes: std::collections::HashMap::new(),
+            }, "Rounds").await;
+        }
+
+        let res = ctx.db.delete_lobby_data(game_id).await.unwrap().unwrap();
+        assert_eq!(res.deleted_count, 2);
+        let game_filter = doc! { "game_id": game_id.simple().to_string() };
+        assert_eq!(ctx.db.count_documents::<Round>(game_filter, "Rounds").await.unwrap().unwrap(), 0);
+        let turn_filter = doc! { "round_id": { "$in": round_ids } };
+        assert_eq!(ctx.db.count_documents::<Turn>(turn_filter, "Turns").await.unwrap().unwrap(), 0);
+    }
+
+    #[test_context(Context)]
+    #[tokio::test]
+    #[ignore]
+    async fn test_get_leaderboard(ctx: &mut Context) {
+        let round_ids = [Uuid::now_v7(), Uuid::now_v7()];
+        // a big winner over two rounds, so that they top the leaderboard whatever else is in the database
+        let winner_id = Uuid::now_v7();
+        let runner_up_id = Uuid::now_v7();
+        let turns = [
+            (round_ids[0], winner_id, Action::Call),
+            (round_ids[0], winner_id, Action::Win(1_000_000_000)),
+            (round_ids[1], winner_id, Action::Win(500_000_000)),
+            (round_ids[0], runner_up_id, Action::Win(1_000_000_000)),
+        ];
+        let mut turn_ids = Vec::new();
+        for (round_id, acting_player_id, action) in turns {
+            let turn_id = Uuid::now_v7();
+            let _ = ctx.db.add_document(Turn { _id: turn_id, round_id, phase_num: 0, acting_player_id, hand: Vec::new(), action }, "Turns").await;
+            turn_ids.push(turn_id.simple().to_string());
+        }
+
+        let leaderboard = ctx.db.get_leaderboard(2).await;
+        let _ = ctx.db.delete_documents::<Turn>(doc! { "_id": { "$in": turn_ids } }, "Turns").await;
+        assert_eq!(leaderboard.unwrap(), vec![
+            LeaderboardEntry { player_id: winner_id, total_winnings: 1_500_000_000, rounds_played: 2 },
+            LeaderboardEntry { player_id: runner_up_id, total_winnings: 1_000_000_000, rounds_played: 1 },
+        ]);
+    }
+
+    #[test_context(Context)]
+    #[tokio::test]
+    #[ignore]
+    async fn test_unique_username(ctx: &mut Context) {
+        ctx.db.create_unique_index::<Account>("username", &ctx.test_collection).await.unwrap().unwrap();
+        let username = format!("user-{}", Uuid::now_v7().simple());
+        let first_id = Uuid::now_v7();
+        let second_id = Uuid::now_v7();
+        let first_res = ctx.db.add_document(Account { _id: first_id, username: Some(username.clone()), starting_balance: DEFAULT_STARTING_BALANCE }, &ctx.test_collection).await.unwrap();
+        let second_res = ctx.db.add_document(Account { _id: second_id, username: Some(username), starting_balance: DEFAULT_STARTING_BALANCE }, &ctx.test_collection).await.unwrap();
+        let _ = ctx.db.delete_document_by_id::<Account>(first_id, &ctx.test_collection).await;
+        let _ = ctx.db.delete_document_by_id::<Account>(second_id, &ctx.test_collection).await;
+        assert!(first_res.is_ok());
+        assert!(DbHandler::is_duplicate_key_error(&second_res.unwrap_err()));
+    }
+}
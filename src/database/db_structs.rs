@@ -1,9 +1,24 @@
+//! Documents stored in the database.
+//!
+//! Migration note: Round documents now have a game_round_number field, the number of the round
+//! within its game (starting from 1). Rounds saved before this field was added don't have it,
+//! and are read back with a game_round_number of 0, so they can still be told apart from numbered rounds.
+//! Their order within a game can still be recovered from their _id, as round ids are time based (UUID v7).
+//!
+//! Round documents also have a final_stakes field, each player's stake in the pot when the round was saved.
+//! Older rounds are read back with no final stakes.
+//!
+//! Lobby documents are new, one is kept for each open lobby so that lobbies can be restored when the server restarts.
+
 use serde::{ Deserialize, Serialize, Serializer, ser::SerializeSeq };
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::card::Card;
 use crate::action::Action;
 use crate::game_type::GameType;
+use crate::config;
+use crate::lobby::LobbyStatus;
 
 /// Game struct
 /// 
@@ -42,6 +57,9 @@ pub struct Round {
     pub _id: Uuid,
     #[serde(with = "uuid::serde::simple")]
     pub game_id: Uuid,
+    // The number of this round within its game, starting from 1 (0 for rounds saved before rounds were numbered).
+    #[serde(default)]
+    pub game_round_number: u32,
 
     // A vector of turn is stored in a document to record turn order.
     // _id of turn documents are thought to not be reliable enough to store turn order.
@@ -50,6 +68,9 @@ pub struct Round {
     pub turn_ids: Vec<Uuid>,
     #[serde(serialize_with = "simple_uuids")]
     pub player_ids: Vec<Uuid>,
+    // Each player's stake in the pot when the round was saved, keyed by their simple UUID string.
+    #[serde(default)]
+    pub final_stakes: HashMap<String, i64>,
 }
 
 /// Turn struct
@@ -82,4 +103,81 @@ pub struct Turn {
 pub struct Account {
     #[serde(with = "uuid::serde::simple")]
     pub _id: Uuid,
-}
\ No newline at end of file
+
+    // Username used to log in, which is unique across all accounts.
+    // Accounts that were registered without a username do not have one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    // Balance the account started with when it was created,
+    // accounts created before this was stored started with the default.
+    #[serde(default = "Account::default_starting_balance")]
+    pub starting_balance: u32,
+}
+
+impl Account {
+    fn default_starting_balance() -> u32 {
+        config::DEFAULT_STARTING_BALANCE
+    }
+}
+
+/// LobbyUser struct
+/// 
+/// A user seated in a lobby, with the stack they brought to it and the hash of the session token
+/// they reconnect to it with (the token itself is never stored). These are stored inside of Lobby documents.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LobbyUser {
+    #[serde(with = "uuid::serde::simple")]
+    pub user_id: Uuid,
+    pub buy_in: u32,
+    // None for users who were saved without a session token, who can't reconnect until they join again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_token_hash: Option<String>,
+    // Name the user has chosen to be shown as, if they have chosen one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+/// Lobby struct
+/// 
+/// The settings and seated users of an open lobby on the server. A lobby is identified
+/// by the ID of its game (which its rounds are stored under), so _id is the game ID,
+/// and lobby_id is the number the lobby is listed under on the server.
+/// A game that was being played when the server stopped can't be carried on,
+/// so the status is only kept for reference and restored lobbies go back to waiting for a game.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lobby {
+    #[serde(with = "uuid::serde::simple")]
+    pub _id: Uuid,
+    pub lobby_id: u32,
+    pub name: String,
+    // Simple UUID string of the user who created the lobby, lobbies made by the server have no creator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creator_id: Option<String>,
+    pub game_type: GameType,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub min_buy_in: u32,
+    pub max_buy_in: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub starting_balance: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_hash: Option<String>,
+    pub users: Vec<LobbyUser>,
+    pub status: LobbyStatus,
+    // Number of the round that was played last, so that round numbers carry on after a restart.
+    pub current_round: u32,
+}
+
+/// LeaderboardEntry struct
+/// 
+/// One player's standing on the leaderboard, which isn't stored but is built from the Turn documents
+/// (see DbHandler::get_leaderboard). total_winnings is the sum of the player's Win turns,
+/// and rounds_played is the number of rounds the player took a turn in.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeaderboardEntry {
+    #[serde(rename = "_id", with = "uuid::serde::simple")]
+    pub player_id: Uuid,
+    pub total_winnings: i64,
+    pub rounds_played: u32,
+}
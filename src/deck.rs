@@ -3,12 +3,35 @@ use rand::prelude::*;
 
 pub use super::card::{Card, Rank, Suit};
 
+/// Errors that can occur when dealing from a Deck
+#[derive(Debug, PartialEq)]
+pub enum DeckError {
+    /// there are no cards remaining in the deck
+    Empty,
+}
+
+impl std::fmt::Display for DeckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckError::Empty => write!(f, "There are no cards remaining in the deck, so no card can be dealt"),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+/// the number of cards in a full deck
+pub const DECK_SIZE: usize = 52;
+/// the number of cards in a short deck, which has no twos, threes, fours or fives
+pub const SHORT_DECK_SIZE: usize = 36;
+
 #[derive(Debug)]
 /// Deck class, representing a normal deck of 52 cards
 /// except that there are no jokers in this deck
 /// Create a new deck with Deck::new().
 /// There should only be one deck per game.
-/// Cards can be dealt (at random), but they must later be returned to the deck.
+/// Cards are dealt from the top of the (shuffled) deck, but they must later be returned to the deck,
+/// where they are put on the bottom.
 /// Example:
 /// ```
 /// use poker_project_rustaceans::deck::Deck;
@@ -17,11 +40,13 @@ pub use super::card::{Card, Rank, Suit};
 /// deck.return_card(card);
 /// ```
 pub struct Deck {
-    cards: Vec<Card>
+    cards: Vec<Card>,
+    /// whether this is a short deck (see Deck::new_short)
+    is_short: bool,
 }
 
 impl Deck {
-    /// Constructor for Deck.
+    /// Constructor for Deck, the deck starts out shuffled.
     /// Example:
     /// ```
     /// use poker_project_rustaceans::deck::Deck;
@@ -29,38 +54,83 @@ impl Deck {
     /// ```
     pub fn new() -> Deck {
         let mut deck = Deck {
-            cards: Vec::new()
+            cards: Vec::new(),
+            is_short: false,
         };
 
         for rank in Rank::iter() {
             for suit in Suit::iter() {
-                deck.cards.push(Card::new(rank.clone(), suit, false));
+                deck.cards.push(Card::new(rank.clone(), suit));
+            }
+        }
+        deck.shuffle();
+
+        return deck;
+    }
+
+    /// Constructor for a short deck (as used in short deck hold'em), which only has the sixes through to the aces,
+    /// the deck starts out shuffled.
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::deck::{Deck, SHORT_DECK_SIZE};
+    /// let deck = Deck::new_short();
+    /// assert_eq!(deck.size(), SHORT_DECK_SIZE);
+    /// ```
+    pub fn new_short() -> Deck {
+        let mut deck = Deck {
+            cards: Vec::new(),
+            is_short: true,
+        };
+
+        for rank in Rank::iter().filter(|rank| *rank >= Rank::Six) {
+            for suit in Suit::iter() {
+                deck.cards.push(Card::new(rank.clone(), suit));
             }
         }
+        deck.shuffle();
 
         return deck;
     }
 
-    /// Deals a card from the deck at random.
-    /// Err(String) if the deck no longer contains any cards,
+    /// Deals the card on top of the deck.
+    /// Err(DeckError::Empty) if the deck no longer contains any cards,
     /// otherwise Ok(Card)
-    pub fn deal(&mut self, is_face_up: bool) -> Result<Card, String> {
+    pub fn deal(&mut self, is_face_up: bool) -> Result<Card, DeckError> {
         if self.cards.is_empty() {
-            return Err("There are no cards remaining in the deck, so no card can be dealt".to_string());
+            return Err(DeckError::Empty);
         }
-        let mut rng = rand::rng();
-        let index = match (0..self.cards.len()).choose(&mut rng) {
-            Some(card) => card,
-            None => panic!("There was a problem picking a card to deal, even though there were cards in the deck...")
-        };
-        let mut card = self.cards.swap_remove(index);
+        let mut card = self.cards.remove(0);
 
         card.set_face_up(is_face_up);
 
         return Ok(card);
     }
 
-    /// Return a card to the deck so that it can be dealt.
+    /// Shuffle the cards that are in the deck into a random order.
+    pub fn shuffle(&mut self) {
+        let mut rng = rand::rng();
+        self.cards.shuffle(&mut rng);
+    }
+
+    /// Look at the next n cards that would be dealt, in the order they would be dealt,
+    /// without dealing them. If there are fewer than n cards in the deck, all of them are returned.
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::deck::Deck;
+    /// let mut deck = Deck::new();
+    /// let next_card = deck.peek(1)[0].clone();
+    /// assert_eq!(deck.deal(true).unwrap(), next_card);
+    /// ```
+    pub fn peek(&self, n: usize) -> &[Card] {
+        return &self.cards[..n.min(self.cards.len())];
+    }
+
+    /// Look at every card in the deck, in the order they would be dealt, without dealing them.
+    pub fn peek_all(&self) -> &[Card] {
+        return &self.cards;
+    }
+
+    /// Return a card to the bottom of the deck so that it can be dealt.
     /// If cards are not returned to the deck, they will never
     /// be able to be dealt again by this deck, and the deck will
     /// run out of cards.
@@ -73,10 +143,75 @@ impl Deck {
         self.cards.push(card);
     }
 
+    /// Cut the deck at the given position, so that the card at that position
+    /// becomes the top card. Positions past the end of the deck wrap around.
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::deck::Deck;
+    /// let mut deck = Deck::new();
+    /// deck.cut(60);
+    /// assert_eq!(deck.size(), 52);
+    /// ```
+    pub fn cut(&mut self, position: usize) {
+        if self.cards.is_empty() {
+            return;
+        }
+        let position = position % self.cards.len();
+        self.cards.rotate_left(position);
+    }
+
+    /// Cut the deck at a random position, as is done after shuffling in live poker.
+    pub fn cut_random(&mut self) {
+        if self.cards.is_empty() {
+            return;
+        }
+        let mut rng = rand::rng();
+        let position = rng.random_range(0..self.cards.len());
+        self.cut(position);
+    }
+
     /// Return the size of the Deck (the number of cards currently in the Deck)
     pub fn size(&self) -> usize {
         return self.cards.len();
     }
+
+    /// Check that every card that belongs in the deck is in it exactly once,
+    /// as it should be between rounds, once every dealt card has been returned.
+    /// Unlike checking the size, this catches a card that was lost while another was duplicated.
+    ///
+    /// panics naming the missing cards if any card is missing, or if a card is in the deck more than once
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::deck::Deck;
+    /// let mut deck = Deck::new();
+    /// let card = deck.deal(true).unwrap();
+    /// deck.return_card(card);
+    /// deck.assert_complete();
+    /// ```
+    pub fn assert_complete(&self) {
+        let full_deck = match self.is_short {
+            true => Deck::new_short(),
+            false => Deck::new(),
+        };
+        let missing_cards: Vec<String> = full_deck.cards.iter()
+            .filter(|card| !self.cards.contains(card))
+            .map(|card| card.to_notation())
+            .collect();
+        assert!(missing_cards.is_empty(), "Cards are missing from the Deck: {}", missing_cards.join(" "));
+        assert_eq!(self.cards.len(), full_deck.cards.len(), "Deck has cards that are duplicates or don't belong in it");
+    }
+
+    /// Return the number of cards that can still be dealt before the Deck runs out.
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::deck::Deck;
+    /// let mut deck = Deck::new();
+    /// let card = deck.deal(true).unwrap();
+    /// assert_eq!(deck.remaining(), 51);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        return self.cards.len();
+    }
 }
 
 #[cfg(test)]
@@ -89,6 +224,19 @@ mod tests {
         assert_eq!(deck.size(), 52);
     }
 
+    #[test]
+    fn short_deck_constructor() {
+        let mut deck = Deck::new_short();
+        assert_eq!(deck.size(), SHORT_DECK_SIZE);
+        let mut cards = Vec::<Card>::new();
+        while let Ok(card) = deck.deal(false) {
+            assert!(*card.rank() >= Rank::Six);
+            assert!(!cards.contains(&card));
+            cards.push(card);
+        }
+        assert_eq!(cards.len(), SHORT_DECK_SIZE);
+    }
+
     #[test]
     fn deal_count() {
         let mut deck = Deck::new();
@@ -137,6 +285,77 @@ mod tests {
         // should panic on the 53rd card, as the deck will be empty
     }
 
+    #[test]
+    fn deal_empty_deck_errors() {
+        let mut deck = Deck::new();
+        for _ in 0..52 {
+            deck.deal(false).unwrap();
+        }
+        assert_eq!(deck.remaining(), 0);
+        assert_eq!(deck.deal(false), Err(DeckError::Empty));
+        assert_eq!(deck.remaining(), 0);
+    }
+
+    #[test]
+    fn cut_moves_card_at_position_to_top() {
+        let mut deck = Deck::new();
+        let original_order = deck.cards.clone();
+        deck.cut(10);
+        assert_eq!(deck.size(), 52);
+        assert_eq!(deck.cards[0], original_order[10]);
+        assert_eq!(deck.cards[51], original_order[9]);
+    }
+
+    #[test]
+    fn cut_wraps_around() {
+        let mut deck = Deck::new();
+        let original_order = deck.cards.clone();
+        deck.cut(52 + 3);
+        assert_eq!(deck.cards[0], original_order[3]);
+        deck.cut_random();
+        assert_eq!(deck.size(), 52);
+    }
+
+    #[test]
+    fn cut_random_empty_deck() {
+        let mut deck = Deck::new();
+        while deck.deal(false).is_ok() {}
+        deck.cut_random();
+        assert_eq!(deck.size(), 0);
+    }
+
+    #[test]
+    fn peek_does_not_deal() {
+        let mut deck = Deck::new();
+        let next_cards: Vec<Card> = deck.peek(3).to_vec();
+        assert_eq!(next_cards.len(), 3);
+        assert_eq!(deck.size(), 52);
+        assert_eq!(deck.peek_all().len(), 52);
+        assert_eq!(&deck.peek_all()[..3], &next_cards[..]);
+        for next_card in next_cards {
+            assert_eq!(deck.deal(false).unwrap(), next_card);
+        }
+        assert_eq!(deck.peek(100).len(), 49);
+    }
+
+    #[test]
+    fn returned_cards_go_to_bottom() {
+        let mut deck = Deck::new();
+        let card = deck.deal(false).unwrap();
+        deck.return_card(card.clone());
+        assert_eq!(deck.peek_all().last(), Some(&card));
+    }
+
+    #[test]
+    fn shuffle_keeps_cards() {
+        let mut deck = Deck::new();
+        deck.shuffle();
+        assert_eq!(deck.size(), 52);
+        for card in Deck::new().peek_all() {
+            assert!(deck.peek_all().contains(card));
+        }
+    }
+
     #[test]
     fn deal_face_up() {
         let mut deck = Deck::new();
@@ -150,4 +369,35 @@ mod tests {
         let card = deck.deal(false).unwrap();
         assert!(!card.is_face_up());
     }
+
+    #[test]
+    fn assert_complete_after_returning_cards() {
+        let mut deck = Deck::new_short();
+        let cards: Vec<Card> = (0..5).map(|_| deck.deal(false).unwrap()).collect();
+        for card in cards {
+            deck.return_card(card);
+        }
+        deck.assert_complete();
+    }
+
+    #[test]
+    #[should_panic(expected = "Cards are missing from the Deck")]
+    fn assert_complete_lost_card() {
+        let mut deck = Deck::new();
+        // the card is never returned
+        let _lost_card = deck.deal(false).unwrap();
+        deck.assert_complete();
+    }
+
+    #[test]
+    #[should_panic(expected = "Cards are missing from the Deck")]
+    fn assert_complete_lost_card_replaced_by_duplicate() {
+        let mut deck = Deck::new();
+        let _lost_card = deck.deal(false).unwrap();
+        // the right number of cards, but one of them is in the deck twice
+        let duplicate = deck.peek(1)[0].clone();
+        deck.cards.push(duplicate);
+        assert_eq!(deck.size(), DECK_SIZE);
+        deck.assert_complete();
+    }
 }
@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::hand_rank::{Hand, HandRank};
+
+/// Equity struct
+///
+/// The chances of a hand winning, tying (splitting the pot) and losing at the showdown,
+/// each between 0 and 1, and adding up to 1 (see monte_carlo).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+/// estimate the equity of a Texas Hold'em hand by playing out random runouts against random opponent hands.
+/// For each of the iterations, every opponent is dealt 2 hole cards and the community cards are dealt up to 5,
+/// from a shuffled deck without the hole cards and community cards that are already known.
+/// The hand wins if it beats every opponent's hand, and ties if it is not beaten but at least one opponent has an equal hand.
+///
+/// With no iterations, there is nothing to estimate from, and all three chances are 0.
+///
+/// panics if there are not enough cards left in the deck to deal every opponent and the rest of the community cards
+/// Example:
+/// ```
+/// use poker_project_rustaceans::card::Card;
+/// use poker_project_rustaceans::equity;
+/// let hole_cards: Vec<Card> = vec!["As".parse().unwrap(), "Ks".parse().unwrap()];
+/// let community_cards: Vec<Card> = vec!["Qs".parse().unwrap(), "Js".parse().unwrap(), "Ts".parse().unwrap()];
+/// // a royal flush can't be beaten
+/// assert_eq!(equity::monte_carlo(&hole_cards, &community_cards, 3, 100).win, 1.0);
+/// ```
+pub fn monte_carlo(hole_cards: &[Card], community_cards: &[Card], opponents: usize, iterations: u32) -> Equity {
+    if iterations == 0 {
+        return Equity { win: 0.0, tie: 0.0, lose: 0.0 };
+    }
+
+    // take the known cards out of the deck, so they can't be dealt again
+    let mut deck = remaining_deck(|card| !hole_cards.contains(card) && !community_cards.contains(card));
+
+    let (mut wins, mut ties) = (0, 0);
+    for _ in 0..iterations {
+        deck.shuffle();
+        let mut dealt_cards = Vec::new();
+        let opponent_hole_cards: Vec<Vec<Card>> = (0..opponents).map(|_| deal_from(&mut deck, 2, &mut dealt_cards)).collect();
+        let runout = board_runout(community_cards, 5, &mut deck, &mut dealt_cards);
+
+        let hand_rank = best_hand_rank(hole_cards, &runout);
+        let best_opponent_rank = opponent_hole_cards.iter()
+            .map(|opponent_cards| best_hand_rank(opponent_cards, &runout))
+            .max();
+        match best_opponent_rank.map(|best_opponent_rank| hand_rank.cmp(&best_opponent_rank)) {
+            None | Some(Ordering::Greater) => wins += 1,
+            Some(Ordering::Equal) => ties += 1,
+            Some(Ordering::Less) => (),
+        }
+
+        for card in dealt_cards {
+            deck.return_card(card);
+        }
+    }
+
+    let win = wins as f64 / iterations as f64;
+    let tie = ties as f64 / iterations as f64;
+    return Equity { win, tie, lose: 1.0 - win - tie };
+}
+
+/// estimate how often each of two known hands wins heads-up, by dealing the rest of the community cards
+/// at random from deck_remaining (the cards not in either hand or the community cards), iterations times.
+/// The community cards are dealt up to 5, unless there are none and the hands already have 5 or more cards,
+/// as in draw games, where the hands are compared as they are.
+/// returns (the chance hand_a wins, the chance hand_b wins, the chance they tie), which are all 0 with no iterations.
+///
+/// panics if a card in deck_remaining is also in either hand or the community cards,
+/// or if there are not enough cards remaining to deal the rest of the community cards
+/// Example:
+/// ```
+/// use poker_project_rustaceans::card::Card;
+/// use poker_project_rustaceans::equity;
+/// let cards = |notation: &str| -> Vec<Card> { notation.split_whitespace().map(|card| card.parse().unwrap()).collect() };
+/// // on the river there is nothing left to deal, the flush beats the straight every time
+/// let equity = equity::equity_vs(&cards("Ah 2h"), &cards("Jc Tc"), &cards("Kh Qd 9s 7h 3h"), &cards("4c 5c 6c"), 10);
+/// assert_eq!(equity, (1.0, 0.0, 0.0));
+/// ```
+pub fn equity_vs(hand_a: &[Card], hand_b: &[Card], community: &[Card], deck_remaining: &[Card], iterations: u32) -> (f64, f64, f64) {
+    if let Some(card) = deck_remaining.iter().find(|card| hand_a.contains(card) || hand_b.contains(card) || community.contains(card)) {
+        panic!("{} is in the remaining cards, but it is already in a hand or the community cards", card);
+    }
+    if iterations == 0 {
+        return (0.0, 0.0, 0.0);
+    }
+    let board_size = match community.is_empty() && hand_a.len() >= 5 {
+        true => 0,
+        false => 5,
+    };
+
+    let mut deck = remaining_deck(|card| deck_remaining.contains(card));
+    let (mut a_wins, mut b_wins, mut ties) = (0, 0, 0);
+    for _ in 0..iterations {
+        deck.shuffle();
+        let mut dealt_cards = Vec::new();
+        let runout = board_runout(community, board_size, &mut deck, &mut dealt_cards);
+        match best_hand_rank(hand_a, &runout).cmp(&best_hand_rank(hand_b, &runout)) {
+            Ordering::Greater => a_wins += 1,
+            Ordering::Less => b_wins += 1,
+            Ordering::Equal => ties += 1,
+        }
+
+        for card in dealt_cards {
+            deck.return_card(card);
+        }
+    }
+    let iterations = iterations as f64;
+    return (a_wins as f64 / iterations, b_wins as f64 / iterations, ties as f64 / iterations);
+}
+
+/// a deck of only the cards that are still remaining, the others are left out so they can't be dealt
+fn remaining_deck(is_remaining: impl Fn(&Card) -> bool) -> Deck {
+    let mut deck = Deck::new();
+    let mut remaining_cards = Vec::new();
+    while let Ok(card) = deck.deal(false) {
+        if is_remaining(&card) {
+            remaining_cards.push(card);
+        }
+    }
+    for card in remaining_cards {
+        deck.return_card(card);
+    }
+    return deck;
+}
+
+/// deal count cards from the deck, keeping track of them in dealt_cards so they can be returned afterwards
+/// panics if there are not enough cards left in the deck
+fn deal_from(deck: &mut Deck, count: usize, dealt_cards: &mut Vec<Card>) -> Vec<Card> {
+    let cards: Vec<Card> = (0..count)
+        .map(|_| deck.deal(false).expect("there are not enough cards in the deck to deal every hand and the community cards"))
+        .collect();
+    dealt_cards.extend(cards.iter().cloned());
+    return cards;
+}
+
+/// the community cards, with the rest dealt from the deck up to board_size
+fn board_runout(community_cards: &[Card], board_size: usize, deck: &mut Deck, dealt_cards: &mut Vec<Card>) -> Vec<Card> {
+    let mut runout = community_cards.to_vec();
+    runout.extend(deal_from(deck, board_size.saturating_sub(community_cards.len()), dealt_cards));
+    return runout;
+}
+
+/// the best hand that can be made from the hole cards and the community cards, as it is ranked at the showdown
+fn best_hand_rank(hole_cards: &[Card], community_cards: &[Card]) -> HandRank {
+    let mut cards = hole_cards.to_vec();
+    cards.extend(community_cards.iter().cloned());
+    return Hand::rank_hand(&Hand::best_five(&cards));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::cards;
+
+    #[test]
+    fn aces_against_random_hand() {
+        let equity = monte_carlo(&cards("Ah As"), &[], 1, 5000);
+        assert!((equity.win - 0.85).abs() < 0.03, "pocket aces won {} of the time", equity.win);
+        assert!((equity.win + equity.tie + equity.lose - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn known_cards_not_dealt() {
+        // four aces can't be beaten or tied on this board, unless the aces are dealt to an opponent again
+        let equity = monte_carlo(&cards("Ah Ad"), &cards("As Ac Kd 7s 2c"), 20, 200);
+        assert_eq!(equity, Equity { win: 1.0, tie: 0.0, lose: 0.0 });
+    }
+
+    #[test]
+    fn no_iterations() {
+        assert_eq!(monte_carlo(&cards("Ah As"), &[], 1, 0), Equity { win: 0.0, tie: 0.0, lose: 0.0 });
+    }
+
+    /// every card in a new deck, except the taken cards
+    fn remaining_cards(taken_cards: &[Card]) -> Vec<Card> {
+        return Deck::new().peek_all().iter()
+            .filter(|card| !taken_cards.contains(card))
+            .cloned()
+            .collect();
+    }
+
+    #[test]
+    fn equity_vs_aces_against_kings() {
+        let (aces, kings) = (cards("Ah As"), cards("Kd Kc"));
+        let deck_remaining = remaining_cards(&[aces.clone(), kings.clone()].concat());
+        let (aces_win, kings_win, tie) = equity_vs(&aces, &kings, &[], &deck_remaining, 5000);
+        // aces are about a 82% to 18% favourite, with a small chance of splitting the pot
+        assert!((aces_win - 0.82).abs() < 0.03, "aces won {} of the time", aces_win);
+        assert!((kings_win - 0.18).abs() < 0.03, "kings won {} of the time", kings_win);
+        assert!(tie < 0.02);
+        assert!((aces_win + kings_win + tie - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn equity_vs_on_the_turn() {
+        let (set, flush_draw) = (cards("8s 8d"), cards("Ah 5h"));
+        let community = cards("8c Kh 7h 2s");
+        let deck_remaining = remaining_cards(&[set.clone(), flush_draw.clone(), community.clone()].concat());
+        assert_eq!(deck_remaining.len(), 44);
+        let (set_wins, flush_wins, tie) = equity_vs(&set, &flush_draw, &community, &deck_remaining, 4000);
+        // 9 hearts are left, but the 2h fills the set up to a full house and the 8h makes quads
+        assert!((flush_wins - 7.0 / 44.0).abs() < 0.03, "the flush draw won {} of the time", flush_wins);
+        assert!((set_wins - 37.0 / 44.0).abs() < 0.03, "the set won {} of the time", set_wins);
+        assert_eq!(tie, 0.0);
+    }
+
+    #[test]
+    fn equity_vs_draw_game() {
+        // five card hands without community cards are compared as they are
+        let (flush, straight) = (cards("2h 5h 9h Jh Kh"), cards("4c 5d 6s 7c 8d"));
+        let deck_remaining = remaining_cards(&[flush.clone(), straight.clone()].concat());
+        assert_eq!(equity_vs(&flush, &straight, &[], &deck_remaining, 100), (1.0, 0.0, 0.0));
+        assert_eq!(equity_vs(&straight, &straight, &[], &deck_remaining, 100), (0.0, 0.0, 1.0));
+        assert_eq!(equity_vs(&flush, &straight, &[], &deck_remaining, 0), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "is in the remaining cards")]
+    fn equity_vs_overlapping_cards() {
+        let (aces, kings) = (cards("Ah As"), cards("Kd Kc"));
+        // the aces are left in the remaining cards, so they could be dealt to the board again
+        let deck_remaining = remaining_cards(&kings);
+        equity_vs(&aces, &kings, &[], &deck_remaining, 10);
+    }
+}
@@ -1,44 +1,269 @@
 use uuid::Uuid;
+use std::collections::HashMap;
+use std::time::Instant;
 use std::vec::Vec;
-use crate::{database::db_handler::DbHandler, player::Player, rules::Rules};
+use crate::{database::db_handler::DbHandler, game_event::GameEvent, player::Player, rules::{BlindSchedule, Kill, KillNotSupported, Rules, SitOutMode}};
 
 
 pub struct Game<T: Rules> {
     players: Vec<Player>,
     rules: T,
     minimum_bet: u32,
+    min_buy_in: usize,
+    max_buy_in: usize,
+    /// the balance that every player must join with, None to allow any balance within the buy-in limits
+    starting_balance: Option<usize>,
+    sit_out_mode: SitOutMode,
+    current_round: u32,
+    blind_schedule: Option<BlindSchedule>,
+    /// when the first round of the game started, for blind schedules with timed levels
+    start_time: Option<Instant>,
+    /// everything that has happened in the rounds played so far, see take_log
+    observer_log: Vec<GameEvent>,
 }
 
 
 impl<T: Rules> Game<T> {
     /// create a new game with the rules set by the generic parameter
-    pub fn new(raise_limit: u32, minimum_bet: u32, db_handler: DbHandler) -> Game<T> {
+    pub fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler) -> Game<T> {
         let game_id = Uuid::now_v7();
         let players = Vec::new();
         return Game {
             players,
-            rules: T::new(raise_limit, minimum_bet, db_handler, game_id),
-            minimum_bet
+            rules: T::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id),
+            minimum_bet,
+            min_buy_in: 0,
+            max_buy_in: usize::MAX,
+            starting_balance: None,
+            sit_out_mode: SitOutMode::SkipHand,
+            current_round: 0,
+            blind_schedule: None,
+            start_time: None,
+            observer_log: Vec::new(),
         };
     }
 
+    /// set the smallest and largest balance that players can bring to this game,
+    /// returns Err(message) if the minimum is more than the maximum
+    pub fn set_buy_in_limits(&mut self, min_buy_in: usize, max_buy_in: usize) -> Result<(), String> {
+        if min_buy_in > max_buy_in {
+            return Err(format!("The minimum buy-in of {min_buy_in} is more than the maximum buy-in of {max_buy_in}"));
+        }
+        self.min_buy_in = min_buy_in;
+        self.max_buy_in = max_buy_in;
+        return Ok(());
+    }
+
+    /// set the balance that every player must join this game with, so that all players start even
+    pub fn set_starting_balance(&mut self, amount: usize) {
+        self.starting_balance = Some(amount);
+    }
+
+    /// set whether players who are sitting out are skipped entirely,
+    /// or dealt in to pay their blinds and antes before automatically folding
+    pub fn set_sit_out_mode(&mut self, sit_out_mode: SitOutMode) {
+        self.sit_out_mode = sit_out_mode;
+    }
+
+    /// get how players who are sitting out are treated
+    pub fn sit_out_mode(&self) -> SitOutMode {
+        return self.sit_out_mode;
+    }
+
+    /// set the house rules for kill pots, or None to play without kills.
+    /// the rules keep track of who won the last kill pot between rounds,
+    /// so the winner posts the kill blind in the next round they are dealt into
+    pub fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        return self.rules.set_kill(kill);
+    }
+
+    /// set a schedule of increasing big blinds for tournament play, or None to keep the same blinds for every round.
+    /// the big blind is looked up from the schedule at the start of each round, by the round number,
+    /// or for timed levels, by the time since the first round started
+    pub fn set_blind_schedule(&mut self, blind_schedule: Option<BlindSchedule>) {
+        self.blind_schedule = blind_schedule;
+    }
+
+    /// set the value of the smallest chip, so that every bet is a whole number of chips (see Rules::set_chip_size)
+    pub fn set_chip_size(&mut self, chip_size: u32) {
+        self.rules.set_chip_size(chip_size);
+    }
+
+    /// cap the number of raises in each betting round, as in limit games (see Rules::set_max_raises_per_round)
+    pub fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.rules.set_max_raises_per_round(max_raises);
+    }
+
+    /// get the history of the last round played in this game as JSON, for reviewing hands after a session,
+    /// returns None if no round has been played yet
+    pub fn export_last_round_json(&self) -> Option<String> {
+        return self.rules.export_last_round_json();
+    }
+
+    /// get the number of the round that was played last in this game, starting from 1,
+    /// returns 0 if no round has been played yet
+    pub fn current_round_number(&self) -> u32 {
+        return self.current_round;
+    }
+
+    /// take the events of the rounds played since the log was last taken, in the order they happened:
+    /// a RoundStarted, then an ActionTaken for each turn, then a RoundEnded once the pot is divided.
+    /// rounds that are aborted are only logged as started
+    pub fn take_log(&mut self) -> Vec<GameEvent> {
+        return self.observer_log.drain(..).collect();
+    }
+
+    /// toggle whether a player is sitting out, keeping their seat and balance while they don't play.
+    /// returns Ok(true) if the player is now sitting out, Ok(false) if they are now playing,
+    /// and Err(message) if the player is not in the game
+    pub fn toggle_sit_out(&mut self, player_id: Uuid) -> Result<bool, String> {
+        return match self.find_player_by_id(player_id) {
+            Ok(i) => {
+                let player = &mut self.players[i];
+                player.set_sit_out(!player.is_sitting_out());
+                Ok(player.is_sitting_out())
+            },
+            Err(_) => Err("Could not find a player in the game with that ID.".to_string()),
+        };
+    }
+
+    /// change the name of a player in the game, names cannot be blank,
+    /// returns Err(message) if the name is blank or the player is not in the game
+    pub fn rename_player(&mut self, player_id: Uuid, new_name: String) -> Result<(), String> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err("Player names cannot be blank.".to_string());
+        }
+        return match self.find_player_by_id(player_id) {
+            Ok(i) => {
+                self.players[i].rename(new_name.to_string());
+                Ok(())
+            },
+            Err(_) => Err("Could not find a player in the game with that ID.".to_string()),
+        };
+    }
+
+    /// true if enough players (but not too many) would be dealt into the next round for it to be played,
+    /// players who can't afford the minimum bet, and players sitting out who are skipped, aren't dealt in
+    pub fn can_start(&self) -> bool {
+        let player_count = self.players.iter()
+            .filter(|player| player.balance() >= self.minimum_bet as usize)
+            .filter(|player| !(self.sit_out_mode == SitOutMode::SkipHand && player.is_sitting_out()))
+            .count();
+        return T::valid_player_count(player_count);
+    }
+
     /// play a round of the game using the rules defined by the generic parameter
     pub async fn play_game(&mut self) {
+        // the blinds go up before deciding who can afford to be dealt in
+        let time_played = self.start_time.map(|start_time| start_time.elapsed()).unwrap_or_default();
+        if let Some(big_blind) = self.blind_schedule.as_ref().and_then(|blind_schedule| blind_schedule.big_blind_for_round(self.current_round + 1, time_played)) {
+            self.set_minimum_bet(big_blind);
+        }
         let mut player_indices_to_remove: Vec<usize> = self.players.iter().enumerate().filter(|(_, player)| player.balance() < self.minimum_bet as usize).map(|(player_index, _)| player_index).collect();
         player_indices_to_remove.reverse();
         player_indices_to_remove.iter().for_each(|player_index| {self.players.remove(*player_index);});
 
+        // players who are sitting out keep their seats, but aren't dealt in unless they have to post blinds and antes
+        let mut sitting_out_players: Vec<(usize, Player)> = Vec::new();
+        if self.sit_out_mode == SitOutMode::SkipHand {
+            let mut player_index = 0;
+            for player in self.players.drain(..).collect::<Vec<Player>>() {
+                if player.is_sitting_out() {
+                    sitting_out_players.push((player_index, player));
+                }
+                else {
+                    self.players.push(player);
+                }
+                player_index += 1;
+            }
+        }
+
         if self.players.len() > 0 {
+            self.start_time.get_or_insert_with(Instant::now);
+            self.current_round += 1;
+            self.rules.set_round_number(self.current_round);
+            self.observer_log.push(GameEvent::RoundStarted { round_number: self.current_round });
             match self.rules.play_round(self.players.drain(..).collect()).await {
-                Ok(players) => self.players = players,
+                Ok(players) => {
+                    self.players = players;
+                    self.observer_log.extend(self.rules.last_round_events());
+                },
                 Err((err, players)) => {
                     println!("Error: {err}");
                     self.players = players;
                 },
             };
+
+            // players who lost all of their money can't play any more rounds
+            let (bankrupt_players, players): (Vec<Player>, Vec<Player>) = self.players.drain(..).partition(|player| player.is_bankrupt());
+            self.players = players;
+            for bankrupt_player in bankrupt_players.iter() {
+                self.rules.announce_player_eliminated(bankrupt_player);
+            }
         } else {
             println!("Not enough players to start a game!");
         }
+
+        for (player_index, player) in sitting_out_players {
+            self.players.insert(player_index.min(self.players.len()), player);
+        }
+    }
+
+    /// play rounds one after another as a cash game, until continue_playing returns false
+    /// (it is asked with the players after every round), or until there aren't enough players to start a round.
+    /// players keep their balances from one round to the next, and the rules move the dealer button on each round.
+    /// returns the number of rounds played
+    pub async fn play_cash_game<F: FnMut(&[&Player]) -> bool>(&mut self, mut continue_playing: F) -> u32 {
+        let first_round = self.current_round;
+        while self.can_start() {
+            self.play_game().await;
+            if !continue_playing(&self.players()) {
+                break;
+            }
+        }
+        return self.current_round - first_round;
+    }
+
+    /// play rounds until one player holds all of the chips, with the blinds going up on the game's blind schedule (see set_blind_schedule).
+    /// players who run out of chips are eliminated, and players who are sitting out post their blinds and fold.
+    /// the dealer button moves on each round, skipping over eliminated players.
+    /// returns the players' ids in finishing order, starting with the winner,
+    /// players eliminated in the same round finish in order of the chips they started that round with
+    pub async fn play_tournament(&mut self) -> Vec<Uuid> {
+        // nobody can sit out without posting their blinds
+        let sit_out_mode = self.sit_out_mode;
+        self.sit_out_mode = SitOutMode::PostAndFold;
+
+        // players in the reverse of their finishing order
+        let mut eliminated_player_ids: Vec<Uuid> = Vec::new();
+        while self.players.len() > 1 {
+            let starting_balances: HashMap<Uuid, usize> = self.players.iter().map(|player| (player.account_id(), player.balance())).collect();
+            let round_number = self.current_round;
+            self.play_game().await;
+            if self.current_round == round_number {
+                // no round could be played, so the tournament can't go on
+                break;
+            }
+
+            let mut eliminated_this_round: Vec<(Uuid, usize)> = starting_balances.into_iter()
+                .filter(|(player_id, _)| self.find_player_by_id(*player_id).is_err())
+                .collect();
+            eliminated_this_round.sort_by_key(|&(_, starting_balance)| starting_balance);
+            eliminated_player_ids.extend(eliminated_this_round.into_iter().map(|(player_id, _)| player_id));
+        }
+
+        self.sit_out_mode = sit_out_mode;
+
+        let mut finishing_order: Vec<Uuid> = self.players.iter().map(|player| player.account_id()).collect();
+        finishing_order.extend(eliminated_player_ids.into_iter().rev());
+        return finishing_order;
+    }
+
+    /// set the minimum bet for the following rounds, both in the rules and for deciding which players can afford to be dealt in
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.minimum_bet = amount;
+        self.rules.set_minimum_bet(amount);
     }
 
     /// find whether a player is in this game or not.
@@ -54,8 +279,17 @@ impl<T: Rules> Game<T> {
 
     /// add a player to this game.
     /// returns Ok(()) if the player was successfully added,
-    /// and Err(message) if the player is already in this game
+    /// and Err(message) if the player is already in this game, their balance is outside of the buy-in limits,
+    /// or their balance is not the starting balance (see set_starting_balance)
     pub fn add_player(&mut self, new_player: Player) -> Result<(), String> {
+        if new_player.balance() < self.min_buy_in || new_player.balance() > self.max_buy_in {
+            return Err(format!("{}'s balance of {} is outside of the buy-in limits of {} to {}", new_player.name(), new_player.balance(), self.min_buy_in, self.max_buy_in));
+        }
+        if let Some(starting_balance) = self.starting_balance {
+            if new_player.balance() != starting_balance {
+                return Err(format!("{}'s balance of {} is not the starting balance of {}", new_player.name(), new_player.balance(), starting_balance));
+            }
+        }
         let player_index = self.find_player_by_id(new_player.account_id());
         return match player_index {
             Ok(_) => Err("Player already in players for this game".to_string()),
@@ -66,6 +300,19 @@ impl<T: Rules> Game<T> {
         }
     }
 
+    /// add a computer controlled player to this game, named after the number of bots in the game.
+    /// returns Ok(()) if the bot was successfully added
+    pub fn add_bot(&mut self, balance: usize) -> Result<(), String> {
+        let bot_number = self.players.iter().filter(|player| player.is_bot()).count() + 1;
+        return self.add_named_bot(&format!("Bot {bot_number}"), balance);
+    }
+
+    /// add a computer controlled player with a specific name to this game.
+    /// returns Ok(()) if the bot was successfully added
+    pub fn add_named_bot(&mut self, name: &str, balance: usize) -> Result<(), String> {
+        return self.add_player(Player::new_bot(Uuid::now_v7(), name.to_string(), balance));
+    }
+
     /// remove a player from this game.
     /// returns Ok(()) if the player was successfully removed,
     /// and Err(message) if the player was not in the game in the first place
@@ -85,3 +332,373 @@ impl<T: Rules> Game<T> {
         return self.players.iter().collect();
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::time::Duration;
+
+    use crate::action::Action;
+    use crate::input::{bot_input::BotInput, test_input::TestInput};
+    use crate::rules::{BlindLevelLength, KillType, RoundError};
+    use crate::rules::five_card_draw::FiveCardDraw;
+
+    use super::*;
+
+    /// rules where the first player wins 100 from every other player each round,
+    /// keeping track of who played in each round and who was eliminated
+    struct TestRules {
+        rounds: Vec<Vec<Uuid>>,
+        eliminated_player_ids: RefCell<Vec<Uuid>>,
+        kill: Option<Kill>,
+        round_numbers: Vec<u32>,
+        minimum_bets: Vec<u32>,
+    }
+
+    impl Rules for TestRules {
+        const MAX_PLAYERS: usize = 4;
+
+        fn new(_raise_limit: u32, _minimum_bet: u32, _small_blind_amount: u32, _db_handler: DbHandler, _game_id: Uuid) -> TestRules {
+            return TestRules {
+                rounds: Vec::new(),
+                eliminated_player_ids: RefCell::new(Vec::new()),
+                kill: None,
+                round_numbers: Vec::new(),
+                minimum_bets: Vec::new(),
+            };
+        }
+
+        async fn play_round(&mut self, mut players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+            self.rounds.push(players.iter().map(|player| player.account_id()).collect());
+            let mut winnings = 0;
+            for player in players.iter_mut().skip(1) {
+                let amount = player.balance().min(100);
+                player.bet(amount).expect("Expected the amount to be covered by the player's balance, it is capped at it");
+                winnings += amount;
+            }
+            players[0].win(winnings);
+            return Ok(players);
+        }
+
+        fn announce_player_eliminated(&self, player: &Player) {
+            assert!(player.is_bankrupt());
+            self.eliminated_player_ids.borrow_mut().push(player.account_id());
+        }
+
+        fn deal_order_description() -> &'static str {
+            return "Test Rules: no cards, the first player wins every round";
+        }
+
+        fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+            self.kill = kill;
+            return Ok(());
+        }
+
+        fn export_last_round_json(&self) -> Option<String> {
+            return self.rounds.last().map(|round| format!("{:?}", round));
+        }
+
+        fn last_round_events(&self) -> Vec<GameEvent> {
+            return Vec::new();
+        }
+
+        fn set_round_number(&mut self, round_number: u32) {
+            self.round_numbers.push(round_number);
+        }
+
+        fn set_minimum_bet(&mut self, amount: u32) {
+            self.minimum_bets.push(amount);
+        }
+
+        fn set_chip_size(&mut self, _chip_size: u32) {}
+
+        fn set_max_raises_per_round(&mut self, _max_raises: Option<u32>) {}
+    }
+
+    #[tokio::test]
+    async fn play_game_eliminates_bankrupt_players() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "short stack".to_string(), 150)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "big stack".to_string(), 1000)).unwrap();
+        let short_stack_id = game.players()[1].account_id();
+
+        // the short stack has 50 left after the first round, and nothing after the second
+        game.play_game().await;
+        assert_eq!(game.players().len(), 3);
+        game.play_game().await;
+        assert_eq!(game.players().len(), 2);
+        assert!(game.find_player_by_id(short_stack_id).is_err());
+        assert_eq!(*game.rules.eliminated_player_ids.borrow(), vec![short_stack_id]);
+
+        game.play_game().await;
+        assert_eq!(game.rules.rounds.len(), 3);
+        assert!(game.rules.rounds[1].contains(&short_stack_id));
+        assert!(!game.rules.rounds[2].contains(&short_stack_id), "A bankrupt player should not play in later rounds!");
+        assert_eq!(game.rules.rounds[2].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn observer_log_records_full_round() {
+        let mut game = Game::<FiveCardDraw<BotInput<TestInput>>>::new(1000, 2, 1, DbHandler::new_dummy());
+        for _ in 0..3 {
+            game.add_bot(1000).unwrap();
+        }
+        let player_ids: Vec<Uuid> = game.players().iter().map(|player| player.account_id()).collect();
+
+        game.play_game().await;
+        let log = game.take_log();
+
+        assert_eq!(log.first(), Some(&GameEvent::RoundStarted { round_number: 1 }));
+        // the blinds are the first turns of the round
+        assert!(matches!(&log[1], GameEvent::ActionTaken { action: Action::Blind(1), phase: 0, .. }));
+        assert!(matches!(&log[2], GameEvent::ActionTaken { action: Action::Blind(2), phase: 0, .. }));
+        assert!(log[1..log.len()-1].iter().all(|event| match event {
+            GameEvent::ActionTaken { player_id, .. } => player_ids.contains(player_id),
+            _ => false,
+        }));
+        match log.last() {
+            Some(GameEvent::RoundEnded { winner_ids, pot_total }) => {
+                assert!(!winner_ids.is_empty());
+                assert!(*pot_total >= 3);
+                // no chips are lost when the pot is divided
+                let total_balance: usize = game.players().iter().map(|player| player.balance()).sum();
+                assert_eq!(total_balance, 3000);
+            },
+            event => panic!("Expected the round to end, but the last event was {:?}", event),
+        }
+        // the log is drained once it has been taken
+        assert!(game.take_log().is_empty());
+    }
+
+    #[test]
+    fn rename_player() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "Alice".to_string(), 1000)).unwrap();
+        let player_id = game.players()[0].account_id();
+        assert_eq!(game.rename_player(player_id, " Bob ".to_string()), Ok(()));
+        assert_eq!(game.players()[0].name(), "Bob");
+        assert!(game.rename_player(player_id, "   ".to_string()).is_err());
+        assert!(game.rename_player(Uuid::now_v7(), "Carol".to_string()).is_err());
+        assert_eq!(game.players()[0].name(), "Bob");
+    }
+
+    #[test]
+    fn add_player_with_starting_balance() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "before".to_string(), 700)).unwrap();
+        game.set_starting_balance(1000);
+        assert_eq!(
+            game.add_player(Player::new(Uuid::now_v7(), "short".to_string(), 999)),
+            Err("short's balance of 999 is not the starting balance of 1000".to_string())
+        );
+        assert!(game.add_player(Player::new(Uuid::now_v7(), "deep".to_string(), 1001)).is_err());
+        assert_eq!(game.add_player(Player::new(Uuid::now_v7(), "even".to_string(), 1000)), Ok(()));
+        assert!(game.add_bot(500).is_err());
+        assert_eq!(game.add_bot(1000), Ok(()));
+        assert_eq!(game.players().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn play_game_skips_sitting_out_players() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "sitting out".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "playing".to_string(), 1000)).unwrap();
+        let sitting_out_id = game.players()[1].account_id();
+        assert_eq!(game.toggle_sit_out(sitting_out_id), Ok(true));
+
+        game.play_game().await;
+        assert!(!game.rules.rounds[0].contains(&sitting_out_id));
+        // the player keeps their seat and balance while sitting out
+        assert_eq!(game.find_player_by_id(sitting_out_id), Ok(1));
+        assert_eq!(game.players()[1].balance(), 1000);
+
+        assert_eq!(game.toggle_sit_out(sitting_out_id), Ok(false));
+        game.play_game().await;
+        assert!(game.rules.rounds[1].contains(&sitting_out_id));
+
+        game.toggle_sit_out(sitting_out_id).unwrap();
+        game.set_sit_out_mode(SitOutMode::PostAndFold);
+        game.play_game().await;
+        assert!(game.rules.rounds[2].contains(&sitting_out_id));
+    }
+
+    #[test]
+    fn set_kill_configures_rules() {
+        let mut game = Game::<TestRules>::new(1000, 2, 1, DbHandler::new_dummy());
+        assert_eq!(game.rules.kill, None);
+        let kill = Kill { kill_type: KillType::HalfKill, qualifying_pot: 40 };
+        game.set_kill(Some(kill)).unwrap();
+        assert_eq!(game.rules.kill, Some(kill));
+        game.set_kill(None).unwrap();
+        assert_eq!(game.rules.kill, None);
+    }
+
+    #[test]
+    fn can_start() {
+        let mut game = Game::<TestRules>::new(1000, 10, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "player".to_string(), 1000)).unwrap();
+        assert!(!game.can_start());
+        let sitting_out_player = Player::new(Uuid::now_v7(), "sitting out".to_string(), 1000);
+        let sitting_out_player_id = sitting_out_player.account_id();
+        game.add_player(sitting_out_player).unwrap();
+        assert!(game.can_start());
+        game.toggle_sit_out(sitting_out_player_id).unwrap();
+        assert!(!game.can_start());
+        // players who are sitting out are dealt in when they post and fold
+        game.set_sit_out_mode(SitOutMode::PostAndFold);
+        assert!(game.can_start());
+        // a player who can't afford the minimum bet isn't dealt in
+        game.add_player(Player::new(Uuid::now_v7(), "short".to_string(), 5)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "player".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "player".to_string(), 1000)).unwrap();
+        assert!(game.can_start());
+        // more players than the rules allow
+        game.add_player(Player::new(Uuid::now_v7(), "player".to_string(), 1000)).unwrap();
+        assert!(!game.can_start());
+    }
+
+    #[tokio::test]
+    async fn export_last_round_json_after_round() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "loser".to_string(), 1000)).unwrap();
+        assert_eq!(game.export_last_round_json(), None);
+
+        game.play_game().await;
+        assert!(game.export_last_round_json().is_some());
+    }
+
+    #[tokio::test]
+    async fn play_game_numbers_rounds() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        assert_eq!(game.current_round_number(), 0);
+        // no round is played without players
+        game.play_game().await;
+        assert_eq!(game.current_round_number(), 0);
+
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "loser".to_string(), 1000)).unwrap();
+        game.play_game().await;
+        game.play_game().await;
+        assert_eq!(game.current_round_number(), 2);
+        assert_eq!(game.rules.round_numbers, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn play_cash_game_carries_stacks_over() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        for _ in 0..3 {
+            game.add_player(Player::new(Uuid::now_v7(), "player".to_string(), 1000)).unwrap();
+        }
+        let mut balances_after_each_round: Vec<Vec<usize>> = Vec::new();
+        let rounds_played = game.play_cash_game(|players| {
+            balances_after_each_round.push(players.iter().map(|player| player.balance()).collect());
+            return balances_after_each_round.len() < 3;
+        }).await;
+
+        // each round starts from the stacks the last one finished with
+        assert_eq!(rounds_played, 3);
+        assert_eq!(game.rules.round_numbers, vec![1, 2, 3]);
+        assert_eq!(balances_after_each_round, vec![
+            vec![1200, 900, 900],
+            vec![1400, 800, 800],
+            vec![1600, 700, 700],
+        ]);
+        assert_eq!(game.players().iter().map(|player| player.balance()).collect::<Vec<usize>>(), vec![1600, 700, 700]);
+    }
+
+    #[tokio::test]
+    async fn play_cash_game_until_players_run_out() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "loser".to_string(), 250)).unwrap();
+        // the loser is eliminated in the third round, leaving too few players for another
+        assert_eq!(game.play_cash_game(|_| true).await, 3);
+        assert_eq!(game.players().len(), 1);
+        assert_eq!(game.players()[0].balance(), 1250);
+    }
+
+    #[tokio::test]
+    async fn play_game_follows_blind_schedule() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.set_blind_schedule(Some(BlindSchedule::new(vec![(3, 8), (2, 4)])));
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 10000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "loser".to_string(), 10000)).unwrap();
+        for _ in 0..4 {
+            game.play_game().await;
+        }
+        // the first round is before the first level, so the blinds aren't changed
+        assert_eq!(game.rules.minimum_bets, vec![4, 8, 8]);
+    }
+
+    #[tokio::test]
+    async fn play_game_deals_in_players_who_can_afford_the_scheduled_blind() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.set_blind_schedule(Some(BlindSchedule::new(vec![(2, 1000)])));
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 10000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "loser".to_string(), 1000)).unwrap();
+        game.play_game().await;
+        // the loser has 900 left, which is less than the big blind of the second round
+        game.play_game().await;
+        assert_eq!(game.players().len(), 1);
+        assert!(!game.can_start());
+    }
+
+    #[tokio::test]
+    async fn play_tournament_finishing_order() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        let winner = Player::new(Uuid::now_v7(), "winner".to_string(), 1000);
+        let second = Player::new(Uuid::now_v7(), "second".to_string(), 250);
+        let third = Player::new(Uuid::now_v7(), "third".to_string(), 150);
+        let fourth = Player::new(Uuid::now_v7(), "fourth".to_string(), 50);
+        let expected_finishing_order = vec![winner.account_id(), second.account_id(), third.account_id(), fourth.account_id()];
+        for player in [winner, third, fourth, second] {
+            game.add_player(player).unwrap();
+        }
+        game.set_sit_out_mode(SitOutMode::SkipHand);
+
+        game.set_blind_schedule(Some(BlindSchedule::with_level_length(vec![2, 4, 8], BlindLevelLength::Rounds(1))));
+        let finishing_order = game.play_tournament().await;
+        assert_eq!(finishing_order, expected_finishing_order);
+        assert_eq!(game.players().len(), 1);
+        assert_eq!(game.players()[0].balance(), 1450);
+        // the fourth player is out after the first round, the third after the second, and the second after the third
+        assert_eq!(game.current_round_number(), 3);
+        assert_eq!(game.rules.minimum_bets, vec![2, 4, 8]);
+        assert_eq!(game.sit_out_mode(), SitOutMode::SkipHand);
+    }
+
+    #[tokio::test]
+    async fn play_tournament_stays_at_last_blind() {
+        let mut game = Game::<TestRules>::new(1000, 1, 0, DbHandler::new_dummy());
+        game.add_player(Player::new(Uuid::now_v7(), "winner".to_string(), 1000)).unwrap();
+        game.add_player(Player::new(Uuid::now_v7(), "loser".to_string(), 350)).unwrap();
+        game.set_blind_schedule(Some(BlindSchedule::with_level_length(vec![2, 4], BlindLevelLength::Rounds(2))));
+        game.play_tournament().await;
+        assert_eq!(game.rules.minimum_bets, vec![2, 2, 4, 4]);
+    }
+
+    #[test]
+    fn default_tournament_blind_schedule() {
+        let blind_schedule = BlindSchedule::default_tournament();
+        assert_eq!(&blind_schedule.levels()[..4], &[(1, 2), (11, 4), (21, 8), (31, 16)]);
+        assert_eq!(blind_schedule.big_blind_for_round(0, Duration::ZERO), None);
+        assert_eq!(blind_schedule.big_blind_for_round(1, Duration::ZERO), Some(2));
+        assert_eq!(blind_schedule.big_blind_for_round(10, Duration::ZERO), Some(2));
+        assert_eq!(blind_schedule.big_blind_for_round(11, Duration::ZERO), Some(4));
+        assert_eq!(blind_schedule.big_blind_for_round(1000, Duration::ZERO), blind_schedule.levels().last().map(|&(_, big_blind)| big_blind));
+    }
+
+    #[test]
+    fn timed_blind_schedule() {
+        let blind_schedule = BlindSchedule::with_level_length(vec![2, 4, 8], BlindLevelLength::Minutes(15));
+        assert_eq!(blind_schedule.levels(), &vec![(0, 2), (15, 4), (30, 8)]);
+        // the round number doesn't matter, only the time played
+        assert_eq!(blind_schedule.big_blind_for_round(100, Duration::from_secs(14 * 60 + 59)), Some(2));
+        assert_eq!(blind_schedule.big_blind_for_round(1, Duration::from_secs(15 * 60)), Some(4));
+        assert_eq!(blind_schedule.big_blind_for_round(1, Duration::from_secs(600 * 60)), Some(8));
+    }
+}
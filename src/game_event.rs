@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::action::Action;
+use crate::card::Card;
+
+/// Game Event enum
+///
+/// Discrete events that happen during a round, which the rules report to the Input
+/// (see Input::on_event) as they happen, so that a client can animate them,
+/// rather than only redrawing the table on each display call.
+///
+/// Events are serialized with lowercase tags, e.g. {"fold": {"player_id": "..."}}.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEvent {
+    /// a card was dealt to a player, the card is only included when it is dealt face up,
+    /// so that face down cards are never revealed to other players
+    CardDealt { player_id: Uuid, card: Option<Card> },
+    /// a community card was dealt face up, for everyone to use
+    CommunityCardDealt { card: Card },
+    /// a player moved chips into the pot, for a blind, ante, bet, call, raise or all in
+    Bet { player_id: Uuid, amount: usize },
+    /// a player folded
+    Fold { player_id: Uuid },
+    /// a player was awarded chips from the pot at the end of the round
+    PotAwarded { player_id: Uuid, amount: usize },
+    /// a player took back their last turn (see Input::request_undo_turn), before the next player acted
+    TurnUndone { player_id: Uuid, action: Action, phase: usize },
+    /// chips were given back to a player from the pot, when they took back a turn or the round was aborted
+    Refund { player_id: Uuid, amount: usize },
+    /// a new round of the game was started, rounds are numbered from 1
+    RoundStarted { round_number: u32 },
+    /// a player took a turn (including posting blinds and antes) in a phase of the round
+    ActionTaken { player_id: Uuid, action: Action, phase: usize },
+    /// the pot was divided between the winners at the end of the round
+    RoundEnded { winner_ids: Vec<Uuid>, pot_total: u32 },
+}
+
+impl GameEvent {
+    /// the event for a card dealt to a player, which only shows the card if it is face up
+    pub fn card_dealt(player_id: Uuid, card: &Card) -> GameEvent {
+        return GameEvent::CardDealt {
+            player_id,
+            card: match card.is_face_up() {
+                true => Some(card.clone()),
+                false => None,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+
+    #[test]
+    fn card_dealt_hides_face_down_cards() {
+        let player_id = Uuid::now_v7();
+        let face_down_card = Card::new(Rank::Ace, Suit::Spades);
+        assert_eq!(GameEvent::card_dealt(player_id, &face_down_card), GameEvent::CardDealt { player_id, card: None });
+        let face_up_card = Card::face_up(Rank::Ace, Suit::Spades);
+        assert_eq!(GameEvent::card_dealt(player_id, &face_up_card), GameEvent::CardDealt { player_id, card: Some(face_up_card) });
+    }
+
+    #[test]
+    fn serialize() {
+        let player_id = Uuid::now_v7();
+        let event = GameEvent::Bet { player_id, amount: 10 };
+        assert_eq!(serde_json::to_value(&event).unwrap(), serde_json::json!({"bet": {"player_id": player_id.to_string(), "amount": 10}}));
+    }
+}
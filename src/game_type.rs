@@ -11,6 +11,9 @@ pub enum GameType {
     FiveCardDraw,
     SevenCardStud,
     TexasHoldem,
+    CommunityDraw,
+    Badugi,
+    ShortDeckHoldem,
 }
 
 impl std::fmt::Display for GameType {
@@ -19,6 +22,9 @@ impl std::fmt::Display for GameType {
             GameType::FiveCardDraw => write!(f, "Five Card Draw"),
             GameType::SevenCardStud => write!(f, "Seven Card Stud"),
             GameType::TexasHoldem => write!(f, "Texas Hold'em"),
+            GameType::CommunityDraw => write!(f, "Community Card Draw"),
+            GameType::Badugi => write!(f, "Badugi"),
+            GameType::ShortDeckHoldem => write!(f, "Short Deck Hold'em"),
         }
     }
 }
@@ -1,5 +1,7 @@
 use crate::card::{Card, Rank, Suit};
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
 
 #[derive(Debug, PartialEq, Eq)]
 /// hand classification rankings, 
@@ -24,10 +26,13 @@ pub enum HandRank {
     FourOfAKind(Rank, Rank), // four of a kind rank plus second highest card
     StraightFlush(Rank),
     RoyalFlush,
+    FiveOfAKind(Rank), // only possible with wild cards (see Hand::rank_hand_with_wilds)
 }
 
 impl HandRank {
-    fn rank_value(&self) -> u8 {
+    /// the tier of the hand classification, from 1 for a high card up to 10 for a royal flush,
+    /// or 11 for five of a kind, which can only be made with wild cards
+    pub fn rank_value(&self) -> u8 {
         match self {
             HandRank::HighCard(_, _) => 1,
             HandRank::OnePair(_, _) => 2,
@@ -39,19 +44,29 @@ impl HandRank {
             HandRank::FourOfAKind(_, _) => 8,
             HandRank::StraightFlush(_) => 9,
             HandRank::RoyalFlush => 10,
+            HandRank::FiveOfAKind(_) => 11,
         }
     }
-}
 
-impl PartialOrd for HandRank {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// the tier of the hand classification in short deck poker, which is the same as rank_value,
+    /// except that a flush beats a full house, as flushes are harder to make with only nine cards of each suit
+    pub fn short_deck_rank_value(&self) -> u8 {
+        return match self {
+            HandRank::Flush(_, _) => 7,
+            HandRank::FullHouse(_, _) => 6,
+            _ => self.rank_value(),
+        };
     }
-}
 
-impl Ord for HandRank {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.rank_value().cmp(&other.rank_value()).then_with(|| match (self, other) {
+    /// compare two hand classifications with the short deck tiers (see short_deck_rank_value),
+    /// hands of the same classification are compared the same way as usual
+    pub fn cmp_short_deck(&self, other: &Self) -> Ordering {
+        return self.short_deck_rank_value().cmp(&other.short_deck_rank_value()).then_with(|| self.cmp_same_classification(other));
+    }
+
+    /// compare two hands of the same classification by their ranks and kickers
+    fn cmp_same_classification(&self, other: &Self) -> Ordering {
+        return match (self, other) {
             (HandRank::HighCard(a, kickers1), HandRank::HighCard(b, kickers2)) => a.cmp(b).then_with(|| kickers1.cmp(kickers2)),
             (HandRank::OnePair(a, kickers1), HandRank::OnePair(b, kickers2)) => a.cmp(b).then_with(|| kickers1.cmp(kickers2)),
             (HandRank::TwoPair(a1, a2, kickers1), HandRank::TwoPair(b1, b2, kickers2)) => (a1, a2).cmp(&(b1, b2)).then_with(|| kickers1.cmp(kickers2)),
@@ -62,8 +77,21 @@ impl Ord for HandRank {
             (HandRank::FourOfAKind(a, kickers1), HandRank::FourOfAKind(b, kickers2)) => a.cmp(b).then_with(|| kickers1.cmp(kickers2)),
             (HandRank::StraightFlush(a), HandRank::StraightFlush(b)) => a.cmp(b),
             (HandRank::RoyalFlush, HandRank::RoyalFlush) => Ordering::Equal,
+            (HandRank::FiveOfAKind(a), HandRank::FiveOfAKind(b)) => a.cmp(b),
             _ => Ordering::Equal,
-        })
+        };
+    }
+}
+
+impl PartialOrd for HandRank {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HandRank {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank_value().cmp(&other.rank_value()).then_with(|| self.cmp_same_classification(other))
     }
 }
 
@@ -88,6 +116,12 @@ impl Hand {
 
         sorted_cards.sort();
 
+        // five cards of the same rank can only be made by substituting wild cards,
+        // and no other hand beats it (even if the substitutes share a suit)
+        if let Some((rank, _)) = Self::count_num_ranks(&sorted_cards).into_iter().find(|&(_, count)| count >= 5) {
+            return HandRank::FiveOfAKind(rank);
+        }
+
         let is_flush = Self::is_flush(&sorted_cards);
         let is_straight = Self::is_straight(&sorted_cards);
         let is_straight_flush = Self::is_straight_flush(&sorted_cards);
@@ -192,20 +226,24 @@ impl Hand {
 
                 pairs.sort_by(|a, b| b.cmp(a));
 
+                if pairs.len() >=2 {
+                    // only the top two pairs are played, so a third pair is left over
+                    // and its rank can be the kicker if it beats the unpaired cards
+                    let kicker = rank_freqs.iter()
+                        .map(|(rank, _)| rank)
+                        .filter(|rank| **rank != pairs[0] && **rank != pairs[1])
+                        .max()
+                        .cloned()
+                        .unwrap_or(Rank::Two);
+                    return HandRank::TwoPair(pairs[0].clone(), pairs[1].clone(), kicker);
+                }
+
                 let kickers: Vec<Rank> = rank_freqs.iter()
                     .map(|(rank, _)| rank)
                     .filter(|rank| !pairs.contains(rank))
                     .take(3)
                     .cloned()
                     .collect();
-                
-                if pairs.len() >=2 {
-                    let mut kicker = &Rank::Two;
-                    if kickers.len() >= 1 {
-                        kicker = &kickers[0];
-                    }
-                    return HandRank::TwoPair(pairs[0].clone(), pairs[1].clone(), kicker.clone());
-                }
 
                 return HandRank::OnePair(pairs[0].clone(), kickers);
             }
@@ -217,6 +255,73 @@ impl Hand {
         };
     }
 
+    /// return the poker hand classified when the cards that match any of the wilds are wild,
+    /// each wild card is substituted by whichever card (of any rank and suit) makes the best hand,
+    /// a substitute can even be a card that is already in the hand, which is how five of a kind is made.
+    /// Every combination of substitutes is tried, so this gets much slower with each wild card in the hand
+    /// Example:
+    /// ```
+    /// use poker_project_rustaceans::card::{Card, Rank};
+    /// use poker_project_rustaceans::hand_rank::{Hand, HandRank};
+    /// let cards: Vec<Card> = ["Ks", "Kh", "Kd", "Kc", "2c"].iter().map(|card| card.parse().unwrap()).collect();
+    /// let joker: Card = "2c".parse().unwrap();
+    /// assert_eq!(Hand::rank_hand_with_wilds(&cards, &[joker]), HandRank::FiveOfAKind(Rank::King));
+    /// ```
+    pub fn rank_hand_with_wilds(cards: &[Card], wilds: &[Card]) -> HandRank {
+        let (wild_cards, mut natural_cards): (Vec<Card>, Vec<Card>) = cards.iter().cloned().partition(|card| wilds.contains(card));
+        if wild_cards.is_empty() {
+            return Self::rank_hand(cards);
+        }
+        let substitutes: Vec<Card> = Rank::iter()
+            .flat_map(|rank| Suit::iter().map(move |suit| Card::new(rank.clone(), suit)))
+            .collect();
+        return Self::best_substitution(&mut natural_cards, wild_cards.len(), &substitutes);
+    }
+
+    /// the best hand that can be made by adding wild_count of the substitutes to the cards (repeats allowed),
+    /// only substitutes from the first one tried onwards are tried for the rest of the wilds, as their order doesn't matter
+    fn best_substitution(cards: &mut Vec<Card>, wild_count: usize, substitutes: &[Card]) -> HandRank {
+        if wild_count == 0 {
+            return Self::rank_hand(cards);
+        }
+        let mut best_hand_rank: Option<HandRank> = None;
+        for (substitute_index, substitute) in substitutes.iter().enumerate() {
+            cards.push(substitute.clone());
+            let hand_rank = Self::best_substitution(cards, wild_count - 1, &substitutes[substitute_index..]);
+            cards.pop();
+            if best_hand_rank.as_ref().map_or(true, |best| hand_rank > *best) {
+                best_hand_rank = Some(hand_rank);
+            }
+        }
+        return best_hand_rank.unwrap();
+    }
+
+    /// return the poker hand classified with short deck rules, where a flush beats a full house
+    /// (see HandRank::cmp_short_deck), and the ace also connects to the six, so A-6-7-8-9 is the lowest straight.
+    /// With more than five cards, the best five cards by the short deck rankings are classified
+    pub fn rank_short_deck_hand(cards: &[Card]) -> HandRank {
+        if cards.len() > 5 {
+            return Self::combinations(cards, 5).iter()
+                .map(|combination| Self::rank_short_deck_hand(combination))
+                .max_by(|left, right| left.cmp_short_deck(right))
+                .unwrap();
+        }
+        if !Self::is_short_deck_wheel(cards) {
+            return Self::rank_hand(cards);
+        }
+        return match Self::is_flush(cards) {
+            true => HandRank::StraightFlush(Rank::Nine),
+            false => HandRank::Straight(Rank::Nine),
+        };
+    }
+
+    /// true if the cards are an ace, six, seven, eight and nine, the lowest straight in short deck poker
+    pub fn is_short_deck_wheel(cards: &[Card]) -> bool {
+        let mut ranks: Vec<Rank> = cards.iter().map(|card| card.rank().clone()).collect();
+        ranks.sort();
+        return ranks == vec![Rank::Six, Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ace];
+    }
+
     /// true if the poker hand is a flush
     pub fn is_flush(cards: &[Card]) -> bool {
         let suits: Vec<Suit> = cards.iter()
@@ -231,6 +336,24 @@ impl Hand {
         false
     }
 
+    /// group the cards by their suit, suits without any cards are left out
+    pub fn suit_distribution(cards: &[Card]) -> HashMap<Suit, Vec<&Card>> {
+        let mut distribution: HashMap<Suit, Vec<&Card>> = HashMap::new();
+        for card in cards {
+            distribution.entry(card.suit().clone()).or_default().push(card);
+        }
+        return distribution;
+    }
+
+    /// if the cards are four to a flush, returns the suit of the flush draw
+    /// and the number of outs (cards of that suit that could still be drawn) to complete it.
+    /// Returns None for anything else, including a completed flush
+    pub fn flush_draw_outs(cards: &[Card]) -> Option<(Suit, usize)> {
+        return Self::suit_distribution(cards).into_iter()
+            .find(|(_, suited_cards)| suited_cards.len() == 4)
+            .map(|(suit, suited_cards)| (suit, 13 - suited_cards.len()));
+    }
+
     /// true if the poker hand is a stright
     /// NOTE: the special case of an ace-low straight is checked
     pub fn is_straight(cards: &[Card]) -> bool {
@@ -272,6 +395,44 @@ impl Hand {
         return false;
     }
 
+    /// find the ranks that, if drawn, would complete a straight,
+    /// each rank is included once for every card of that rank that could still be drawn,
+    /// so the length is the number of outs:
+    /// 8 for an outside (open-ended) straight draw, and 4 for an inside (gutshot) straight draw.
+    /// Returns no outs if the cards already make a straight
+    pub fn straight_draw_outs(cards: &[Card]) -> Vec<Rank> {
+        if Hand::is_straight(cards) {
+            return Vec::new();
+        }
+        let mut outs = Vec::new();
+        for rank in Rank::iter() {
+            let mut drawn_cards = cards.to_vec();
+            drawn_cards.push(Card::new(rank.clone(), Suit::Clubs));
+            if Hand::is_straight(&drawn_cards) {
+                let cards_held = cards.iter().filter(|card| *card.rank() == rank).count();
+                for _ in cards_held..4 {
+                    outs.push(rank.clone());
+                }
+            }
+        }
+        return outs;
+    }
+
+    /// find the cards remaining in the deck that, if drawn, would make the current cards
+    /// a hand classification of the target tier or better (see HandRank::rank_value),
+    /// e.g. every remaining card of the suit for a four-flush and a target tier of 6 (a flush).
+    /// If the current cards already make the target tier, every remaining card is an out
+    pub fn outs_to_rank(current: &[Card], target_rank_tier: u8, deck_remaining: &[Card]) -> Vec<Card> {
+        return deck_remaining.iter()
+            .filter(|card| {
+                let mut drawn_cards = current.to_vec();
+                drawn_cards.push((*card).clone());
+                Self::rank_hand(&drawn_cards).rank_value() >= target_rank_tier
+            })
+            .cloned()
+            .collect();
+    }
+
     /// necessary because hands may or may not have 5 cards
     /// true if the poker hand is a straight flush
     pub fn is_straight_flush(cards: &[Card]) -> bool {
@@ -357,6 +518,77 @@ impl Hand {
 
         freqs
     }
+
+    /// return the best poker hand classification that can be made using exactly two of the
+    /// hole cards and exactly three of the community cards, as per Omaha rules.
+    /// if there are not enough hole cards or community cards to do that
+    /// (e.g. the community cards were never dealt), all of the cards are ranked together instead
+    pub fn rank_omaha_hand(hole_cards: &[Card], community_cards: &[Card]) -> HandRank {
+        if hole_cards.len() < 2 || community_cards.len() < 3 {
+            let all_cards: Vec<Card> = hole_cards.iter().chain(community_cards.iter()).cloned().collect();
+            return Self::rank_hand(&all_cards);
+        }
+
+        let mut best_hand_rank: Option<HandRank> = None;
+        for hole_combination in Self::combinations(hole_cards, 2) {
+            for community_combination in Self::combinations(community_cards, 3) {
+                let mut cards = hole_combination.clone();
+                cards.extend(community_combination);
+                let hand_rank = Self::rank_hand(&cards);
+                best_hand_rank = match best_hand_rank {
+                    Some(best) if best >= hand_rank => Some(best),
+                    _ => Some(hand_rank),
+                };
+            }
+        }
+        return best_hand_rank.unwrap();
+    }
+
+    /// return the five cards that make up the best poker hand classification of the cards,
+    /// ordered from highest to lowest rank. This is the same classification as rank_hand gives
+    /// for those five cards, including the kickers.
+    /// if there are five or fewer cards, all of them are returned
+    pub fn best_five(cards: &[Card]) -> Vec<Card> {
+        let mut best_cards = cards.to_vec();
+        if cards.len() > 5 {
+            let mut best_hand_rank: Option<HandRank> = None;
+            for combination in Self::combinations(cards, 5) {
+                let hand_rank = Self::rank_hand(&combination);
+                if best_hand_rank.as_ref().map_or(true, |best| hand_rank > *best) {
+                    best_hand_rank = Some(hand_rank);
+                    best_cards = combination;
+                }
+            }
+        }
+        best_cards.sort_by(|a, b| b.cmp(a));
+        return best_cards;
+    }
+
+    /// returns every combination of size k that can be chosen from the cards
+    fn combinations(cards: &[Card], k: usize) -> Vec<Vec<Card>> {
+        if k == 0 {
+            return vec![Vec::new()];
+        }
+        if cards.len() < k {
+            return Vec::new();
+        }
+        let mut combinations = Vec::new();
+        for (card_index, card) in cards.iter().enumerate() {
+            for mut combination in Self::combinations(&cards[card_index+1..], k-1) {
+                combination.insert(0, card.clone());
+                combinations.push(combination);
+            }
+        }
+        return combinations;
+    }
+}
+
+/// Displays the cards in the Hand separated by spaces
+impl std::fmt::Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cards: Vec<String> = self.cards.iter().map(|card| card.to_string()).collect();
+        write!(f, "{}", cards.join(" "))
+    }
 }
 
 impl PartialOrd for Hand {
@@ -376,16 +608,20 @@ impl Ord for Hand {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::card::{Card, Rank, Suit};
+    use crate::card::{cards, Card, Rank, Suit};
+    use crate::deck::Deck;
+
+    #[test]
+    fn test_display() {
+        let mut hand_cards = cards("Ah Kd Tc");
+        hand_cards.iter_mut().take(2).for_each(|card| card.set_face_up(true));
+        assert_eq!(Hand::new(hand_cards).to_string(), "A♥ K♦ 10♣ [face down]");
+        assert_eq!(Hand::new(Vec::new()).to_string(), "");
+    }
+
     #[test]
     fn test_new() {
-        let cards = vec![
-            Card::new(Rank::King, Suit::Hearts, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Ace, Suit::Hearts, false),
-            Card::new(Rank::Queen, Suit::Hearts, false),
-        ];
+        let cards = cards("Kh Jh Th Ah Qh");
         let hand = Hand::new(cards.clone());
 
         assert_eq!(hand.cards.len(), 5);
@@ -394,703 +630,294 @@ mod tests {
 
     #[test]
     fn test_high_card() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Spades, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 4d 6c 8s Jh");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::HighCard(Rank::Jack, vec![Rank::Eight, Rank::Six, Rank::Four, Rank::Two]));
     }
 
     #[test]
     fn test_one_pair() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Spades, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 6d 6c 8s Jh");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::OnePair(Rank::Six, vec![Rank::Jack, Rank::Eight, Rank::Two]));
     }
 
     #[test]
     fn test_two_pair() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 6d 6c 2s Jh");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::TwoPair(Rank::Six, Rank::Two, Rank::Jack));
     }
 
+    #[test]
+    fn test_two_pair_third_pair_kicker() {
+        // the third pair of fives is left over, and beats the four as the kicker
+        let hand = cards("Kh Kd 9c 9s 5h 5d 4c");
+        let hand_rank = Hand::rank_hand(&hand);
+        assert_eq!(hand_rank, HandRank::TwoPair(Rank::King, Rank::Nine, Rank::Five));
+
+        // the unpaired queen still beats the third pair
+        let hand = cards("Kh Kd 9c 9s 5h 5d Qc");
+        let hand_rank = Hand::rank_hand(&hand);
+        assert_eq!(hand_rank, HandRank::TwoPair(Rank::King, Rank::Nine, Rank::Queen));
+    }
+
     #[test]
     fn test_three_of_a_kind() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 6d 6c 8s 6h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::ThreeOfAKind(Rank::Six, vec![Rank::Eight, Rank::Two]));
     }
 
     #[test]
     fn test_straight() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 3d 6c 5s 4h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::Straight(Rank::Six));
     }
 
     #[test]
     fn test_straight_w_ace() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Ace, Suit::Clubs, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 3d Ac 5s 4h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::Straight(Rank::Five));
     }
 
     #[test]
     fn test_flush() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Hearts, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 3h 6h 5h 7h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::Flush(Rank::Seven, vec![Rank::Six, Rank::Five, Rank::Three, Rank::Two]));
     }
 
     #[test]
     fn test_full_house() {
-        let hand = vec![
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Hearts, false),
-        ];
+        let hand = cards("8h 6d 6c 8s 6h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::FullHouse(Rank::Six, Rank::Eight));
     }
 
     #[test]
     fn test_four_of_a_kind() {
-        let hand = vec![
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Six, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Hearts, false),
-        ];
+        let hand = cards("8h 6d 6c 6s 6h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::FourOfAKind(Rank::Six, Rank::Eight));
     }
 
     #[test]
     fn test_straight_flush() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 3h 6h 5h 4h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::StraightFlush(Rank::Six));
     }
 
     #[test]
     fn test_straight_flush_w_ace() {
-        let hand = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Hearts, false),
-            Card::new(Rank::Ace, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-        ];
+        let hand = cards("2h 3h 5h Ah 4h");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::StraightFlush(Rank::Five));
     }
 
     #[test]
     fn test_royal_flush() {
-        let hand = vec![
-            Card::new(Rank::King, Suit::Hearts, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Ace, Suit::Hearts, false),
-            Card::new(Rank::Queen, Suit::Hearts, false),
-        ];
+        let hand = cards("Kh Jh Th Ah Qh");
         let hand_rank = Hand::rank_hand(&hand);
         assert_eq!(hand_rank, HandRank::RoyalFlush);
     }
 
     #[test]
     fn test_ordering() {
-        let cards1 = vec![
-            Card::new(Rank::King, Suit::Hearts, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Ace, Suit::Hearts, false),
-            Card::new(Rank::Queen, Suit::Hearts, false),
-        ];
+        let cards1 = cards("Kh Jh Th Ah Qh");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Jack, Suit::Hearts, false),
-        ];
+        let cards2 = cards("2h 6d 6c 2s Jh");
         let hand2 = Hand::new(cards2);
         assert!(hand1 > hand2);
     }
 
     #[test]
     fn test_ordering_one_card() {
-        let cards1 = vec![
-            Card::new(Rank::King, Suit::Spades, false)
-        ];
+        let cards1 = cards("Ks");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Three, Suit::Hearts, false)
-        ];
+        let cards2 = cards("3h");
         let hand2 = Hand::new(cards2);
         assert!(hand1 > hand2);
     }
 
     #[test]
     fn test_ordering_two_cards() {
-        let cards1 = vec![
-            Card::new(Rank::King, Suit::Spades, false),
-            Card::new(Rank::Queen, Suit::Spades, false)
-        ];
+        let cards1 = cards("Ks Qs");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Diamonds, false)
-        ];
+        let cards2 = cards("3h 3d");
         let hand2 = Hand::new(cards2);
         assert!(hand1 < hand2);
     }
 
     #[test]
     fn test_ordering_three_cards_high_card_to_one_pair() {
-        let high_card = Hand::new(vec![
-            Card::new(Rank::King, Suit::Spades, false),
-            Card::new(Rank::Queen, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Clubs, false)
-        ]);
-        let one_pair = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Clubs, false)
-        ]);
+        let high_card = Hand::new(cards("Ks Qs Ac"));
+        let one_pair = Hand::new(cards("3h 3d 2c"));
         assert!(high_card < one_pair);
     }
 
     #[test]
     fn test_ordering_three_cards_one_pair_to_three_of_a_kind() {
-        let one_pair = Hand::new(vec![
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Clubs, false)
-        ]);
-        let three_of_a_kind = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Three, Suit::Clubs, false)
-        ]);
+        let one_pair = Hand::new(cards("4h 4d 2c"));
+        let three_of_a_kind = Hand::new(cards("3s 3d 3c"));
         assert!(one_pair < three_of_a_kind);
     }
 
     #[test]
     fn test_ordering_three_cards_both_one_pair() {
-        let one_pair1 = Hand::new(vec![
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Diamonds, false),
-            Card::new(Rank::Three, Suit::Clubs, false)
-        ]);
-        let one_pair2 = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Clubs, false)
-        ]);
+        let one_pair1 = Hand::new(cards("4h 4d 3c"));
+        let one_pair2 = Hand::new(cards("3s 3d 4c"));
         assert!(one_pair1 > one_pair2);
     }
 
     #[test]
     fn test_ordering_seven_cards() {
-        let cards1 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ];
+        let cards1 = cards("As Ad 2s 4h 6c 8c Td");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Seven, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Spades, false)
-        ];
+        let cards2 = cards("As 2c 3d 4h 5s 7c Ts");
         let hand2 = Hand::new(cards2);
         assert!(hand1 < hand2);
     }
 
     #[test]
     fn test_ordering_seven_cards_2() {
-        let cards1 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ];
+        let cards1 = cards("As Ad 2s 4h 6c 8c Td");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Seven, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Hearts, false)
-        ];
+        let cards2 = cards("As 3d 4h 5s 7c Ts 6h");
         let hand2 = Hand::new(cards2);
         assert!(hand1 < hand2);
     }
 
     #[test]
     fn test_ordering_seven_cards_3() {
-        let cards1 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Diamonds, false),
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Hearts, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ];
+        let cards1 = cards("As Ad As Ah 6c 8c Td");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Seven, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Hearts, false)
-        ];
+        let cards2 = cards("As 3d 4h 5s 7c Ts 6h");
         let hand2 = Hand::new(cards2);
         assert!(hand1 > hand2);
     }
 
     #[test]
     fn test_ordering_seven_cards_4() {
-        let cards1 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Ace, Suit::Diamonds, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ];
+        let cards1 = cards("As As 2h 8c 2c Ad Td");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Seven, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Spades, false)
-        ];
+        let cards2 = cards("As 3s 4h 5s 7c Ts 6s");
         let hand2 = Hand::new(cards2);
         assert!(hand1 > hand2);
     }
 
     #[test]
     fn test_ordering_seven_cards_5() {
-        let cards1 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Ace, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ];
+        let cards1 = cards("As Ad 2h 4c Ac 8c Td");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Seven, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Spades, false)
-        ];
+        let cards2 = cards("As 3h 4h 5s 7c Ts 6s");
         let hand2 = Hand::new(cards2);
         assert!(hand1 < hand2);
     }
 
     #[test]
     fn test_ordering_seven_cards_6() {
-        let cards1 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Ace, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Ace, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ];
+        let cards1 = cards("As Ad 4c Ac 8c Ah Td");
         let hand1 = Hand::new(cards1);
-        let cards2 = vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Hearts, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Six, Suit::Hearts, false)
-        ];
+        let cards2 = cards("As 3h 4h 5h 7h Ts 6h");
         let hand2 = Hand::new(cards2);
         assert!(hand1 < hand2);
     }
 
     #[test]
     fn test_ordering_seven_cards_high_card_to_one_pair() {
-        let high_card = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let one_pair = Hand::new(vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Queen, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Five, Suit::Clubs, false)
-        ]);
+        let high_card = Hand::new(cards("3d 2s 4c 6c 8c 7h Td"));
+        let one_pair = Hand::new(cards("As Qh 8h 5d 7h Ts 5c"));
         assert!(high_card < one_pair);
     }
 
     #[test]
     fn test_ordering_seven_cards_one_pair_to_two_pair() {
-        let one_pair = Hand::new(vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Queen, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Five, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Five, Suit::Clubs, false)
-        ]);
-        let two_pair = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
+        let one_pair = Hand::new(cards("As Qh 8h 5d 7h Ts 5c"));
+        let two_pair = Hand::new(cards("3d 2s 3c 2c 8c 7h Td"));
         assert!(one_pair < two_pair);
     }
 
     #[test]
     fn test_ordering_seven_cards_two_pair_to_three_of_a_kind() {
-        let two_pair = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let three_of_a_kind = Hand::new(vec![
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Queen, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Two, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Two, Suit::Clubs, false)
-        ]);
+        let two_pair = Hand::new(cards("3d 4s 3c 4c 8c 7h Td"));
+        let three_of_a_kind = Hand::new(cards("2s Qh 8h 2d 7h Ts 2c"));
         assert!(two_pair < three_of_a_kind);
     }
 
     #[test]
     fn test_ordering_seven_cards_three_of_a_kind_to_straight() {
-        let three_of_a_kind = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
-        let straight = Hand::new(vec![
-            Card::new(Rank::Two, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Five, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
+        let three_of_a_kind = Hand::new(cards("9s 3h 8h 9d 7h Ts 9c"));
+        let straight = Hand::new(cards("2d 4s 3c 4c 6c 5h Td"));
         assert!(three_of_a_kind < straight);
     }
 
     #[test]
     fn test_ordering_seven_cards_straight_to_flush() {
-        let straight = Hand::new(vec![
-            Card::new(Rank::Two, Suit::Diamonds, false),
-            Card::new(Rank::Four, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Six, Suit::Clubs, false),
-            Card::new(Rank::Five, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let flush = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
+        let straight = Hand::new(cards("2d 4s 3c 4c 6c 5h Td"));
+        let flush = Hand::new(cards("9h 3h 8h 9d 7h Th 9c"));
         assert!(straight < flush);
     }
 
     #[test]
     fn test_ordering_seven_cards_flush_to_full_house() {
-        let flush = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
-        let full_house = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
+        let flush = Hand::new(cards("9h 3h 8h 9d 7h Th 9c"));
+        let full_house = Hand::new(cards("9s 3h 8s 9d 8h Ts 9c"));
         assert!(flush < full_house);
     }
 
     #[test]
     fn test_ordering_seven_cards_full_house_to_four_of_a_kind() {
-        let full_house = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Eight, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
-        let four_of_a_kind = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
+        let full_house = Hand::new(cards("9s 3h 8s 9d 8h Ts 9c"));
+        let four_of_a_kind = Hand::new(cards("9h 3h 9s 9d 7h Th 9c"));
         assert!(full_house < four_of_a_kind);
     }
 
     #[test]
     fn test_ordering_seven_cards_four_of_a_kind_to_straight_flush() {
-        let four_of_a_kind = Hand::new(vec![
-            Card::new(Rank::Nine, Suit::Hearts, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Diamonds, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Hearts, false),
-            Card::new(Rank::Nine, Suit::Clubs, false)
-        ]);
-        let straight_flush = Hand::new(vec![
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Six, Suit::Spades, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
+        let four_of_a_kind = Hand::new(cards("9h 3h 9s 9d 7h Th 9c"));
+        let straight_flush = Hand::new(cards("2s 4s 3s 4c 6s 5s Td"));
         assert!(four_of_a_kind < straight_flush);
     }
 
     #[test]
     fn test_ordering_seven_cards_straight_flush_to_royal_flush() {
-        let straight_flush = Hand::new(vec![
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Four, Suit::Clubs, false),
-            Card::new(Rank::Six, Suit::Spades, false),
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let royal_flush = Hand::new(vec![
-            Card::new(Rank::Ace, Suit::Spades, false),
-            Card::new(Rank::Ten, Suit::Spades, false),
-            Card::new(Rank::Five, Suit::Clubs, false),
-            Card::new(Rank::Queen, Suit::Spades, false),
-            Card::new(Rank::King, Suit::Spades, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false),
-            Card::new(Rank::Jack, Suit::Spades, false)
-        ]);
+        let straight_flush = Hand::new(cards("2s 4s 3s 4c 6s 5s Td"));
+        let royal_flush = Hand::new(cards("As Ts 5c Qs Ks Td Js"));
         assert!(straight_flush < royal_flush);
     }
 
     #[test]
     fn test_ordering_seven_cards_two_pair_to_two_pair_with_kicker() {
-        let two_pair_high_kicker = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let two_pair_low_kicker = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Diamonds, false)
-        ]);
+        let two_pair_high_kicker = Hand::new(cards("3d 2s 3c 2c 8c 7h Td"));
+        let two_pair_low_kicker = Hand::new(cards("3d 2s 3c 2c 8c 7h 4d"));
         assert!(two_pair_low_kicker < two_pair_high_kicker);
         assert!(two_pair_low_kicker != two_pair_high_kicker);
     }
 
     #[test]
     fn test_ordering_seven_cards_one_pair_to_one_pair_with_kicker() {
-        let one_pair_high_kicker = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Ace, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let one_pair_low_kicker = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::King, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Diamonds, false)
-        ]);
+        let one_pair_high_kicker = Hand::new(cards("3d 2s Ac 2c 8c 7h Td"));
+        let one_pair_low_kicker = Hand::new(cards("3d 2s Kc 2c 8c 7h 4d"));
         assert!(one_pair_low_kicker < one_pair_high_kicker);
         assert!(one_pair_low_kicker != one_pair_high_kicker);
     }
 
     #[test]
     fn test_ordering_seven_cards_three_of_a_kind_to_three_of_a_kind_with_kicker() {
-        let two_pair_high_kicker = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let two_pair_low_kicker = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Three, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Four, Suit::Diamonds, false)
-        ]);
+        let two_pair_high_kicker = Hand::new(cards("3d 3s 3c 2c 8c 7h Td"));
+        let two_pair_low_kicker = Hand::new(cards("3d 3s 3c 2c 8c 7h 4d"));
         assert!(two_pair_low_kicker < two_pair_high_kicker);
         assert!(two_pair_low_kicker != two_pair_high_kicker);
     }
 
     #[test]
     fn test_ordering_seven_cards_two_pair_to_two_pair_equal() {
-        let two_pair1 = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Ten, Suit::Diamonds, false)
-        ]);
-        let two_pair2 = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Ten, Suit::Clubs, false),
-            Card::new(Rank::Seven, Suit::Hearts, false),
-            Card::new(Rank::Eight, Suit::Diamonds, false)
-        ]);
+        let two_pair1 = Hand::new(cards("3d 2s 3c 2c 8c 7h Td"));
+        let two_pair2 = Hand::new(cards("3d 2s 3c 2c Tc 7h 8d"));
         let pair1 = Hand::rank_hand(&two_pair1.cards);
         let pair2 = Hand::rank_hand(&two_pair2.cards);
         assert!(!(pair1 < pair2));
@@ -1100,20 +927,8 @@ mod tests {
 
     #[test]
     fn test_ordering_five_cards_one_pair_equal() {
-        let two_pair1 = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Clubs, false),
-            Card::new(Rank::Two, Suit::Clubs, false),
-            Card::new(Rank::Eight, Suit::Clubs, false)
-        ]);
-        let two_pair2 = Hand::new(vec![
-            Card::new(Rank::Three, Suit::Diamonds, false),
-            Card::new(Rank::Two, Suit::Spades, false),
-            Card::new(Rank::Three, Suit::Hearts, false),
-            Card::new(Rank::Two, Suit::Diamonds, false),
-            Card::new(Rank::Eight, Suit::Hearts, false)
-        ]);
+        let two_pair1 = Hand::new(cards("3d 2s 3c 2c 8c"));
+        let two_pair2 = Hand::new(cards("3d 2s 3h 2d 8h"));
         let pair1 = Hand::rank_hand(&two_pair1.cards);
         let pair2 = Hand::rank_hand(&two_pair2.cards);
         assert!(!(pair1 < pair2));
@@ -1123,14 +938,8 @@ mod tests {
 
     #[test]
     fn test_ordering_two_cards_equal() {
-        let high_card1 = Hand::new(vec![
-            Card::new(Rank::Five, Suit::Diamonds, false),
-            Card::new(Rank::Nine, Suit::Hearts, false)
-        ]);
-        let high_card2 = Hand::new(vec![
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Spades, false)
-        ]);
+        let high_card1 = Hand::new(cards("5d 9h"));
+        let high_card2 = Hand::new(cards("5s 9s"));
         println!("high card 1 - {:?}", high_card1);
         println!("high card 2 - {:?}", high_card2);
         let high1 = Hand::rank_hand(&high_card1.cards);
@@ -1142,15 +951,207 @@ mod tests {
 
     #[test]
     fn test_ordering_two_cards_with_kicker() {
-        let high_card1 = Hand::new(vec![
-            Card::new(Rank::Five, Suit::Spades, false),
-            Card::new(Rank::Nine, Suit::Spades, false)
-        ]);
-        let high_card2 = Hand::new(vec![
-            Card::new(Rank::Six, Suit::Diamonds, false),
-            Card::new(Rank::Nine, Suit::Hearts, false)
-        ]);
+        let high_card1 = Hand::new(cards("5s 9s"));
+        let high_card2 = Hand::new(cards("6d 9h"));
         assert!(high_card1 < high_card2);
         assert!(high_card1 != high_card2);
     }
+
+    #[test]
+    fn test_straight_draw_outs_outside() {
+        let outs = Hand::straight_draw_outs(&cards("5h 6d 7c 8s Kh"));
+        assert_eq!(outs.len(), 8);
+        assert_eq!(outs.iter().filter(|&rank| *rank == Rank::Four).count(), 4);
+        assert_eq!(outs.iter().filter(|&rank| *rank == Rank::Nine).count(), 4);
+    }
+
+    #[test]
+    fn test_straight_draw_outs_inside() {
+        assert_eq!(Hand::straight_draw_outs(&cards("5h 6d 8c 9s Kh")), vec![Rank::Seven; 4]);
+        // ace-low straight draws only have one way to be completed
+        assert_eq!(Hand::straight_draw_outs(&cards("Ah 2d 3c 4s Kh")), vec![Rank::Five; 4]);
+    }
+
+    #[test]
+    fn test_suit_distribution() {
+        let hand = cards("Ah Kd 9h 7c 4h");
+        let distribution = Hand::suit_distribution(&hand);
+        assert_eq!(distribution.len(), 3);
+        assert_eq!(distribution[&Suit::Hearts], vec![&hand[0], &hand[2], &hand[4]]);
+        assert_eq!(distribution[&Suit::Diamonds], vec![&hand[1]]);
+        assert_eq!(distribution[&Suit::Clubs], vec![&hand[3]]);
+        assert!(!distribution.contains_key(&Suit::Spades));
+    }
+
+    #[test]
+    fn test_flush_draw_outs_three_suited() {
+        assert_eq!(Hand::flush_draw_outs(&cards("Ah Kd 9h 7c 4h")), None);
+    }
+
+    #[test]
+    fn test_flush_draw_outs_four_suited() {
+        assert_eq!(Hand::flush_draw_outs(&cards("Ah Kh 9h 7c 4h")), Some((Suit::Hearts, 9)));
+        // the off suit cards can be from the same suit as each other
+        assert_eq!(Hand::flush_draw_outs(&cards("2s 3s 5s 8s Kd Qd 9h")), Some((Suit::Spades, 9)));
+    }
+
+    #[test]
+    fn test_flush_draw_outs_five_suited() {
+        assert_eq!(Hand::flush_draw_outs(&cards("Ah Kh 9h 7h 4h")), None, "A completed flush has no outs!");
+    }
+
+    #[test]
+    fn test_short_deck_flush_beats_full_house() {
+        let flush = Hand::rank_hand(&cards("Kh Jh 9h 7h 6h"));
+        let full_house = Hand::rank_hand(&cards("Ks Kd Kc 6s 6d"));
+        assert!(full_house > flush);
+        assert_eq!(flush.cmp_short_deck(&full_house), Ordering::Greater);
+        assert_eq!(full_house.cmp_short_deck(&flush), Ordering::Less);
+        // the other classifications keep their usual order
+        let four_of_a_kind = Hand::rank_hand(&cards("As Ah Ad Ac 6d"));
+        assert_eq!(four_of_a_kind.cmp_short_deck(&flush), Ordering::Greater);
+
+        // on a board of Kh Kd 6h Jh 9c, a player with Kc 6d makes a full house, and a player with 8h 7h makes a flush
+        let full_house = Hand::rank_short_deck_hand(&cards("Kc 6d Kh Kd 6h Jh 9c"));
+        let flush = Hand::rank_short_deck_hand(&cards("8h 7h Kh Kd 6h Jh 9c"));
+        assert_eq!(full_house, HandRank::FullHouse(Rank::King, Rank::Six));
+        assert!(matches!(flush, HandRank::Flush(Rank::King, _)));
+        assert_eq!(flush.cmp_short_deck(&full_house), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_short_deck_wheel() {
+        let wheel = cards("Ah 6d 7c 8s 9h");
+        assert!(Hand::is_short_deck_wheel(&wheel));
+        assert!(matches!(Hand::rank_hand(&wheel), HandRank::HighCard(Rank::Ace, _)));
+        assert_eq!(Hand::rank_short_deck_hand(&wheel), HandRank::Straight(Rank::Nine));
+        assert_eq!(Hand::rank_short_deck_hand(&cards("Ah 6h 7h 8h 9h")), HandRank::StraightFlush(Rank::Nine));
+
+        // the wheel is the lowest straight
+        let six_to_ten = Hand::rank_short_deck_hand(&cards("6d 7c 8s 9h Td"));
+        assert_eq!(Hand::rank_short_deck_hand(&wheel).cmp_short_deck(&six_to_ten), Ordering::Less);
+        let trips = Hand::rank_short_deck_hand(&cards("Ah Ad Ac 7s 9h"));
+        assert_eq!(Hand::rank_short_deck_hand(&wheel).cmp_short_deck(&trips), Ordering::Greater);
+        // and it is found among more than five cards
+        assert_eq!(Hand::rank_short_deck_hand(&cards("Ah 6d 7c 8s 9h Kd Kc")), HandRank::Straight(Rank::Nine));
+    }
+
+    #[test]
+    fn test_best_five_full_house() {
+        // two three of a kinds, the best full house uses the kings and two of the nines
+        let best_five = Hand::best_five(&cards("9c Kh 2s Kd 9d Ks 9h"));
+        assert_eq!(best_five.len(), 5);
+        assert_eq!(best_five[..3], cards("Ks Kh Kd"));
+        assert!(best_five[3..].iter().all(|card| *card.rank() == Rank::Nine));
+        assert_eq!(Hand::rank_hand(&best_five), HandRank::FullHouse(Rank::King, Rank::Nine));
+    }
+
+    #[test]
+    fn test_best_five_flush() {
+        // six hearts, the lowest heart and the off suit queen are left out
+        let best_five = Hand::best_five(&cards("3h Ah Qs 9h Kh 4h 7h"));
+        assert_eq!(best_five, cards("Ah Kh 9h 7h 4h"));
+        assert_eq!(Hand::rank_hand(&best_five), HandRank::Flush(Rank::Ace, vec![Rank::King, Rank::Nine, Rank::Seven, Rank::Four]));
+    }
+
+    #[test]
+    fn test_best_five_fewer_than_five_cards() {
+        assert_eq!(Hand::best_five(&cards("2c Ah 7d")), cards("Ah 7d 2c"));
+    }
+
+    #[test]
+    fn test_straight_draw_outs_none() {
+        assert_eq!(Hand::straight_draw_outs(&cards("2h 6d 8c Js Kh")), Vec::new());
+        assert_eq!(Hand::straight_draw_outs(&cards("5h 6d 7c 8s 9h")), Vec::new(), "A completed straight has no outs!");
+    }
+
+    #[test]
+    fn test_omaha_must_use_two_hole_cards() {
+        // four hearts in the hole and one on the board is not a flush,
+        // as only two hole cards may be used
+        let hole_cards = cards("Ah Kh 9h 4h");
+        let community_cards = cards("2h 7c Js");
+        assert_eq!(Hand::rank_omaha_hand(&hole_cards, &community_cards), HandRank::HighCard(Rank::Ace, vec![Rank::King, Rank::Jack, Rank::Seven, Rank::Two]));
+    }
+
+    #[test]
+    fn test_omaha_combines_hole_and_community_cards() {
+        let hole_cards = cards("Th 9c 2d 2s");
+        let community_cards = cards("8h 7s 6d 2c Kc");
+        // the ten and nine from the hole complete a straight with the board,
+        // which beats the three of a kind made with the pair of twos in the hole
+        assert_eq!(Hand::rank_omaha_hand(&hole_cards, &community_cards), HandRank::Straight(Rank::Ten));
+    }
+
+    #[test]
+    fn test_omaha_not_enough_community_cards() {
+        let hole_cards = cards("Ah Ac 9h 4d");
+        assert_eq!(Hand::rank_omaha_hand(&hole_cards, &Vec::new()), HandRank::OnePair(Rank::Ace, vec![Rank::Nine, Rank::Four]));
+    }
+
+    /// the cards left in a full deck once the given cards are taken out
+    fn remaining_cards(taken_cards: &[Card]) -> Vec<Card> {
+        return Deck::new().peek_all().iter()
+            .filter(|card| !taken_cards.contains(card))
+            .cloned()
+            .collect();
+    }
+
+    /// the notation of each card, sorted, so that outs can be compared regardless of deck order
+    fn sorted_notation(cards: &[Card]) -> Vec<String> {
+        let mut notation: Vec<String> = cards.iter().map(|card| card.to_notation()).collect();
+        notation.sort();
+        return notation;
+    }
+
+    #[test]
+    fn test_outs_to_rank_flush() {
+        let hand = cards("Ah Kh 9h 7c 4h");
+        let outs = Hand::outs_to_rank(&hand, HandRank::Flush(Rank::Ace, Vec::new()).rank_value(), &remaining_cards(&hand));
+        assert_eq!(sorted_notation(&outs), sorted_notation(&cards("2h 3h 5h 6h 7h 8h Th Jh Qh")));
+    }
+
+    #[test]
+    fn test_outs_to_rank_straight() {
+        let hand = cards("8s 9d Tc Jh 2c");
+        let outs = Hand::outs_to_rank(&hand, HandRank::Straight(Rank::Jack).rank_value(), &remaining_cards(&hand));
+        assert_eq!(sorted_notation(&outs), sorted_notation(&cards("7s 7h 7d 7c Qs Qh Qd Qc")));
+    }
+
+    #[test]
+    fn test_outs_to_rank_pair_to_trips() {
+        // pairing the kicker only makes two pair, which isn't good enough
+        let hand = cards("8s 8d Kc 4h 2c");
+        let outs = Hand::outs_to_rank(&hand, HandRank::ThreeOfAKind(Rank::Eight, Vec::new()).rank_value(), &remaining_cards(&hand));
+        assert_eq!(sorted_notation(&outs), sorted_notation(&cards("8h 8c")));
+        // cards that were already dealt aren't outs
+        let outs = Hand::outs_to_rank(&hand, 4, &cards("8h Ks 3d"));
+        assert_eq!(outs, cards("8h"));
+    }
+
+    #[test]
+    fn test_wild_five_of_a_kind() {
+        // the wild two makes a fifth queen, even though all four queens are already in the hand
+        let hand_rank = Hand::rank_hand_with_wilds(&cards("Qs Qh Qd Qc 2c"), &cards("2c"));
+        assert_eq!(hand_rank, HandRank::FiveOfAKind(Rank::Queen));
+        assert!(hand_rank > HandRank::RoyalFlush);
+        assert!(HandRank::FiveOfAKind(Rank::Ace) > hand_rank);
+        // with two wilds, three of a kind becomes five of a kind
+        assert_eq!(Hand::rank_hand_with_wilds(&cards("9s 9h 9d 2c 2d"), &cards("2c 2d")), HandRank::FiveOfAKind(Rank::Nine));
+    }
+
+    #[test]
+    fn test_wild_straight_flush() {
+        // the wild fills the gap in the straight flush
+        assert_eq!(Hand::rank_hand_with_wilds(&cards("5h 6h 8h 9h Jc"), &cards("Jc")), HandRank::StraightFlush(Rank::Nine));
+        // or extends it at the top, rather than the bottom
+        assert_eq!(Hand::rank_hand_with_wilds(&cards("9d Td Jd Qd 3s"), &cards("3s")), HandRank::StraightFlush(Rank::King));
+        assert_eq!(Hand::rank_hand_with_wilds(&cards("Ts Js Qs Ks 3s"), &cards("3s")), HandRank::RoyalFlush);
+    }
+
+    #[test]
+    fn test_wild_not_in_hand() {
+        let hand = cards("8s 8d Kc 4h 2c");
+        assert_eq!(Hand::rank_hand_with_wilds(&hand, &cards("Jc")), Hand::rank_hand(&hand));
+    }
 }
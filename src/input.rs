@@ -1,12 +1,16 @@
 use std::io;
+use uuid::Uuid;
 use crate::game_type::GameType;
 
 use crate::player::Player;
-use crate::{action_option::ActionOption, card::Card};
+use crate::pot::{Pot, SidePot};
+use crate::{action::Action, action_option::ActionOption, card::Card};
+use crate::game_event::GameEvent;
 
 pub mod cli_input;
 pub mod test_input;
 pub mod server_input;
+pub mod bot_input;
 
 /// Trait for input (and output) handling.
 /// The game rules use implementations of this trait to display information to players,
@@ -27,6 +31,10 @@ pub trait Input {
     /// returns the amount that the player chose, after validation
     fn request_raise_amount(&mut self, limit: u32, player: &Player) -> u32;
 
+    /// ask player to pick the amount of an opening bet, when nobody has bet yet in this phase,
+    /// returns the amount that the player chose, which is between min_bet and max_bet, after validation
+    fn request_bet_amount(&mut self, min_bet: u32, max_bet: u32, player: &Player) -> u32;
+
     /// ask player to choose any number of cards from their cards
     /// to be replaced, and return the cards chosen by the player (to be replaced)
     fn request_replace_cards<'a>(&mut self, player: &'a Player) -> Vec<&'a Card>;
@@ -43,15 +51,64 @@ pub trait Input {
     /// it is assumed that they will be shown using a different method.
     fn display_other_player_up_cards_to_player(&self, other_players: Vec<&Player>, player: &Player);
 
+    /// ask a player whose hand has already been beaten at the showdown
+    /// whether they want to show their cards, or muck them (nobody sees them),
+    /// returns true if the player chose to show their cards
+    fn request_show_or_muck(&mut self, player: &Player) -> bool;
+
+    /// ask a player who is all in (or has no one left to bet against) before the river
+    /// whether they agree to deal the remaining community cards twice, splitting the pot between both runouts,
+    /// returns true if the player agrees
+    fn request_run_it_twice(&mut self, player: &Player) -> bool;
+
+    /// ask a player whether they want to straddle, posting a blind of straddle_amount before the cards are dealt,
+    /// which makes them the last to act before the flop, returns true if the player chose to straddle
+    fn request_straddle(&mut self, player: &Player, straddle_amount: u32) -> bool;
+
     /// display which player's turn it is
     fn display_current_player(&self, player: &Player);
 
     /// display the winner(s) of a round to all players
     fn announce_winner(&self, winner: Vec<&Player>, all_players: Vec<&Player>);
 
-    /// display the amount currently in the pot to all players
-    fn display_pot(&self, pot_amount: u32, all_players: Vec<&Player>);
+    /// display the amount currently in the pot to all players,
+    /// along with a summary of the actions taken in each phase so far
+    fn display_pot(&self, pot: &Pot, all_players: Vec<&Player>);
+
+    /// display the main pot and each side pot to all players, along with which players
+    /// are eligible to win each of them (this is only necessary when one or more players are all in)
+    fn display_side_pots(&self, side_pots: Vec<SidePot>, all_players: Vec<&Player>);
 
     /// display to each player the amount of money in each player's wallet (including their own)
     fn display_player_balances(&self, all_players: Vec<&Player>);
+
+    /// announce to all players that a player has run out of money, and has been removed from the game
+    fn announce_player_eliminated(&self, player: &Player);
+
+    /// display to all players every turn taken in a betting phase once the phase has ended, in the order they were taken
+    /// (see Pot::get_phase_history), the cards in each turn are the player's hand and must not be shown to the other players
+    fn display_turn_summary(&self, turns: &[(Uuid, Action, usize, Vec<Card>)], players: &[&Player]);
+
+    /// called by the rules for each discrete event in a round (cards dealt, chips moved, folds, pots awarded) as it happens,
+    /// for clients that animate events rather than redrawing on the display calls above
+    fn on_event(&mut self, event: GameEvent);
+
+    /// called about once a second while waiting for a player to choose an action, with the number of seconds
+    /// they have left before they are automatically folded, so that a countdown can be shown to them.
+    /// only inputs that fold players who take too long need to call this, so by default it does nothing
+    fn notify_action_timeout_seconds(&self, _player: &Player, _seconds_remaining: u32) {}
+
+    /// ask a player who has just taken a turn (action) whether they want to take it back, to correct a mistake,
+    /// before the next player acts, returns true if the player chose to undo their turn.
+    /// This is asked straight after the turn, rather than offering an undo among the next action options,
+    /// because those are offered to the next player, who can't take back someone else's turn (see Pot::undo_last_turn).
+    /// by default players never undo their turns
+    fn request_undo_turn(&mut self, _player: &Player, _action: &Action) -> bool {
+        return false;
+    }
+
+    /// called by the rules at the start of each round with the type of game being played,
+    /// for inputs that play differently in different games (like bots, see BotInput).
+    /// by default it does nothing
+    fn set_game_type(&mut self, _game_type: GameType) {}
 }
@@ -0,0 +1,462 @@
+use rand::seq::IndexedRandom;
+
+use super::*;
+use crate::badugi_hand::BadugiHand;
+use crate::game_type::GameType;
+use crate::card::Rank;
+use crate::deck::Deck;
+use crate::equity;
+use crate::hand_rank::{Hand, HandRank};
+use crate::pot::SidePot;
+
+/// BotInput is an implementation of the Input trait that plays for computer controlled players
+/// (players created with Player::new_bot), and passes everything else through to another Input
+/// implementation, so that people and bots can play in the same game.
+/// Bots follow a simple strategy based on the rank of the cards they hold:
+/// they fold a high card below a Jack (unless checking is free), call with a pair or better,
+/// and raise with three of a kind or better.
+/// In badugi, where the lowest hand wins, bots play by their badugi hand instead (see BadugiHand):
+/// they raise with an eight high badugi or better, call with any other badugi, and otherwise check or fold.
+/// Before the flop in Texas Hold'em, bots call if their hole cards win at least half the time heads-up
+/// against a random hand (see equity::equity_vs), and otherwise check or fold.
+/// Information displayed only to a bot is not passed through, so bots' cards stay hidden.
+pub struct BotInput<I: Input> {
+    input: I,
+    /// the game being played, as set by the rules at the start of each round (None before the first round)
+    game_type: Option<GameType>,
+    /// how many community cards have been dealt in the round being played
+    community_cards_dealt: usize
+}
+
+/// how many random opponent hands a bot plays its hole cards against before the flop in Texas Hold'em
+const PREFLOP_OPPONENT_HANDS: u32 = 20;
+/// how many random boards a bot deals for each of those opponent hands
+const PREFLOP_RUNOUTS: u32 = 10;
+/// the least heads-up equity a bot calls with before the flop in Texas Hold'em
+const PREFLOP_CALL_EQUITY: f64 = 0.5;
+
+impl<I: Input> BotInput<I> {
+    /// get the Input implementation used for players who are not bots
+    pub fn input(&mut self) -> &mut I {
+        return &mut self.input;
+    }
+
+    /// pick the first of the preferred action options that is possible
+    fn first_possible(possible_actions: &Vec<ActionOption>, preferred_actions: Vec<ActionOption>) -> Option<ActionOption> {
+        return preferred_actions.into_iter().find(|preferred_action| possible_actions.iter()
+            .any(|possible_action| std::mem::discriminant(possible_action) == std::mem::discriminant(preferred_action)));
+    }
+
+    /// true if the game being played is badugi, which bots play with a lowball strategy
+    fn is_badugi(&self) -> bool {
+        return matches!(self.game_type, Some(GameType::Badugi));
+    }
+
+    /// choose which of a bot's cards to keep when drawing, for the game being played
+    fn cards_worth_keeping(&self, cards: &Vec<&Card>) -> Vec<bool> {
+        return match self.is_badugi() {
+            true => BotInput::<I>::badugi_cards_to_keep(cards),
+            false => BotInput::<I>::cards_to_keep(cards),
+        };
+    }
+
+    /// choose which of a bot's cards to keep when drawing in badugi, keeping the cards of its best badugi hand,
+    /// which is every card once it has a complete badugi
+    fn badugi_cards_to_keep(cards: &Vec<&Card>) -> Vec<bool> {
+        let owned_cards: Vec<Card> = cards.iter().map(|&card| card.clone()).collect();
+        let best_cards = BadugiHand::best_cards(&owned_cards);
+        return cards.iter().map(|card| best_cards.contains(card)).collect();
+    }
+
+    /// true if no community cards have been dealt yet in a round of Texas Hold'em
+    fn is_texas_holdem_preflop(&self) -> bool {
+        return matches!(self.game_type, Some(GameType::TexasHoldem)) && self.community_cards_dealt == 0;
+    }
+
+    /// estimate how often a bot's hole cards win heads-up before the flop, counting a tie as half a win,
+    /// by playing them against random opponent hole cards dealt from the rest of the deck (see equity::equity_vs)
+    fn preflop_equity(hole_cards: &[Card]) -> f64 {
+        let unknown_cards: Vec<Card> = Deck::new().peek_all().iter()
+            .filter(|card| !hole_cards.contains(card))
+            .cloned()
+            .collect();
+        let mut total_equity = 0.0;
+        for _ in 0..PREFLOP_OPPONENT_HANDS {
+            let opponent_cards: Vec<Card> = unknown_cards.choose_multiple(&mut rand::rng(), 2).cloned().collect();
+            let deck_remaining: Vec<Card> = unknown_cards.iter()
+                .filter(|card| !opponent_cards.contains(card))
+                .cloned()
+                .collect();
+            let (win, _, tie) = equity::equity_vs(hole_cards, &opponent_cards, &[], &deck_remaining, PREFLOP_RUNOUTS);
+            total_equity += win + tie / 2.0;
+        }
+        return total_equity / PREFLOP_OPPONENT_HANDS as f64;
+    }
+
+    /// the action options a bot prefers in a bet phase before the flop in Texas Hold'em, in order of preference
+    fn preflop_preferred_actions(hole_cards: &[Card]) -> Vec<ActionOption> {
+        return match BotInput::<I>::preflop_equity(hole_cards) >= PREFLOP_CALL_EQUITY {
+            true => vec![ActionOption::Call, ActionOption::Check, ActionOption::AllIn],
+            false => vec![ActionOption::Check, ActionOption::Fold],
+        };
+    }
+
+    /// the action options a bot prefers in a bet phase of badugi, in order of preference
+    fn badugi_preferred_actions(cards: &[Card]) -> Vec<ActionOption> {
+        let badugi_hand = BadugiHand::new(cards);
+        let eight_high = badugi_hand.highest_card_value().is_some_and(|value| value <= 8);
+        return match (badugi_hand.is_badugi(), eight_high) {
+            (true, true) => vec![ActionOption::Raise, ActionOption::Bet, ActionOption::Call, ActionOption::Check, ActionOption::AllIn],
+            (true, false) => vec![ActionOption::Call, ActionOption::Check, ActionOption::AllIn],
+            (false, _) => vec![ActionOption::Check, ActionOption::Fold],
+        };
+    }
+
+    /// how far a card's rank is from an out of a straight draw, where an Ace is also low (one away from a Two)
+    /// for cards of a Five or lower, so that the cards of a wheel draw (e.g. 2-3-4-5) are close to the Ace that completes it
+    fn straight_distance(rank: &Rank, out: &Rank) -> u8 {
+        let high_distance = Rank::distance(rank, out);
+        return match (rank, out) {
+            (Rank::Two | Rank::Three | Rank::Four | Rank::Five, Rank::Ace) => high_distance.min(rank.to_u8() - 1),
+            _ => high_distance,
+        };
+    }
+
+    /// choose which of a bot's cards to keep when drawing, keeping all cards of a straight or better,
+    /// otherwise keeping four cards to a flush, or any pairs (or better), or four cards to an outside straight draw,
+    /// or else just the highest card. Inside straight draws are not worth keeping
+    fn cards_to_keep(cards: &Vec<&Card>) -> Vec<bool> {
+        let owned_cards: Vec<Card> = cards.iter().map(|&card| card.clone()).collect();
+        let hand_rank = Hand::rank_hand(&owned_cards);
+        if hand_rank >= HandRank::Straight(Rank::Two) {
+            return vec![true; cards.len()];
+        }
+
+        if let Some((flush_draw_suit, _)) = Hand::flush_draw_outs(&owned_cards) {
+            return cards.iter().map(|card| *card.suit() == flush_draw_suit).collect();
+        }
+
+        let matched_cards: Vec<bool> = cards.iter()
+            .map(|card| cards.iter().filter(|other_card| other_card.rank() == card.rank()).count() >= 2)
+            .collect();
+        if matched_cards.contains(&true) {
+            return matched_cards;
+        }
+
+        let straight_draw_outs = Hand::straight_draw_outs(&owned_cards);
+        if straight_draw_outs.len() >= 8 {
+            // the cards that are part of the draw are close enough to every out to make a straight with it
+            return cards.iter()
+                .map(|card| straight_draw_outs.iter().all(|out| BotInput::<I>::straight_distance(card.rank(), out) <= 4))
+                .collect();
+        }
+
+        let highest_card = cards.iter().max().expect("Expected the bot to have cards");
+        let mut kept_highest_card = false;
+        return cards.iter().map(|card| {
+            let keep = !kept_highest_card && card.rank() == highest_card.rank();
+            kept_highest_card |= keep;
+            keep
+        }).collect();
+    }
+}
+
+impl<I: Input> Input for BotInput<I> {
+    fn new() -> Self {
+        return BotInput {
+            input: I::new(),
+            game_type: None,
+            community_cards_dealt: 0
+        };
+    }
+
+    fn request_username(&mut self) -> String {
+        return self.input.request_username();
+    }
+
+    fn input_variation(&mut self) -> GameType {
+        return self.input.input_variation();
+    }
+
+    fn input_action_options(&mut self, possible_actions: Vec<ActionOption>, player: &Player) -> ActionOption {
+        if !player.is_bot() {
+            return self.input.input_action_options(possible_actions, player);
+        }
+
+        let cards: Vec<Card> = player.peek_at_cards().iter().map(|&card| card.clone()).collect();
+        let hand_rank = Hand::rank_hand(&cards);
+        let preferred_actions = if possible_actions.iter().any(|action| matches!(action, ActionOption::Replace)) {
+            // draw phase, draw unless all of the bot's cards are worth keeping
+            match self.cards_worth_keeping(&player.peek_at_cards()).contains(&false) {
+                true => vec![ActionOption::Replace, ActionOption::Check],
+                false => vec![ActionOption::Check, ActionOption::Replace],
+            }
+        }
+        else if self.is_badugi() {
+            BotInput::<I>::badugi_preferred_actions(&cards)
+        }
+        else if self.is_texas_holdem_preflop() {
+            BotInput::<I>::preflop_preferred_actions(&cards)
+        }
+        else if hand_rank >= HandRank::ThreeOfAKind(Rank::Two, Vec::new()) {
+            vec![ActionOption::Raise, ActionOption::Bet, ActionOption::Call, ActionOption::Check, ActionOption::AllIn]
+        }
+        else if hand_rank >= HandRank::OnePair(Rank::Two, Vec::new()) {
+            vec![ActionOption::Call, ActionOption::Check, ActionOption::AllIn]
+        }
+        else if hand_rank >= HandRank::HighCard(Rank::Jack, Vec::new()) {
+            vec![ActionOption::Check, ActionOption::Call, ActionOption::Fold]
+        }
+        else {
+            vec![ActionOption::Check, ActionOption::Fold]
+        };
+
+        return BotInput::<I>::first_possible(&possible_actions, preferred_actions)
+            .unwrap_or_else(|| *possible_actions.last().expect("Expected at least one possible action"));
+    }
+
+    fn request_raise_amount(&mut self, limit: u32, player: &Player) -> u32 {
+        if !player.is_bot() {
+            return self.input.request_raise_amount(limit, player);
+        }
+        // raise by a quarter of the limit, but always by something
+        return (limit / 4).max(1).min(limit);
+    }
+
+    fn request_bet_amount(&mut self, min_bet: u32, max_bet: u32, player: &Player) -> u32 {
+        if !player.is_bot() {
+            return self.input.request_bet_amount(min_bet, max_bet, player);
+        }
+        // bet a quarter of the limit, but never less than the minimum bet
+        return (max_bet / 4).max(min_bet).min(max_bet);
+    }
+
+    fn request_replace_cards<'a>(&mut self, player: &'a Player) -> Vec<&'a Card> {
+        if !player.is_bot() {
+            return self.input.request_replace_cards(player);
+        }
+        let cards = player.peek_at_cards();
+        let cards_to_keep = self.cards_worth_keeping(&cards);
+        return cards.into_iter()
+            .zip(cards_to_keep)
+            .filter(|(_, keep)| !keep)
+            .map(|(card, _)| card)
+            .collect();
+    }
+
+    fn request_show_or_muck(&mut self, player: &Player) -> bool {
+        if !player.is_bot() {
+            return self.input.request_show_or_muck(player);
+        }
+        // there is no reason for a bot to show a beaten hand
+        return false;
+    }
+
+    fn request_run_it_twice(&mut self, player: &Player) -> bool {
+        if !player.is_bot() {
+            return self.input.request_run_it_twice(player);
+        }
+        // running it twice doesn't change how much a bot expects to win, so they always agree
+        return true;
+    }
+
+    fn request_straddle(&mut self, player: &Player, straddle_amount: u32) -> bool {
+        if !player.is_bot() {
+            return self.input.request_straddle(player, straddle_amount);
+        }
+        // a straddle is a bet made without seeing any cards, which the bots' strategy never makes
+        return false;
+    }
+
+    fn request_undo_turn(&mut self, player: &Player, action: &Action) -> bool {
+        if !player.is_bot() {
+            return self.input.request_undo_turn(player, action);
+        }
+        // bots never make mistakes, so they never undo a turn
+        return false;
+    }
+
+    fn set_game_type(&mut self, game_type: GameType) {
+        // the rules set the game type at the start of each round, before any community cards are dealt
+        self.community_cards_dealt = 0;
+        self.game_type = Some(game_type.clone());
+        self.input.set_game_type(game_type);
+    }
+
+    fn display_player_cards_to_player(&self, player: &Player) {
+        if !player.is_bot() {
+            self.input.display_player_cards_to_player(player);
+        }
+    }
+
+    fn display_community_cards_to_player(&self, community_cards: Vec<&Card>, player: &Player) {
+        if !player.is_bot() {
+            self.input.display_community_cards_to_player(community_cards, player);
+        }
+    }
+
+    fn display_other_player_up_cards_to_player(&self, other_players: Vec<&Player>, player: &Player) {
+        if !player.is_bot() {
+            self.input.display_other_player_up_cards_to_player(other_players, player);
+        }
+    }
+
+    fn display_current_player(&self, player: &Player) {
+        self.input.display_current_player(player);
+    }
+
+    fn announce_winner(&self, winner: Vec<&Player>, all_players: Vec<&Player>) {
+        self.input.announce_winner(winner, all_players);
+    }
+
+    fn display_pot(&self, pot: &Pot, all_players: Vec<&Player>) {
+        self.input.display_pot(pot, all_players);
+    }
+
+    fn display_side_pots(&self, side_pots: Vec<SidePot>, all_players: Vec<&Player>) {
+        self.input.display_side_pots(side_pots, all_players);
+    }
+
+    fn display_player_balances(&self, all_players: Vec<&Player>) {
+        self.input.display_player_balances(all_players);
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.input.announce_player_eliminated(player);
+    }
+
+    fn display_turn_summary(&self, turns: &[(Uuid, Action, usize, Vec<Card>)], players: &[&Player]) {
+        self.input.display_turn_summary(turns, players);
+    }
+
+    fn on_event(&mut self, event: GameEvent) {
+        if matches!(event, GameEvent::CommunityCardDealt { .. }) {
+            self.community_cards_dealt += 1;
+        }
+        self.input.on_event(event);
+    }
+
+    fn notify_action_timeout_seconds(&self, player: &Player, seconds_remaining: u32) {
+        if !player.is_bot() {
+            self.input.notify_action_timeout_seconds(player, seconds_remaining);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::input::test_input::TestInput;
+
+    use super::*;
+
+    fn bot_with_cards(notation: &str) -> Player {
+        let mut bot = Player::new_bot(Uuid::now_v7(), "bot".to_string(), 1000);
+        for card in notation.split_whitespace() {
+            bot.obtain_card(card.parse().unwrap());
+        }
+        return bot;
+    }
+
+    #[test]
+    fn input_action_options_strategy() {
+        let mut bot_input = BotInput::<TestInput>::new();
+        let betting_actions = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("2h 5d 7c 9s Th")), ActionOption::Fold));
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("2h 5d 7c 9s Jh")), ActionOption::Call));
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("2h 2d 7c 9s Th")), ActionOption::Call));
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("2h 2d 2c 9s Th")), ActionOption::Raise));
+        let checking_actions = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
+        assert!(matches!(bot_input.input_action_options(checking_actions, &bot_with_cards("2h 5d 7c 9s Th")), ActionOption::Check));
+    }
+
+    #[test]
+    fn request_replace_cards_keeps_best_partial_hand() {
+        let mut bot_input = BotInput::<TestInput>::new();
+        let bot = bot_with_cards("Kh Kd 7c 3s 2h");
+        let replaced_cards: Vec<Card> = bot_input.request_replace_cards(&bot).into_iter().map(|card| card.clone()).collect();
+        assert_eq!(replaced_cards, vec!["7c".parse().unwrap(), "3s".parse().unwrap(), "2h".parse().unwrap()]);
+
+        let bot = bot_with_cards("Kh 9h 7h 3h 2s");
+        let replaced_cards: Vec<Card> = bot_input.request_replace_cards(&bot).into_iter().map(|card| card.clone()).collect();
+        assert_eq!(replaced_cards, vec!["2s".parse().unwrap()]);
+
+        let bot = bot_with_cards("Ah Jd 7c 3s 2h");
+        assert_eq!(bot_input.request_replace_cards(&bot).len(), 4);
+
+        let bot = bot_with_cards("9h Td Jc Qs Kh");
+        assert_eq!(bot_input.request_replace_cards(&bot).len(), 0);
+    }
+
+    #[test]
+    fn request_replace_cards_straight_draws() {
+        let mut bot_input = BotInput::<TestInput>::new();
+        // an outside straight draw is kept
+        let bot = bot_with_cards("5h 6d 7c 8s Kh");
+        let replaced_cards: Vec<Card> = bot_input.request_replace_cards(&bot).into_iter().map(|card| card.clone()).collect();
+        assert_eq!(replaced_cards, vec!["Kh".parse().unwrap()]);
+
+        // an inside straight draw is discarded, keeping only the highest card
+        let bot = bot_with_cards("5h 6d 8c 9s Kh");
+        let replaced_cards: Vec<Card> = bot_input.request_replace_cards(&bot).into_iter().map(|card| card.clone()).collect();
+        assert_eq!(replaced_cards, vec!["5h".parse().unwrap(), "6d".parse().unwrap(), "8c".parse().unwrap(), "9s".parse().unwrap()]);
+
+        // a wheel draw is kept, since the Ace that completes it plays low
+        let bot = bot_with_cards("2h 3d 4c 5s Kh");
+        let replaced_cards: Vec<Card> = bot_input.request_replace_cards(&bot).into_iter().map(|card| card.clone()).collect();
+        assert_eq!(replaced_cards, vec!["Kh".parse().unwrap()]);
+    }
+
+    #[test]
+    fn badugi_strategy() {
+        let mut bot_input = BotInput::<TestInput>::new();
+        bot_input.set_game_type(GameType::Badugi);
+        let betting_actions = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
+        // a low badugi is raised, even though it is only a high card hand in the other games
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("Ac 3d 5h 7s")), ActionOption::Raise));
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("Ac 3d 5h Ks")), ActionOption::Call));
+        // a pair is only a three card badugi
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("Ac Ad 5h 7s")), ActionOption::Fold));
+
+        // a complete badugi stands pat, otherwise the cards that aren't part of the best badugi hand are replaced
+        assert_eq!(bot_input.request_replace_cards(&bot_with_cards("Kc Qd Jh Ts")).len(), 0);
+        let bot = bot_with_cards("Kd 4h 3c 2d");
+        let replaced_cards: Vec<Card> = bot_input.request_replace_cards(&bot).into_iter().map(|card| card.clone()).collect();
+        assert_eq!(replaced_cards, vec!["Kd".parse().unwrap()]);
+    }
+
+    #[test]
+    fn texas_holdem_preflop_strategy() {
+        let mut bot_input = BotInput::<TestInput>::new();
+        bot_input.set_game_type(GameType::TexasHoldem);
+        let betting_actions = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
+        // pocket aces win about 85% of the time heads-up, seven deuce offsuit only about 35%
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("Ah As")), ActionOption::Call));
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("7h 2s")), ActionOption::Fold));
+        // ace king high is folded by the hand rank strategy, but is well ahead of a random hand
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("Ah Ks")), ActionOption::Call));
+        let checking_actions = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
+        assert!(matches!(bot_input.input_action_options(checking_actions, &bot_with_cards("7h 2s")), ActionOption::Check));
+
+        // once the flop has been dealt, the bot goes back to its hand rank strategy
+        for card in ["Kd", "9c", "4h"] {
+            bot_input.on_event(GameEvent::CommunityCardDealt { card: card.parse().unwrap() });
+        }
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("Ah Ks")), ActionOption::Call));
+        assert!(matches!(bot_input.input_action_options(betting_actions.clone(), &bot_with_cards("8h 6s")), ActionOption::Fold));
+        // and it is back before the flop at the start of the next round
+        bot_input.set_game_type(GameType::TexasHoldem);
+        assert!(matches!(bot_input.input_action_options(betting_actions, &bot_with_cards("7h 2s")), ActionOption::Fold));
+    }
+
+    #[test]
+    fn passes_through_for_people() {
+        let mut bot_input = BotInput::<TestInput>::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        bot_input.input().set_action_option_selections(vec![ActionOption::Fold]);
+        bot_input.input().set_raise_amounts(vec![7]);
+        assert!(matches!(bot_input.input_action_options(vec![ActionOption::Call, ActionOption::Fold], &player), ActionOption::Fold));
+        assert_eq!(bot_input.request_raise_amount(100, &player), 7);
+    }
+}
@@ -1,14 +1,111 @@
 use super::*;
+use crate::card::SuitColor;
 use crate::game_type::GameType;
+use std::io::IsTerminal;
+
+/// the ANSI escape codes used to print red cards, and to go back to the terminal's own colour afterwards
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_RESET: &str = "\x1b[0m";
 
 /// CliInput is an implementation of the Input trait for processing user input
 /// via the command line interface
 /// text is shown to the user via stdout (println!), and input is received via stdin
-pub struct CliInput;
+pub struct CliInput {
+    /// print hearts and diamonds in red, black cards are left in the terminal's own colour (black or white),
+    /// on by default only when stdout is a terminal, so that piped output stays plain text
+    colored: bool
+}
+
+impl CliInput {
+    /// turn coloured cards on or off
+    pub fn set_colored(&mut self, colored: bool) {
+        self.colored = colored;
+    }
+
+    /// the text shown for a card, coloured by its suit when colours are on
+    fn format_card(&self, card: &Card) -> String {
+        return match (self.colored, card.color()) {
+            (true, SuitColor::Red) => format!("{ANSI_RED}{card}{ANSI_RESET}"),
+            _ => card.to_string(),
+        };
+    }
+
+    /// the text shown for a list of cards, separated by spaces
+    fn format_cards(&self, cards: Vec<&Card>) -> String {
+        return cards.into_iter().map(|card| self.format_card(card)).collect::<Vec<String>>().join(" ");
+    }
+
+    /// the player's cards in the order they are listed when choosing cards to replace (sorted, see Player::sorted_cards),
+    /// each with whether it is selected, the indices shown to the player are indices into this list,
+    /// and the selected cards are returned as references to the player's own cards, no matter how they are sorted
+    fn replace_card_selections<'a>(player: &'a Player) -> Vec<(bool, &'a Card)> {
+        return player.sorted_cards().into_iter().map(|card| (false, card)).collect();
+    }
+
+    /// the options in the order they are listed in the menu (see ActionOption::display_priority),
+    /// so that the menu looks the same no matter what order the options were offered in
+    fn sorted_action_options(mut possible_actions: Vec<ActionOption>) -> Vec<ActionOption> {
+        possible_actions.sort_by_key(|action_option| action_option.display_priority());
+        return possible_actions;
+    }
+    /// read a line of input, panics if the input has ended, since nothing more can be read to ask again
+    fn read_input_line<R: io::BufRead>(reader: &mut R) -> String {
+        let mut input = String::new();
+        let bytes_read = reader
+            .read_line(&mut input)
+            .expect("Failed to read line from user input");
+        assert!(bytes_read > 0, "Reached the end of the user input");
+        return input;
+    }
+
+    /// keep asking for an amount to raise by until a number between 1 and limit is entered
+    fn read_raise_amount<R: io::BufRead>(reader: &mut R, limit: u32) -> u32 {
+        loop {
+            println!("Enter amount to raise by, limit is {limit}: ");
+            match CliInput::read_input_line(reader).trim().parse::<u32>() {
+                Ok(amount) => {
+                    if amount == 0 {
+                        println!("You must enter a positive and non-zero raise amount");
+                    }
+                    else if amount > limit {
+                        println!("You must enter an amount that is at most {limit}");
+                    }
+                    else {
+                        return amount;
+                    }
+                },
+                _ => println!("You must enter a number")
+            }
+        }
+    }
+
+    /// keep asking for an amount to bet until a number between min_bet and max_bet is entered
+    fn read_bet_amount<R: io::BufRead>(reader: &mut R, min_bet: u32, max_bet: u32) -> u32 {
+        loop {
+            println!("Enter amount to bet, between {min_bet} and {max_bet}: ");
+            match CliInput::read_input_line(reader).trim().parse::<u32>() {
+                Ok(amount) => {
+                    if amount < min_bet {
+                        println!("You must bet at least {min_bet}");
+                    }
+                    else if amount > max_bet {
+                        println!("You must enter an amount that is at most {max_bet}");
+                    }
+                    else {
+                        return amount;
+                    }
+                },
+                _ => println!("You must enter a number")
+            }
+        }
+    }
+}
 
 impl Input for CliInput {
     fn new() -> Self {
-        return Self;
+        return Self {
+            colored: io::stdout().is_terminal()
+        };
     }
 
     fn request_username(&mut self) -> String {
@@ -34,7 +131,7 @@ impl Input for CliInput {
 
     fn input_variation(&mut self) -> GameType {
         loop {
-            println!("\nSelect a game:\n1 - Five Card Draw\n2 - Seven Card Stud\n3 - Texas Hold'em");
+            println!("\nSelect a game:\n1 - Five Card Draw\n2 - Seven Card Stud\n3 - Texas Hold'em\n4 - Community Card Draw\n5 - Badugi\n6 - Short Deck Hold'em");
             let mut input = String::new();
             io::stdin()
                 .read_line(&mut input)
@@ -44,13 +141,17 @@ impl Input for CliInput {
                 Ok(1) => return GameType::FiveCardDraw,
                 Ok(2) => return GameType::SevenCardStud,
                 Ok(3) => return GameType::TexasHoldem,
-                _ => println!("invalid! enter 1, 2, or 3."),
+                Ok(4) => return GameType::CommunityDraw,
+                Ok(5) => return GameType::Badugi,
+                Ok(6) => return GameType::ShortDeckHoldem,
+                _ => println!("invalid! enter 1, 2, 3, 4, 5, or 6."),
             }
         }
     }
 
     fn input_action_options(&mut self, possible_actions: Vec<ActionOption>, player: &Player) -> ActionOption {
         println!("\nPlayer: {}", player.name());
+        let possible_actions = CliInput::sorted_action_options(possible_actions);
         loop {
             println!("Select an action:");
             for (i, action) in possible_actions.iter().enumerate() {
@@ -69,36 +170,16 @@ impl Input for CliInput {
 
     fn request_raise_amount(&mut self, limit: u32, player: &Player) -> u32 {
         println!("\nPlayer: {}", player.name());
-        loop {
-            println!("Enter amount to raise by, limit is {limit}: ");
-            let mut input = String::new();
-            io::stdin()
-                .read_line(&mut input)
-                .expect("Failed to read line from user input");
+        return CliInput::read_raise_amount(&mut io::stdin().lock(), limit);
+    }
 
-            match input.trim().parse::<u32>() {
-                Ok(amount) => {
-                    if amount <= 0 {
-                        println!("You must enter a positive and non-zero raise amount");
-                    }
-                    else if amount > limit {
-                        println!("You must enter an amount that is at most {limit}");
-                    }
-                    else {
-                        return amount;
-                    }
-                },
-                _ => println!("You must enter a number")
-            }
-        }
+    fn request_bet_amount(&mut self, min_bet: u32, max_bet: u32, player: &Player) -> u32 {
+        println!("\nPlayer: {}", player.name());
+        return CliInput::read_bet_amount(&mut io::stdin().lock(), min_bet, max_bet);
     }
 
     fn request_replace_cards<'a>(&mut self, player: &'a Player) -> Vec<&'a Card> {
-        let cards = player.peek_at_cards();
-        let mut selected_cards = Vec::new();
-        for card in cards.iter() {
-            selected_cards.push((false, *card));
-        }
+        let mut selected_cards = CliInput::replace_card_selections(player);
         println!("\nPlayer: {}", player.name());
         loop {
             println!("Here are your {} cards:", selected_cards.len());
@@ -107,17 +188,14 @@ impl Input for CliInput {
                     true => "[x]",
                     false => "[ ]",
                 };
-                println!("-> {selected_marker} {card_index}: {card} <-");
+                println!("-> {selected_marker} {card_index}: {} <-", self.format_card(card));
             }
 
             println!("Selected cards (which will be replaced) are marked with [x]");
             println!("Select a card to be replaced, or");
             println!("x: finish");
 
-            let mut input = String::new();
-            io::stdin()
-                .read_line(&mut input)
-                .expect("Failed to read line from user input");
+            let input = CliInput::read_input_line(&mut io::stdin().lock());
 
             match input.trim() {
                 "x" => break,
@@ -132,7 +210,7 @@ impl Input for CliInput {
                                 selected_cards[value as usize].0 = !selected_cards[value as usize].0;
                             }
                         },
-                        Err(_) => println!("Invalid selection"),
+                        Err(_) => println!("Invalid selection\nYou must enter the number of one of your cards, or x to finish"),
                     }
                 }
             }
@@ -144,24 +222,88 @@ impl Input for CliInput {
             .collect();
     }
 
+    fn request_show_or_muck(&mut self, player: &Player) -> bool {
+        println!("\nPlayer: {}", player.name());
+        println!("Your hand is beaten by a hand that has already been shown");
+        loop {
+            println!("Select an option:\n0 - Show your cards\n1 - Muck your cards");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<usize>() {
+                Ok(0) => return true,
+                Ok(1) => return false,
+                _ => println!("invalid input, please enter 0 or 1:"),
+            }
+        }
+    }
+
+    fn request_run_it_twice(&mut self, player: &Player) -> bool {
+        println!("\nPlayer: {}", player.name());
+        println!("Everyone is all in, the remaining cards can be dealt twice, with half of the pot going to the winner of each");
+        loop {
+            println!("Select an option:\n0 - Run it twice\n1 - Run it once");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<usize>() {
+                Ok(0) => return true,
+                Ok(1) => return false,
+                _ => println!("invalid input, please enter 0 or 1:"),
+            }
+        }
+    }
+
+    fn request_straddle(&mut self, player: &Player, straddle_amount: u32) -> bool {
+        println!("\nPlayer: {}", player.name());
+        println!("You can straddle, posting a blind of {straddle_amount} before the cards are dealt, and acting last before the flop");
+        loop {
+            println!("Select an option:\n0 - Straddle\n1 - Don't straddle");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<usize>() {
+                Ok(0) => return true,
+                Ok(1) => return false,
+                _ => println!("invalid input, please enter 0 or 1:"),
+            }
+        }
+    }
+
+    fn request_undo_turn(&mut self, player: &Player, action: &Action) -> bool {
+        println!("\nPlayer: {}", player.name());
+        println!("You chose {action}");
+        loop {
+            println!("Select an option:\n0 - Keep it\n1 - Undo (take it back and choose again)");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<usize>() {
+                Ok(0) => return false,
+                Ok(1) => return true,
+                _ => println!("invalid input, please enter 0 or 1:"),
+            }
+        }
+    }
+
     fn display_current_player(&self, player: &Player) {
         println!("\nIt is now {}'s turn", player.name());
     }
 
     fn display_player_cards_to_player(&self, player: &Player) {
-        let cards = player.peek_at_cards();
+        let cards: Vec<&Card> = player.sorted_cards();
         println!("\nPlayer: {},", player.name());
         println!("Here are your {} cards:", cards.len());
-        for card in cards {
-            println!("-> {card} <-");
-        }
+        println!("-> {} <-", self.format_cards(cards));
     }
 
     fn display_community_cards_to_player(&self, community_cards: Vec<&Card>, _player: &Player) {
         println!("\nHere are the community cards:");
-        for card in community_cards {
-            println!("-> {card} <-");
-        }
+        println!("-> {} <-", self.format_cards(community_cards));
     }
 
     fn display_other_player_up_cards_to_player(&self, other_players: Vec<&Player>, player: &Player) {
@@ -169,11 +311,9 @@ impl Input for CliInput {
         println!("\nPlayer: {},", player.name());
         println!("Here are the other {} players' up cards:", other_players.len());
         for other_player in other_players {
-            let up_cards: Vec<&Card> = other_player.peek_at_cards().into_iter().filter(|card| card.is_face_up()).collect();
+            let up_cards: Vec<&Card> = other_player.peek_at_up_cards();
             println!("\tPlayer {}'s up cards:", other_player.name());
-            for up_card in up_cards {
-                println!("\t-> {up_card} <-");
-            }
+            println!("\t-> {} <-", self.format_cards(up_cards));
         }
     }
 
@@ -190,13 +330,135 @@ impl Input for CliInput {
         }
     }
 
-    fn display_pot(&self, pot_amount: u32, _all_players: Vec<&Player>) {
-        println!("\nThe pot currently holds {pot_amount}");
+    fn display_pot(&self, pot: &Pot, all_players: Vec<&Player>) {
+        println!("\nThe pot currently holds {}", pot.get_total_stake());
+        for phase_num in pot.phases_played() {
+            let actions: Vec<String> = pot.get_phase_history(phase_num).iter()
+                .map(|(player_id, action, _, _)| {
+                    let name = all_players.iter()
+                        .find(|player| player.account_id() == *player_id)
+                        .map_or(player_id.to_string(), |player| player.name().to_string());
+                    format!("{} {}", name, action.public_description())
+                })
+                .collect();
+            println!("Phase {}: {}", phase_num, actions.join(", "));
+        }
+    }
+
+    fn display_side_pots(&self, side_pots: Vec<SidePot>, all_players: Vec<&Player>) {
+        for (side_pot_index, side_pot) in side_pots.iter().enumerate() {
+            let eligible_player_names: Vec<&str> = all_players.iter()
+                .filter(|player| side_pot.eligible_player_ids.contains(&player.account_id()))
+                .map(|player| player.name())
+                .collect();
+            match side_pot_index {
+                0 => println!("Main pot: {}, eligible players: {}", side_pot.amount, eligible_player_names.join(", ")),
+                _ => println!("Side pot {}: {}, eligible players: {}", side_pot_index, side_pot.amount, eligible_player_names.join(", ")),
+            }
+        }
     }
 
     fn display_player_balances(&self, all_players: Vec<&Player>) {
         for player in all_players {
-            println!("Player: {}, has balance: {}", player.name(), player.balance());
+            println!("Player: {player}");
         }
     }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        println!("\n{} has run out of money, and has been eliminated!", player.name());
+    }
+
+    fn display_turn_summary(&self, turns: &[(Uuid, Action, usize, Vec<Card>)], players: &[&Player]) {
+        println!("\nThe betting phase is over:");
+        for (player_id, action, _, _) in turns {
+            let name = players.iter()
+                .find(|player| player.account_id() == *player_id)
+                .map_or(player_id.to_string(), |player| player.name().to_string());
+            println!("{} {}", name, action.public_description());
+        }
+    }
+
+    fn on_event(&mut self, _event: GameEvent) {
+        // the command line only shows what the display methods print
+    }
+
+    fn notify_action_timeout_seconds(&self, player: &Player, seconds_remaining: u32) {
+        // go back to the start of the line, so each second overwrites the last
+        print!("\r{} has {} seconds left to act ", player.name(), seconds_remaining);
+        if seconds_remaining == 0 {
+            println!();
+        }
+        let _ = io::Write::flush(&mut io::stdout());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_raise_amount_reprompts() {
+        // not a number, zero, and over the limit are all asked again
+        let mut input = io::Cursor::new("lots\n0\n500\n-5\n 40 \n");
+        assert_eq!(CliInput::read_raise_amount(&mut input, 100), 40);
+        let mut input = io::Cursor::new("100\n");
+        assert_eq!(CliInput::read_raise_amount(&mut input, 100), 100);
+    }
+
+    #[test]
+    fn read_bet_amount_reprompts() {
+        let mut input = io::Cursor::new("1\n\n21\n2.5\n20\n");
+        assert_eq!(CliInput::read_bet_amount(&mut input, 2, 20), 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reached the end of the user input")]
+    fn read_raise_amount_end_of_input() {
+        CliInput::read_raise_amount(&mut io::Cursor::new("500\n"), 100);
+    }
+
+    #[test]
+    fn sorted_action_options_check_raise_fold() {
+        let expected = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
+        assert_eq!(CliInput::sorted_action_options(vec![ActionOption::Fold, ActionOption::Raise, ActionOption::Check]), expected);
+        assert_eq!(CliInput::sorted_action_options(vec![ActionOption::Raise, ActionOption::Fold, ActionOption::Check]), expected);
+        assert_eq!(CliInput::sorted_action_options(expected.clone()), expected);
+    }
+
+    #[test]
+    fn replace_card_selections_target_the_chosen_card() {
+        let mut player = Player::new(uuid::Uuid::now_v7(), "player".to_string(), 1000);
+        for card in ["Kh", "2c", "As", "7d"] {
+            player.obtain_card(card.parse().unwrap());
+        }
+        let mut selected_cards = CliInput::replace_card_selections(&player);
+        // the 2c is listed first, even though it was the second card obtained
+        assert_eq!(selected_cards[0].1.to_notation(), "2c");
+        selected_cards[0].0 = true;
+        selected_cards[3].0 = true;
+        let cards_to_replace: Vec<&Card> = selected_cards.iter().filter(|(is_selected, _)| *is_selected).map(|(_, card)| *card).collect();
+        let cards_to_replace: Vec<String> = cards_to_replace.iter().map(|card| card.to_notation()).collect();
+        assert_eq!(cards_to_replace, vec!["2c", "As"]);
+        // the player's cards stay in the order they were obtained
+        let cards: Vec<String> = player.peek_at_cards().iter().map(|card| card.to_notation()).collect();
+        assert_eq!(cards, vec!["Kh", "2c", "As", "7d"]);
+    }
+
+    #[test]
+    fn format_heart_in_red() {
+        let heart: Card = "Qh".parse().unwrap();
+        let mut cli_input = CliInput::new();
+        cli_input.set_colored(true);
+        assert!(cli_input.format_card(&heart).contains("\x1b[31m"));
+    }
+
+    #[test]
+    fn format_cards_with_colors() {
+        let cards: Vec<Card> = ["Ah", "Ks"].iter().map(|notation| notation.parse::<Card>().unwrap()).collect();
+        let mut cli_input = CliInput::new();
+        cli_input.set_colored(true);
+        assert_eq!(cli_input.format_cards(cards.iter().collect()), "\x1b[31mA♥ [face down]\x1b[0m K♠ [face down]");
+        cli_input.set_colored(false);
+        assert_eq!(cli_input.format_cards(cards.iter().collect()), "A♥ [face down] K♠ [face down]");
+    }
 }
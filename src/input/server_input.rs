@@ -1,18 +1,291 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::future::Future;
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
+use crate::server::http_requests::{LobbyEvent, LobbyUpdate, TurnSummary};
+
 use super::*;
 
+/// how often the submitted actions are checked while waiting for a player to respond
+const POLL_INTERVAL_MILLISECONDS: u64 = 100;
+/// the most messages kept for a player whose client isn't collecting them, the oldest are dropped first
+const MAX_QUEUED_MESSAGES: usize = 100;
+
 /// Implementation of the Input trait for server-client interaction
 /// Each method that requires user input must first send the new data to the client,
 /// and then wait until the client responds (or a timeout) before returning.
 /// The display methods (the ones that don't return anything) don't need to
 /// wait for any response from the client.
 #[derive(Clone)]
-pub struct ServerInput;
+pub struct ServerInput {
+    timeout_seconds: u64,
+    /// extra time each player starts with, which they can spend on turns that take longer than the timeout
+    time_bank: Duration,
+    /// time left in the bank of each player who has spent some of it
+    time_banks: Arc<Mutex<HashMap<Uuid, Duration>>>,
+    submitted_actions: Arc<Mutex<HashMap<Uuid, ActionOption>>>,
+    /// bet amounts submitted by clients, waiting to be picked up by request_bet_amount
+    submitted_amounts: Arc<Mutex<HashMap<Uuid, u32>>>,
+    /// yes or no answers submitted by clients, waiting to be picked up by request_choice
+    submitted_choices: Arc<Mutex<HashMap<Uuid, bool>>>,
+    offered_actions: Arc<Mutex<HashMap<Uuid, String>>>,
+    disconnected_players: Arc<Mutex<HashSet<Uuid>>>,
+    /// players who left the lobby during the hand being played, they are folded at their turns until it is over
+    left_players: Arc<Mutex<HashSet<Uuid>>>,
+    /// messages from the display methods, waiting to be collected by each player's client (see take_messages_json).
+    /// a player is only ever sent their own down cards, everyone else's cards are only sent once face up
+    messages: Arc<Mutex<HashMap<Uuid, Vec<serde_json::Value>>>>,
+    /// the lobby that this input's game is played in, and the channel that its game events and action timer ticks are broadcast on
+    lobby_events: Arc<Mutex<Option<(u32, broadcast::Sender<LobbyEvent>)>>>,
+}
+
+impl ServerInput {
+    /// the input with how many seconds a player has to respond before they are automatically folded
+    pub fn set_timeout(mut self, timeout_seconds: u64) -> Self {
+        self.timeout_seconds = timeout_seconds;
+        return self;
+    }
+
+    /// set how much extra time each player has in their time bank, which they can spend on turns that take longer
+    /// than the timeout, so they are only folded once the timeout and the rest of their bank have run out.
+    /// Time that is spent from the bank is not given back
+    pub fn set_time_bank(&mut self, time_bank: Duration) {
+        self.time_bank = time_bank;
+    }
+
+    /// the time left in a player's time bank (see set_time_bank)
+    pub fn time_bank(&self, player_id: Uuid) -> Duration {
+        return *self.time_banks.lock().unwrap().get(&player_id).unwrap_or(&self.time_bank);
+    }
+
+    /// take the time that a turn went over the timeout out of the player's time bank
+    fn spend_time_bank(&self, player_id: Uuid, turn_time: Duration) {
+        let overtime = turn_time.saturating_sub(Duration::from_secs(self.timeout_seconds));
+        if overtime > Duration::ZERO {
+            let time_bank = self.time_bank(player_id).saturating_sub(overtime);
+            self.time_banks.lock().unwrap().insert(player_id, time_bank);
+        }
+    }
+
+    /// broadcast the game events and action timer ticks to the clients watching a lobby's events (see on_event and notify_action_timeout_seconds)
+    pub fn set_lobby_events(&self, lobby_id: u32, lobby_events: broadcast::Sender<LobbyEvent>) {
+        *self.lobby_events.lock().unwrap() = Some((lobby_id, lobby_events));
+    }
+
+    /// store an action option submitted by a client (over HTTP) for a player,
+    /// it will be picked up the next time that player is polled for an action
+    pub fn submit_action(&self, player_id: Uuid, action_option: ActionOption) {
+        self.submitted_actions.lock().unwrap().insert(player_id, action_option);
+    }
+
+    /// store a bet amount submitted by a client (over HTTP) for a player,
+    /// it will be picked up the next time that player is asked for an amount to bet
+    pub fn submit_amount(&self, player_id: Uuid, amount: u32) {
+        self.submitted_amounts.lock().unwrap().insert(player_id, amount);
+    }
+
+    /// store a yes or no answer submitted by a client (over HTTP) for a player,
+    /// it will be picked up the next time that player is asked a yes or no question, e.g. whether to show their hand
+    pub fn submit_choice(&self, player_id: Uuid, choice: bool) {
+        self.submitted_choices.lock().unwrap().insert(player_id, choice);
+    }
+
+    /// parse an action option submitted by a client as JSON (e.g. "call") and store it for a player,
+    /// returns Err if the response is not a valid action option
+    pub fn submit_action_json(&self, player_id: Uuid, response: &str) -> Result<(), serde_json::Error> {
+        let action_option: ActionOption = serde_json::from_str(response)?;
+        self.submit_action(player_id, action_option);
+        return Ok(());
+    }
+
+    /// get the action options currently offered to a player as a JSON array (e.g. ["check","raise","fold"]),
+    /// or None if the player is not being asked for an action
+    pub fn offered_actions(&self, player_id: Uuid) -> Option<String> {
+        return self.offered_actions.lock().unwrap().get(&player_id).cloned();
+    }
+
+    /// whether a player stopped responding, players who are disconnected
+    /// are folded straight away instead of waiting for them to time out again
+    pub fn is_disconnected(&self, player_id: Uuid) -> bool {
+        return self.disconnected_players.lock().unwrap().contains(&player_id);
+    }
+
+    /// reattach a player whose connection dropped, so that they are asked for their actions again.
+    /// returns the action options they are currently being offered as JSON (see offered_actions), so they can be re-sent,
+    /// or None if they are not being asked for an action, e.g. because they were folded while they were disconnected,
+    /// in which case they resume playing from the next hand
+    pub fn reconnect(&self, player_id: Uuid) -> Option<String> {
+        self.disconnected_players.lock().unwrap().remove(&player_id);
+        // anything submitted before reconnecting was for a prompt that has already been answered
+        self.submitted_actions.lock().unwrap().remove(&player_id);
+        self.submitted_amounts.lock().unwrap().remove(&player_id);
+        self.submitted_choices.lock().unwrap().remove(&player_id);
+        return self.offered_actions(player_id);
+    }
+
+    /// fold a player who left the lobby in the middle of a hand at each of their turns for the rest of it,
+    /// including the turn they are being asked for right now, so that the chips they have already bet stay in the pot
+    pub fn fold_left_player(&self, player_id: Uuid) {
+        self.left_players.lock().unwrap().insert(player_id);
+    }
+
+    /// whether a player left the lobby during the hand being played (see fold_left_player)
+    pub fn has_left(&self, player_id: Uuid) -> bool {
+        return self.left_players.lock().unwrap().contains(&player_id);
+    }
+
+    /// forget the players who left during the hand that just finished, once they have been removed from the lobby,
+    /// along with the messages that were waiting for them
+    pub fn clear_left_players(&self) {
+        let mut messages = self.messages.lock().unwrap();
+        for player_id in self.left_players.lock().unwrap().drain() {
+            messages.remove(&player_id);
+        }
+    }
+
+    /// take the messages waiting to be sent to a player's client, as a JSON array,
+    /// the client collects them through the server's messages route
+    pub fn take_messages_json(&self, player_id: Uuid) -> String {
+        let messages = self.messages.lock().unwrap().remove(&player_id).unwrap_or_default();
+        return serde_json::to_string(&messages).expect("Expected messages to serialize to JSON");
+    }
+
+    /// queue a message to be sent to a player's client, dropping the oldest one if the queue is full
+    fn send_to_player(&self, player_id: Uuid, message: serde_json::Value) {
+        let mut messages = self.messages.lock().unwrap();
+        let player_messages = messages.entry(player_id).or_default();
+        if player_messages.len() >= MAX_QUEUED_MESSAGES {
+            player_messages.remove(0);
+        }
+        player_messages.push(message);
+    }
+
+    /// queue a message to be sent to each of the players' clients
+    fn send_to_players(&self, players: &[&Player], message: serde_json::Value) {
+        for player in players {
+            self.send_to_player(player.account_id(), message.clone());
+        }
+    }
+
+    /// the cards of another player that a player is allowed to see, which are only the face up ones,
+    /// so face down cards (including those of players who folded or mucked) never leave the server
+    fn up_cards(player: &Player) -> Vec<&Card> {
+        return player.peek_at_up_cards();
+    }
+
+    /// take the action submitted for this player, if there is one and it is one of the possible actions
+    fn take_submitted_action(&self, possible_actions: &Vec<ActionOption>, player: &Player) -> Option<ActionOption> {
+        let mut submitted_actions = self.submitted_actions.lock().unwrap();
+        return match submitted_actions.remove(&player.account_id()) {
+            Some(action_option) if possible_actions.iter().any(|possible_action| std::mem::discriminant(possible_action) == std::mem::discriminant(&action_option)) => Some(action_option),
+            Some(action_option) => {
+                println!("Player {} submitted {}, which is not one of the possible actions", player.name(), action_option);
+                None
+            },
+            None => None,
+        };
+    }
+
+    /// the Input trait is synchronous, so block on a polling loop until it finishes.
+    /// On a multi threaded runtime, this doesn't stall the runtime that the game itself is running on.
+    /// block_in_place isn't allowed on a current thread runtime (which #[tokio::test] uses by default),
+    /// so there the loop runs on a runtime of its own on another thread, and the calling thread is blocked until it finishes.
+    /// If that is the runtime's only thread, nothing else on the runtime (like the requests submitting the responses) runs meanwhile,
+    /// which is why the server plays its hands on blocking threads (see ServerState::start_game)
+    fn block_on<F: Future + Send>(future: F) -> F::Output where F::Output: Send {
+        let new_runtime = || tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("Failed to build a runtime to poll for the player's response");
+        return match tokio::runtime::Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::CurrentThread => std::thread::scope(|scope| {
+                scope.spawn(|| new_runtime().block_on(future)).join().expect("Polling for the player's response panicked")
+            }),
+            Ok(handle) => tokio::task::block_in_place(|| handle.block_on(future)),
+            Err(_) => new_runtime().block_on(future),
+        };
+    }
+
+    /// take the amount submitted for this player, if there is one, limited to between min_amount and max_amount
+    fn take_submitted_amount(&self, min_amount: u32, max_amount: u32, player: &Player) -> Option<u32> {
+        return self.submitted_amounts.lock().unwrap().remove(&player.account_id()).map(|amount| amount.clamp(min_amount, max_amount));
+    }
+
+    /// ask a player a yes or no question, sent to their client as a message with the question's name,
+    /// and poll for their answer, players who are disconnected, leave or don't answer in time get the default answer
+    fn request_choice(&self, player: &Player, question: &str, default: bool) -> bool {
+        if self.is_disconnected(player.account_id()) || self.has_left(player.account_id()) {
+            return default;
+        }
+        self.send_to_player(player.account_id(), json!({ question: true }));
+        let take_submitted_choice = || self.submitted_choices.lock().unwrap().remove(&player.account_id());
+        let response = Self::block_on(self.poll_response(player, take_submitted_choice));
+        return match response {
+            Some(choice) => choice,
+            None if self.has_left(player.account_id()) => default,
+            None => {
+                println!("Player {} did not answer {} within {} seconds or their time bank, and answers {}", player.name(), question, self.timeout_seconds, default);
+                self.disconnected_players.lock().unwrap().insert(player.account_id());
+                default
+            },
+        };
+    }
+
+    /// poll for the player's submitted action until one arrives, the player leaves, or the timeout and their time bank have run out,
+    /// returns None if the player did not respond in time or left
+    async fn poll_action(&self, possible_actions: &Vec<ActionOption>, player: &Player) -> Option<ActionOption> {
+        return self.poll_response(player, || self.take_submitted_action(possible_actions, player)).await;
+    }
+
+    /// poll for a response submitted for the player (taken with take_response) until one arrives, the player leaves,
+    /// or the timeout and their time bank have run out, returns None if the player did not respond in time or left
+    async fn poll_response<T>(&self, player: &Player, take_response: impl Fn() -> Option<T>) -> Option<T> {
+        let time_limit = Duration::from_secs(self.timeout_seconds) + self.time_bank(player.account_id());
+        let start = tokio::time::Instant::now();
+        let polling_loop = async {
+            let mut last_seconds_remaining = None;
+            loop {
+                if let Some(response) = take_response() {
+                    return Some(response);
+                }
+                if self.has_left(player.account_id()) {
+                    return None;
+                }
+                // count down once a second, rather than on every poll
+                let seconds_remaining = time_limit.as_secs().saturating_sub(start.elapsed().as_secs()) as u32;
+                if last_seconds_remaining != Some(seconds_remaining) {
+                    self.notify_action_timeout_seconds(player, seconds_remaining);
+                    last_seconds_remaining = Some(seconds_remaining);
+                }
+                tokio::time::sleep(Duration::from_millis(POLL_INTERVAL_MILLISECONDS)).await;
+            }
+        };
+        let response = tokio::time::timeout(time_limit, polling_loop).await.ok().flatten();
+        self.spend_time_bank(player.account_id(), start.elapsed());
+        return response;
+    }
+}
 
 impl Input for ServerInput {
     fn new() -> Self {
-        return Self;
+        return ServerInput {
+            timeout_seconds: 30,
+            time_bank: Duration::ZERO,
+            time_banks: Arc::new(Mutex::new(HashMap::new())),
+            submitted_actions: Arc::new(Mutex::new(HashMap::new())),
+            submitted_amounts: Arc::new(Mutex::new(HashMap::new())),
+            submitted_choices: Arc::new(Mutex::new(HashMap::new())),
+            offered_actions: Arc::new(Mutex::new(HashMap::new())),
+            disconnected_players: Arc::new(Mutex::new(HashSet::new())),
+            left_players: Arc::new(Mutex::new(HashSet::new())),
+            messages: Arc::new(Mutex::new(HashMap::new())),
+            lobby_events: Arc::new(Mutex::new(None)),
+        };
     }
 
     fn request_username(&mut self) -> String {
@@ -24,42 +297,491 @@ impl Input for ServerInput {
     }
 
     fn input_action_options(&mut self, possible_actions: Vec<ActionOption>, player: &Player) -> ActionOption {
-        todo!()
+        if self.is_disconnected(player.account_id()) {
+            println!("Player {} is disconnected, and has been folded", player.name());
+            return ActionOption::Fold;
+        }
+        if self.has_left(player.account_id()) {
+            println!("Player {} has left the lobby, and has been folded", player.name());
+            return ActionOption::Fold;
+        }
+
+        // the possible actions are sent to the client as JSON, and its choice comes back through submit_action_json
+        let offered_actions = serde_json::to_string(&possible_actions).expect("Failed to serialize the possible actions");
+        self.offered_actions.lock().unwrap().insert(player.account_id(), offered_actions);
+
+        let response = Self::block_on(self.poll_action(&possible_actions, player));
+        self.offered_actions.lock().unwrap().remove(&player.account_id());
+        return match response {
+            Some(action_option) => action_option,
+            None if self.has_left(player.account_id()) => ActionOption::Fold,
+            None => {
+                println!("Player {} did not respond within {} seconds or their time bank, and has been folded", player.name(), self.timeout_seconds);
+                // don't make the other players wait for them again until they reconnect
+                self.disconnected_players.lock().unwrap().insert(player.account_id());
+                ActionOption::Fold
+            },
+        };
     }
 
     fn request_raise_amount(&mut self, limit: u32, player: &Player) -> u32 {
         todo!()
     }
 
+    fn request_bet_amount(&mut self, min_bet: u32, max_bet: u32, player: &Player) -> u32 {
+        if self.is_disconnected(player.account_id()) || self.has_left(player.account_id()) {
+            return min_bet;
+        }
+        self.send_to_player(player.account_id(), json!({ "bet_amount": { "min": min_bet, "max": max_bet } }));
+        let response = Self::block_on(self.poll_response(player, || self.take_submitted_amount(min_bet, max_bet, player)));
+        return match response {
+            Some(bet_amount) => bet_amount,
+            None if self.has_left(player.account_id()) => min_bet,
+            None => {
+                // the player already chose to bet, so they bet the least they can rather than being folded
+                println!("Player {} did not choose an amount to bet within {} seconds or their time bank, and bets {}", player.name(), self.timeout_seconds, min_bet);
+                self.disconnected_players.lock().unwrap().insert(player.account_id());
+                min_bet
+            },
+        };
+    }
+
     fn request_replace_cards<'a>(&mut self, player: &'a Player) -> Vec<&'a Card> {
         todo!()
     }
 
+    fn request_show_or_muck(&mut self, player: &Player) -> bool {
+        // a player who doesn't answer shows their hand, as if it had been tabled
+        return self.request_choice(player, "show_or_muck", true);
+    }
+
+    fn request_run_it_twice(&mut self, player: &Player) -> bool {
+        // the board is only run twice if everyone agrees, so a player who doesn't answer declines
+        return self.request_choice(player, "run_it_twice", false);
+    }
+
+    fn request_straddle(&mut self, _player: &Player, _straddle_amount: u32) -> bool {
+        // clients can't be offered a straddle yet, so server players always decline
+        return false;
+    }
+
     fn display_player_cards_to_player(&self, player: &Player) {
-        todo!()
+        self.send_to_player(player.account_id(), json!({ "your_cards": player.peek_at_cards() }));
     }
 
     fn display_community_cards_to_player(&self, community_cards: Vec<&Card>, player: &Player) {
-        todo!()
+        self.send_to_player(player.account_id(), json!({ "community_cards": community_cards }));
     }
 
     fn display_other_player_up_cards_to_player(&self, other_players: Vec<&Player>, player: &Player) {
-        todo!()
+        let other_players: Vec<serde_json::Value> = other_players.into_iter()
+            .filter(|other_player| other_player.account_id() != player.account_id())
+            .map(|other_player| json!({
+                "player_id": other_player.account_id(),
+                "name": other_player.name(),
+                "up_cards": Self::up_cards(other_player),
+            }))
+            .collect();
+        self.send_to_player(player.account_id(), json!({ "other_players": other_players }));
     }
 
     fn display_current_player(&self, player: &Player) {
-        todo!()
+        self.send_to_player(player.account_id(), json!({ "current_player": player.account_id() }));
     }
 
     fn announce_winner(&self, winner: Vec<&Player>, all_players: Vec<&Player>) {
-        todo!()
+        let winner_ids: Vec<Uuid> = winner.iter().map(|player| player.account_id()).collect();
+        self.send_to_players(&all_players, json!({ "winners": winner_ids }));
     }
 
-    fn display_pot(&self, pot_amount: u32, all_players: Vec<&Player>) {
-        todo!()
+    fn display_pot(&self, pot: &Pot, all_players: Vec<&Player>) {
+        let phases: Vec<serde_json::Value> = pot.phases_played().into_iter()
+            .map(|phase_num| json!({
+                "phase_num": phase_num,
+                "actions": pot.get_phase_history(phase_num).iter()
+                    .map(|(player_id, action, _, _)| json!({ "player_id": player_id, "action": action.public_description() }))
+                    .collect::<Vec<serde_json::Value>>(),
+            }))
+            .collect();
+        self.send_to_players(&all_players, json!({ "pot": pot.get_total_stake(), "phases": phases }));
+    }
+
+    fn display_side_pots(&self, side_pots: Vec<SidePot>, all_players: Vec<&Player>) {
+        let side_pots: Vec<serde_json::Value> = side_pots.iter()
+            .map(|side_pot| json!({ "amount": side_pot.amount, "eligible_player_ids": side_pot.eligible_player_ids }))
+            .collect();
+        self.send_to_players(&all_players, json!({ "side_pots": side_pots }));
     }
 
     fn display_player_balances(&self, all_players: Vec<&Player>) {
-        todo!()
+        let balances: Vec<serde_json::Value> = all_players.iter()
+            .map(|player| json!({ "player_id": player.account_id(), "balance": player.balance() }))
+            .collect();
+        self.send_to_players(&all_players, json!({ "balances": balances }));
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.send_to_player(player.account_id(), json!({ "eliminated": player.account_id() }));
+    }
+
+    fn display_turn_summary(&self, turns: &[(Uuid, Action, usize, Vec<Card>)], _players: &[&Player]) {
+        if let Some((lobby_id, lobby_events)) = self.lobby_events.lock().unwrap().as_ref() {
+            // the whole lobby watches this stream, so each player's cards are left out of their turns
+            let turns = turns.iter()
+                .map(|(player_id, action, _, _)| TurnSummary { player_id: player_id.to_string(), action: action.public_description() })
+                .collect();
+            // sending only fails when nobody is watching the lobby, which is fine
+            let _ = lobby_events.send(LobbyEvent { lobby_id: *lobby_id, update: LobbyUpdate::PhaseSummary { turns } });
+        }
+    }
+
+    fn on_event(&mut self, event: GameEvent) {
+        if let Some((lobby_id, lobby_events)) = self.lobby_events.lock().unwrap().as_ref() {
+            // the whole lobby watches this stream, which is fine since the events never include face down cards
+            let _ = lobby_events.send(LobbyEvent { lobby_id: *lobby_id, update: LobbyUpdate::GameEvent { event } });
+        }
+    }
+
+    fn notify_action_timeout_seconds(&self, player: &Player, seconds_remaining: u32) {
+        if let Some((lobby_id, lobby_events)) = self.lobby_events.lock().unwrap().as_ref() {
+            let update = LobbyUpdate::ActionTimerTick { player_id: player.account_id().to_string(), seconds_remaining };
+            // sending only fails when nobody is watching the lobby, which is fine
+            let _ = lobby_events.send(LobbyEvent { lobby_id: *lobby_id, update });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn input_action_options_timeout_folds() {
+        let mut server_input = ServerInput::new().set_timeout(1);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        let action_option = server_input.input_action_options(vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold], &player);
+
+        assert!(matches!(action_option, ActionOption::Fold));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn input_action_options_submitted_action() {
+        let mut server_input = ServerInput::new().set_timeout(5);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        let client_input = server_input.clone();
+        let player_id = player.account_id();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            client_input.submit_action(player_id, ActionOption::Check);
+        });
+
+        let action_option = server_input.input_action_options(vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold], &player);
+
+        assert!(matches!(action_option, ActionOption::Check));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn input_action_options_json_round_trip() {
+        let mut server_input = ServerInput::new().set_timeout(5);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        let client_input = server_input.clone();
+        let player_id = player.account_id();
+        let client = tokio::spawn(async move {
+            // wait until the options have been offered, then respond with one of them
+            let offered_actions = loop {
+                if let Some(offered_actions) = client_input.offered_actions(player_id) {
+                    break offered_actions;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            };
+            assert!(client_input.submit_action_json(player_id, "\"bogus\"").is_err());
+            client_input.submit_action_json(player_id, "\"raise\"").unwrap();
+            return offered_actions;
+        });
+
+        let action_option = server_input.input_action_options(vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold], &player);
+
+        assert_eq!(action_option, ActionOption::Raise);
+        assert_eq!(client.await.unwrap(), "[\"check\",\"raise\",\"fold\"]");
+        assert_eq!(server_input.offered_actions(player_id), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn left_player_folded_while_being_asked() {
+        let mut server_input = ServerInput::new().set_timeout(30);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        let possible_actions = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
+
+        let client_input = server_input.clone();
+        let player_id = player.account_id();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            client_input.fold_left_player(player_id);
+        });
+
+        // folded as soon as they leave, rather than after the timeout
+        let start = std::time::Instant::now();
+        assert_eq!(server_input.input_action_options(possible_actions.clone(), &player), ActionOption::Fold);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!server_input.is_disconnected(player_id));
+        // and at their later turns, until the hand is over
+        server_input.submit_action(player_id, ActionOption::Check);
+        assert_eq!(server_input.input_action_options(possible_actions, &player), ActionOption::Fold);
+        server_input.clear_left_players();
+        assert!(!server_input.has_left(player_id));
+    }
+
+    #[test]
+    fn queued_messages_are_bounded() {
+        let server_input = ServerInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        for message_number in 0..MAX_QUEUED_MESSAGES + 5 {
+            server_input.send_to_player(player.account_id(), json!({ "message_number": message_number }));
+        }
+        // the oldest messages are dropped once the queue is full
+        let messages: serde_json::Value = serde_json::from_str(&server_input.take_messages_json(player.account_id())).unwrap();
+        assert_eq!(messages.as_array().unwrap().len(), MAX_QUEUED_MESSAGES);
+        assert_eq!(messages[0], json!({ "message_number": 5 }));
+
+        // and the messages of a player who left are dropped once the hand is over
+        server_input.send_to_player(player.account_id(), json!({ "message_number": 0 }));
+        server_input.fold_left_player(player.account_id());
+        server_input.clear_left_players();
+        assert_eq!(server_input.take_messages_json(player.account_id()), "[]");
+    }
+
+    #[tokio::test]
+    async fn input_action_options_current_thread_runtime() {
+        // #[tokio::test] runs on a current thread runtime, where block_in_place isn't allowed
+        let mut server_input = ServerInput::new().set_timeout(1);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        server_input.submit_action(player.account_id(), ActionOption::Check);
+        assert_eq!(server_input.input_action_options(vec![ActionOption::Check, ActionOption::Fold], &player), ActionOption::Check);
+        assert_eq!(server_input.input_action_options(vec![ActionOption::Check, ActionOption::Fold], &player), ActionOption::Fold);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_bet_amount_submitted_or_timed_out() {
+        let mut server_input = ServerInput::new().set_timeout(1);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        // amounts are limited to the range the player was offered
+        server_input.submit_amount(player.account_id(), 5000);
+        assert_eq!(server_input.request_bet_amount(2, 100, &player), 100);
+        server_input.submit_amount(player.account_id(), 50);
+        assert_eq!(server_input.request_bet_amount(2, 100, &player), 50);
+        let messages: serde_json::Value = serde_json::from_str(&server_input.take_messages_json(player.account_id())).unwrap();
+        assert_eq!(messages[0]["bet_amount"], json!({ "min": 2, "max": 100 }));
+
+        // a player who doesn't choose an amount bets the least they can
+        assert_eq!(server_input.request_bet_amount(2, 100, &player), 2);
+        assert!(server_input.is_disconnected(player.account_id()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_show_or_muck_submitted_or_timed_out() {
+        let mut server_input = ServerInput::new().set_timeout(1);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        server_input.submit_choice(player.account_id(), false);
+        assert!(!server_input.request_show_or_muck(&player));
+        let messages: serde_json::Value = serde_json::from_str(&server_input.take_messages_json(player.account_id())).unwrap();
+        assert_eq!(messages[0], json!({ "show_or_muck": true }));
+
+        // a player who doesn't answer shows their hand
+        assert!(server_input.request_show_or_muck(&player));
+        assert!(server_input.is_disconnected(player.account_id()));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_run_it_twice_submitted_or_timed_out() {
+        let mut server_input = ServerInput::new().set_timeout(1);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        server_input.submit_choice(player.account_id(), true);
+        assert!(server_input.request_run_it_twice(&player));
+        // a player who doesn't answer declines
+        assert!(!server_input.request_run_it_twice(&player));
+        assert!(server_input.is_disconnected(player.account_id()));
+    }
+
+    #[test]
+    fn straddle_declined() {
+        let mut server_input = ServerInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        assert!(!server_input.request_straddle(&player, 4));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reconnect_after_timeout() {
+        let mut server_input = ServerInput::new().set_timeout(1);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        let possible_actions = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
+
+        assert_eq!(server_input.input_action_options(possible_actions.clone(), &player), ActionOption::Fold);
+        assert!(server_input.is_disconnected(player.account_id()));
+
+        // a disconnected player is folded without waiting for them, even if an action was submitted
+        server_input.submit_action(player.account_id(), ActionOption::Check);
+        let start = std::time::Instant::now();
+        assert_eq!(server_input.input_action_options(possible_actions.clone(), &player), ActionOption::Fold);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // they were folded, so there is nothing to re-send, and they are asked again from the next hand
+        assert_eq!(server_input.reconnect(player.account_id()), None);
+        assert!(!server_input.is_disconnected(player.account_id()));
+        server_input.submit_action(player.account_id(), ActionOption::Raise);
+        assert_eq!(server_input.input_action_options(possible_actions, &player), ActionOption::Raise);
+    }
+
+    #[test]
+    fn phase_summary_broadcast() {
+        let server_input = ServerInput::new();
+        let (lobby_events, mut receiver) = broadcast::channel(16);
+        server_input.set_lobby_events(7, lobby_events);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        let turns = vec![
+            (player.account_id(), Action::Call, 1, vec!["Ah".parse().unwrap(), "Kd".parse().unwrap()]),
+            (player.account_id(), Action::Fold, 1, vec!["Ah".parse().unwrap(), "Kd".parse().unwrap()]),
+        ];
+
+        server_input.display_turn_summary(&turns, &[&player]);
+
+        // the cards in each turn are left out, since the whole lobby sees the summary
+        let player_id = player.account_id().to_string();
+        assert_eq!(receiver.try_recv().unwrap(), LobbyEvent {
+            lobby_id: 7,
+            update: LobbyUpdate::PhaseSummary { turns: vec![
+                TurnSummary { player_id: player_id.clone(), action: Action::Call.public_description() },
+                TurnSummary { player_id, action: Action::Fold.public_description() },
+            ] },
+        });
+    }
+
+    #[test]
+    fn game_events_broadcast() {
+        let mut server_input = ServerInput::new();
+        let (lobby_events, mut receiver) = broadcast::channel(16);
+        server_input.set_lobby_events(7, lobby_events);
+        let player_id = Uuid::now_v7();
+
+        server_input.on_event(GameEvent::card_dealt(player_id, &"Ah".parse().unwrap()));
+        server_input.on_event(GameEvent::Bet { player_id, amount: 10 });
+
+        // the face down card is left out, since the whole lobby sees the events
+        assert_eq!(receiver.try_recv().unwrap().update, LobbyUpdate::GameEvent { event: GameEvent::CardDealt { player_id, card: None } });
+        assert_eq!(receiver.try_recv().unwrap().update, LobbyUpdate::GameEvent { event: GameEvent::Bet { player_id, amount: 10 } });
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn action_timer_ticks_broadcast() {
+        let mut server_input = ServerInput::new().set_timeout(2);
+        let (lobby_events, mut receiver) = broadcast::channel(16);
+        server_input.set_lobby_events(7, lobby_events);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        assert_eq!(server_input.input_action_options(vec![ActionOption::Check, ActionOption::Fold], &player), ActionOption::Fold);
+
+        // one tick per second, counting down from the timeout
+        let player_id = player.account_id().to_string();
+        for seconds_remaining in [2, 1] {
+            assert_eq!(receiver.try_recv().unwrap(), LobbyEvent {
+                lobby_id: 7,
+                update: LobbyUpdate::ActionTimerTick { player_id: player_id.clone(), seconds_remaining },
+            });
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn time_bank_avoids_fold() {
+        let mut server_input = ServerInput::new().set_timeout(1);
+        server_input.set_time_bank(Duration::from_secs(3));
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        let player_id = player.account_id();
+        assert_eq!(server_input.time_bank(player_id), Duration::from_secs(3));
+
+        // answering after the timeout dips into the time bank instead of folding
+        let client_input = server_input.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(2000)).await;
+            client_input.submit_action(player_id, ActionOption::Check);
+        });
+        assert_eq!(server_input.input_action_options(vec![ActionOption::Check, ActionOption::Fold], &player), ActionOption::Check);
+        let time_bank = server_input.time_bank(player_id);
+        assert!(time_bank <= Duration::from_secs(2));
+        assert!(time_bank > Duration::from_secs(1));
+
+        // once the timeout and the rest of the bank run out, the player is folded and their bank is empty
+        assert_eq!(server_input.input_action_options(vec![ActionOption::Check, ActionOption::Fold], &player), ActionOption::Fold);
+        assert_eq!(server_input.time_bank(player_id), Duration::ZERO);
+    }
+
+    /// a player holding the given cards, which are face up if they are marked with a "+", e.g. "As+"
+    fn player_with_cards(name: &str, cards: &str) -> Player {
+        let mut player = Player::new(Uuid::now_v7(), name.to_string(), 1000);
+        for notation in cards.split_whitespace() {
+            let mut card: Card = notation.trim_end_matches('+').parse().unwrap();
+            card.set_face_up(notation.ends_with('+'));
+            player.obtain_card(card);
+        }
+        return player;
+    }
+
+    #[test]
+    fn opponent_down_cards_never_sent() {
+        let server_input = ServerInput::new();
+        let player = player_with_cards("player", "2c 3d 4h+");
+        let opponent = player_with_cards("opponent", "As Ks Qs+");
+        let players = vec![&player, &opponent];
+        let opponent_down_cards: Vec<serde_json::Value> = ["As", "Ks"].iter()
+            .map(|notation| serde_json::to_value(notation.parse::<Card>().unwrap()).unwrap())
+            .collect();
+
+        for shown_player in players.iter() {
+            server_input.display_player_cards_to_player(shown_player);
+            server_input.display_other_player_up_cards_to_player(players.clone(), shown_player);
+            server_input.display_current_player(shown_player);
+        }
+        server_input.display_player_balances(players.clone());
+        server_input.announce_winner(vec![&opponent], players.clone());
+
+        let messages: serde_json::Value = serde_json::from_str(&server_input.take_messages_json(player.account_id())).unwrap();
+        let messages_text = messages.to_string();
+        for down_card in opponent_down_cards.iter() {
+            assert!(!messages_text.contains(&down_card.to_string()), "{} was sent to another player", down_card);
+        }
+        assert!(messages.as_array().unwrap().contains(&json!({ "your_cards": player.peek_at_cards() })));
+        assert!(messages.as_array().unwrap().contains(&json!({ "other_players": [{
+            "player_id": opponent.account_id(),
+            "name": "opponent",
+            "up_cards": [opponent.peek_at_cards()[2]],
+        }] })));
+
+        // the opponent is only sent their own cards, and the messages are only sent once
+        let opponent_messages: serde_json::Value = serde_json::from_str(&server_input.take_messages_json(opponent.account_id())).unwrap();
+        assert!(opponent_messages.as_array().unwrap().contains(&json!({ "your_cards": opponent.peek_at_cards() })));
+        assert!(!opponent_messages.to_string().contains(&serde_json::to_value(&player.peek_at_cards()[0]).unwrap().to_string()));
+        assert_eq!(server_input.take_messages_json(player.account_id()), "[]");
+    }
+
+    #[test]
+    fn showdown_only_sends_shown_hands() {
+        let server_input = ServerInput::new();
+        let player = player_with_cards("player", "2c 3d");
+        // the rules turn the cards of players who show their hands face up,
+        // the cards of players who folded or mucked stay face down
+        let shown = player_with_cards("shown", "As+ Ks+");
+        let mucked = player_with_cards("mucked", "Qh Jh");
+        let folded = player_with_cards("folded", "7d 7c");
+
+        server_input.display_other_player_up_cards_to_player(vec![&player, &shown, &mucked, &folded], &player);
+        let messages: serde_json::Value = serde_json::from_str(&server_input.take_messages_json(player.account_id())).unwrap();
+        let up_cards: Vec<(String, usize)> = messages[0]["other_players"].as_array().unwrap().iter()
+            .map(|other_player| (other_player["name"].as_str().unwrap().to_string(), other_player["up_cards"].as_array().unwrap().len()))
+            .collect();
+        assert_eq!(up_cards, vec![("shown".to_string(), 2), ("mucked".to_string(), 0), ("folded".to_string(), 0)]);
     }
 }
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use super::*;
 use crate::game_type::GameType;
 
@@ -9,13 +11,37 @@ use crate::game_type::GameType;
 /// and the methods that return something pop from a preset vector of inputs to return.
 /// Setter methods (not part of the Input trait) are provided to set the actions that
 /// will be performed in the order they occur.
+/// If no show or muck selections are set, beaten players always show their cards at the showdown.
+/// If no run it twice selections are set, players never agree to run it twice.
+/// If no straddle selections are set, players never straddle.
+/// If no undo selections are set, players never undo their turns.
+/// If a test does not provide enough inputs, TestInput panics with a message saying
+/// which inputs ran out and on which turn, rather than failing on a bare unwrap.
+/// This strict mode is on by default. With strict mode off, running out of inputs
+/// falls back to defaults instead (see set_strict).
+/// assert_all_actions_consumed can be called at the end of a test to check that
+/// no inputs were left over.
 /// This struct should only be used for testing purposes.
 pub struct TestInput {
     player_names: Vec<String>,
     game_variation: Option<GameType>,
     action_option_selections: Vec<ActionOption>,
     raise_amounts: Vec<u32>,
-    card_replace_selections: Vec<Vec<usize>>
+    bet_amounts: Vec<u32>,
+    card_replace_selections: Vec<Vec<usize>>,
+    show_or_muck_selections: Vec<bool>,
+    run_it_twice_selections: Vec<bool>,
+    straddle_selections: Vec<bool>,
+    undo_selections: Vec<bool>,
+    strict: bool,
+    player_name_turns: usize,
+    action_option_turns: usize,
+    raise_amount_turns: usize,
+    bet_amount_turns: usize,
+    card_replace_turns: usize,
+    events: Vec<GameEvent>,
+    offered_action_options: Vec<Vec<ActionOption>>,
+    turn_summaries: RefCell<Vec<Vec<(Uuid, Action, usize, Vec<Card>)>>>
 }
 
 impl Input for TestInput {
@@ -25,30 +51,105 @@ impl Input for TestInput {
             game_variation: None,
             action_option_selections: Vec::new(),
             raise_amounts: Vec::new(),
-            card_replace_selections: Vec::new()
+            bet_amounts: Vec::new(),
+            card_replace_selections: Vec::new(),
+            show_or_muck_selections: Vec::new(),
+            run_it_twice_selections: Vec::new(),
+            straddle_selections: Vec::new(),
+            undo_selections: Vec::new(),
+            strict: true,
+            player_name_turns: 0,
+            action_option_turns: 0,
+            raise_amount_turns: 0,
+            bet_amount_turns: 0,
+            card_replace_turns: 0,
+            events: Vec::new(),
+            offered_action_options: Vec::new(),
+            turn_summaries: RefCell::new(Vec::new())
         };
     }
 
     fn request_username(&mut self) -> String {
-        return self.player_names.pop().unwrap();
+        self.player_name_turns += 1;
+        return match self.player_names.pop() {
+            Some(player_name) => player_name,
+            None if !self.strict => format!("Player {}", self.player_name_turns),
+            None => panic!("TestInput ran out of player names at turn {}", self.player_name_turns)
+        };
     }
 
     fn input_variation(&mut self) -> GameType {
-        return self.game_variation.clone().unwrap();
+        return self.game_variation.clone().expect("TestInput has no game variation set");
     }
 
-    fn input_action_options(&mut self, _possible_actions: Vec<ActionOption>, _player: &Player) -> ActionOption {
-        return self.action_option_selections.pop().unwrap();
+    fn input_action_options(&mut self, possible_actions: Vec<ActionOption>, player: &Player) -> ActionOption {
+        self.action_option_turns += 1;
+        self.offered_action_options.push(possible_actions.clone());
+        return match self.action_option_selections.pop() {
+            Some(action_option) => action_option,
+            None if !self.strict => {
+                if possible_actions.contains(&ActionOption::Check) {
+                    ActionOption::Check
+                } else {
+                    ActionOption::Fold
+                }
+            },
+            None => panic!(
+                "TestInput ran out of action selections at turn {} (player {}, possible actions {:?})",
+                self.action_option_turns, player.name(), possible_actions
+            )
+        };
     }
 
-    fn request_raise_amount(&mut self, _limit: u32, _player: &Player) -> u32 {
-        return self.raise_amounts.pop().unwrap();
+    fn request_raise_amount(&mut self, _limit: u32, player: &Player) -> u32 {
+        self.raise_amount_turns += 1;
+        return match self.raise_amounts.pop() {
+            Some(raise_amount) => raise_amount,
+            None if !self.strict => 1,
+            None => panic!("TestInput ran out of raise amounts at turn {} (player {})", self.raise_amount_turns, player.name())
+        };
+    }
+
+    fn request_bet_amount(&mut self, min_bet: u32, _max_bet: u32, player: &Player) -> u32 {
+        self.bet_amount_turns += 1;
+        return match self.bet_amounts.pop() {
+            Some(bet_amount) => bet_amount,
+            None if !self.strict => min_bet,
+            None => panic!("TestInput ran out of bet amounts at turn {} (player {})", self.bet_amount_turns, player.name())
+        };
     }
 
     fn request_replace_cards<'a>(&mut self, player: &'a Player) -> Vec<&'a Card> {
+        self.card_replace_turns += 1;
         let cards = player.peek_at_cards();
-        let card_indices = self.card_replace_selections.pop().unwrap();
-        return card_indices.into_iter().map(|card_index| *cards.get(card_index).unwrap()).collect();
+        let card_indices = match self.card_replace_selections.pop() {
+            Some(card_indices) => card_indices,
+            None if !self.strict => Vec::new(),
+            None => panic!("TestInput ran out of card replace selections at turn {} (player {})", self.card_replace_turns, player.name())
+        };
+        return card_indices.into_iter().map(|card_index| match cards.get(card_index) {
+            Some(card) => *card,
+            None => panic!(
+                "TestInput card replace selection at turn {} (player {}) has index {} but the player only has {} cards",
+                self.card_replace_turns, player.name(), card_index, cards.len()
+            )
+        }).collect();
+    }
+
+    fn request_show_or_muck(&mut self, _player: &Player) -> bool {
+        return self.show_or_muck_selections.pop().unwrap_or(true);
+    }
+
+    fn request_run_it_twice(&mut self, _player: &Player) -> bool {
+        return self.run_it_twice_selections.pop().unwrap_or(false);
+    }
+
+    fn request_straddle(&mut self, _player: &Player, _straddle_amount: u32) -> bool {
+        return self.straddle_selections.pop().unwrap_or(false);
+    }
+
+    fn request_undo_turn(&mut self, _player: &Player, _action: &Action) -> bool {
+        return self.undo_selections.pop().unwrap_or(false);
     }
 
     fn display_player_cards_to_player(&self, _player: &Player) {
@@ -71,16 +172,91 @@ impl Input for TestInput {
         // do nothing at all
     }
 
-    fn display_pot(&self, _pot_amount: u32, _all_players: Vec<&Player>) {
+    fn display_pot(&self, _pot: &Pot, _all_players: Vec<&Player>) {
+        // do nothing at all
+    }
+
+    fn display_side_pots(&self, _side_pots: Vec<SidePot>, _all_players: Vec<&Player>) {
         // do nothing at all
     }
 
     fn display_player_balances(&self, _all_players: Vec<&Player>) {
         // do nothing at all
     }
+
+    fn announce_player_eliminated(&self, _player: &Player) {
+        // do nothing at all
+    }
+
+    fn display_turn_summary(&self, turns: &[(Uuid, Action, usize, Vec<Card>)], _players: &[&Player]) {
+        self.turn_summaries.borrow_mut().push(turns.to_vec());
+    }
+
+    fn on_event(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    fn notify_action_timeout_seconds(&self, _player: &Player, _seconds_remaining: u32) {
+        // do nothing at all
+    }
 }
 
 impl TestInput {
+    /// set whether running out of preset inputs panics (true, the default), or falls back to defaults (false).
+    /// the defaults are to check (or fold if checking isn't possible), raise by 1, bet the minimum, replace no cards,
+    /// and name players "Player 1", "Player 2", and so on
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// the events reported by the rules so far, in the order they happened
+    pub fn events(&self) -> &Vec<GameEvent> {
+        return &self.events;
+    }
+
+    /// the turns of each betting phase that was summarized so far (see Input::display_turn_summary), in the order the phases ended
+    pub fn turn_summaries(&self) -> Vec<Vec<(Uuid, Action, usize, Vec<Card>)>> {
+        return self.turn_summaries.borrow().clone();
+    }
+
+    /// the action options that were offered on each turn so far, in the order they were offered
+    pub fn offered_action_options(&self) -> &Vec<Vec<ActionOption>> {
+        return &self.offered_action_options;
+    }
+
+    /// panic if any of the preset inputs have not been used up, listing the ones that remain
+    pub fn assert_all_actions_consumed(&self) {
+        let mut remaining = Vec::new();
+        // the inputs are stored reversed, so reverse them back to show them in the order they were set
+        if !self.player_names.is_empty() {
+            remaining.push(format!("player names {:?}", self.player_names.iter().rev().collect::<Vec<_>>()));
+        }
+        if !self.action_option_selections.is_empty() {
+            remaining.push(format!("action selections {:?}", self.action_option_selections.iter().rev().collect::<Vec<_>>()));
+        }
+        if !self.raise_amounts.is_empty() {
+            remaining.push(format!("raise amounts {:?}", self.raise_amounts.iter().rev().collect::<Vec<_>>()));
+        }
+        if !self.bet_amounts.is_empty() {
+            remaining.push(format!("bet amounts {:?}", self.bet_amounts.iter().rev().collect::<Vec<_>>()));
+        }
+        if !self.card_replace_selections.is_empty() {
+            remaining.push(format!("card replace selections {:?}", self.card_replace_selections.iter().rev().collect::<Vec<_>>()));
+        }
+        if !self.show_or_muck_selections.is_empty() {
+            remaining.push(format!("show or muck selections {:?}", self.show_or_muck_selections.iter().rev().collect::<Vec<_>>()));
+        }
+        if !self.run_it_twice_selections.is_empty() {
+            remaining.push(format!("run it twice selections {:?}", self.run_it_twice_selections.iter().rev().collect::<Vec<_>>()));
+        }
+        if !remaining.is_empty() {
+            panic!(
+                "TestInput has unused inputs after {} action selections, {} raise amounts, {} bet amounts and {} card replace selections: {}",
+                self.action_option_turns, self.raise_amount_turns, self.bet_amount_turns, self.card_replace_turns, remaining.join(", ")
+            );
+        }
+    }
+
     pub fn set_player_names(&mut self, player_names: Vec<String>) {
         self.player_names = player_names;
         self.player_names.reverse();
@@ -100,8 +276,131 @@ impl TestInput {
         self.raise_amounts.reverse(); // reverse since we pop from the end for performance reasons
     }
 
+    pub fn set_bet_amounts(&mut self, bet_amounts: Vec<u32>) {
+        self.bet_amounts = bet_amounts;
+        self.bet_amounts.reverse(); // reverse since we pop from the end for performance reasons
+    }
+
     pub fn set_card_replace_selections(&mut self, card_replace_selections: Vec<Vec<usize>>) {
         self.card_replace_selections = card_replace_selections;
         self.card_replace_selections.reverse(); // reverse since we pop from the end for performance reasons
     }
+
+    /// set whether each beaten player shows (true) or mucks (false) their cards at the showdown
+    pub fn set_show_or_muck_selections(&mut self, show_or_muck_selections: Vec<bool>) {
+        self.show_or_muck_selections = show_or_muck_selections;
+        self.show_or_muck_selections.reverse(); // reverse since we pop from the end for performance reasons
+    }
+
+    /// set whether each player asked agrees (true) or not (false) to run the remaining cards twice
+    pub fn set_run_it_twice_selections(&mut self, run_it_twice_selections: Vec<bool>) {
+        self.run_it_twice_selections = run_it_twice_selections;
+        self.run_it_twice_selections.reverse(); // reverse since we pop from the end for performance reasons
+    }
+
+    /// set whether each player asked chooses to straddle (true) or not (false)
+    pub fn set_straddle_selections(&mut self, straddle_selections: Vec<bool>) {
+        self.straddle_selections = straddle_selections;
+        self.straddle_selections.reverse(); // reverse since we pop from the end for performance reasons
+    }
+
+    /// set whether each player, after each turn they take, chooses to undo it (true) or not (false)
+    pub fn set_undo_selections(&mut self, undo_selections: Vec<bool>) {
+        self.undo_selections = undo_selections;
+        self.undo_selections.reverse(); // reverse since we pop from the end for performance reasons
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn input_action_options_in_order() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.set_action_option_selections(vec![ActionOption::Call, ActionOption::Fold]);
+        assert!(matches!(test_input.input_action_options(vec![ActionOption::Call, ActionOption::Fold], &player), ActionOption::Call));
+        assert!(matches!(test_input.input_action_options(vec![ActionOption::Call, ActionOption::Fold], &player), ActionOption::Fold));
+    }
+
+    #[test]
+    #[should_panic(expected = "TestInput ran out of action selections at turn 2 (player player")]
+    fn input_action_options_ran_out() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.set_action_option_selections(vec![ActionOption::Call]);
+        test_input.input_action_options(vec![ActionOption::Call, ActionOption::Fold], &player);
+        test_input.input_action_options(vec![ActionOption::Call, ActionOption::Fold], &player);
+    }
+
+    #[test]
+    #[should_panic(expected = "TestInput ran out of raise amounts at turn 1")]
+    fn request_raise_amount_ran_out() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.request_raise_amount(10, &player);
+    }
+
+    #[test]
+    fn request_bet_amount_in_order() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.set_bet_amounts(vec![20, 5]);
+        assert_eq!(test_input.request_bet_amount(2, 100, &player), 20);
+        assert_eq!(test_input.request_bet_amount(2, 100, &player), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "TestInput ran out of card replace selections at turn 1")]
+    fn request_replace_cards_ran_out() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.request_replace_cards(&player);
+    }
+
+    #[test]
+    fn non_strict_falls_back_to_defaults() {
+        let mut test_input = TestInput::new();
+        test_input.set_strict(false);
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        assert_eq!(test_input.input_action_options(vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold], &player), ActionOption::Check);
+        assert_eq!(test_input.input_action_options(vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold], &player), ActionOption::Fold);
+        assert_eq!(test_input.request_raise_amount(10, &player), 1);
+        assert_eq!(test_input.request_bet_amount(2, 10, &player), 2);
+        assert!(test_input.request_replace_cards(&player).is_empty());
+        assert_eq!(test_input.request_username(), "Player 1");
+        test_input.assert_all_actions_consumed();
+    }
+
+    #[test]
+    #[should_panic(expected = "TestInput ran out of player names at turn 1")]
+    fn request_username_ran_out() {
+        let mut test_input = TestInput::new();
+        test_input.request_username();
+    }
+
+    #[test]
+    fn all_actions_consumed() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.set_action_option_selections(vec![ActionOption::Raise]);
+        test_input.set_raise_amounts(vec![10]);
+        test_input.input_action_options(vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold], &player);
+        test_input.request_raise_amount(10, &player);
+        test_input.assert_all_actions_consumed();
+    }
+
+    #[test]
+    #[should_panic(expected = "TestInput has unused inputs after 1 action selections, 0 raise amounts, 0 bet amounts and 0 card replace selections: action selections [Fold, Call], raise amounts [10]")]
+    fn actions_left_over() {
+        let mut test_input = TestInput::new();
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+        test_input.set_action_option_selections(vec![ActionOption::Raise, ActionOption::Fold, ActionOption::Call]);
+        test_input.set_raise_amounts(vec![10]);
+        test_input.input_action_options(vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold], &player);
+        test_input.assert_all_actions_consumed();
+    }
 }
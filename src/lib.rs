@@ -3,6 +3,8 @@ pub mod deck;
 pub mod rules;
 pub mod input;
 pub mod hand_rank;
+pub mod equity;
+pub mod badugi_hand;
 pub mod pot;
 pub mod database;
 pub mod game;
@@ -10,6 +12,8 @@ pub mod player;
 pub mod action;
 pub mod action_option;
 pub mod game_type;
+pub mod game_event;
 pub mod server;
 pub mod lobby;
 pub mod menu_navigation;
+pub mod config;
@@ -1,21 +1,75 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::database::db_handler::DbHandler;
-use crate::database::db_structs::Game;
+use crate::database::db_structs::{self, Game, LobbyUser};
 use crate::game_type::GameType;
 use crate::input::Input;
-use crate::rules::five_card_draw::FiveCardDraw;
-use crate::rules::seven_card_stud::SevenCardStud;
-use crate::rules::texas_holdem::TexasHoldem;
-use crate::rules::{Rules, RulesEnum};
+use crate::rules::{Rules, RoundError, RulesEnum};
 use crate::player::Player;
 use crate::input::cli_input::CliInput;
 
 
+// Stack that users bring to a lobby if they don't choose one.
+pub const DEFAULT_BUY_IN: u32 = 1000;
+// Blinds of a lobby if its creator doesn't choose them.
+pub const DEFAULT_SMALL_BLIND: u32 = 1;
+pub const DEFAULT_BIG_BLIND: u32 = 2;
+// Raise limit of every lobby's game.
+pub const DEFAULT_RAISE_LIMIT: u32 = 1000;
+
+
+// Session token of each user in a lobby, which lets them get back to their seat if their connection drops.
+// Only the hash of each token is kept, as they are saved with the lobby.
+// Cloning it shares the same tokens, so that the server can check them while a hand is being played and the lobby is locked.
+#[derive(Clone, Default)]
+pub struct SessionTokens(Arc<Mutex<HashMap<Uuid, String>>>);
+
+
+impl SessionTokens {
+    // Hashes a session token. Tokens are random, so unlike passwords they don't need a salt.
+    fn hash(session_token: Uuid) -> String {
+        Sha256::digest(session_token.as_bytes()).iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+
+    // Issues a new session token to a user, replacing any they had.
+    fn issue(&self, user_id: Uuid) -> Uuid {
+        // random rather than time based, so that tokens can't be guessed
+        let session_token = Uuid::new_v4();
+        self.insert_hash(user_id, Self::hash(session_token));
+        session_token
+    }
+
+
+    fn insert_hash(&self, user_id: Uuid, session_token_hash: String) {
+        self.0.lock().unwrap().insert(user_id, session_token_hash);
+    }
+
+
+    fn remove(&self, user_id: Uuid) {
+        self.0.lock().unwrap().remove(&user_id);
+    }
+
+
+    fn get_hash(&self, user_id: Uuid) -> Option<String> {
+        self.0.lock().unwrap().get(&user_id).cloned()
+    }
+
+
+    // Whether a session token is the one that was issued to a user.
+    pub fn check(&self, user_id: Uuid, session_token: Uuid) -> bool {
+        self.get_hash(user_id) == Some(Self::hash(session_token))
+    }
+}
+
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub enum LobbyStatus {
     InLobby,
@@ -25,70 +79,276 @@ pub enum LobbyStatus {
 
 pub struct Lobby<I: Input> {
     id: u32,
+    name: String,
+    creator_id: Option<Uuid>,
+    game_id: Uuid,
     status: LobbyStatus,
     users: HashSet<Uuid>,
+    buy_ins: HashMap<Uuid, u32>,
+    min_buy_in: u32,
+    max_buy_in: u32,
+    // Stack that every user must join with, so that all players start even, None to allow any stack within the buy-in limits.
+    starting_balance: Option<u32>,
+    small_blind_amount: u32,
+    big_blind_amount: u32,
+    // Hash of the password needed to join the lobby, None for public lobbies.
+    password_hash: Option<String>,
+    session_tokens: SessionTokens,
+    // Name each user has chosen to be shown as, users without one are shown by their ID.
+    display_names: HashMap<Uuid, String>,
     active_players: Vec<Player>,
+    // Number of the round being played, or that was played last.
+    current_round: u32,
+    // When the last user left the lobby (or when it was created), None while users are in it.
+    empty_since: Option<Instant>,
     rules: RulesEnum<I>,
 }
 
 
 impl<I: Input> Lobby<I> {
     pub async fn new(id: u32, game_type: GameType) -> Self {
-        let db_handler = match DbHandler::new("mongodb://localhost:27017/".to_string(), "poker".to_string()).await {
+        Self::new_with_blinds(id, game_type, DEFAULT_SMALL_BLIND, DEFAULT_BIG_BLIND).await
+    }
+
+    // Creates a lobby whose game uses the given blinds (the big blind is also the minimum bet).
+    pub async fn new_with_blinds(id: u32, game_type: GameType, small_blind_amount: u32, big_blind_amount: u32) -> Self {
+        Self::new_with_game_id(id, game_type, small_blind_amount, big_blind_amount, Uuid::now_v7(), Self::connect_db_handler().await)
+    }
+
+    // Creates a lobby whose game saves its rounds through the given database handler, with the default blinds.
+    pub fn new_with_db_handler(id: u32, game_type: GameType, db_handler: DbHandler) -> Self {
+        Self::new_with_game_id(id, game_type, DEFAULT_SMALL_BLIND, DEFAULT_BIG_BLIND, Uuid::now_v7(), db_handler)
+    }
+
+    // Connects to the database that lobbies save their games' rounds to.
+    async fn connect_db_handler() -> DbHandler {
+        match DbHandler::new("mongodb://localhost:27017/".to_string(), "poker".to_string()).await {
             Ok(handler) => handler,
             Err(e) => {
                 println!("Using dummy DbHandler due to error: {}", e);
                 DbHandler::new_dummy()
             }
-        };
+        }
+    }
+
+    // Creates a lobby whose rounds are stored under the given game ID.
+    fn new_with_game_id(id: u32, game_type: GameType, small_blind_amount: u32, big_blind_amount: u32, game_id: Uuid, db_handler: DbHandler) -> Self {
         Self { 
             id: id, 
+            name: format!("Lobby #{}", id),
+            creator_id: None,
+            game_id: game_id,
             status: LobbyStatus::InLobby, 
             users: HashSet::new(), 
+            buy_ins: HashMap::new(),
+            min_buy_in: 1,
+            max_buy_in: u32::MAX,
+            starting_balance: None,
+            small_blind_amount: small_blind_amount,
+            big_blind_amount: big_blind_amount,
+            password_hash: None,
+            session_tokens: SessionTokens::default(),
+            display_names: HashMap::new(),
             active_players: Vec::new(), 
-            rules: match game_type {
-                GameType::FiveCardDraw => RulesEnum::FiveCardDraw(FiveCardDraw::new(1000, 1, db_handler, Uuid::now_v7())),
-                GameType::SevenCardStud => RulesEnum::SevenCardStud(SevenCardStud::new(1000, 1, db_handler, Uuid::now_v7())),
-                GameType::TexasHoldem => RulesEnum::TexasHoldem(TexasHoldem::new(1000, 1, db_handler, Uuid::now_v7())),
-            }
+            current_round: 0,
+            empty_since: Some(Instant::now()),
+            rules: RulesEnum::new(game_type, DEFAULT_RAISE_LIMIT, big_blind_amount, small_blind_amount, db_handler, game_id),
+        }
+    }
+
+    // Creates a lobby that was requested by a user, who is the only one allowed to rename it.
+    // If no name is given, the lobby is given the default name.
+    pub async fn new_with_creator(id: u32, game_type: GameType, creator_id: Uuid, name: Option<String>, small_blind_amount: u32, big_blind_amount: u32) -> Self {
+        let mut lobby = Self::new_with_blinds(id, game_type, small_blind_amount, big_blind_amount).await;
+        lobby.creator_id = Some(creator_id);
+        if let Some(name) = name {
+            let _ = lobby.rename(name);
         }
+        lobby
     }
 
     // Starts for a specific lobby.
     pub async fn start_game(&mut self) {
         self.active_players.clear();
         for user in self.users.iter() {
-            self.active_players.push(Player::new(*user, user.simple().to_string(), 1000));
+            let buy_in = *self.buy_ins.get(user).unwrap_or(&DEFAULT_BUY_IN);
+            self.active_players.push(Player::new(*user, self.display_name(*user), buy_in as usize));
         }
         self.status = LobbyStatus::InGame;
+        self.current_round += 1;
+        self.rules.set_round_number(self.current_round);
         let _ = match &mut self.rules {
             RulesEnum::FiveCardDraw(ref mut rules) => rules.play_round(self.active_players.clone()).await,
             RulesEnum::SevenCardStud(ref mut rules) => rules.play_round(self.active_players.clone()).await,
             RulesEnum::TexasHoldem(ref mut rules) => rules.play_round(self.active_players.clone()).await,
+            RulesEnum::CommunityDraw(ref mut rules) => rules.play_round(self.active_players.clone()).await,
+            RulesEnum::Badugi(ref mut rules) => rules.play_round(self.active_players.clone()).await,
+            RulesEnum::ShortDeckHoldem(ref mut rules) => rules.play_round(self.active_players.clone()).await,
         };
     }
 
+    // Restores a lobby that was saved to the database, with its settings and seated users.
+    // A game that was being played when it was saved is abandoned, so the lobby goes back to waiting for one.
+    pub async fn from_document(document: db_structs::Lobby) -> Self {
+        let mut lobby = Self::new_with_game_id(document.lobby_id, document.game_type, document.small_blind, document.big_blind, document._id, Self::connect_db_handler().await);
+        lobby.name = document.name;
+        lobby.creator_id = document.creator_id.and_then(|creator_id| Uuid::parse_str(&creator_id).ok());
+        lobby.min_buy_in = document.min_buy_in;
+        lobby.max_buy_in = document.max_buy_in;
+        lobby.starting_balance = document.starting_balance;
+        lobby.password_hash = document.password_hash;
+        lobby.current_round = document.current_round;
+        for user in document.users {
+            lobby.users.insert(user.user_id);
+            lobby.buy_ins.insert(user.user_id, user.buy_in);
+            if let Some(session_token_hash) = user.session_token_hash {
+                lobby.session_tokens.insert_hash(user.user_id, session_token_hash);
+            }
+            if let Some(display_name) = user.display_name {
+                lobby.display_names.insert(user.user_id, display_name);
+            }
+        }
+        if !lobby.users.is_empty() {
+            lobby.empty_since = None;
+        }
+        lobby
+    }
+
+    // The document the lobby is saved to the database as, see from_document.
+    pub fn to_document(&self) -> db_structs::Lobby {
+        let users = self.users.iter()
+            .map(|user_id| LobbyUser {
+                user_id: *user_id,
+                buy_in: *self.buy_ins.get(user_id).unwrap_or(&DEFAULT_BUY_IN),
+                session_token_hash: self.session_tokens.get_hash(*user_id),
+                display_name: self.display_names.get(user_id).cloned(),
+            })
+            .collect();
+        db_structs::Lobby {
+            _id: self.game_id,
+            lobby_id: self.id,
+            name: self.name.clone(),
+            creator_id: self.creator_id.map(|creator_id| creator_id.simple().to_string()),
+            game_type: self.game_type(),
+            small_blind: self.small_blind_amount,
+            big_blind: self.big_blind_amount,
+            min_buy_in: self.min_buy_in,
+            max_buy_in: self.max_buy_in,
+            starting_balance: self.starting_balance,
+            password_hash: self.password_hash.clone(),
+            users,
+            status: self.status.clone(),
+            current_round: self.current_round,
+        }
+    }
+
     pub fn status(&self) -> LobbyStatus {
         self.status.clone()
     }
 
+    // When the lobby was left empty, or None if there are users in it.
+    pub fn empty_since(&self) -> Option<Instant> {
+        self.empty_since
+    }
+
     // Counts the number of users.
     pub fn count_users(&self) -> u32 {
         self.users.len() as u32
     }
 
+    // Checks that the game type can be played with the users in the lobby, before a game is started.
+    pub fn can_start_game(&self) -> Result<(), RoundError> {
+        self.rules.check_player_count(self.users.len())
+    }
+
 
     pub fn rules(&self) -> &RulesEnum<I> {
         &self.rules
     }
 
-    // Adds user to user list.
-    pub fn join_user(&mut self, user_id: Uuid) -> Result<(), ()> {
+
+    pub fn min_buy_in(&self) -> u32 {
+        self.min_buy_in
+    }
+
+
+    pub fn max_buy_in(&self) -> u32 {
+        self.max_buy_in
+    }
+
+    // Sets the smallest and largest stacks that users can join the lobby with.
+    pub fn set_buy_in_limits(&mut self, min_buy_in: u32, max_buy_in: u32) -> Result<(), ()> {
+        if min_buy_in == 0 || min_buy_in > max_buy_in {
+            return Err(());
+        }
+        self.min_buy_in = min_buy_in;
+        self.max_buy_in = max_buy_in;
+        Ok(())
+    }
+
+    pub fn starting_balance(&self) -> Option<u32> {
+        self.starting_balance
+    }
+
+    // Sets the stack that every user must join the lobby with, which has to be within the buy-in limits.
+    pub fn set_starting_balance(&mut self, starting_balance: Option<u32>) -> Result<(), ()> {
+        if let Some(amount) = starting_balance {
+            if amount < self.min_buy_in || amount > self.max_buy_in {
+                return Err(());
+            }
+        }
+        self.starting_balance = starting_balance;
+        Ok(())
+    }
+
+    // Hashes a password for this lobby.
+    // The lobby's game ID is used as a salt, so the same password hashes differently in each lobby.
+    fn hash_password(&self, password: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.game_id.as_bytes());
+        hasher.update(password.as_bytes());
+        hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    // Makes the lobby private, so that users need the password to join it.
+    // Only the hash of the password is kept. A blank password or None makes the lobby public.
+    pub fn set_password(&mut self, password: Option<String>) {
+        self.password_hash = match password {
+            Some(password) if !password.is_empty() => Some(self.hash_password(&password)),
+            _ => None,
+        };
+    }
+
+    // Whether users need a password to join the lobby.
+    pub fn is_private(&self) -> bool {
+        self.password_hash.is_some()
+    }
+
+    // Adds user to user list, with the stack they are bringing to the lobby.
+    // The stack must be within the lobby's buy-in limits (and be the starting balance if the lobby has one),
+    // and the password must match if the lobby is private.
+    // Returns the user's session token, which they need to reconnect to the lobby.
+    pub fn join_user(&mut self, user_id: Uuid, buy_in: u32, password: Option<&str>) -> Result<Uuid, ()> {
+        if buy_in < self.min_buy_in || buy_in > self.max_buy_in {
+            return Err(());
+        }
+        if self.starting_balance.is_some_and(|starting_balance| buy_in != starting_balance) {
+            return Err(());
+        }
+        if let Some(password_hash) = &self.password_hash {
+            match password {
+                Some(password) if self.hash_password(password) == *password_hash => (),
+                _ => return Err(()),
+            }
+        }
         match self.users.get(&user_id) {
             Some(_) => Err(()),
             None => {
                 self.users.insert(user_id);
-                Ok(())
+                self.buy_ins.insert(user_id, buy_in);
+                let session_token = self.session_tokens.issue(user_id);
+                self.empty_since = None;
+                Ok(session_token)
             },
         }
     }
@@ -99,6 +359,12 @@ impl<I: Input> Lobby<I> {
             None => Err(()),
             Some(_) => {
                 self.users.remove(&user_id);
+                self.buy_ins.remove(&user_id);
+                self.session_tokens.remove(user_id);
+                self.display_names.remove(&user_id);
+                if self.users.is_empty() {
+                    self.empty_since = Some(Instant::now());
+                }
                 Ok(())
             },
         }
@@ -110,6 +376,74 @@ impl<I: Input> Lobby<I> {
     } 
 
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+
+    pub fn creator_id(&self) -> Option<Uuid> {
+        self.creator_id
+    }
+
+
+    // Number of the round being played, or that was played last, which is 0 until a game is started.
+    pub fn current_round(&self) -> u32 {
+        self.current_round
+    }
+
+
+    // ID of the game being played in this lobby, which the lobby's rounds are stored under.
+    pub fn game_id(&self) -> Uuid {
+        self.game_id
+    }
+
+    // Changes the name of the lobby, names cannot be blank.
+    pub fn rename(&mut self, new_name: String) -> Result<(), ()> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(());
+        }
+        self.name = new_name.to_string();
+        Ok(())
+    }
+
+
+    // Changes the name a user in the lobby is shown as, names cannot be blank.
+    // The user's player is renamed too if a game is being played.
+    pub fn set_display_name(&mut self, user_id: Uuid, display_name: String) -> Result<(), ()> {
+        let display_name = display_name.trim();
+        if display_name.is_empty() || self.get_user(user_id).is_none() {
+            return Err(());
+        }
+        self.display_names.insert(user_id, display_name.to_string());
+        if let Some(player) = self.active_players.iter_mut().find(|player| player.account_id() == user_id) {
+            player.rename(display_name.to_string());
+        }
+        Ok(())
+    }
+
+
+    // Name a user is shown as, which is their ID until they choose a display name.
+    pub fn display_name(&self, user_id: Uuid) -> String {
+        match self.display_names.get(&user_id) {
+            Some(display_name) => display_name.clone(),
+            None => user_id.simple().to_string(),
+        }
+    }
+
+
+    // Whether a session token is the one that was issued to a user when they joined the lobby.
+    pub fn check_session_token(&self, user_id: Uuid, session_token: Uuid) -> bool {
+        self.session_tokens.check(user_id, session_token)
+    }
+
+
+    // The session tokens of the lobby's users, shared with the lobby (see SessionTokens).
+    pub fn session_tokens(&self) -> SessionTokens {
+        self.session_tokens.clone()
+    }
+
+
     pub fn get_user(&self, user_id: Uuid) -> Option<&Uuid> {
         self.users.get(&user_id)
     }
@@ -125,11 +459,19 @@ impl<I: Input> Lobby<I> {
     }
 
 
+    pub fn deal_order_description(&self) -> &'static str {
+        self.rules.deal_order_description()
+    }
+
+
     pub fn game_type(&self) -> GameType {
         match self.rules {
             RulesEnum::FiveCardDraw(_) => GameType::FiveCardDraw,
             RulesEnum::SevenCardStud(_) => GameType::SevenCardStud,
             RulesEnum::TexasHoldem(_) => GameType::TexasHoldem,
+            RulesEnum::CommunityDraw(_) => GameType::CommunityDraw,
+            RulesEnum::Badugi(_) => GameType::Badugi,
+            RulesEnum::ShortDeckHoldem(_) => GameType::ShortDeckHoldem,
         }
     }
 }
\ No newline at end of file
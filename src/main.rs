@@ -1,5 +1,6 @@
 use std::io;
 
+use poker_project_rustaceans::config::Config;
 use poker_project_rustaceans::menu_navigation::MenuNavigation;
 use poker_project_rustaceans::server;
 use strum::IntoEnumIterator;
@@ -24,6 +25,7 @@ impl std::fmt::Display for ModeSelection {
 
 #[tokio::main]
 async fn main() {
+    let config = Config::from_env();
     loop {
         println!("\nPoker Project Rustaceans Dealer");
         println!("Select an execution mode:");
@@ -44,8 +46,8 @@ async fn main() {
             },
         };
         match mode_selection {
-            ModeSelection::CommandLine => MenuNavigation::start_page().await,
-            ModeSelection::ServerClient => server::run_server().await,
+            ModeSelection::CommandLine => MenuNavigation::start_page(&config).await,
+            ModeSelection::ServerClient => server::run_server(&config).await,
             ModeSelection::Exit => break,
         };
     }
@@ -1,10 +1,12 @@
+use std::fs;
 use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use uuid::Uuid;
 
-use crate::{database::db_handler::DbHandler, game::Game, game_type::GameType, input::cli_input::CliInput, player::Player, rules::{five_card_draw::FiveCardDraw, seven_card_stud::SevenCardStud, texas_holdem::TexasHoldem, Rules}};
+use crate::{card::Card, config::Config, database::{db_handler::DbHandler, db_structs::{Round, Turn}}, game::Game, game_type::GameType, input::{bot_input::BotInput, cli_input::CliInput, Input}, player::Player, pot::Pot, rules::{five_card_draw::FiveCardDraw, seven_card_stud::SevenCardStud, texas_holdem::TexasHoldem, community_draw::CommunityDraw, badugi::Badugi, short_deck_holdem::ShortDeckHoldem, BlindSchedule, Rules, SitOutMode}};
 
 #[derive(EnumIter)]
 enum StartPageOption {
@@ -44,9 +46,15 @@ impl std::fmt::Display for HomePageOption {
 
 #[derive(EnumIter)]
 enum LobbyCreationPageOption {
+    SelectName,
     SelectGameType,
     SelectRaiseLimit,
     SelectMinimumBet,
+    SelectSmallBlind,
+    SelectMinimumBuyIn,
+    SelectMaximumBuyIn,
+    SelectStartingBalance,
+    ToggleTournamentBlinds,
     Finish,
     Cancel
 }
@@ -54,9 +62,15 @@ enum LobbyCreationPageOption {
 impl std::fmt::Display for LobbyCreationPageOption {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            LobbyCreationPageOption::SelectName => write!(f, "Select Lobby Name"),
             LobbyCreationPageOption::SelectGameType => write!(f, "Select Game Type"),
             LobbyCreationPageOption::SelectRaiseLimit => write!(f, "Select Raise Limit"),
             LobbyCreationPageOption::SelectMinimumBet => write!(f, "Select Minimum Bet"),
+            LobbyCreationPageOption::SelectSmallBlind => write!(f, "Select Small Blind"),
+            LobbyCreationPageOption::SelectMinimumBuyIn => write!(f, "Select Minimum Buy-In"),
+            LobbyCreationPageOption::SelectMaximumBuyIn => write!(f, "Select Maximum Buy-In"),
+            LobbyCreationPageOption::SelectStartingBalance => write!(f, "Select Starting Balance"),
+            LobbyCreationPageOption::ToggleTournamentBlinds => write!(f, "Toggle Tournament Blinds"),
             LobbyCreationPageOption::Finish => write!(f, "Finish"),
             LobbyCreationPageOption::Cancel => write!(f, "Cancel"),
         }
@@ -67,6 +81,11 @@ impl std::fmt::Display for LobbyCreationPageOption {
 enum LobbyPageOption {
     RefreshPlayerList,
     AddLocalPlayer, // TODO: this is only here for CLI, as there is otherwise no way to have more than one player
+    AddBot,
+    ToggleSitOut,
+    ToggleSitOutMode,
+    ChangeDisplayName,
+    ExportHistory,
     StartRound,
     LeaveLobby
 }
@@ -78,6 +97,11 @@ impl std::fmt::Display for LobbyPageOption {
             LobbyPageOption::StartRound => write!(f, "Start Round"),
             LobbyPageOption::LeaveLobby => write!(f, "Leave Lobby"),
             LobbyPageOption::AddLocalPlayer => write!(f, "Add Local Player"),
+            LobbyPageOption::AddBot => write!(f, "Add Bot"),
+            LobbyPageOption::ToggleSitOut => write!(f, "Sit Out / Return to Play"),
+            LobbyPageOption::ToggleSitOutMode => write!(f, "Toggle Blinds and Antes While Sitting Out"),
+            LobbyPageOption::ChangeDisplayName => write!(f, "Change Display Name"),
+            LobbyPageOption::ExportHistory => write!(f, "Export Last Round History"),
         }
     }
 }
@@ -85,7 +109,7 @@ impl std::fmt::Display for LobbyPageOption {
 pub struct MenuNavigation;
 
 impl MenuNavigation {
-    pub async fn start_page() {
+    pub async fn start_page(config: &Config) {
         loop {
             println!("\nStart Page");
             println!("Select an option:");
@@ -106,23 +130,23 @@ impl MenuNavigation {
                 },
             };
             match next_page {
-                StartPageOption::LogIn => MenuNavigation::home_page(MenuNavigation::login_page()).await,
-                StartPageOption::Register => MenuNavigation::home_page(MenuNavigation::register_page()).await,
+                StartPageOption::LogIn => MenuNavigation::home_page(MenuNavigation::login_page(config), config).await,
+                StartPageOption::Register => MenuNavigation::home_page(MenuNavigation::register_page(config), config).await,
                 StartPageOption::Exit => break,
             };
         }
     }
 
-    pub fn login_page() -> Player {
+    pub fn login_page(config: &Config) -> Player {
         loop {
             println!("\nLogin Page");
             println!("Enter your username:");
             println!("This has not yet been implemented! redirecting to register page");
-            break MenuNavigation::register_page();
+            break MenuNavigation::register_page(config);
         }
     }
 
-    pub fn register_page() -> Player {
+    pub fn register_page(config: &Config) -> Player {
         loop {
             println!("\nRegister Page");
             println!("Enter a username:");
@@ -130,16 +154,26 @@ impl MenuNavigation {
             io::stdin()
                 .read_line(&mut input)
                 .expect("failed to read line");
-            input = input.trim().to_string();
-            if input.len() == 0 {
-                println!("username cannot be blank");
-                continue;
+            match MenuNavigation::registered_player(&input, config) {
+                Some(player) => return player,
+                None => println!("username cannot be blank"),
             }
-            return Player::new(Uuid::now_v7(), input, 1000);
         }
     }
 
-    pub async fn home_page(player: Player) {
+    /// the player registered with a username, who starts with the configured starting balance,
+    /// or None if the username is blank
+    pub fn registered_player(username: &str, config: &Config) -> Option<Player> {
+        let username = username.trim();
+        if username.is_empty() {
+            return None;
+        }
+        return Some(Player::new(Uuid::now_v7(), username.to_string(), config.starting_balance as usize));
+    }
+
+    pub async fn home_page(player: Player, config: &Config) {
+        // the games created here save their rounds to the same database that the statistics and replays are read from
+        let db_handler = config.connect_db_handler().await;
         loop {
             println!("\nHome Page");
             println!("Select an option:");
@@ -160,23 +194,40 @@ impl MenuNavigation {
                 },
             };
             match next_page {
-                HomePageOption::CreateLobby => MenuNavigation::lobby_creation_page(player.clone()).await, // FIXME: should not be cloning Player, because their balance may change but this copy will not see that change
+                HomePageOption::CreateLobby => MenuNavigation::lobby_creation_page(player.clone(), config, &db_handler).await, // FIXME: should not be cloning Player, because their balance may change but this copy will not see that change
                 HomePageOption::JoinLobby => MenuNavigation::lobby_join_page(player.clone()).await, // FIXME: should not be cloning Player, because their balance may change but this copy will not see that change
-                HomePageOption::ViewStatistics => MenuNavigation::game_statistics_page(),
+                HomePageOption::ViewStatistics => MenuNavigation::game_statistics_page(&player, &db_handler).await,
                 HomePageOption::LogOut => break,
             };
         }
     }
 
-    pub async fn lobby_creation_page(player: Player) {
+    pub async fn lobby_creation_page(player: Player, config: &Config, db_handler: &DbHandler) {
+        let mut lobby_name: Option<String> = None;
         let mut game_type = GameType::TexasHoldem;
         let mut raise_limit = 1000;
         let mut minimum_bet = 2;
+        let mut small_blind = 1;
+        let mut min_buy_in = 100;
+        let mut max_buy_in = 10000;
+        let mut starting_balance: Option<usize> = None;
+        let mut blind_schedule: Option<BlindSchedule> = None;
         loop {
             println!("\nLobby Creation Page");
+            println!("Currently Selected Lobby Name: {}", lobby_name.as_deref().unwrap_or("(default)"));
             println!("Currently Selected Game Type: {}", game_type);
             println!("Currently Selected Raise Limit: {}", raise_limit);
-            println!("Currently Selected Minimum Bet: {}", minimum_bet);
+            println!("Currently Selected Minimum Bet (Big Blind): {}", minimum_bet);
+            println!("Currently Selected Small Blind: {}", small_blind);
+            println!("Currently Selected Buy-In Limits: {} to {}", min_buy_in, max_buy_in);
+            match starting_balance {
+                Some(starting_balance) => println!("Currently Selected Starting Balance: {}", starting_balance),
+                None => println!("Currently Selected Starting Balance: (any balance within the buy-in limits)"),
+            }
+            match &blind_schedule {
+                Some(blind_schedule) => println!("Tournament Blinds: big blinds of {:?} by {}", blind_schedule.levels(), if blind_schedule.is_timed() { "minute" } else { "round" }),
+                None => println!("Tournament Blinds: off"),
+            }
             println!("Select an option:");
             for (i, page) in LobbyCreationPageOption::iter().enumerate() {
                 println!("{} - {}", i, page);
@@ -195,21 +246,54 @@ impl MenuNavigation {
                 },
             };
             match next_page {
+                LobbyCreationPageOption::SelectName => lobby_name = MenuNavigation::lobby_name_selection_page(),
                 LobbyCreationPageOption::SelectGameType => game_type = MenuNavigation::game_type_selection_page(),
                 LobbyCreationPageOption::SelectRaiseLimit => raise_limit = MenuNavigation::raise_limit_selection_page(),
                 LobbyCreationPageOption::SelectMinimumBet => minimum_bet = MenuNavigation::minimum_bet_selection_page(),
+                LobbyCreationPageOption::SelectSmallBlind => small_blind = MenuNavigation::small_blind_selection_page(),
+                LobbyCreationPageOption::SelectMinimumBuyIn => min_buy_in = MenuNavigation::buy_in_selection_page("minimum"),
+                LobbyCreationPageOption::SelectMaximumBuyIn => max_buy_in = MenuNavigation::buy_in_selection_page("maximum"),
+                LobbyCreationPageOption::SelectStartingBalance => starting_balance = MenuNavigation::starting_balance_selection_page(),
+                LobbyCreationPageOption::ToggleTournamentBlinds => blind_schedule = match blind_schedule {
+                    Some(_) => None,
+                    None => Some(BlindSchedule::default_tournament()),
+                },
                 LobbyCreationPageOption::Finish => {
+                    if min_buy_in > max_buy_in {
+                        println!("The minimum buy-in must be at most the maximum buy-in");
+                        continue;
+                    }
+                    if small_blind > minimum_bet {
+                        println!("The small blind must be at most the minimum bet (the big blind)");
+                        continue;
+                    }
+                    if starting_balance.is_some_and(|starting_balance| starting_balance < min_buy_in || starting_balance > max_buy_in) {
+                        println!("The starting balance must be within the buy-in limits");
+                        continue;
+                    }
                     match game_type {
                         GameType::FiveCardDraw => {
-                            MenuNavigation::lobby_page(player, Game::<FiveCardDraw<CliInput>>::new(raise_limit, minimum_bet, DbHandler::new_dummy())).await;
+                            MenuNavigation::lobby_page(player, lobby_name, min_buy_in, max_buy_in, starting_balance, blind_schedule, config, Game::<FiveCardDraw<BotInput<CliInput>>>::new(raise_limit, minimum_bet, small_blind, db_handler.clone())).await;
                             break;
                         },
                         GameType::SevenCardStud => {
-                            MenuNavigation::lobby_page(player, Game::<SevenCardStud<CliInput>>::new(raise_limit, minimum_bet, DbHandler::new_dummy())).await;
+                            MenuNavigation::lobby_page(player, lobby_name, min_buy_in, max_buy_in, starting_balance, blind_schedule, config, Game::<SevenCardStud<BotInput<CliInput>>>::new(raise_limit, minimum_bet, small_blind, db_handler.clone())).await;
                             break;
                         },
                         GameType::TexasHoldem => {
-                            MenuNavigation::lobby_page(player, Game::<TexasHoldem<CliInput>>::new(raise_limit, minimum_bet, DbHandler::new_dummy())).await;
+                            MenuNavigation::lobby_page(player, lobby_name, min_buy_in, max_buy_in, starting_balance, blind_schedule, config, Game::<TexasHoldem<BotInput<CliInput>>>::new(raise_limit, minimum_bet, small_blind, db_handler.clone())).await;
+                            break;
+                        },
+                        GameType::CommunityDraw => {
+                            MenuNavigation::lobby_page(player, lobby_name, min_buy_in, max_buy_in, starting_balance, blind_schedule, config, Game::<CommunityDraw<BotInput<CliInput>>>::new(raise_limit, minimum_bet, small_blind, db_handler.clone())).await;
+                            break;
+                        },
+                        GameType::Badugi => {
+                            MenuNavigation::lobby_page(player, lobby_name, min_buy_in, max_buy_in, starting_balance, blind_schedule, config, Game::<Badugi<BotInput<CliInput>>>::new(raise_limit, minimum_bet, small_blind, db_handler.clone())).await;
+                            break;
+                        },
+                        GameType::ShortDeckHoldem => {
+                            MenuNavigation::lobby_page(player, lobby_name, min_buy_in, max_buy_in, starting_balance, blind_schedule, config, Game::<ShortDeckHoldem<BotInput<CliInput>>>::new(raise_limit, minimum_bet, small_blind, db_handler.clone())).await;
                             break;
                         },
                     };
@@ -219,6 +303,20 @@ impl MenuNavigation {
         }
     }
 
+    pub fn lobby_name_selection_page() -> Option<String> {
+        println!("\nLobby Name Selection Page");
+        println!("Enter a lobby name (leave blank to use the default name):");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("failed to read line");
+        let name = input.trim();
+        if name.is_empty() {
+            return None;
+        }
+        return Some(name.to_string());
+    }
+
     pub fn game_type_selection_page() -> GameType {
         loop {
             println!("\nGame Type Selection Page");
@@ -259,6 +357,47 @@ impl MenuNavigation {
         }
     }
 
+    pub fn buy_in_selection_page(limit_name: &str) -> usize {
+        loop {
+            println!("\nBuy-In Selection Page");
+            println!("Set a {} buy-in:", limit_name);
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<usize>() {
+                Ok(amount) => {
+                    if amount == 0 {
+                        println!("You must enter a positive and non-zero {} buy-in", limit_name);
+                    }
+                    else {
+                        return amount;
+                    }
+                },
+                _ => println!("You must enter a number")
+            }
+        }
+    }
+
+    pub fn starting_balance_selection_page() -> Option<usize> {
+        loop {
+            println!("\nStarting Balance Selection Page");
+            println!("Set the balance every player starts with (leave blank to allow any balance within the buy-in limits):");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            if input.trim().is_empty() {
+                return None;
+            }
+            match input.trim().parse::<usize>() {
+                Ok(0) => println!("You must enter a positive and non-zero starting balance"),
+                Ok(amount) => return Some(amount),
+                _ => println!("You must enter a number")
+            }
+        }
+    }
+
     pub fn minimum_bet_selection_page() -> u32 {
         loop {
             println!("\nMinimum Bet Selection Page");
@@ -269,7 +408,7 @@ impl MenuNavigation {
                 .expect("failed to read line");
             match input.trim().parse::<u32>() {
                 Ok(amount) => {
-                    if amount <= 0 {
+                    if amount == 0 {
                         println!("You must enter a positive and non-zero minimum bet");
                     }
                     else {
@@ -281,11 +420,46 @@ impl MenuNavigation {
         }
     }
 
-    pub async fn lobby_page<T: Rules>(player: Player, mut game: Game<T>) {
-        game.add_player(player).unwrap();
+    pub fn small_blind_selection_page() -> u32 {
+        loop {
+            println!("\nSmall Blind Selection Page");
+            println!("Set a small blind:");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<u32>() {
+                Ok(amount) => return amount,
+                _ => println!("You must enter a number")
+            }
+        }
+    }
+
+    pub async fn lobby_page<T: Rules>(player: Player, lobby_name: Option<String>, min_buy_in: usize, max_buy_in: usize, starting_balance: Option<usize>, blind_schedule: Option<BlindSchedule>, config: &Config, mut game: Game<T>) {
+        game.set_buy_in_limits(min_buy_in, max_buy_in).unwrap();
+        if let Some(starting_balance) = starting_balance {
+            game.set_starting_balance(starting_balance);
+        }
+        game.set_blind_schedule(blind_schedule);
+        if let Err(message) = game.add_player(player) {
+            println!("Cannot join the lobby: {}", message);
+            return;
+        }
         loop {
             println!("\nLobby Page");
+            if let Some(lobby_name) = &lobby_name {
+                println!("Lobby: {}", lobby_name);
+            }
+            println!("{}", T::deal_order_description());
             println!("Current players: {:?}", game.players().iter().map(|player| player.name()).collect::<Vec<&str>>());
+            println!("Sitting out: {:?}", game.players().iter().filter(|player| player.is_sitting_out()).map(|player| player.name()).collect::<Vec<&str>>());
+            match game.sit_out_mode() {
+                SitOutMode::SkipHand => println!("Players sitting out are skipped entirely"),
+                SitOutMode::PostAndFold => println!("Players sitting out post blinds and antes, then fold"),
+            }
+            if !game.can_start() {
+                println!("Warning: a round needs between {} and {} players who are playing and can afford the minimum bet", T::MIN_PLAYERS, T::MAX_PLAYERS);
+            }
             println!("Select an option:");
             for (i, option) in LobbyPageOption::iter().enumerate() {
                 println!("{} - {}", i, option);
@@ -305,15 +479,85 @@ impl MenuNavigation {
             };
             match option {
                 LobbyPageOption::RefreshPlayerList => continue,
-                LobbyPageOption::StartRound => game.play_game().await,
+                LobbyPageOption::StartRound => {
+                    if game.can_start() {
+                        game.play_game().await;
+                    }
+                    else {
+                        println!("Cannot start a round with these players, a round needs between {} and {} players who are playing and can afford the minimum bet", T::MIN_PLAYERS, T::MAX_PLAYERS);
+                    }
+                },
                 LobbyPageOption::LeaveLobby => break,
                 LobbyPageOption::AddLocalPlayer => {
-                    game.add_player(MenuNavigation::register_page()).unwrap();
+                    if let Err(message) = game.add_player(MenuNavigation::register_page(config)) {
+                        println!("Cannot add player: {}", message);
+                    }
+                },
+                LobbyPageOption::AddBot => {
+                    // bots buy in for the starting balance, or else for 1000, or as close to it as the buy-in limits allow
+                    if let Err(message) = game.add_bot(starting_balance.unwrap_or(1000_usize.clamp(min_buy_in, max_buy_in))) {
+                        println!("Cannot add bot: {}", message);
+                    }
+                },
+                LobbyPageOption::ToggleSitOut => {
+                    let player_id = MenuNavigation::player_selection_page(game.players());
+                    match game.toggle_sit_out(player_id) {
+                        Ok(true) => println!("Sitting out from the next round"),
+                        Ok(false) => println!("Playing again from the next round"),
+                        Err(message) => println!("Cannot sit out: {}", message),
+                    }
+                },
+                LobbyPageOption::ToggleSitOutMode => {
+                    game.set_sit_out_mode(match game.sit_out_mode() {
+                        SitOutMode::SkipHand => SitOutMode::PostAndFold,
+                        SitOutMode::PostAndFold => SitOutMode::SkipHand,
+                    });
+                },
+                LobbyPageOption::ChangeDisplayName => {
+                    let player_id = MenuNavigation::player_selection_page(game.players());
+                    let new_name = CliInput::new().request_username();
+                    if let Err(message) = game.rename_player(player_id, new_name) {
+                        println!("Cannot change name: {}", message);
+                    }
+                },
+                LobbyPageOption::ExportHistory => {
+                    let history = match game.export_last_round_json() {
+                        Some(history) => history,
+                        None => {
+                            println!("No rounds have been played yet");
+                            continue;
+                        },
+                    };
+                    // timestamped so that exporting after each round doesn't overwrite earlier exports
+                    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).expect("System time is before the unix epoch").as_secs();
+                    let file_name = format!("hand_history_{timestamp}.json");
+                    match fs::write(&file_name, history) {
+                        Ok(()) => println!("Exported the last round to {}", file_name),
+                        Err(e) => println!("Failed to export the last round: {}", e),
+                    }
                 },
             };
         }
     }
 
+    pub fn player_selection_page(players: Vec<&Player>) -> Uuid {
+        loop {
+            println!("\nPlayer Selection Page");
+            println!("Select a player:");
+            for (i, player) in players.iter().enumerate() {
+                println!("{} - {}", i, player.name());
+            }
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim().parse::<usize>() {
+                Ok(index) if index < players.len() => return players[index].account_id(),
+                _ => println!("invalid input, please enter a number between 0 and {}:", players.len()-1),
+            };
+        }
+    }
+
     pub async fn lobby_join_page(player: Player) {
         loop {
             println!("\nLobby Join Page");
@@ -323,12 +567,199 @@ impl MenuNavigation {
         }
     }
 
-    pub fn game_statistics_page() {
+    pub async fn game_statistics_page(player: &Player, db_handler: &DbHandler) {
+        let rounds = Pot::load_player_rounds(db_handler, &player.account_id()).await;
+        // the pot of each round is rebuilt from its turns, to break the round down by phase
+        let mut round_pots = Vec::new();
+        for round in rounds.iter() {
+            round_pots.push(Pot::from_turns(&round.player_ids, &Pot::load_turns(db_handler, round).await));
+        }
         loop {
             println!("\nGame Statistics Page");
-            println!("Select a game:");
-            println!("This has not yet been implemented!");
-            break;
+            if rounds.is_empty() {
+                println!("No rounds have been recorded for {}", player.name());
+                break;
+            }
+            println!("Select a round:");
+            for (i, (round, round_pot)) in rounds.iter().zip(round_pots.iter()).enumerate() {
+                println!("{} - Replay this round ({} players, {} turns)", i, round.player_ids.len(), round.turn_ids.len());
+                for phase_num in round_pot.phases_played() {
+                    let actions: Vec<String> = round_pot.get_phase_history(phase_num).iter()
+                        .map(|(_, action, _, _)| action.to_string())
+                        .collect();
+                    println!("    phase {}: {}", phase_num, actions.join(", "));
+                }
+            }
+            println!("x - Back");
+            let mut input = String::new();
+            io::stdin()
+                .read_line(&mut input)
+                .expect("failed to read line");
+            match input.trim() {
+                "x" => break,
+                _ => match input.trim().parse::<usize>() {
+                    Ok(index) if index < rounds.len() => MenuNavigation::round_replay_page(player, &rounds[index], db_handler).await,
+                    _ => println!("invalid input, please enter a number between 0 and {}, or x:", rounds.len()-1),
+                },
+            };
         }
     }
+
+    pub async fn round_replay_page(player: &Player, round: &Round, db_handler: &DbHandler) {
+        let turns = Pot::load_turns(db_handler, round).await;
+        MenuNavigation::replay_page(player, round, turns);
+    }
+
+    /// step through a stored round one turn at a time, showing the pot as it was after each turn
+    pub fn replay_page(player: &Player, round: &Round, turns: Vec<Turn>) {
+        let players = MenuNavigation::round_players(player, round);
+        let input = CliInput::new();
+        println!("\nRound Replay Page");
+        println!("Press enter to advance to the next action");
+        let mut turn_number = 0;
+        Pot::replay_each_turn(&round.player_ids, &turns, |turn, pot| {
+            turn_number += 1;
+            println!("\n{}", MenuNavigation::describe_turn(&players, turn_number, turn));
+            let visible_cards = MenuNavigation::visible_cards(player, turn);
+            if !visible_cards.is_empty() {
+                println!("Their cards were:");
+                for card in visible_cards {
+                    println!("-> {card} <-");
+                }
+            }
+            input.display_pot(pot, players.iter().collect());
+            let mut line = String::new();
+            io::stdin()
+                .read_line(&mut line)
+                .expect("failed to read line");
+        });
+        println!("\nEnd of round replay");
+    }
+
+    /// the players of a stored round, in seat order, only the viewing player's name is known,
+    /// so the others are named by their seat
+    fn round_players(player: &Player, round: &Round) -> Vec<Player> {
+        return round.player_ids.iter()
+            .enumerate()
+            .map(|(seat, player_id)| match *player_id == player.account_id() {
+                true => Player::new(*player_id, player.name().to_string(), 0),
+                false => Player::new(*player_id, format!("Seat {}", seat + 1), 0),
+            })
+            .collect();
+    }
+
+    /// the cards of a replayed turn that the viewing player could see at the time,
+    /// which are all of their own cards, and only the face up cards of the other players
+    fn visible_cards<'a>(player: &Player, turn: &'a Turn) -> Vec<&'a Card> {
+        return turn.hand.iter()
+            .filter(|card| turn.acting_player_id == player.account_id() || card.is_face_up())
+            .collect();
+    }
+
+    /// one line describing a turn of a replayed round, like "Turn 3 (phase 1): Alice raise 20"
+    fn describe_turn(players: &Vec<Player>, turn_number: usize, turn: &Turn) -> String {
+        let name = players.iter()
+            .find(|player| player.account_id() == turn.acting_player_id)
+            .map_or(turn.acting_player_id.to_string(), |player| player.name().to_string());
+        return format!("Turn {} (phase {}): {} {}", turn_number, turn.phase_num, name, turn.action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::Action;
+    use crate::card::{Rank, Suit};
+
+    #[test]
+    fn registered_player_starting_balance() {
+        let config = Config { starting_balance: 2500, ..Config::default() };
+        let player = MenuNavigation::registered_player(" Alice \n", &config).unwrap();
+        assert_eq!(player.name(), "Alice");
+        assert_eq!(player.balance(), 2500);
+        assert_eq!(MenuNavigation::registered_player("Bob", &Config::default()).unwrap().balance(), 1000);
+        assert!(MenuNavigation::registered_player("  \n", &config).is_none());
+    }
+
+    fn stored_round(player_ids: &Vec<Uuid>) -> Round {
+        return Round {
+            _id: Uuid::now_v7(),
+            game_id: Uuid::now_v7(),
+            game_round_number: 1,
+            turn_ids: Vec::new(),
+            player_ids: player_ids.clone(),
+            final_stakes: std::collections::HashMap::new(),
+        };
+    }
+
+    #[test]
+    fn describe_replayed_turns() {
+        let player = Player::new(Uuid::now_v7(), "Alice".to_string(), 1000);
+        let round = stored_round(&vec![Uuid::now_v7(), player.account_id()]);
+        let players = MenuNavigation::round_players(&player, &round);
+        assert_eq!(players[0].name(), "Seat 1");
+        assert_eq!(players[1].name(), "Alice");
+
+        let turn = Turn {
+            _id: Uuid::now_v7(),
+            round_id: round._id,
+            phase_num: 1,
+            acting_player_id: player.account_id(),
+            hand: Vec::new(),
+            action: Action::Raise(20),
+        };
+        assert_eq!(MenuNavigation::describe_turn(&players, 3, &turn), "Turn 3 (phase 1): Alice raise 20");
+    }
+
+    #[test]
+    fn replay_only_shows_visible_cards() {
+        let player = Player::new(Uuid::now_v7(), "Alice".to_string(), 1000);
+        let opponent_id = Uuid::now_v7();
+        let hand = || -> Vec<Card> { vec![Card::face_up(Rank::Ace, Suit::Spades), Card::new(Rank::King, Suit::Hearts)] };
+        let turn = |acting_player_id: Uuid| Turn {
+            _id: Uuid::now_v7(),
+            round_id: Uuid::now_v7(),
+            phase_num: 1,
+            acting_player_id,
+            hand: hand(),
+            action: Action::Call,
+        };
+
+        // the viewing player sees all of their own cards, but only the other players' face up cards
+        assert_eq!(MenuNavigation::visible_cards(&player, &turn(player.account_id())).len(), 2);
+        let opponent_turn = turn(opponent_id);
+        assert_eq!(MenuNavigation::visible_cards(&player, &opponent_turn), vec![&hand()[0]]);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn replay_saved_round() {
+        let db_handler = DbHandler::new("mongodb://localhost:27017/".to_string(), "test".to_string()).await.unwrap();
+        let player = Player::new(Uuid::now_v7(), "Alice".to_string(), 1000);
+        let player_ids = vec![player.account_id(), Uuid::now_v7()];
+        let mut pot = Pot::new_uuids(&player_ids, db_handler.clone());
+        pot.add_turn(&player_ids[0], Action::Blind(1), 0, Vec::new());
+        pot.add_turn(&player_ids[1], Action::Blind(2), 0, Vec::new());
+        pot.add_turn(&player_ids[0], Action::Raise(10), 1, Vec::new());
+        pot.add_turn(&player_ids[1], Action::Call, 1, Vec::new());
+        pot.divide_winnings(vec![vec![player_ids[0]], vec![player_ids[1]]], &player_ids).unwrap();
+        let game_id = Uuid::now_v7();
+        pot.save(game_id, 1).await;
+
+        let rounds = Pot::load_player_rounds(&db_handler, &player.account_id()).await;
+        let round = rounds.iter().find(|round| round.game_id == game_id).unwrap();
+        let turns = Pot::load_turns(&db_handler, round).await;
+        let players = MenuNavigation::round_players(&player, round);
+        let mut descriptions = Vec::new();
+        let replayed_pot = Pot::replay_each_turn(&round.player_ids, &turns, |turn, _| {
+            descriptions.push(MenuNavigation::describe_turn(&players, descriptions.len() + 1, turn));
+        });
+        assert_eq!(descriptions[..4], [
+            "Turn 1 (phase 0): Alice ante 1",
+            "Turn 2 (phase 0): Seat 2 ante 2",
+            "Turn 3 (phase 1): Alice raise 10",
+            "Turn 4 (phase 1): Seat 2 call"
+        ]);
+        assert_eq!(replayed_pot.get_total_stake(), 20);
+    }
 }
@@ -10,7 +10,14 @@ pub struct Player {
     account_id: Uuid,
     name: String,
     balance: usize,
-    cards: Vec<Card>
+    cards: Vec<Card>,
+    #[serde(default)]
+    is_bot: bool,
+    #[serde(default)]
+    sit_out: bool,
+    /// whether the player has folded in the current round, the rules clear it at the start of each round
+    #[serde(skip)]
+    folded: bool
 }
 
 impl Player {
@@ -21,27 +28,43 @@ impl Player {
             account_id,
             name,
             balance,
-            cards
+            cards,
+            is_bot: false,
+            sit_out: false,
+            folded: false
         };
     }
 
+    /// create a new computer controlled player,
+    /// their actions are chosen by a BotInput rather than a person
+    pub fn new_bot(account_id: Uuid, name: String, balance: usize) -> Player {
+        let mut player = Player::new(account_id, name, balance);
+        player.is_bot = true;
+        return player;
+    }
+
     // get the player's current wallet balance
     pub fn balance(&self) -> usize {
         return self.balance;
     }
 
+    /// true if the player has no money left
+    pub fn is_bankrupt(&self) -> bool {
+        return self.balance() == 0;
+    }
+
     /// Removes the amount from the Player's wallet.
     /// Returns Ok(amount remaining in wallet) on success,
     /// but if the Player does not have enough funds to make the bet,
-    /// Returns Err() and does not remove funds.
+    /// Returns Err("Insufficient balance") and does not remove funds.
     pub fn bet(&mut self, amount: usize) -> Result<usize, &'static str> {
-        if self.balance >= amount {
-            self.balance = self.balance - amount;
-            return Ok(self.balance);
-        }
-        else {
-            return Err("Player does not have enough money remaining to make this bet");
-        }
+        return match self.balance.checked_sub(amount) {
+            Some(balance) => {
+                self.balance = balance;
+                Ok(self.balance)
+            },
+            None => Err("Insufficient balance"),
+        };
     }
 
     /// Adds the amount to the PLayer's wallet, which occurs when they win a pot
@@ -59,6 +82,37 @@ impl Player {
         return &self.name;
     }
 
+    /// change the name the player is shown as
+    pub fn rename(&mut self, new_name: String) {
+        self.name = new_name;
+    }
+
+    /// whether the player is computer controlled
+    pub fn is_bot(&self) -> bool {
+        return self.is_bot;
+    }
+
+    /// whether the player is sitting out, keeping their seat but not playing hands
+    pub fn is_sitting_out(&self) -> bool {
+        return self.sit_out;
+    }
+
+    /// set whether the player is sitting out
+    pub fn set_sit_out(&mut self, sit_out: bool) {
+        self.sit_out = sit_out;
+    }
+
+    /// whether the player has folded in the current round,
+    /// this always agrees with Pot::player_has_folded, but doesn't search the pot's history
+    pub fn has_folded(&self) -> bool {
+        return self.folded;
+    }
+
+    /// set whether the player has folded in the current round
+    pub fn set_folded(&mut self, folded: bool) {
+        self.folded = folded;
+    }
+
     /// the player obtains this card
     pub fn obtain_card(&mut self, card: Card) {
         self.cards.push(card);
@@ -74,10 +128,37 @@ impl Player {
         return cards;
     }
 
-    /// take a peek at the player's cards without returning them
+    /// take a peek at the player's cards without returning them,
+    /// the cards are always in the order that the player obtained them
     pub fn peek_at_cards(&self) -> Vec<&Card> {
         return self.cards.iter().collect();
     }
+
+    /// take a peek at the player's face up cards (which every player can see), in the order that the player obtained them
+    pub fn peek_at_up_cards(&self) -> Vec<&Card> {
+        return self.cards.iter().filter(|card| card.is_face_up()).collect();
+    }
+
+    /// take a peek at the player's face down cards (which only the player can see), in the order that the player obtained them
+    pub fn peek_at_down_cards(&self) -> Vec<&Card> {
+        return self.cards.iter().filter(|card| !card.is_face_up()).collect();
+    }
+
+    /// take a peek at the player's cards sorted by rank, then by suit,
+    /// without changing the order of the cards that the player holds (see peek_at_cards)
+    pub fn sorted_cards(&self) -> Vec<&Card> {
+        let mut cards: Vec<&Card> = self.cards.iter().collect();
+        cards.sort();
+        return cards;
+    }
+
+    /// true if the player holds every one of these cards, and no card is given more than once,
+    /// used to check cards that a player chose (e.g. to be replaced) before acting on them
+    pub fn holds_cards(&self, cards: &[&Card]) -> bool {
+        return cards.iter().enumerate().all(|(card_index, card)| {
+            self.cards.contains(card) && !cards[..card_index].contains(card)
+        });
+    }
 }
 
 impl PartialEq for Player {
@@ -86,8 +167,88 @@ impl PartialEq for Player {
     }
 }
 
+/// Displays the Player's name followed by their balance
+impl std::fmt::Display for Player {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (${})", self.name, self.balance)
+    }
+}
+
 impl Clone for Player {
     fn clone(&self) -> Self {
-        Self { account_id: self.account_id.clone(), name: self.name.clone(), balance: self.balance.clone(), cards: self.cards.clone() }
+        Self { account_id: self.account_id.clone(), name: self.name.clone(), balance: self.balance.clone(), cards: self.cards.clone(), is_bot: self.is_bot, sit_out: self.sit_out, folded: self.folded }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let player = Player::new(Uuid::now_v7(), "Alice".to_string(), 250);
+        assert_eq!(player.to_string(), "Alice ($250)");
+    }
+
+    #[test]
+    fn bet_more_than_balance() {
+        let mut player = Player::new(Uuid::now_v7(), "Alice".to_string(), 250);
+        assert_eq!(player.bet(251), Err("Insufficient balance"));
+        assert!(player.bet(usize::MAX).is_err());
+        // the failed bets took nothing out of the wallet
+        assert_eq!(player.balance(), 250);
+        assert_eq!(player.bet(250), Ok(0));
+        assert!(player.bet(1).is_err());
+    }
+
+    #[test]
+    fn rename() {
+        let mut player = Player::new(Uuid::now_v7(), "Alice".to_string(), 250);
+        player.rename("Bob".to_string());
+        assert_eq!(player.name(), "Bob");
+        assert_eq!(player.to_string(), "Bob ($250)");
+    }
+
+    #[test]
+    fn up_cards_and_down_cards() {
+        let mut player = Player::new(Uuid::now_v7(), "Alice".to_string(), 250);
+        for (card, is_face_up) in [("Kh", false), ("2c", true), ("As", false), ("Kd", true), ("7s", true)] {
+            let mut card: Card = card.parse().unwrap();
+            card.set_face_up(is_face_up);
+            player.obtain_card(card);
+        }
+        let up_cards: Vec<String> = player.peek_at_up_cards().iter().map(|card| card.to_notation()).collect();
+        assert_eq!(up_cards, vec!["2c", "Kd", "7s"]);
+        let down_cards: Vec<String> = player.peek_at_down_cards().iter().map(|card| card.to_notation()).collect();
+        assert_eq!(down_cards, vec!["Kh", "As"]);
+    }
+
+    #[test]
+    fn sorted_cards() {
+        let mut player = Player::new(Uuid::now_v7(), "Alice".to_string(), 250);
+        for card in ["Kh", "2c", "As", "Kd"] {
+            player.obtain_card(card.parse().unwrap());
+        }
+        let sorted_cards: Vec<String> = player.sorted_cards().iter().map(|card| card.to_notation()).collect();
+        assert_eq!(sorted_cards, vec!["2c", "Kd", "Kh", "As"]);
+        // the order that the cards were obtained in is kept
+        let cards: Vec<String> = player.peek_at_cards().iter().map(|card| card.to_notation()).collect();
+        assert_eq!(cards, vec!["Kh", "2c", "As", "Kd"]);
+    }
+
+    #[test]
+    fn holds_cards() {
+        let mut player = Player::new(Uuid::now_v7(), "Alice".to_string(), 250);
+        let ace: Card = "As".parse().unwrap();
+        let king: Card = "Kh".parse().unwrap();
+        let queen: Card = "Qd".parse().unwrap();
+        player.obtain_card(ace.clone());
+        player.obtain_card(king.clone());
+        assert!(player.holds_cards(&[]));
+        assert!(player.holds_cards(&[&king, &ace]));
+        // a card that the player doesn't hold
+        assert!(!player.holds_cards(&[&ace, &queen]));
+        // the same card twice
+        assert!(!player.holds_cards(&[&ace, &ace]));
     }
 }
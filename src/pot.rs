@@ -1,30 +1,101 @@
 use std::vec::Vec;
 use std::collections::HashSet;
 use std::clone::Clone;
+use std::cmp::min;
 
 use uuid::Uuid;
-use bson::de::from_bson;
+use bson::{de::from_bson, doc};
+use futures::TryStreamExt;
+use serde_json::json;
 
 use crate::database::db_handler::DbHandler;
 use crate::database::db_structs::{Round, Turn};
 use crate::action::Action;
 use crate::player::Player;
 use crate::card::Card;
+use crate::game_event::GameEvent;
 
 mod stakes;
 use stakes::Stakes;
 
+/// A main pot or side pot, as decomposed from the players' stakes in a Pot.
+/// Only players who have not folded are eligible to win it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SidePot {
+    pub amount: u32,
+    pub eligible_player_ids: Vec<Uuid>,
+}
+
+/// Errors that can occur when dividing the winnings of a Pot,
+/// these indicate that the pot's history or the winning order is malformed.
+#[derive(Debug, PartialEq)]
+pub enum PotError {
+    /// a player has a negative stake in the pot
+    NegativeStakes(Uuid, i64),
+    /// money was left over after all of the pots were divided
+    UndividedStakes(i64),
+    /// the winning order does not contain any players
+    EmptyWinningOrder,
+    /// none of the players in the winning order are still in the round (all have folded)
+    NoEligibleWinners,
+    /// there are no turns in the pot's history to undo
+    NothingToUndo,
+    /// the last turn is an ante, blind, win or loss, which can't be taken back
+    CannotUndo(Action),
+    /// the last turn was taken by another player (the player given), players can only take back their own turns
+    UndoOtherPlayersTurn(Uuid),
+    /// the last turn was played in an earlier phase (the phase given), turns can't be undone across phases
+    UndoPreviousPhase(usize),
+}
+
+impl std::fmt::Display for PotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PotError::NegativeStakes(player_id, stake) => write!(f, "Player {player_id} has negative stakes of {stake}"),
+            PotError::UndividedStakes(remaining_amount) => write!(f, "${remaining_amount} was left over after dividing the pot"),
+            PotError::EmptyWinningOrder => write!(f, "The winning order does not contain any players"),
+            PotError::NoEligibleWinners => write!(f, "None of the players in the winning order are eligible to win"),
+            PotError::NothingToUndo => write!(f, "There are no turns to undo"),
+            PotError::CannotUndo(action) => write!(f, "The last turn ({action:?}) cannot be undone"),
+            PotError::UndoOtherPlayersTurn(player_id) => write!(f, "The last turn was taken by player {player_id}, players can only undo their own turns"),
+            PotError::UndoPreviousPhase(phase_num) => write!(f, "The last turn was played in phase {phase_num}, turns cannot be undone across phases"),
+        }
+    }
+}
+
+/// A turn that was taken back with Pot::undo_last_turn.
+#[derive(Debug, PartialEq)]
+pub struct UndoneAction {
+    pub player_id: Uuid,
+    pub action: Action,
+    pub phase_num: usize,
+    /// the amount taken back out of the pot, which should be returned to the player
+    pub refund: i64,
+}
+
 /// Pot struct
 /// 
 /// Intended to keep track of what moves player made during a game as well
 /// as the current stakes for players. The stakes are updated each time a
 /// turn played and added to the pot's history.
 /// 
+/// Antes are tracked separately from the rest of the stakes, as they are dead money:
+/// they are part of the pot, but do not count towards the amount a player has to call.
+/// 
 /// NOTE: No checks for correctness are implemented in Pot. This must be
 /// done when Turns are being created.
 pub struct Pot {
     history: Vec<(Uuid, Action, usize, Vec<Card>)>,
+    /// the acting player's total stake before each turn in the history, so that turns can be undone
+    previous_stakes: Vec<i64>,
     stakes: Stakes,
+    antes: Stakes,
+    /// the least that players have to call, even if nobody has put that much in,
+    /// which is the full big blind when the big blind player is all in for less
+    minimum_call_amount: i64,
+    /// the value of the smallest chip, every bet should be a whole number of chips,
+    /// and pots are divided in whole chips
+    chip_size: i64,
     db_handler: DbHandler,
 }
 
@@ -33,7 +104,11 @@ impl Pot {
     pub fn new_uuids(players: &Vec<Uuid>, db_handler: DbHandler) -> Pot {
         return Pot {
             history: Vec::new(),
+            previous_stakes: Vec::new(),
             stakes: Stakes::new_uuids(players),
+            antes: Stakes::new_uuids(players),
+            minimum_call_amount: 0,
+            chip_size: 1,
             db_handler: db_handler,
         };
     }
@@ -42,14 +117,20 @@ impl Pot {
     pub fn new(players: &Vec<&Player>, db_handler: DbHandler) -> Pot {
         return Pot {
             history: Vec::new(),
+            previous_stakes: Vec::new(),
             stakes: Stakes::new(players),
+            antes: Stakes::new(players),
+            minimum_call_amount: 0,
+            chip_size: 1,
             db_handler: db_handler,
         };
     }
 
-    /// Gets the current call amount.
+    /// Gets the current call amount (not including antes).
     pub fn get_call_amount(&self) -> i64 {
-        let amount = self.stakes.max();
+        let amount = self.stakes.iter()
+            .map(|(player_id, stake)| *stake - self.antes.get(player_id))
+            .fold(self.minimum_call_amount, |acc, stake| acc.max(stake));
         assert!(amount >= 0, "Found negative call amount!");
         return amount;
     }
@@ -61,76 +142,67 @@ impl Pot {
     /// and least winning (last index). Only IDs of players who have played during a pot should
     /// be in winning_order.
     /// 
+    /// seat_order is the player IDs in seat order, starting with the player closest to the left of the dealer.
+    /// When a pot cannot be split evenly between tied winners, the odd chips go to the winners
+    /// closest to the left of the dealer, so that no money is lost.
+    /// 
     /// This function will modify pot's history and add additional turns that specify winnings/losings
     /// of each player at the end of the round.
     /// 
     /// A HashMap of player winnings is returned from this method so balance fields in Player structs 
     /// can be updated based on their wins and losses.
-    pub fn divide_winnings(&mut self, winning_order: Vec<Vec<Uuid>>) -> Stakes { 
-        let mut remaining_stakes = self.stakes.clone();
-        let mut net_balance_changes  = Stakes::new_uuids(&self.stakes.get_player_ids().iter().map(|x| **x).collect());
-        let mut winnings = Stakes::new_uuids(&self.get_player_ids());
-        loop {
-            let remaining_amount = remaining_stakes.sum();
-            if remaining_amount == 0 { break; }
-            
-            // Find minimum non-zero player stakes (this will determine pot amount).
-            let min_stakes: i64 = remaining_stakes.iter().fold(10000000000, |acc, (_, stake)| {
-                if *stake != 0 && *stake < acc {
-                    return *stake;
-                }
-                acc
-            });
-            assert!(0 < min_stakes && min_stakes <= 10000000000, "Illegal min stakes");
-
-            // Find elligible winners.
-            let mut highest_non_folding_players = Vec::new();
-            let mut pot_winners = Vec::new();
-            let mut winners_with_stakes = false;
-            let mut highest_non_folded = false;
-            for winners in winning_order.iter() {
-                for player in winners {
-                    if !self.player_has_folded(&player) {
-                        if !highest_non_folded {
-                            highest_non_folding_players.push(player);
-                            highest_non_folded = true;
-                        }
-
-                        if remaining_stakes.get(&player) >= min_stakes {
-                            pot_winners.push(player);
-                            winners_with_stakes = true;
-                        }
-                    }
-                }
-                if winners_with_stakes { break; }
-            }
-
-            // Gather pot money from players.
-            let mut pot_amount = 0;
-            for player in self.get_player_ids() {
-                let stakes = remaining_stakes.get(&player);
-                if  stakes != 0 {
-                    assert!(stakes >= min_stakes, "Player {} has ${} while the minimum stakes are {}", player, stakes, min_stakes);
-                    remaining_stakes.add(player, -(min_stakes as i64));
-                    net_balance_changes.add(player, -(min_stakes as i64));
-                    pot_amount += min_stakes;
-                }
+    /// 
+    /// If the stakes or winning order are malformed (which would be caused by a bug elsewhere),
+    /// a PotError is returned instead, and the pot's history is left unchanged.
+    pub fn divide_winnings(&mut self, winning_order: Vec<Vec<Uuid>>, seat_order: &Vec<Uuid>) -> Result<Stakes, PotError> {
+        if winning_order.iter().all(|winners| winners.is_empty()) {
+            return Err(PotError::EmptyWinningOrder);
+        }
+        for (player_id, stake) in self.stakes.iter() {
+            if *stake < 0 {
+                return Err(PotError::NegativeStakes(*player_id, *stake));
             }
+        }
 
-            // Give pot money to winners.
-            if pot_winners.len() > 0 {
-                for winner in pot_winners.iter() {
-                    net_balance_changes.add(**winner, pot_amount / pot_winners.len() as i64);
-                    winnings.add(**winner, pot_amount / pot_winners.len() as i64);
+        // Give each pot to the best of its eligible players.
+        let mut winnings = Stakes::new_uuids(&self.get_player_ids());
+        for (pot_amount, eligible_player_ids) in self.compute_pots() {
+            let mut pot_winners: Vec<Uuid> = match winning_order.iter()
+                .map(|winners| winners.iter().filter(|player_id| eligible_player_ids.contains(player_id)).copied().collect::<Vec<Uuid>>())
+                .find(|winners| !winners.is_empty()) {
+                Some(pot_winners) => pot_winners,
+                // nobody in the winning order is eligible, so the best player who hasn't folded wins the pot
+                None => match winning_order.iter().flatten().find(|player_id| !self.player_has_folded(player_id)) {
+                    Some(player_id) => vec![*player_id],
+                    None => return Err(PotError::NoEligibleWinners),
+                },
+            };
+            // The pot is divided in whole chips, odd chips go to the winners closest to the left of the dealer.
+            pot_winners.sort_by_key(|winner| seat_order.iter().position(|player_id| player_id == winner).unwrap_or(seat_order.len()));
+            let pot_amount = pot_amount as i64;
+            let chips = pot_amount / self.chip_size;
+            let odd_chips = chips % pot_winners.len() as i64;
+            for (winner_index, winner) in pot_winners.iter().enumerate() {
+                let mut winner_amount = chips / pot_winners.len() as i64 * self.chip_size;
+                if (winner_index as i64) < odd_chips {
+                    winner_amount += self.chip_size;
                 }
-            } else {
-                for player in highest_non_folding_players.iter() {
-                    net_balance_changes.add(**player, pot_amount / highest_non_folding_players.len() as i64);
-                    winnings.add(**player, pot_amount / highest_non_folding_players.len() as i64);
+                if winner_index == 0 {
+                    // less than a chip is only left over when someone went all in with less than a whole chip
+                    winner_amount += pot_amount % self.chip_size;
                 }
+                winnings.add(*winner, winner_amount);
             }
         }
 
+        if winnings.sum() != self.stakes.sum() {
+            return Err(PotError::UndividedStakes(self.stakes.sum() - winnings.sum()));
+        }
+        let mut net_balance_changes = Stakes::new_uuids(&self.stakes.get_player_ids().iter().map(|x| **x).collect());
+        for (player_id, stake) in self.stakes.iter() {
+            net_balance_changes.set(*player_id, winnings.get(player_id) - stake);
+        }
+
         // Adds wins and losses to history.
         let next_phase_num = match self.history.last() {
             Some((_, _, last_phase_num, _)) => last_phase_num + 1,
@@ -144,39 +216,170 @@ impl Pot {
             }
         }
 
-        assert_eq!(remaining_stakes.sum(), 0);
+        return Ok(winnings);
+    }
 
-        winnings
+    /// Divides winnings of the current pot when the remaining cards were run twice.
+    /// 
+    /// Each player's stake is split in half, and each half is divided with divide_winnings,
+    /// using the winning order of its own runout. When a stake can't be split evenly (in whole chips),
+    /// the odd chip goes with the first runout.
+    /// 
+    /// The combined winnings of both runouts are returned, and if either division fails,
+    /// a PotError is returned instead, and the pot's history is left unchanged.
+    pub fn divide_winnings_twice(&mut self, first_winning_order: Vec<Vec<Uuid>>, second_winning_order: Vec<Vec<Uuid>>, seat_order: &Vec<Uuid>) -> Result<Stakes, PotError> {
+        let stakes = self.stakes.clone();
+        let history_length = self.history.len();
+        let mut first_half = stakes.clone();
+        let mut second_half = stakes.clone();
+        for (player_id, stake) in stakes.iter() {
+            // stakes are split in whole chips
+            let second_half_stake = stake / self.chip_size / 2 * self.chip_size;
+            first_half.set(*player_id, stake - second_half_stake);
+            second_half.set(*player_id, second_half_stake);
+        }
+
+        self.stakes = first_half;
+        let winnings = self.divide_winnings(first_winning_order, seat_order).and_then(|mut winnings| {
+            self.stakes = second_half;
+            for (player_id, amount) in self.divide_winnings(second_winning_order, seat_order)?.iter() {
+                winnings.add(*player_id, *amount);
+            }
+            return Ok(winnings);
+        });
+        self.stakes = stakes;
+        if winnings.is_err() {
+            // the first runout's wins and losses may have already been added
+            self.history.truncate(history_length);
+            self.previous_stakes.truncate(history_length);
+        }
+        return winnings;
+    }
+
+    /// Decomposes the pot into the main pot (at the first index) followed by each side pot.
+    /// 
+    /// A side pot is created whenever a player is all in for less than the other players,
+    /// each pot only contains the amount that all of its eligible players have staked.
+    /// Money staked by players who have folded still goes into the pots, but they are not eligible to win any of them.
+    /// Each pot is returned as its amount, and the sorted IDs of the players who are eligible to win it.
+    /// This is how the winnings are divided at the showdown (see divide_winnings).
+    pub fn compute_pots(&self) -> Vec<(u32, Vec<Uuid>)> {
+        let mut remaining_stakes = self.stakes.clone();
+        let mut pots: Vec<(u32, Vec<Uuid>)> = Vec::new();
+        loop {
+            // the smallest non-zero stake determines the amount each player puts into this pot
+            let min_stakes = remaining_stakes.iter()
+                .map(|(_, stake)| *stake)
+                .filter(|stake| *stake > 0)
+                .min();
+            let min_stakes = match min_stakes {
+                Some(min_stakes) => min_stakes,
+                None => break,
+            };
+
+            let mut amount = 0;
+            let mut eligible_player_ids = Vec::new();
+            let contributing_player_ids: Vec<Uuid> = remaining_stakes.iter()
+                .filter(|(_, stake)| **stake > 0)
+                .map(|(player_id, _)| *player_id)
+                .collect();
+            for player_id in contributing_player_ids {
+                remaining_stakes.add(player_id, -min_stakes);
+                amount += min_stakes;
+                if !self.player_has_folded(&player_id) {
+                    eligible_player_ids.push(player_id);
+                }
+            }
+            eligible_player_ids.sort();
+
+            match pots.last_mut() {
+                // stakes from folded players can split a pot without changing who is eligible,
+                // and money nobody is eligible for goes to the last pot that someone can win
+                Some((last_pot_amount, last_pot_player_ids)) if *last_pot_player_ids == eligible_player_ids || eligible_player_ids.is_empty() => {
+                    *last_pot_amount += amount as u32;
+                },
+                _ => pots.push((amount as u32, eligible_player_ids)),
+            }
+        }
+        return pots;
+    }
+
+    /// The main pot (at the first index) followed by each side pot, as computed by compute_pots.
+    pub fn get_side_pots(&self) -> Vec<SidePot> {
+        return self.compute_pots().into_iter()
+            .map(|(amount, eligible_player_ids)| SidePot { amount, eligible_player_ids })
+            .collect();
     }
 
     /// Reset pot to be ready for a new round.
     pub fn clear(&mut self, players: &Vec<&Player>) {
         self.history = Vec::new();
+        self.previous_stakes = Vec::new();
         self.stakes = Stakes::new(players);
+        self.antes = Stakes::new(players);
+        self.minimum_call_amount = 0;
     }
 
     /// Reset pot to be ready for a new round.
     pub fn clear_uuids(&mut self, player_ids: &Vec<Uuid>) {
         self.history = Vec::new();
+        self.previous_stakes = Vec::new();
         self.stakes = Stakes::new_uuids(player_ids);
+        self.antes = Stakes::new_uuids(player_ids);
+        self.minimum_call_amount = 0;
     }
 
-    /// Get the stake for a particular player in the pot.
+    /// Make players call at least this amount (not including antes), even if nobody has put that much in,
+    /// for when a player is all in for less than the big blind.
+    pub fn set_minimum_call_amount(&mut self, minimum_call_amount: i64) {
+        self.minimum_call_amount = minimum_call_amount;
+    }
+
+    /// Set the value of the smallest chip, so that pots are divided in whole chips,
+    /// a chip size of 1 (the default) allows any amount. The chip size is kept when the pot is cleared.
+    pub fn set_chip_size(&mut self, chip_size: u32) {
+        self.chip_size = chip_size.max(1) as i64;
+    }
+
+    /// Get the value of the smallest chip.
+    pub fn chip_size(&self) -> u32 {
+        return self.chip_size as u32;
+    }
+
+    /// Get the stake for a particular player in the pot (not including their ante),
+    /// this is the amount that is compared against the call amount.
     pub fn get_player_stake(&self, player_id: &Uuid) -> i64 {
-        let player_stakes = self.stakes.get(player_id);
+        let player_stakes = self.stakes.get(player_id) - self.antes.get(player_id);
         assert!(player_stakes >= 0, "Player {} cannot have negative stakes!", *player_id);
         return player_stakes;
     }
 
-    /// Get the total stake from all players in the pot.
+    /// Get everything a player has put into the pot, including their ante.
+    pub fn get_player_total_stake(&self, player_id: &Uuid) -> i64 {
+        return self.stakes.get(player_id);
+    }
+
+    /// Get the total stake from all players in the pot (including antes).
     pub fn get_total_stake(&self) -> u32 {
         let mut total_stake = 0;
         for player_id in self.get_player_ids() {
-            total_stake += self.get_player_stake(&player_id);
+            total_stake += self.stakes.get(&player_id);
         }
         return total_stake as u32;
     }
 
+    /// Gets the most a player could win from the pot (including their own stake).
+    /// A player can only win up to their own stake from each other player,
+    /// so a player who is all in for a small amount cannot win the full pot.
+    /// A player who has folded cannot win anything.
+    pub fn max_winnable(&self, player_id: &Uuid) -> i64 {
+        if self.player_has_folded(player_id) {
+            return 0;
+        }
+        let player_stake = self.stakes.get(player_id);
+        return self.stakes.iter().map(|(_, stake)| min(*stake, player_stake)).sum();
+    }
+
     /// Checks if a particular player has folded in the pot's history.
     pub fn player_has_folded(&self, player_id: &Uuid) -> bool {
         self.history.iter().fold(false, |acc, (acting_player_id, action, _, _)| {
@@ -204,30 +407,200 @@ impl Pot {
         id_set.into_iter().collect()
     }
 
+    /// Gets the last player to bet or raise in the final betting phase of the pot's history.
+    /// An all in only counts if it raised the call amount.
+    /// This is the player who reveals their hand first at a showdown.
+    /// Returns None if nobody bet or raised in the final betting phase.
+    pub fn get_last_aggressor(&self) -> Option<Uuid> {
+        let mut call_amount = 0;
+        let mut last_aggression: Option<(Uuid, usize)> = None;
+        let mut last_betting_phase = 0;
+        for (player_id, action, phase_num, _) in self.history.iter() {
+            match action {
+                Action::Ante(amount) | Action::Blind(amount) => {
+                    call_amount = call_amount.max(*amount);
+                },
+                Action::Bet(amount) | Action::Raise(amount) | Action::AllIn(amount) => {
+                    if *amount > call_amount {
+                        call_amount = *amount;
+                        last_aggression = Some((*player_id, *phase_num));
+                    }
+                    last_betting_phase = *phase_num;
+                },
+                Action::Call | Action::Check | Action::Fold => {
+                    last_betting_phase = *phase_num;
+                },
+                _ => (),
+            }
+        }
+        return match last_aggression {
+            Some((player_id, phase_num)) if phase_num == last_betting_phase => Some(player_id),
+            _ => None,
+        };
+    }
+
+    /// Gets the turns of the pot's history that were played in a phase, in the order they were played.
+    pub fn get_phase_history(&self, phase_num: usize) -> Vec<&(Uuid, Action, usize, Vec<Card>)> {
+        return self.history.iter()
+            .filter(|(_, _, turn_phase_num, _)| *turn_phase_num == phase_num)
+            .collect();
+    }
+
+    /// Gets the pot's history as game events, an ActionTaken for each turn played,
+    /// followed by a RoundEnded once the winnings have been divided (see divide_winnings).
+    pub fn round_events(&self) -> Vec<GameEvent> {
+        let mut events: Vec<GameEvent> = self.history.iter()
+            .filter(|(_, action, _, _)| !matches!(action, Action::Win(_) | Action::Lose(_)))
+            .map(|(player_id, action, phase_num, _)| GameEvent::ActionTaken { player_id: *player_id, action: action.clone(), phase: *phase_num })
+            .collect();
+        if self.history.iter().any(|(_, action, _, _)| matches!(action, Action::Win(_) | Action::Lose(_))) {
+            let winner_ids = self.history.iter()
+                .filter(|(_, action, _, _)| matches!(action, Action::Win(_)))
+                .map(|(player_id, _, _, _)| *player_id)
+                .collect();
+            events.push(GameEvent::RoundEnded { winner_ids, pot_total: self.get_total_stake() });
+        }
+        return events;
+    }
+
+    /// Gets the number of each phase that has turns in the pot's history, in the order they were first played.
+    pub fn phases_played(&self) -> Vec<usize> {
+        let mut phases = Vec::new();
+        for (_, _, phase_num, _) in self.history.iter() {
+            if !phases.contains(phase_num) {
+                phases.push(*phase_num);
+            }
+        }
+        return phases;
+    }
+
+    /// Adds an ante to the pot's history, recorded as an Ante action.
+    /// Unlike blinds (which are added with add_turn, as Blind actions), antes are dead money,
+    /// they are added to the player's stake in the pot, but do not count towards calling a bet.
+    pub fn add_ante(&mut self, player_id: &Uuid, amount: usize, phase_num: usize, hand: Vec<Card>) {
+        self.previous_stakes.push(self.stakes.get(player_id));
+        self.stakes.add(*player_id, amount as i64);
+        self.antes.add(*player_id, amount as i64);
+        self.history.push((*player_id, Action::Ante(amount), phase_num, hand));
+    }
+
     /// Adds a turn to the pot's history.
     /// This method does minimial checks and integrity of pot history has to
     /// be maintained by the owner of the pot instance.
     pub fn add_turn(&mut self, player_id: &Uuid, action: Action, phase_num: usize, hand: Vec<Card>) {
-        let player_stake= self.stakes.get(&player_id);
+        let player_stake = self.get_player_stake(player_id);
+        let player_ante = self.antes.get(player_id);
+        self.previous_stakes.push(self.stakes.get(player_id));
 
         match action {
-            Action::Ante(amount) | Action::Bet(amount) | Action::Raise(amount) | Action::AllIn(amount) => {
+            Action::Ante(amount) | Action::Blind(amount) | Action::Bet(amount) | Action::Raise(amount) | Action::AllIn(amount) => {
                 assert!(amount > player_stake as usize);
-                self.stakes.set(*player_id, amount as i64);
+                self.stakes.set(*player_id, amount as i64 + player_ante);
             },
             Action::Call => {
                 let call_amount = self.get_call_amount();
                 assert!(call_amount > player_stake);
-                self.stakes.set(*player_id, call_amount);
+                self.stakes.set(*player_id, call_amount + player_ante);
             },
             _ => (),
         }
         self.history.push((*player_id, action, phase_num, hand));
     }
 
-    /// Saves turns in DB and adds new round document to Rounds.
+    /// Records that a player mucked their cards at the showdown, in the phase of the last turn played.
+    /// Their stake stays in the pot, and as they haven't folded, they are still in the running for the pots they are in.
+    /// The mucked cards are not saved with the turn, so that they aren't shown when the round is replayed.
+    pub fn add_muck(&mut self, player_id: &Uuid) {
+        let phase_num = match self.history.last() {
+            Some((_, _, last_phase_num, _)) => *last_phase_num,
+            None => 0,
+        };
+        self.add_turn(player_id, Action::Muck, phase_num, Vec::new());
+    }
+
+    /// Takes back the last turn in the pot's history, for correcting input mistakes,
+    /// the acting player's stake goes back to what it was before that turn.
+    /// Only the player who took the last turn (player_id) can take it back, and only while
+    /// it is still the current phase (phase_num), antes, blinds, wins and losses can't be undone.
+    /// The player and phase are given by the rules, which know who is asking and which phase is being played,
+    /// since the pot's history alone can't tell whether the phase of its last turn is still being played.
+    /// Returns the undone turn, including the amount that should be returned to the player.
+    pub fn undo_last_turn(&mut self, player_id: &Uuid, phase_num: usize) -> Result<UndoneAction, PotError> {
+        let (last_player_id, action, last_phase_num, _) = match self.history.last() {
+            Some(turn) => turn.clone(),
+            None => return Err(PotError::NothingToUndo),
+        };
+        if matches!(action, Action::Ante(_) | Action::Blind(_) | Action::Win(_) | Action::Lose(_)) {
+            return Err(PotError::CannotUndo(action));
+        }
+        if last_player_id != *player_id {
+            return Err(PotError::UndoOtherPlayersTurn(last_player_id));
+        }
+        if last_phase_num != phase_num {
+            return Err(PotError::UndoPreviousPhase(last_phase_num));
+        }
+        let player_id = last_player_id;
+
+        self.history.pop();
+        let previous_stake = self.previous_stakes.pop().expect("Expected a previous stake for every turn in the history");
+        let refund = self.stakes.get(&player_id) - previous_stake;
+        self.stakes.set(player_id, previous_stake);
+        return Ok(UndoneAction {
+            player_id,
+            action,
+            phase_num,
+            refund,
+        });
+    }
+
+    /// Checks that the pot's stakes and history agree with each other, panicking if they don't.
+    /// Pot doesn't check the turns it is given (see add_turn), so this is for catching bugs in the rules,
+    /// which call it after each betting phase in debug builds.
+    pub fn assert_consistency(&self) {
+        assert_eq!(self.previous_stakes.len(), self.history.len(), "Expected a previous stake for every turn in the history");
+        for (player_id, stake) in self.stakes.iter() {
+            let ante = self.antes.get(player_id);
+            assert!(ante >= 0, "Player {player_id} has a negative ante of {ante}");
+            assert!(*stake >= ante, "Player {player_id} has a stake of {stake}, which is less than their ante of {ante}");
+        }
+
+        let largest_stake = self.stakes.iter()
+            .map(|(player_id, stake)| *stake - self.antes.get(player_id))
+            .max()
+            .unwrap_or(0);
+        assert_eq!(self.get_call_amount(), largest_stake.max(self.minimum_call_amount), "Expected the call amount to be the largest stake (not including antes)");
+
+        let mut folded_player_ids: HashSet<Uuid> = HashSet::new();
+        let mut last_stakes = Stakes::new_uuids(&Vec::new());
+        let mut winnings_divided = false;
+        for ((player_id, action, _, _), previous_stake) in self.history.iter().zip(self.previous_stakes.iter()) {
+            match action {
+                Action::Win(_) | Action::Lose(_) => winnings_divided = true,
+                _ => {
+                    assert!(!winnings_divided, "Player {player_id} played {action:?} after the winnings were divided");
+                    assert!(!folded_player_ids.contains(player_id), "Player {player_id} played {action:?} after folding");
+                },
+            }
+            if *action == Action::Fold {
+                folded_player_ids.insert(*player_id);
+            }
+            assert!(*previous_stake >= last_stakes.get(player_id), "Player {player_id}'s stake went down before they played {action:?}");
+            // amounts are the player's total stake, so their stake is at least that much after the turn
+            let stake_after_turn = match action {
+                Action::Ante(amount) | Action::Blind(amount) | Action::Bet(amount) | Action::Raise(amount) | Action::AllIn(amount) => (*previous_stake).max(*amount as i64),
+                _ => *previous_stake,
+            };
+            last_stakes.set(*player_id, stake_after_turn);
+        }
+        for (player_id, last_stake) in last_stakes.iter() {
+            assert!(self.stakes.get(player_id) >= *last_stake, "Player {player_id}'s stake went down after their last turn");
+        }
+    }
+
+    /// Saves turns in DB and adds new round document to Rounds, numbered round_number within the game,
+    /// along with the final stake of each player.
     /// This is intended to be used at the end of a round when no more turns will be played.
-    pub async fn save(&self, game_id: Uuid) {
+    pub async fn save(&self, game_id: Uuid, round_number: u32) {
         if self.db_handler.is_dummy() {
             return; // nothing to save with a dummy
         }
@@ -257,8 +630,12 @@ impl Pot {
         let round = Round {
             _id: round_id,
             game_id: game_id,
+            game_round_number: round_number,
             turn_ids: turn_ids,
             player_ids: self.get_player_ids(),
+            final_stakes: self.stakes.to_map().into_iter()
+                .map(|(player_id, stake)| (player_id.simple().to_string(), stake))
+                .collect(),
         };
 
         match self.db_handler.add_document(round, "Rounds").await.unwrap() {
@@ -266,6 +643,123 @@ impl Pot {
             Err(e) => println!("Error when adding round to Rounds collection: {:?}", e),
         }
     }
+
+    /// Exports the history of this round as a JSON array with one entry per turn, for reviewing hands after a session.
+    /// Each entry looks like {"player_id": "...", "action": "raise 10", "phase": 1, "hand": ["Ah", "Kd"]}.
+    pub fn export_to_json(&self) -> String {
+        let turns: Vec<serde_json::Value> = self.history.iter()
+            .map(|(player_id, action, phase_num, hand)| json!({
+                "player_id": player_id.to_string(),
+                "action": action.to_string(),
+                "phase": phase_num,
+                "hand": hand.iter().map(|card| card.to_notation()).collect::<Vec<String>>(),
+            }))
+            .collect();
+        return serde_json::to_string_pretty(&turns).expect("Failed to serialize the pot history");
+    }
+
+    /// Exports the history of this round (see export_to_json), or None if no turns have been played into the pot yet.
+    pub fn export_played_round_to_json(&self) -> Option<String> {
+        if self.get_player_ids().is_empty() {
+            return None;
+        }
+        return Some(self.export_to_json());
+    }
+
+    /// Loads the rounds that a player has played in from DB.
+    /// Nothing is loaded with a dummy.
+    pub async fn load_player_rounds(db_handler: &DbHandler, player_id: &Uuid) -> Vec<Round> {
+        match db_handler.get_documents::<Round>(doc! { "player_ids": player_id.simple().to_string() }, "Rounds").await {
+            Some(Ok(cursor)) => {
+                match cursor.try_collect().await {
+                    Ok(rounds) => return rounds,
+                    Err(e) => println!("Error when reading rounds from Rounds collection: {:?}", e),
+                }
+            },
+            Some(Err(e)) => println!("Error when retrieving rounds from Rounds collection: {:?}", e),
+            None => (),
+        }
+        return Vec::new();
+    }
+
+    /// Loads the turns of a round from DB, in the order that they were played.
+    /// Nothing is loaded with a dummy.
+    pub async fn load_turns(db_handler: &DbHandler, round: &Round) -> Vec<Turn> {
+        let mut turns = Vec::new();
+        for turn_id in round.turn_ids.iter() {
+            match db_handler.get_document_by_id::<Turn>(*turn_id, "Turns").await {
+                Some(Ok(Some(turn))) => turns.push(turn),
+                Some(Ok(None)) => println!("Turn {} of round {} is missing from Turns collection", turn_id, round._id),
+                Some(Err(e)) => println!("Error when retrieving turn from Turns collection: {:?}", e),
+                None => (),
+            }
+        }
+        return turns;
+    }
+
+    /// Replays the turns of a stored round in order, returning the total stake in the pot after each turn.
+    /// Antes are replayed as dead money (see add_ante), and blinds and every other turn are replayed with add_turn.
+    /// Rounds saved before blinds had their own action recorded the blinds (or bring-in) as antes, so in those rounds
+    /// the blinds are taken to be the last two antes, posted by different players.
+    pub fn replay(player_ids: &Vec<Uuid>, turns: &Vec<Turn>) -> Vec<u32> {
+        let mut pot_sizes = Vec::new();
+        Self::replay_each_turn(player_ids, turns, |_, pot| pot_sizes.push(pot.get_total_stake()));
+        return pot_sizes;
+    }
+
+    /// Rebuilds the pot of a stored round from its turns (see replay).
+    pub fn from_turns(player_ids: &Vec<Uuid>, turns: &Vec<Turn>) -> Pot {
+        return Self::replay_each_turn(player_ids, turns, |_, _| ());
+    }
+
+    /// Replays the turns of a stored round in order (see replay), calling on_turn with each turn
+    /// and the pot as it was right after that turn, and returns the resulting pot.
+    pub fn replay_each_turn(player_ids: &Vec<Uuid>, turns: &Vec<Turn>, mut on_turn: impl FnMut(&Turn, &Pot)) -> Pot {
+        let blind_turn_indices = Self::legacy_blind_turn_indices(turns);
+        let mut pot = Pot::new_uuids(player_ids, DbHandler::new_dummy());
+        for (turn_index, turn) in turns.iter().enumerate() {
+            match turn.action {
+                Action::Ante(amount) if !blind_turn_indices.contains(&turn_index) => {
+                    pot.add_ante(&turn.acting_player_id, amount, turn.phase_num, turn.hand.clone());
+                },
+                _ => pot.add_turn(&turn.acting_player_id, turn.action.clone(), turn.phase_num, turn.hand.clone()),
+            }
+            on_turn(turn, &pot);
+        }
+        return pot;
+    }
+
+    /// Gets the indices of the antes that were really blinds, in a round saved before blinds had their own action,
+    /// which are the last two antes, posted by different players (see replay).
+    /// Returns no indices for rounds that record their blinds as Blind actions.
+    fn legacy_blind_turn_indices(turns: &Vec<Turn>) -> Vec<usize> {
+        let mut blind_turn_indices = Vec::new();
+        if turns.iter().any(|turn| matches!(turn.action, Action::Blind(_))) {
+            return blind_turn_indices;
+        }
+        let mut blind_player_ids = Vec::new();
+        for (turn_index, turn) in turns.iter().enumerate().rev() {
+            if let Action::Ante(_) = turn.action {
+                if blind_turn_indices.len() == 2 || blind_player_ids.contains(&turn.acting_player_id) {
+                    break;
+                }
+                blind_turn_indices.push(turn_index);
+                blind_player_ids.push(turn.acting_player_id);
+            }
+        }
+        return blind_turn_indices;
+    }
+}
+
+/// Displays the total of the Pot followed by each player's stake (including antes), ordered by player ID,
+/// like "Pot: $30 | Stakes: {player_id: 10, other_player_id: 20}"
+impl std::fmt::Display for Pot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut stakes: Vec<(&Uuid, &i64)> = self.stakes.iter().collect();
+        stakes.sort();
+        let stakes: Vec<String> = stakes.iter().map(|(player_id, stake)| format!("{player_id}: {stake}")).collect();
+        write!(f, "Pot: ${} | Stakes: {{{}}}", self.get_total_stake(), stakes.join(", "))
+    }
 }
 
 
@@ -303,6 +797,321 @@ mod tests {
         assert_eq!(ctx.pot.get_player_stake(&ctx.player_ids[0]), bet_amount as i64, "Stake amount is not the same after bet turn!");
     }
 
+    #[test_context(Context)]
+    #[test]
+    fn test_get_phase_history_single_phase(ctx: &mut Context) {
+        assert!(ctx.pot.phases_played().is_empty());
+        assert!(ctx.pot.get_phase_history(0).is_empty());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Fold, 0, Vec::new());
+        assert_eq!(ctx.pot.phases_played(), vec![0]);
+        let phase_actions: Vec<(Uuid, Action)> = ctx.pot.get_phase_history(0).iter().map(|(player_id, action, _, _)| (*player_id, action.clone())).collect();
+        assert_eq!(phase_actions, vec![(ctx.player_ids[0], Action::Bet(10)), (ctx.player_ids[1], Action::Fold)]);
+        assert!(ctx.pot.get_phase_history(1).is_empty());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_phase_history_multiple_phases(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Ante(1), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Ante(2), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Call, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Check, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(4), 3, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 3, Vec::new());
+        assert_eq!(ctx.pot.phases_played(), vec![0, 1, 3]);
+        assert_eq!(ctx.pot.get_phase_history(0).len(), 2);
+        assert_eq!(ctx.pot.get_phase_history(1).len(), 2);
+        assert!(ctx.pot.get_phase_history(2).is_empty());
+        let phase_actions: Vec<&Action> = ctx.pot.get_phase_history(3).iter().map(|(_, action, _, _)| action).collect();
+        assert_eq!(phase_actions, vec![&Action::Bet(4), &Action::Call]);
+        // the winnings are recorded in a phase of their own
+        ctx.pot.divide_winnings(vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]]], &ctx.player_ids).unwrap();
+        assert_eq!(ctx.pot.phases_played().len(), 4);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_last_aggressor(ctx: &mut Context) {
+        assert_eq!(ctx.pot.get_last_aggressor(), None);
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Ante(1), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Ante(2), 0, Vec::new());
+        assert_eq!(ctx.pot.get_last_aggressor(), None, "Antes should not count as aggression!");
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Raise(10), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Raise(20), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Call, 1, Vec::new());
+        assert_eq!(ctx.pot.get_last_aggressor(), Some(ctx.player_ids[1]));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_last_aggressor_final_phase_all_checks(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Check, 2, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Check, 2, Vec::new());
+        assert_eq!(ctx.pot.get_last_aggressor(), None, "Nobody bet in the final phase, so there should be no aggressor!");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_last_aggressor_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::AllIn(5), 1, Vec::new());
+        assert_eq!(ctx.pot.get_last_aggressor(), Some(ctx.player_ids[0]), "An all in for less than the call amount is not a raise!");
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::AllIn(50), 1, Vec::new());
+        assert_eq!(ctx.pot.get_last_aggressor(), Some(ctx.player_ids[2]));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_side_pots_no_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Fold, 0, Vec::new());
+        let mut eligible_player_ids = vec![ctx.player_ids[0], ctx.player_ids[1]];
+        eligible_player_ids.sort();
+        assert_eq!(ctx.pot.get_side_pots(), vec![SidePot { amount: 20, eligible_player_ids }]);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_side_pots_two_way_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(100), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::AllIn(40), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Call, 0, Vec::new());
+        let side_pots = ctx.pot.get_side_pots();
+        let mut main_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[1], ctx.player_ids[2]];
+        main_pot_player_ids.sort();
+        let mut side_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[2]];
+        side_pot_player_ids.sort();
+        assert_eq!(side_pots, vec![
+            SidePot { amount: 120, eligible_player_ids: main_pot_player_ids },
+            SidePot { amount: 120, eligible_player_ids: side_pot_player_ids }
+        ]);
+        assert_eq!(side_pots.iter().map(|side_pot| side_pot.amount).sum::<u32>(), ctx.pot.get_total_stake());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_get_side_pots_three_way_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(100), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::AllIn(20), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::AllIn(50), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[3], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[4], Action::Ante(30), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[4], Action::Fold, 0, Vec::new());
+        let side_pots = ctx.pot.get_side_pots();
+        let mut main_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[1], ctx.player_ids[2], ctx.player_ids[3]];
+        main_pot_player_ids.sort();
+        let mut first_side_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[2], ctx.player_ids[3]];
+        first_side_pot_player_ids.sort();
+        let mut second_side_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[3]];
+        second_side_pot_player_ids.sort();
+        assert_eq!(side_pots, vec![
+            // 20 from each of the 5 players
+            SidePot { amount: 100, eligible_player_ids: main_pot_player_ids },
+            // 30 from players 0, 2 and 3, plus the remaining 10 from the folded player 4
+            SidePot { amount: 100, eligible_player_ids: first_side_pot_player_ids },
+            // 50 from each of players 0 and 3
+            SidePot { amount: 100, eligible_player_ids: second_side_pot_player_ids }
+        ]);
+        assert_eq!(side_pots.iter().map(|side_pot| side_pot.amount).sum::<u32>(), ctx.pot.get_total_stake());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_compute_pots_three_way_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(100), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::AllIn(20), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::AllIn(50), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[3], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[4], Action::Ante(30), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[4], Action::Fold, 0, Vec::new());
+        let mut main_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[1], ctx.player_ids[2], ctx.player_ids[3]];
+        main_pot_player_ids.sort();
+        let mut first_side_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[2], ctx.player_ids[3]];
+        first_side_pot_player_ids.sort();
+        let mut second_side_pot_player_ids = vec![ctx.player_ids[0], ctx.player_ids[3]];
+        second_side_pot_player_ids.sort();
+        assert_eq!(ctx.pot.compute_pots(), vec![
+            (100, main_pot_player_ids),
+            (100, first_side_pot_player_ids),
+            (100, second_side_pot_player_ids)
+        ]);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_three_way_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(100), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::AllIn(20), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::AllIn(50), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[3], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[4], Action::Ante(30), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[4], Action::Fold, 0, Vec::new());
+        // each all in player has the best hand of the players eligible for their pot,
+        // so each of the pots computed above goes to a different player
+        let winning_order = vec![
+            vec![ctx.player_ids[1]],
+            vec![ctx.player_ids[2]],
+            vec![ctx.player_ids[0]],
+            vec![ctx.player_ids[3]],
+            vec![ctx.player_ids[4]]
+        ];
+        let winnings = ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap();
+        assert_eq!(winnings.get(&ctx.player_ids[1]), 100);
+        assert_eq!(winnings.get(&ctx.player_ids[2]), 100);
+        assert_eq!(winnings.get(&ctx.player_ids[0]), 100);
+        assert_eq!(winnings.get(&ctx.player_ids[3]), 0);
+        assert_eq!(winnings.get(&ctx.player_ids[4]), 0);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_max_winnable_short_all_in(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(100), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::AllIn(20), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[3], Action::Ante(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[3], Action::Fold, 0, Vec::new());
+        assert_eq!(ctx.pot.get_total_stake(), 230);
+        // the all in player can only win 20 from each player, and the 10 from the folded player
+        assert_eq!(ctx.pot.max_winnable(&ctx.player_ids[1]), 70, "Short all in player should only be able to win the capped main pot!");
+        assert_eq!(ctx.pot.max_winnable(&ctx.player_ids[1]), ctx.pot.get_side_pots()[0].amount as i64);
+        assert_eq!(ctx.pot.max_winnable(&ctx.player_ids[0]), 230);
+        assert_eq!(ctx.pot.max_winnable(&ctx.player_ids[3]), 0, "Folded player should not be able to win anything!");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_add_ante(ctx: &mut Context) {
+        ctx.pot.add_ante(&ctx.player_ids[0], 5, 0, Vec::new());
+        ctx.pot.add_ante(&ctx.player_ids[1], 5, 0, Vec::new());
+        assert_eq!(ctx.pot.get_call_amount(), 0, "Antes should not count towards the call amount!");
+        assert_eq!(ctx.pot.get_total_stake(), 10);
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 1, Vec::new());
+        assert_eq!(ctx.pot.get_call_amount(), 10);
+        assert_eq!(ctx.pot.get_player_stake(&ctx.player_ids[1]), 10);
+        assert_eq!(ctx.pot.get_total_stake(), 30);
+        assert_eq!(ctx.pot.max_winnable(&ctx.player_ids[0]), 30);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_replay(ctx: &mut Context) {
+        ctx.pot.add_ante(&ctx.player_ids[0], 5, 0, Vec::new());
+        ctx.pot.add_ante(&ctx.player_ids[1], 5, 0, Vec::new());
+        ctx.pot.add_ante(&ctx.player_ids[2], 5, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Blind(5), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Blind(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Check, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Bet(30), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Fold, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Call, 1, Vec::new());
+        ctx.pot.divide_winnings(vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]], vec![ctx.player_ids[2]]], &ctx.player_ids).unwrap();
+
+        let round_id = Uuid::now_v7();
+        let turns: Vec<Turn> = ctx.pot.history.iter().map(|(player_id, action, phase_num, hand)| Turn {
+            _id: Uuid::now_v7(),
+            round_id,
+            phase_num: *phase_num,
+            acting_player_id: *player_id,
+            hand: hand.clone(),
+            action: action.clone(),
+        }).collect();
+        let pot_sizes = Pot::replay(&ctx.player_ids, &turns);
+        assert_eq!(pot_sizes.len(), turns.len());
+        assert_eq!(pot_sizes[..11], [5, 10, 15, 20, 30, 40, 45, 45, 65, 65, 85]);
+        // the winnings being paid out does not change the size of the pot
+        assert!(pot_sizes[11..].iter().all(|&pot_size| pot_size == 85));
+
+        // each turn is seen with the pot as it was right after that turn
+        let mut turns_seen = 0;
+        let pot = Pot::replay_each_turn(&ctx.player_ids, &turns, |turn, pot| {
+            assert_eq!(turn.action, turns[turns_seen].action);
+            assert_eq!(pot.get_total_stake(), pot_sizes[turns_seen]);
+            turns_seen += 1;
+            assert_eq!(pot.history.len(), turns_seen);
+        });
+        assert_eq!(turns_seen, turns.len());
+        assert_eq!(pot.get_player_stake(&ctx.player_ids[0]), 30);
+
+        // rounds saved before blinds had their own action recorded the blinds as antes
+        let legacy_turns: Vec<Turn> = turns.iter().map(|turn| Turn {
+            _id: turn._id,
+            round_id,
+            phase_num: turn.phase_num,
+            acting_player_id: turn.acting_player_id,
+            hand: turn.hand.clone(),
+            action: match turn.action {
+                Action::Blind(amount) => Action::Ante(amount),
+                ref action => action.clone(),
+            },
+        }).collect();
+        assert_eq!(Pot::replay(&ctx.player_ids, &legacy_turns), pot_sizes);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_replay_straddle(ctx: &mut Context) {
+        for player_id in ctx.player_ids[..3].iter() {
+            ctx.pot.add_ante(player_id, 5, 0, Vec::new());
+        }
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Blind(5), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Blind(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Blind(20), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Call, 0, Vec::new());
+
+        let round_id = Uuid::now_v7();
+        let turns: Vec<Turn> = ctx.pot.history.iter().map(|(player_id, action, phase_num, hand)| Turn {
+            _id: Uuid::now_v7(),
+            round_id,
+            phase_num: *phase_num,
+            acting_player_id: *player_id,
+            hand: hand.clone(),
+            action: action.clone(),
+        }).collect();
+        // the straddle is a third blind, all three count towards calling and the antes don't
+        assert_eq!(Pot::replay(&ctx.player_ids, &turns), [5, 10, 15, 20, 30, 50, 65, 75]);
+        let pot = Pot::from_turns(&ctx.player_ids, &turns);
+        assert_eq!(pot.get_call_amount(), 20);
+        assert_eq!(pot.get_player_stake(&ctx.player_ids[2]), 20);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_empty_winning_order(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 0, Vec::new());
+        assert_eq!(ctx.pot.divide_winnings(Vec::new(), &ctx.player_ids).unwrap_err(), PotError::EmptyWinningOrder);
+        assert_eq!(ctx.pot.divide_winnings(vec![Vec::new()], &ctx.player_ids).unwrap_err(), PotError::EmptyWinningOrder);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_negative_stakes(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 0, Vec::new());
+        ctx.pot.stakes.set(ctx.player_ids[1], -5);
+        let winning_order = vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]]];
+        assert_eq!(ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap_err(), PotError::NegativeStakes(ctx.player_ids[1], -5));
+        assert_eq!(ctx.pot.history.len(), 1, "History should be unchanged after an error!");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_no_eligible_winners(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Fold, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Fold, 0, Vec::new());
+        let winning_order = vec![vec![ctx.player_ids[0], ctx.player_ids[1]]];
+        assert_eq!(ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap_err(), PotError::NoEligibleWinners);
+        assert_eq!(ctx.pot.history.len(), 3, "History should be unchanged after an error!");
+    }
+
     #[test_context(Context)]
     #[test]
     fn test_get_non_player_id(ctx: &mut Context) {
@@ -329,7 +1138,7 @@ mod tests {
         players.swap(8, 9);
         players.reverse();
         let winning_order = players.iter().map(|x| vec![*x]).collect();
-        let winnings = ctx.pot.divide_winnings(winning_order);
+        let winnings = ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap();
         assert_eq!(winnings.get(&ctx.player_ids[0]), 0, "Player 0 has non-zero winnings");
         assert_eq!(winnings.get(&ctx.player_ids[1]), 0, "Player 1 has non-zero winnings");
         assert_eq!(winnings.get(&ctx.player_ids[2]), 0, "Player 2 has non-zero winnings");
@@ -362,7 +1171,7 @@ mod tests {
         winning_order.extend(players[3..].iter().map(|x| vec![*x]));
         println!("{:?}", winning_order);
 
-        let pot_winnings = ctx.pot.divide_winnings(winning_order);
+        let pot_winnings = ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap();
         for (&player, &winnings) in pot_winnings.iter() {
             if player == ctx.player_ids[9] || player == ctx.player_ids[8] || player == ctx.player_ids[7] {
                 assert_eq!(winnings, 5);
@@ -372,6 +1181,195 @@ mod tests {
         }
     }
 
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_odd_chips(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(3), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Ante(1), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Fold, 0, Vec::new());
+        assert_eq!(ctx.pot.get_total_stake(), 7);
+
+        // player 1 is closest to the left of the dealer, so they get the odd chip
+        let seat_order = vec![ctx.player_ids[2], ctx.player_ids[1], ctx.player_ids[0]];
+        let winning_order = vec![vec![ctx.player_ids[0], ctx.player_ids[1]], vec![ctx.player_ids[2]]];
+        let winnings = ctx.pot.divide_winnings(winning_order, &seat_order).unwrap();
+        assert_eq!(winnings.get(&ctx.player_ids[1]), 4);
+        assert_eq!(winnings.get(&ctx.player_ids[0]), 3);
+        assert_eq!(winnings.get(&ctx.player_ids[2]), 0);
+        assert_eq!(winnings.sum(), 7);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_whole_chips(ctx: &mut Context) {
+        ctx.pot.set_chip_size(5);
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(15), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Ante(5), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Fold, 0, Vec::new());
+        assert_eq!(ctx.pot.get_total_stake(), 35);
+
+        // the pot is 7 chips, so player 1 gets the odd chip (rather than 17 and 18)
+        let seat_order = vec![ctx.player_ids[2], ctx.player_ids[1], ctx.player_ids[0]];
+        let winning_order = vec![vec![ctx.player_ids[0], ctx.player_ids[1]], vec![ctx.player_ids[2]]];
+        let winnings = ctx.pot.divide_winnings(winning_order, &seat_order).unwrap();
+        assert_eq!(winnings.get(&ctx.player_ids[1]), 20);
+        assert_eq!(winnings.get(&ctx.player_ids[0]), 15);
+        assert_eq!(winnings.sum(), 35);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_twice_each_player_wins_one_runout(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::AllIn(100), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        assert_eq!(ctx.pot.get_total_stake(), 200);
+
+        let seat_order = vec![ctx.player_ids[0], ctx.player_ids[1]];
+        let first_winning_order = vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]]];
+        let second_winning_order = vec![vec![ctx.player_ids[1]], vec![ctx.player_ids[0]]];
+        let winnings = ctx.pot.divide_winnings_twice(first_winning_order, second_winning_order, &seat_order).unwrap();
+        assert_eq!(winnings.get(&ctx.player_ids[0]), 100);
+        assert_eq!(winnings.get(&ctx.player_ids[1]), 100);
+        // the stakes are left as they were, so the pot can still be displayed and saved
+        assert_eq!(ctx.pot.get_total_stake(), 200);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_twice_odd_stakes_and_side_pot(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::AllIn(31), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Raise(51), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Call, 0, Vec::new());
+        assert_eq!(ctx.pot.get_total_stake(), 133);
+
+        // player 0 wins the first runout's main pot, player 1 wins everything else
+        let seat_order = vec![ctx.player_ids[0], ctx.player_ids[1], ctx.player_ids[2]];
+        let first_winning_order = vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]], vec![ctx.player_ids[2]]];
+        let second_winning_order = vec![vec![ctx.player_ids[1]], vec![ctx.player_ids[0]], vec![ctx.player_ids[2]]];
+        let winnings = ctx.pot.divide_winnings_twice(first_winning_order, second_winning_order, &seat_order).unwrap();
+        assert_eq!(winnings.get(&ctx.player_ids[0]), 48); // half of the 93 main pot, with the odd chips of each stake
+        assert_eq!(winnings.get(&ctx.player_ids[1]), 85); // the rest of both runouts
+        assert_eq!(winnings.get(&ctx.player_ids[2]), 0);
+        assert_eq!(winnings.sum(), 133);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_divide_winnings_twice_error_leaves_history_unchanged(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 0, Vec::new());
+        let player_ids = ctx.pot.get_player_ids();
+
+        let seat_order = vec![ctx.player_ids[0], ctx.player_ids[1]];
+        let first_winning_order = vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]]];
+        let result = ctx.pot.divide_winnings_twice(first_winning_order, Vec::new(), &seat_order);
+        assert_eq!(result.err(), Some(PotError::EmptyWinningOrder));
+        assert_eq!(ctx.pot.history.len(), 2);
+        assert_eq!(ctx.pot.get_player_ids().len(), player_ids.len());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_undo_last_turn(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Ante(1), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Ante(2), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Call, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Raise(10), 1, Vec::new());
+        assert_eq!(ctx.pot.get_total_stake(), 14);
+
+        // only the player who raised can take it back
+        assert_eq!(ctx.pot.undo_last_turn(&ctx.player_ids[1], 1), Err(PotError::UndoOtherPlayersTurn(ctx.player_ids[0])));
+        assert_eq!(ctx.pot.get_total_stake(), 14);
+        let undone = ctx.pot.undo_last_turn(&ctx.player_ids[0], 1).unwrap();
+        assert_eq!(undone, UndoneAction { player_id: ctx.player_ids[0], action: Action::Raise(10), phase_num: 1, refund: 9 });
+        assert_eq!(ctx.pot.get_player_stake(&ctx.player_ids[0]), 1);
+        assert_eq!(ctx.pot.get_call_amount(), 2);
+        assert_eq!(ctx.pot.get_total_stake(), 5);
+
+        // the player can then take a different action, as if the undone turn never happened
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Call, 1, Vec::new());
+        assert_eq!(ctx.pot.get_player_stake(&ctx.player_ids[0]), 2);
+        assert_eq!(ctx.pot.get_total_stake(), 6);
+
+        let undone = ctx.pot.undo_last_turn(&ctx.player_ids[0], 1).unwrap();
+        assert_eq!(undone.refund, 1);
+        let undone = ctx.pot.undo_last_turn(&ctx.player_ids[2], 1).unwrap();
+        assert_eq!(undone.player_id, ctx.player_ids[2]);
+        assert_eq!(undone.refund, 2);
+        assert_eq!(ctx.pot.get_total_stake(), 3);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_undo_last_turn_fold_and_check(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(5), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Fold, 1, Vec::new());
+        assert!(ctx.pot.player_has_folded(&ctx.player_ids[1]));
+
+        let undone = ctx.pot.undo_last_turn(&ctx.player_ids[1], 1).unwrap();
+        assert_eq!(undone.action, Action::Fold);
+        assert_eq!(undone.refund, 0);
+        assert!(!ctx.pot.player_has_folded(&ctx.player_ids[1]));
+        assert_eq!(ctx.pot.get_total_stake(), 5);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_undo_last_turn_forced_bets(ctx: &mut Context) {
+        assert_eq!(ctx.pot.undo_last_turn(&ctx.player_ids[0], 0), Err(PotError::NothingToUndo));
+
+        ctx.pot.add_ante(&ctx.player_ids[0], 1, 0, Vec::new());
+        assert_eq!(ctx.pot.undo_last_turn(&ctx.player_ids[0], 0), Err(PotError::CannotUndo(Action::Ante(1))));
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Ante(2), 0, Vec::new());
+        assert_eq!(ctx.pot.undo_last_turn(&ctx.player_ids[1], 0), Err(PotError::CannotUndo(Action::Ante(2))));
+        assert_eq!(ctx.pot.get_total_stake(), 3);
+
+        let winning_order = vec![vec![ctx.player_ids[1]], vec![ctx.player_ids[0]]];
+        ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap();
+        assert!(matches!(ctx.pot.undo_last_turn(&ctx.player_ids[1], 1), Err(PotError::CannotUndo(_))));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_undo_last_turn_previous_phase(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(5), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 1, Vec::new());
+        // the betting has moved on to the next phase, so the call can't be taken back
+        assert_eq!(ctx.pot.undo_last_turn(&ctx.player_ids[1], 2), Err(PotError::UndoPreviousPhase(1)));
+        assert_eq!(ctx.pot.get_total_stake(), 10);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_export_to_json(ctx: &mut Context) {
+        assert_eq!(ctx.pot.export_to_json(), "[]");
+
+        let hand: Vec<Card> = vec!["Ah".parse().unwrap(), "Td".parse().unwrap()];
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Ante(2), 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Raise(10), 1, hand.clone());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Fold, 1, Vec::new());
+
+        let exported: serde_json::Value = serde_json::from_str(&ctx.pot.export_to_json()).unwrap();
+        let turns = exported.as_array().unwrap();
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[0], json!({"player_id": ctx.player_ids[0].to_string(), "action": "ante 2", "phase": 0, "hand": []}));
+        assert_eq!(turns[1], json!({"player_id": ctx.player_ids[1].to_string(), "action": "raise 10", "phase": 1, "hand": ["Ah", "10d"]}));
+        assert_eq!(turns[2]["action"], "fold");
+        assert_eq!(turns[2]["player_id"].as_str().unwrap().parse::<Uuid>().unwrap(), ctx.player_ids[0]);
+    }
+
+    #[test]
+    fn test_display() {
+        let player_ids = vec![Uuid::now_v7(), Uuid::now_v7()];
+        let mut pot = Pot::new_uuids(&player_ids, DbHandler::new_dummy());
+        pot.add_ante(&player_ids[1], 1, 0, Vec::new());
+        pot.add_turn(&player_ids[0], Action::Bet(10), 1, Vec::new());
+        pot.add_turn(&player_ids[1], Action::Call, 1, Vec::new());
+        assert_eq!(pot.to_string(), format!("Pot: $21 | Stakes: {{{}: 10, {}: 11}}", player_ids[0], player_ids[1]));
+    }
+
     #[test_context(Context)]
     #[test]
     fn test_divide_winnings_only_main_pot(ctx: &mut Context) {
@@ -389,7 +1387,7 @@ mod tests {
         let mut players = ctx.player_ids.clone();
         players.reverse();
         let winning_order = players.iter().map(|x| vec![*x]).collect();
-        let winnings = ctx.pot.divide_winnings(winning_order);
+        let winnings = ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap();
         assert_eq!(winnings.get(&ctx.player_ids[0]), 0, "Player 0 has non-zero winnings");
         assert_eq!(winnings.get(&ctx.player_ids[1]), 0, "Player 1 has non-zero winnings");
         assert_eq!(winnings.get(&ctx.player_ids[2]), 0, "Player 2 has non-zero winnings");
@@ -419,7 +1417,7 @@ mod tests {
         let mut players = ctx.player_ids.clone();
         players.reverse();
         let winning_order = players.iter().map(|x| vec![*x]).collect();
-        let winnings = ctx.pot.divide_winnings(winning_order);
+        let winnings = ctx.pot.divide_winnings(winning_order, &ctx.player_ids).unwrap();
         assert_eq!(winnings.get(&ctx.player_ids[0]), 0, "Player 0 has non-zero winnings");
         assert_eq!(winnings.get(&ctx.player_ids[1]), 0, "Player 1 has non-zero winnings");
         assert_eq!(winnings.get(&ctx.player_ids[2]), 0, "Player 2 has non-zero winnings");
@@ -445,6 +1443,65 @@ mod tests {
 
         assert_eq!(ctx.pot.number_of_players_folded(), 4);
     }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_add_muck(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Call, 1, Vec::new());
+        ctx.pot.add_muck(&ctx.player_ids[1]);
+
+        // the muck is played in the last phase, and the mucked stake stays in the pot
+        assert_eq!(*ctx.pot.get_phase_history(1).last().unwrap(), &(ctx.player_ids[1], Action::Muck, 1, Vec::new()));
+        assert!(!ctx.pot.player_has_folded(&ctx.player_ids[1]));
+        assert_eq!(ctx.pot.get_player_stake(&ctx.player_ids[1]), 10);
+        ctx.pot.assert_consistency();
+
+        let winnings = ctx.pot.divide_winnings(vec![vec![ctx.player_ids[0]], vec![ctx.player_ids[1]]], &ctx.player_ids).unwrap();
+        assert_eq!(winnings.get(&ctx.player_ids[0]), 20);
+        assert_eq!(ctx.pot.get_phase_history(2).len(), ctx.player_ids.len());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_assert_consistency(ctx: &mut Context) {
+        ctx.pot.add_ante(&ctx.player_ids[0], 1, 0, Vec::new());
+        ctx.pot.add_ante(&ctx.player_ids[1], 1, 0, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[1], Action::Raise(20), 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[2], Action::Fold, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Call, 1, Vec::new());
+        ctx.pot.assert_consistency();
+        ctx.pot.divide_winnings(vec![vec![ctx.player_ids[0]]], &ctx.player_ids).unwrap();
+        ctx.pot.assert_consistency();
+    }
+
+    #[test_context(Context)]
+    #[test]
+    #[should_panic(expected = "after folding")]
+    fn test_assert_consistency_action_after_fold(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Fold, 1, Vec::new());
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        ctx.pot.assert_consistency();
+    }
+
+    #[test_context(Context)]
+    #[test]
+    #[should_panic(expected = "stake went down")]
+    fn test_assert_consistency_stake_went_down(ctx: &mut Context) {
+        ctx.pot.add_turn(&ctx.player_ids[0], Action::Bet(10), 1, Vec::new());
+        // nothing in the pot should take money back out without undoing the turn
+        ctx.pot.stakes.set(ctx.player_ids[0], 5);
+        ctx.pot.assert_consistency();
+    }
+
+    #[test_context(Context)]
+    #[test]
+    #[should_panic(expected = "negative ante")]
+    fn test_assert_consistency_negative_ante(ctx: &mut Context) {
+        ctx.pot.antes.set(ctx.player_ids[0], -5);
+        ctx.pot.assert_consistency();
+    }
 }
 
 
@@ -491,7 +1548,7 @@ mod db_tests {
                 3 => Suit::Spades,
                 _ => panic!("Unexpected value when generating random hand.")
             };
-            ran_hand.push(Card::new(rand_rank, rand_suit, false));
+            ran_hand.push(Card::new(rand_rank, rand_suit));
         }
         ran_hand
     }
@@ -509,10 +1566,17 @@ mod db_tests {
         ctx.pot.add_turn(&ctx.player_ids[1], Action::Bet(100), 0, gen_random_hand(5));
         ctx.pot.add_turn(&ctx.player_ids[2], Action::Bet(1000), 0, gen_random_hand(5));
         ctx.pot.add_turn(&ctx.player_ids[2], Action::Bet(2000), 0, gen_random_hand(5));
-        ctx.pot.save(game_id).await;
+        ctx.pot.save(game_id, 1).await;
 
         assert_eq!(ctx.test_conn.count_documents::<Turn>(doc! {"acting_player_id": &ctx.player_ids[0].simple().to_string()}, "Turns").await.unwrap().unwrap(), 4);
         assert_eq!(ctx.test_conn.count_documents::<Turn>(doc! {"acting_player_id": &ctx.player_ids[1].simple().to_string()}, "Turns").await.unwrap().unwrap(), 1);
         assert_eq!(ctx.test_conn.count_documents::<Turn>(doc! {"acting_player_id": &ctx.player_ids[2].simple().to_string()}, "Turns").await.unwrap().unwrap(), 2);
+
+        let round = ctx.test_conn.get_document::<Round>(doc! {"game_id": game_id.simple().to_string()}, "Rounds").await.unwrap().unwrap().unwrap();
+        assert_eq!(round.final_stakes.len(), ctx.player_ids.len());
+        assert_eq!(round.final_stakes[&ctx.player_ids[0].simple().to_string()], 40);
+        assert_eq!(round.final_stakes[&ctx.player_ids[1].simple().to_string()], 100);
+        assert_eq!(round.final_stakes[&ctx.player_ids[2].simple().to_string()], 2000);
+        assert_eq!(round.final_stakes[&ctx.player_ids[3].simple().to_string()], 0);
     }
 }
\ No newline at end of file
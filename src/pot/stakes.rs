@@ -41,6 +41,21 @@ impl Stakes {
         return new_stakes
     }
 
+    /// Constructor with a map of each player's stake.
+    pub fn new_from_map(map: HashMap<Uuid, i64>) -> Stakes {
+        return Stakes {
+            stakes: map,
+        };
+    }
+
+    /// Adds each player's stake in other onto their stake in self,
+    /// players that are only in other are added with other's stake.
+    pub fn merge(&mut self, other: &Stakes) {
+        for (player_id, amount) in other.iter() {
+            self.add(*player_id, *amount);
+        }
+    }
+
     /// Adds the amount onto the player's stakes. 
     /// The sum should be non-negative otherwise it will panic!
     pub fn add(&mut self, player_id: Uuid, amount: i64) {
@@ -85,6 +100,11 @@ impl Stakes {
         return player_ids;
     }
 
+    /// Copies the stakes into a HashMap of each player's stake.
+    pub fn to_map(&self) -> HashMap<Uuid, i64> {
+        return self.stakes.clone();
+    }
+
     pub fn iter(&self) -> std::collections::hash_map::Iter<'_, Uuid, i64> {
         self.stakes.iter()
     }
@@ -166,6 +186,18 @@ mod tests {
         assert_eq!(ctx.stakes.sum(), 550);
     }
 
+    #[test_context(Context)]
+    #[test]
+    fn test_to_map(ctx: &mut Context) {
+        ctx.stakes.set(ctx.player_ids[0], 10);
+        ctx.stakes.set(ctx.player_ids[1], 500);
+        let stakes_map = ctx.stakes.to_map();
+        assert_eq!(stakes_map.len(), ctx.player_ids.len());
+        assert_eq!(stakes_map[&ctx.player_ids[0]], 10);
+        assert_eq!(stakes_map[&ctx.player_ids[1]], 500);
+        assert_eq!(stakes_map[&ctx.player_ids[2]], 0);
+    }
+
     #[test_context(Context)]
     #[test]
     fn test_get_player_ids(ctx: &mut Context) {
@@ -174,4 +206,42 @@ mod tests {
             assert!(ctx.player_ids.contains(id));
         }
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_new() {
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player 1".to_string(), 100),
+            Player::new(Uuid::now_v7(), "player 2".to_string(), 100),
+        ];
+        let stakes = Stakes::new(&players.iter().collect());
+        assert_eq!(stakes.get_player_ids().len(), 2);
+        for player in players.iter() {
+            assert_eq!(stakes.get(&player.account_id()), 0);
+        }
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_new_from_map(ctx: &mut Context) {
+        let map = HashMap::from([(ctx.player_ids[0], 10), (ctx.player_ids[1], 25)]);
+        let stakes = Stakes::new_from_map(map.clone());
+        assert_eq!(stakes.to_map(), map);
+        assert_eq!(stakes.sum(), 35);
+        assert_eq!(stakes.get(&ctx.player_ids[2]), 0);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn test_merge(ctx: &mut Context) {
+        ctx.stakes.set(ctx.player_ids[0], 10);
+        ctx.stakes.set(ctx.player_ids[1], 20);
+        let new_player_id = Uuid::now_v7();
+        let other = Stakes::new_from_map(HashMap::from([(ctx.player_ids[0], 5), (new_player_id, 7)]));
+        ctx.stakes.merge(&other);
+        assert_eq!(ctx.stakes.get(&ctx.player_ids[0]), 15);
+        assert_eq!(ctx.stakes.get(&ctx.player_ids[1]), 20);
+        assert_eq!(ctx.stakes.get(&new_player_id), 7);
+        assert_eq!(ctx.stakes.sum(), 42);
+        // other is left as it was
+        assert_eq!(other.sum(), 12);
+    }
+}
@@ -1,34 +1,505 @@
 use five_card_draw::FiveCardDraw;
 use seven_card_stud::SevenCardStud;
 use texas_holdem::TexasHoldem;
+use community_draw::CommunityDraw;
+use badugi::Badugi;
+use short_deck_holdem::ShortDeckHoldem;
 use uuid::Uuid;
 
-use crate::{database::db_handler::DbHandler, input::Input, player::Player};
+use std::time::Duration;
+
+use crate::{card::Card, database::db_handler::DbHandler, input::Input, player::Player};
 use crate::game_type::GameType;
+use crate::game_event::GameEvent;
+use crate::deck::DeckError;
+use crate::pot::PotError;
+
+/// Errors that stop a round from being played (see Rules::play_round),
+/// the players are handed back along with the error so that they can carry on in the next round
+#[derive(Debug, PartialEq)]
+pub enum RoundError {
+    /// there are fewer players than the rules need (see Rules::MIN_PLAYERS)
+    TooFewPlayers,
+    /// there are more players than can be dealt in without the deck running out of cards (see Rules::MAX_PLAYERS)
+    TooManyPlayers,
+    /// the winnings of the pot couldn't be divided, so the round was aborted and every player was refunded their stake
+    PotNotDivided(PotError),
+    /// the deck ran out of cards part way through the round, so the round was aborted and every player was refunded their stake
+    DeckExhausted(DeckError),
+}
+
+impl std::fmt::Display for RoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoundError::TooFewPlayers => write!(f, "There are not enough players to start a round"),
+            RoundError::TooManyPlayers => write!(f, "There are too many players to start a round, the deck would run out of cards"),
+            RoundError::PotNotDivided(pot_error) => write!(f, "Failed to divide the winnings of the pot ({pot_error}), so the round was aborted"),
+            RoundError::DeckExhausted(deck_error) => write!(f, "The deck ran out of cards ({deck_error}), so the round was aborted"),
+        }
+    }
+}
 
 /// trait containing necessary methods for each set of poker Rules
 pub trait Rules {
-    /// create a new instance of the rules, with a certain raise limit, minimum bet, and game ID
-    fn new(raise_limit: u32, minimum_bet: u32, db_handler: DbHandler, game_id: Uuid) -> Self where Self: Sized;
+    /// the fewest players that can be dealt into a round
+    const MIN_PLAYERS: usize = 2;
+    /// the most players that can be dealt into a round without the deck running out of cards
+    const MAX_PLAYERS: usize;
+    /// create a new instance of the rules, with a certain raise limit, minimum bet, small blind, and game ID,
+    /// the minimum bet is the big blind in variants with blinds, and the small blind is ignored by variants without them
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> Self where Self: Sized;
     /// the play_round method takes care of all of the logic required the entire game, for a given variant of poker,
     /// the players are assumed to stay in the game for the entire round (but may change between rounds),
     /// and if a player leaves, they will be automatically folded
-    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (&'static str, Vec<Player>)>;
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)>;
+    /// announce to all players that a player has run out of money, and has been removed from the game
+    fn announce_player_eliminated(&self, player: &Player);
+    /// a short description of how the cards are dealt and how many bet rounds there are, for players joining a game
+    fn deal_order_description() -> &'static str where Self: Sized;
+    /// set the house rules for kill pots (None for no kills), the rules keep track of who has to post
+    /// a kill blind from one round to the next, variants without blinds reject kills with KillNotSupported
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported>;
+    /// the history of the last round played as JSON (see Pot::export_to_json), or None if no round has been played
+    fn export_last_round_json(&self) -> Option<String>;
+    /// the turns of the last round played as game events (see Pot::round_events), empty if no round has been played
+    fn last_round_events(&self) -> Vec<GameEvent>;
+    /// the community cards that have been dealt face up in the current round, for every player to use,
+    /// variants without community cards always return an empty slice
+    fn community_cards(&self) -> &[Card] {
+        return &[];
+    }
+    /// set the number of the next round within the game (starting from 1), which is saved with the round
+    fn set_round_number(&mut self, round_number: u32);
+    /// set the minimum bet (the big blind, or the smallest opening bet in stud) for the following rounds,
+    /// the small blind (or the bring in) keeps the proportion to the minimum bet that it started with
+    fn set_minimum_bet(&mut self, amount: u32);
+    /// set the value of the smallest chip, so that every blind, ante, bet and raise is a whole number of chips
+    /// (amounts chosen by players are snapped to the nearest chip), and pots are divided in whole chips,
+    /// a chip size of 1 (the default) allows any amount
+    fn set_chip_size(&mut self, chip_size: u32);
+    /// cap the number of raises in each betting round, as in limit games (commonly 3 or 4 raises after the opening bet),
+    /// once the cap is reached the other players can only call or fold, None (the default) for no cap
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>);
+    /// true if a round can be played with this many players
+    fn valid_player_count(player_count: usize) -> bool where Self: Sized {
+        return Self::check_player_count(player_count).is_ok();
+    }
+    /// Ok if a round can be played with this many players, otherwise the RoundError that play_round would give
+    fn check_player_count(player_count: usize) -> Result<(), RoundError> where Self: Sized {
+        if player_count < Self::MIN_PLAYERS {
+            return Err(RoundError::TooFewPlayers);
+        }
+        if player_count > Self::MAX_PLAYERS {
+            return Err(RoundError::TooManyPlayers);
+        }
+        return Ok(());
+    }
+}
+
+/// how antes are charged at the start of each round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnteMode {
+    /// every player pays the ante
+    EveryoneAntes,
+    /// only the big blind pays an ante, which covers the ante for every player at the table,
+    /// as is done in most modern tournaments
+    BigBlindAnte,
+}
+
+/// how players who are sitting out are treated at the start of each round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SitOutMode {
+    /// players sitting out are not dealt in, and don't pay blinds or antes
+    SkipHand,
+    /// players sitting out are dealt in and pay blinds and antes as usual,
+    /// but automatically fold when it is their turn to act
+    PostAndFold,
+}
+
+/// how much the stakes increase in a kill pot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KillType {
+    /// the killer posts a kill blind of one and a half big blinds
+    HalfKill,
+    /// the killer posts a kill blind of two big blinds
+    FullKill,
+}
+
+impl KillType {
+    /// the effective big blind in a kill pot, given the usual big blind
+    pub fn kill_blind(&self, big_blind_amount: u32) -> u32 {
+        return match self {
+            KillType::HalfKill => big_blind_amount * 3 / 2,
+            KillType::FullKill => big_blind_amount * 2,
+        };
+    }
+}
+
+/// house rules for kill pots, as played in some high stakes home games:
+/// a player who wins a big enough pot by themselves must post a kill blind in the next round,
+/// which becomes the effective big blind for that round
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Kill {
+    /// how much the stakes increase in a kill pot
+    pub kill_type: KillType,
+    /// the smallest pot that makes its winner post a kill blind in the next round
+    pub qualifying_pot: usize,
+}
+
+/// the rules have no big blind for a kill blind to replace, so they can't be played with kill pots (see Rules::set_kill)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KillNotSupported;
+
+impl std::fmt::Display for KillNotSupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Kill pots can only be played in variants with blinds")
+    }
+}
+
+/// a schedule of increasing big blinds for tournament play, so that the blinds force action as the tournament goes on
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlindSchedule {
+    /// (level start, big blind) pairs sorted by level start, where a level starts at a round number,
+    /// or for timed levels, a number of minutes into the game.
+    /// each big blind is played from the start of its level until the start of the next level
+    levels: Vec<(u32, u32)>,
+    /// true if the levels start a number of minutes into the game, rather than at a round number
+    timed: bool,
+}
+
+impl BlindSchedule {
+    /// the number of rounds played at each level of the default tournament schedule
+    const DEFAULT_ROUNDS_PER_LEVEL: u32 = 10;
+    /// the number of levels in the default tournament schedule
+    const DEFAULT_NUMBER_OF_LEVELS: u32 = 10;
+
+    /// create a blind schedule from (round number, big blind) pairs, which don't need to be in order
+    pub fn new(mut levels: Vec<(u32, u32)>) -> BlindSchedule {
+        levels.sort_by_key(|&(round_number, _)| round_number);
+        return BlindSchedule { levels, timed: false };
+    }
+
+    /// create a blind schedule that plays each of big_blinds in turn, moving to the next one every level_length
+    /// from the first round, and staying at the last big blind once it is reached
+    pub fn with_level_length(big_blinds: Vec<u32>, level_length: BlindLevelLength) -> BlindSchedule {
+        return match level_length {
+            BlindLevelLength::Rounds(rounds) => BlindSchedule::new(big_blinds.into_iter()
+                .enumerate()
+                .map(|(level, big_blind)| (level as u32 * rounds.max(1) + 1, big_blind))
+                .collect()),
+            BlindLevelLength::Minutes(minutes) => BlindSchedule {
+                levels: big_blinds.into_iter()
+                    .enumerate()
+                    .map(|(level, big_blind)| ((level as u64 * minutes.max(1)) as u32, big_blind))
+                    .collect(),
+                timed: true,
+            },
+        };
+    }
+
+    /// a standard tournament schedule, where the big blind starts at 2 and doubles every 10 rounds
+    pub fn default_tournament() -> BlindSchedule {
+        let big_blinds = (0..Self::DEFAULT_NUMBER_OF_LEVELS).map(|level| 2 << level).collect();
+        return BlindSchedule::with_level_length(big_blinds, BlindLevelLength::Rounds(Self::DEFAULT_ROUNDS_PER_LEVEL));
+    }
+
+    /// the (level start, big blind) pairs of this schedule, sorted by level start
+    pub fn levels(&self) -> &Vec<(u32, u32)> {
+        return &self.levels;
+    }
+
+    /// true if the levels start a number of minutes into the game, rather than at a round number
+    pub fn is_timed(&self) -> bool {
+        return self.timed;
+    }
+
+    /// the big blind for a round, which is time_played into the game, from the last level that started at or before then,
+    /// returns None if it is before the first level
+    pub fn big_blind_for_round(&self, round_number: u32, time_played: Duration) -> Option<u32> {
+        let level_start = match self.timed {
+            true => (time_played.as_secs() / 60) as u32,
+            false => round_number,
+        };
+        return self.levels.iter()
+            .rev()
+            .find(|&&(start, _)| start <= level_start)
+            .map(|&(_, big_blind)| big_blind);
+    }
+}
+
+/// how long each level of a tournament's blinds lasts before the big blind goes up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BlindLevelLength {
+    /// the blinds go up every so many rounds
+    Rounds(u32),
+    /// the blinds go up every so many minutes
+    Minutes(u64),
 }
 
 pub enum RulesEnum<I: Input> {
     FiveCardDraw(FiveCardDraw<I>),
     SevenCardStud(SevenCardStud<I>),
-    TexasHoldem(TexasHoldem<I>)
+    TexasHoldem(TexasHoldem<I>),
+    CommunityDraw(CommunityDraw<I>),
+    Badugi(Badugi<I>),
+    ShortDeckHoldem(ShortDeckHoldem<I>)
 }
 
 
 impl<I: Input> RulesEnum<I> {
+    /// create the rules for a game type, see Rules::new
+    pub fn new(game_type: GameType, raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> RulesEnum<I> {
+        match game_type {
+            GameType::FiveCardDraw => RulesEnum::FiveCardDraw(FiveCardDraw::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)),
+            GameType::SevenCardStud => RulesEnum::SevenCardStud(SevenCardStud::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)),
+            GameType::TexasHoldem => RulesEnum::TexasHoldem(TexasHoldem::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)),
+            GameType::CommunityDraw => RulesEnum::CommunityDraw(CommunityDraw::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)),
+            GameType::Badugi => RulesEnum::Badugi(Badugi::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)),
+            GameType::ShortDeckHoldem => RulesEnum::ShortDeckHoldem(ShortDeckHoldem::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)),
+        }
+    }
+
     pub fn to_game_type(&self) -> GameType {
         match self {
             RulesEnum::FiveCardDraw(_) => GameType::FiveCardDraw,
             RulesEnum::SevenCardStud(_) => GameType::SevenCardStud,
             RulesEnum::TexasHoldem(_) => GameType::TexasHoldem,
+            RulesEnum::CommunityDraw(_) => GameType::CommunityDraw,
+            RulesEnum::Badugi(_) => GameType::Badugi,
+            RulesEnum::ShortDeckHoldem(_) => GameType::ShortDeckHoldem,
+        }
+    }
+
+    pub fn input(&self) -> &I {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.input(),
+            RulesEnum::SevenCardStud(rules) => rules.input(),
+            RulesEnum::TexasHoldem(rules) => rules.input(),
+            RulesEnum::CommunityDraw(rules) => rules.input(),
+            RulesEnum::Badugi(rules) => rules.input(),
+            RulesEnum::ShortDeckHoldem(rules) => rules.input(),
+        }
+    }
+
+    /// the input of the rules, to lend another one for a round (see MixedGame)
+    fn input_mut(&mut self) -> &mut I {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.input_mut(),
+            RulesEnum::SevenCardStud(rules) => rules.input_mut(),
+            RulesEnum::TexasHoldem(rules) => rules.input_mut(),
+            RulesEnum::CommunityDraw(rules) => rules.input_mut(),
+            RulesEnum::Badugi(rules) => rules.input_mut(),
+            RulesEnum::ShortDeckHoldem(rules) => rules.input_mut(),
+        }
+    }
+
+    /// the seat of the dealer in the last round (see MixedGame)
+    fn dealer_position(&self) -> usize {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.dealer_position(),
+            RulesEnum::SevenCardStud(rules) => rules.dealer_position(),
+            RulesEnum::TexasHoldem(rules) => rules.dealer_position(),
+            RulesEnum::CommunityDraw(rules) => rules.dealer_position(),
+            RulesEnum::Badugi(rules) => rules.dealer_position(),
+            RulesEnum::ShortDeckHoldem(rules) => rules.dealer_position(),
+        }
+    }
+
+    /// move the dealer button to a seat, before the next round moves it on by one (see MixedGame)
+    fn set_dealer_position(&mut self, dealer_position: usize) {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.set_dealer_position(dealer_position),
+            RulesEnum::SevenCardStud(rules) => rules.set_dealer_position(dealer_position),
+            RulesEnum::TexasHoldem(rules) => rules.set_dealer_position(dealer_position),
+            RulesEnum::CommunityDraw(rules) => rules.set_dealer_position(dealer_position),
+            RulesEnum::Badugi(rules) => rules.set_dealer_position(dealer_position),
+            RulesEnum::ShortDeckHoldem(rules) => rules.set_dealer_position(dealer_position),
+        }
+    }
+
+    pub fn set_round_number(&mut self, round_number: u32) {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.set_round_number(round_number),
+            RulesEnum::SevenCardStud(rules) => rules.set_round_number(round_number),
+            RulesEnum::TexasHoldem(rules) => rules.set_round_number(round_number),
+            RulesEnum::CommunityDraw(rules) => rules.set_round_number(round_number),
+            RulesEnum::Badugi(rules) => rules.set_round_number(round_number),
+            RulesEnum::ShortDeckHoldem(rules) => rules.set_round_number(round_number),
+        }
+    }
+
+    /// the fewest players that can be dealt into a round
+    pub fn min_players(&self) -> usize {
+        match self {
+            RulesEnum::FiveCardDraw(_) => FiveCardDraw::<I>::MIN_PLAYERS,
+            RulesEnum::SevenCardStud(_) => SevenCardStud::<I>::MIN_PLAYERS,
+            RulesEnum::TexasHoldem(_) => TexasHoldem::<I>::MIN_PLAYERS,
+            RulesEnum::CommunityDraw(_) => CommunityDraw::<I>::MIN_PLAYERS,
+            RulesEnum::Badugi(_) => Badugi::<I>::MIN_PLAYERS,
+            RulesEnum::ShortDeckHoldem(_) => ShortDeckHoldem::<I>::MIN_PLAYERS,
+        }
+    }
+
+    /// the most players that can be dealt into a round without the deck running out of cards
+    pub fn max_players(&self) -> usize {
+        match self {
+            RulesEnum::FiveCardDraw(_) => FiveCardDraw::<I>::MAX_PLAYERS,
+            RulesEnum::SevenCardStud(_) => SevenCardStud::<I>::MAX_PLAYERS,
+            RulesEnum::TexasHoldem(_) => TexasHoldem::<I>::MAX_PLAYERS,
+            RulesEnum::CommunityDraw(_) => CommunityDraw::<I>::MAX_PLAYERS,
+            RulesEnum::Badugi(_) => Badugi::<I>::MAX_PLAYERS,
+            RulesEnum::ShortDeckHoldem(_) => ShortDeckHoldem::<I>::MAX_PLAYERS,
+        }
+    }
+
+    /// whether a round of these rules can be played with this many players (see Rules::valid_player_count)
+    pub fn valid_player_count(&self, player_count: usize) -> bool {
+        match self {
+            RulesEnum::FiveCardDraw(_) => FiveCardDraw::<I>::valid_player_count(player_count),
+            RulesEnum::SevenCardStud(_) => SevenCardStud::<I>::valid_player_count(player_count),
+            RulesEnum::TexasHoldem(_) => TexasHoldem::<I>::valid_player_count(player_count),
+            RulesEnum::CommunityDraw(_) => CommunityDraw::<I>::valid_player_count(player_count),
+            RulesEnum::Badugi(_) => Badugi::<I>::valid_player_count(player_count),
+            RulesEnum::ShortDeckHoldem(_) => ShortDeckHoldem::<I>::valid_player_count(player_count),
+        }
+    }
+
+    /// Ok if a round of these rules can be played with this many players, see Rules::check_player_count
+    pub fn check_player_count(&self, player_count: usize) -> Result<(), RoundError> {
+        match self {
+            RulesEnum::FiveCardDraw(_) => FiveCardDraw::<I>::check_player_count(player_count),
+            RulesEnum::SevenCardStud(_) => SevenCardStud::<I>::check_player_count(player_count),
+            RulesEnum::TexasHoldem(_) => TexasHoldem::<I>::check_player_count(player_count),
+            RulesEnum::CommunityDraw(_) => CommunityDraw::<I>::check_player_count(player_count),
+            RulesEnum::Badugi(_) => Badugi::<I>::check_player_count(player_count),
+            RulesEnum::ShortDeckHoldem(_) => ShortDeckHoldem::<I>::check_player_count(player_count),
+        }
+    }
+
+    /// whether the rules start the betting with blinds (and a small blind), rather than a bring in
+    pub fn uses_blinds(&self) -> bool {
+        match self {
+            RulesEnum::SevenCardStud(_) => false,
+            RulesEnum::FiveCardDraw(_) | RulesEnum::TexasHoldem(_) | RulesEnum::CommunityDraw(_) | RulesEnum::Badugi(_) | RulesEnum::ShortDeckHoldem(_) => true,
+        }
+    }
+
+    pub fn deal_order_description(&self) -> &'static str {
+        match self {
+            RulesEnum::FiveCardDraw(_) => FiveCardDraw::<I>::deal_order_description(),
+            RulesEnum::SevenCardStud(_) => SevenCardStud::<I>::deal_order_description(),
+            RulesEnum::TexasHoldem(_) => TexasHoldem::<I>::deal_order_description(),
+            RulesEnum::CommunityDraw(_) => CommunityDraw::<I>::deal_order_description(),
+            RulesEnum::Badugi(_) => Badugi::<I>::deal_order_description(),
+            RulesEnum::ShortDeckHoldem(_) => ShortDeckHoldem::<I>::deal_order_description(),
+        }
+    }
+
+    /// play a round with the rules for this game type, see Rules::play_round
+    pub async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.play_round(players).await,
+            RulesEnum::SevenCardStud(rules) => rules.play_round(players).await,
+            RulesEnum::TexasHoldem(rules) => rules.play_round(players).await,
+            RulesEnum::CommunityDraw(rules) => rules.play_round(players).await,
+            RulesEnum::Badugi(rules) => rules.play_round(players).await,
+            RulesEnum::ShortDeckHoldem(rules) => rules.play_round(players).await,
+        }
+    }
+
+    /// see Rules::announce_player_eliminated
+    pub fn announce_player_eliminated(&self, player: &Player) {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.announce_player_eliminated(player),
+            RulesEnum::SevenCardStud(rules) => rules.announce_player_eliminated(player),
+            RulesEnum::TexasHoldem(rules) => rules.announce_player_eliminated(player),
+            RulesEnum::CommunityDraw(rules) => rules.announce_player_eliminated(player),
+            RulesEnum::Badugi(rules) => rules.announce_player_eliminated(player),
+            RulesEnum::ShortDeckHoldem(rules) => rules.announce_player_eliminated(player),
+        }
+    }
+
+    /// see Rules::set_kill
+    pub fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.set_kill(kill),
+            RulesEnum::SevenCardStud(rules) => rules.set_kill(kill),
+            RulesEnum::TexasHoldem(rules) => rules.set_kill(kill),
+            RulesEnum::CommunityDraw(rules) => rules.set_kill(kill),
+            RulesEnum::Badugi(rules) => rules.set_kill(kill),
+            RulesEnum::ShortDeckHoldem(rules) => rules.set_kill(kill),
+        }
+    }
+
+    /// see Rules::export_last_round_json
+    pub fn export_last_round_json(&self) -> Option<String> {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.export_last_round_json(),
+            RulesEnum::SevenCardStud(rules) => rules.export_last_round_json(),
+            RulesEnum::TexasHoldem(rules) => rules.export_last_round_json(),
+            RulesEnum::CommunityDraw(rules) => rules.export_last_round_json(),
+            RulesEnum::Badugi(rules) => rules.export_last_round_json(),
+            RulesEnum::ShortDeckHoldem(rules) => rules.export_last_round_json(),
+        }
+    }
+
+    /// see Rules::last_round_events
+    pub fn last_round_events(&self) -> Vec<GameEvent> {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.last_round_events(),
+            RulesEnum::SevenCardStud(rules) => rules.last_round_events(),
+            RulesEnum::TexasHoldem(rules) => rules.last_round_events(),
+            RulesEnum::CommunityDraw(rules) => rules.last_round_events(),
+            RulesEnum::Badugi(rules) => rules.last_round_events(),
+            RulesEnum::ShortDeckHoldem(rules) => rules.last_round_events(),
+        }
+    }
+
+    /// see Rules::community_cards
+    pub fn community_cards(&self) -> &[Card] {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.community_cards(),
+            RulesEnum::SevenCardStud(rules) => rules.community_cards(),
+            RulesEnum::TexasHoldem(rules) => rules.community_cards(),
+            RulesEnum::CommunityDraw(rules) => rules.community_cards(),
+            RulesEnum::Badugi(rules) => rules.community_cards(),
+            RulesEnum::ShortDeckHoldem(rules) => rules.community_cards(),
+        }
+    }
+
+    /// see Rules::set_minimum_bet
+    pub fn set_minimum_bet(&mut self, amount: u32) {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.set_minimum_bet(amount),
+            RulesEnum::SevenCardStud(rules) => rules.set_minimum_bet(amount),
+            RulesEnum::TexasHoldem(rules) => rules.set_minimum_bet(amount),
+            RulesEnum::CommunityDraw(rules) => rules.set_minimum_bet(amount),
+            RulesEnum::Badugi(rules) => rules.set_minimum_bet(amount),
+            RulesEnum::ShortDeckHoldem(rules) => rules.set_minimum_bet(amount),
+        }
+    }
+
+    /// see Rules::set_chip_size
+    pub fn set_chip_size(&mut self, chip_size: u32) {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.set_chip_size(chip_size),
+            RulesEnum::SevenCardStud(rules) => rules.set_chip_size(chip_size),
+            RulesEnum::TexasHoldem(rules) => rules.set_chip_size(chip_size),
+            RulesEnum::CommunityDraw(rules) => rules.set_chip_size(chip_size),
+            RulesEnum::Badugi(rules) => rules.set_chip_size(chip_size),
+            RulesEnum::ShortDeckHoldem(rules) => rules.set_chip_size(chip_size),
+        }
+    }
+
+    /// see Rules::set_max_raises_per_round
+    pub fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        match self {
+            RulesEnum::FiveCardDraw(rules) => rules.set_max_raises_per_round(max_raises),
+            RulesEnum::SevenCardStud(rules) => rules.set_max_raises_per_round(max_raises),
+            RulesEnum::TexasHoldem(rules) => rules.set_max_raises_per_round(max_raises),
+            RulesEnum::CommunityDraw(rules) => rules.set_max_raises_per_round(max_raises),
+            RulesEnum::Badugi(rules) => rules.set_max_raises_per_round(max_raises),
+            RulesEnum::ShortDeckHoldem(rules) => rules.set_max_raises_per_round(max_raises),
         }
     }
 }
@@ -37,3 +508,10 @@ impl<I: Input> RulesEnum<I> {
 pub mod five_card_draw;
 pub mod seven_card_stud;
 pub mod texas_holdem;
+pub mod community_draw;
+pub mod badugi;
+pub mod short_deck_holdem;
+pub mod mixed_game;
+mod betting;
+mod drawing;
+mod showdown;
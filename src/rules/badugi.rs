@@ -0,0 +1,134 @@
+use uuid::Uuid;
+
+use crate::database::db_handler::DbHandler;
+use crate::deck::DECK_SIZE;
+use crate::game_event::GameEvent;
+use crate::input::Input;
+use crate::player::Player;
+use super::five_card_draw::FiveCardDraw;
+use super::{Kill, KillNotSupported, Rules, RoundError};
+
+/// Badugi Rules
+///
+/// Badugi is a lowball draw game where each player is dealt 4 cards, and there are three draw phases,
+/// each followed by a bet phase. The best hand has four cards of different ranks and different suits,
+/// with the lowest cards (see BadugiHand for how hands are compared).
+/// Apart from that, it is played like five card draw, so its rounds are played by the five card draw rules
+/// (see FiveCardDraw::new_badugi), with the same blinds, kill pots and showdown.
+pub struct Badugi<I: Input> {
+    five_card_draw: FiveCardDraw<I>,
+}
+
+impl<I: Input> Badugi<I> {
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return self.five_card_draw.input();
+    }
+
+    /// see FiveCardDraw::input_mut
+    pub(super) fn input_mut(&mut self) -> &mut I {
+        return self.five_card_draw.input_mut();
+    }
+
+    /// see FiveCardDraw::dealer_position
+    pub(super) fn dealer_position(&self) -> usize {
+        return self.five_card_draw.dealer_position();
+    }
+
+    /// see FiveCardDraw::set_dealer_position
+    pub(super) fn set_dealer_position(&mut self, dealer_position: usize) {
+        self.five_card_draw.set_dealer_position(dealer_position);
+    }
+}
+
+impl<I: Input> Rules for Badugi<I> {
+    /// the most players that can be dealt into a round without the deck running out of cards,
+    /// each player is dealt 4 cards, and discarded cards are returned to the deck before their replacements are dealt
+    const MAX_PLAYERS: usize = DECK_SIZE / 4;
+
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> Badugi<I> {
+        return Badugi {
+            five_card_draw: FiveCardDraw::new_badugi(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id),
+        };
+    }
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        // with 4 cards each, badugi deals more players than five card draw does
+        if let Err(round_error) = Self::check_player_count(players.len()) {
+            return Err((round_error, players));
+        }
+        return self.five_card_draw.play_draw_round(players).await;
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.five_card_draw.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Badugi: 4 cards per player, three draw phases, four bet rounds, lowest badugi wins";
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.five_card_draw.export_last_round_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.five_card_draw.last_round_events();
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        return self.five_card_draw.set_kill(kill);
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        self.five_card_draw.set_round_number(round_number);
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.five_card_draw.set_minimum_bet(amount);
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        self.five_card_draw.set_chip_size(chip_size);
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.five_card_draw.set_max_raises_per_round(max_raises);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::bot_input::BotInput;
+    use crate::input::test_input::TestInput;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn play_round_with_bots() {
+        let mut badugi = Badugi::<BotInput<TestInput>>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new_bot(Uuid::now_v7(), "bot 1".to_string(), initial_balance),
+            Player::new_bot(Uuid::now_v7(), "bot 2".to_string(), initial_balance),
+            Player::new_bot(Uuid::now_v7(), "bot 3".to_string(), initial_balance)
+        ];
+
+        let players = badugi.play_round(players).await.unwrap();
+        assert_eq!(players.len(), 3);
+        assert_eq!(players.iter().map(|player| player.balance()).sum::<usize>(), 3 * initial_balance);
+        assert!(players.iter().all(|player| player.peek_at_cards().is_empty()));
+    }
+
+    #[tokio::test]
+    async fn play_round_with_more_players_than_five_card_draw() {
+        let mut badugi = Badugi::<BotInput<TestInput>>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players: Vec<Player> = (0..Badugi::<TestInput>::MAX_PLAYERS)
+            .map(|bot_number| Player::new_bot(Uuid::now_v7(), format!("bot {bot_number}"), 1000))
+            .collect();
+        assert!(players.len() > FiveCardDraw::<TestInput>::MAX_PLAYERS);
+
+        let players = badugi.play_round(players).await.unwrap();
+        assert_eq!(players.iter().map(|player| player.balance()).sum::<usize>(), 1000 * Badugi::<TestInput>::MAX_PLAYERS);
+    }
+}
@@ -0,0 +1,355 @@
+use crate::action::Action;
+use crate::action_option::ActionOption;
+use crate::card::Card;
+use crate::game_event::GameEvent;
+use crate::input::Input;
+use crate::player::Player;
+use crate::pot::Pot;
+
+use super::Kill;
+
+use std::cmp::min;
+use uuid::Uuid;
+
+/// round an amount up to a whole number of chips
+pub(crate) fn round_up_to_chip_size(amount: usize, chip_size: u32) -> usize {
+    return amount.div_ceil(chip_size as usize) * chip_size as usize;
+}
+
+/// the small blind for a new big blind, in the same proportion to it as the starting small blind was to the starting big blind,
+/// working from the starting blinds so that the proportion doesn't drift through rounding as the blinds go up
+pub(crate) fn proportional_small_blind(starting_blinds: (u32, u32), big_blind_amount: u32) -> u32 {
+    let (starting_small_blind, starting_big_blind) = starting_blinds;
+    if starting_big_blind == 0 {
+        return starting_small_blind;
+    }
+    return (starting_small_blind as u64 * big_blind_amount as u64 / starting_big_blind as u64) as u32;
+}
+
+/// snap an amount chosen by a player to the nearest whole number of chips between min_amount and max_amount,
+/// falling back to min_amount when there is no whole number of chips in between (when a player has less than a chip left)
+fn snap_to_chip_size(amount: u32, chip_size: u32, min_amount: u32, max_amount: u32) -> u32 {
+    let mut snapped_amount = (amount + chip_size / 2) / chip_size * chip_size;
+    if snapped_amount > max_amount {
+        snapped_amount = max_amount / chip_size * chip_size;
+    }
+    return snapped_amount.max(min_amount);
+}
+
+/// post a blind for a player, a player who can't cover the whole blind goes all in for what they have left,
+/// and the other players still have to call the full blind
+pub(crate) fn post_blind<I: Input>(player: &mut Player, pot: &mut Pot, input: &mut I, blind_amount: usize) {
+    let blind_amount = round_up_to_chip_size(blind_amount, pot.chip_size());
+    let player_stake = pot.get_player_stake(&player.account_id()) as usize;
+    if blind_amount <= player_stake || player.balance() == 0 {
+        return;
+    }
+    let hand = player.peek_at_cards().iter().map(|&card| card.clone()).collect();
+    let amount_paid = if player.balance() >= blind_amount - player_stake {
+        pot.add_turn(&player.account_id(), Action::Blind(blind_amount), 0, hand);
+        blind_amount - player_stake
+    }
+    else {
+        pot.add_turn(&player.account_id(), Action::AllIn(player_stake + player.balance()), 0, hand);
+        pot.set_minimum_call_amount(blind_amount as i64);
+        player.balance()
+    };
+    player.bet(amount_paid).expect("Expected the blind to be covered by the player's balance, a player who can't cover it goes all in");
+    input.on_event(GameEvent::Bet { player_id: player.account_id(), amount: amount_paid });
+}
+
+/// display the pot to all players, as well as the side pots when any player is all in
+pub(crate) fn display_pot<I: Input>(players: &[Player], pot: &Pot, input: &I) {
+    let all_players: Vec<&Player> = players.iter().collect();
+    input.display_pot(pot, all_players.clone());
+    if players.iter().any(|player| player.balance() == 0) {
+        input.display_side_pots(pot.get_side_pots(), all_players);
+    }
+}
+
+/// give each player back everything they put into the pot, for when a round has to be aborted
+pub(crate) fn refund_stakes<I: Input>(players: &mut [Player], pot: &Pot, input: &mut I) {
+    for player in players.iter_mut() {
+        let stake = pot.get_player_total_stake(&player.account_id()) as usize;
+        player.win(stake);
+        if stake > 0 {
+            input.on_event(GameEvent::Refund { player_id: player.account_id(), amount: stake });
+        }
+    }
+}
+
+/// the winner of the last kill pot (the killer) posts a kill blind, which is the effective big blind for this round,
+/// if the killer is one of the blinds, the kill blind replaces their blind.
+/// Returns the index of the killer, who acts last in the first betting phase,
+/// or None if this round isn't a kill pot
+pub(crate) fn post_kill_blind<I: Input>(players: &mut [Player], pot: &mut Pot, input: &mut I, kill: Option<Kill>, killer: Option<Uuid>, big_blind_amount: u32) -> Option<usize> {
+    let kill_blind_amount = match (kill, killer) {
+        (Some(kill), Some(_)) => round_up_to_chip_size(kill.kill_type.kill_blind(big_blind_amount) as usize, pot.chip_size()),
+        _ => return None,
+    };
+    // the killer may have left the game or be sitting out
+    let killer_index = players.iter().position(|player| Some(player.account_id()) == killer)?;
+    let killer = &mut players[killer_index];
+    let killer_stake = pot.get_player_stake(&killer.account_id()) as usize;
+    if kill_blind_amount <= killer_stake || killer.balance() < kill_blind_amount - killer_stake {
+        // the killer can't cover the kill blind
+        return None;
+    }
+    post_blind(killer, pot, input, kill_blind_amount);
+    return Some(killer_index);
+}
+
+/// Betting Round
+///
+/// The betting phase logic shared by every set of rules.
+/// The rules lend their players, pot and input to a BettingRound for the length of one betting phase,
+/// and decide which player starts the betting and what is shown to each player before they act.
+pub(crate) struct BettingRound<'a, I: Input> {
+    pub players: &'a mut Vec<Player>,
+    pub pot: &'a mut Pot,
+    pub input: &'a mut I,
+    pub raise_limit: u32,
+    /// the smallest opening bet (the big blind, or the bring in),
+    /// bets and raises are snapped to whole chips (see Pot::chip_size)
+    pub minimum_bet: u32,
+    /// the most raises that can be made in this betting round, after which players can only call or fold,
+    /// as in limit games. The opening bet (or the blinds) doesn't count as a raise. None for no cap
+    pub max_raises_per_round: Option<u32>,
+    /// show every player's balance before each turn
+    pub show_balances: bool,
+    /// the community cards shown to each player before their turn, None for variants without community cards
+    pub community_cards: Option<&'a Vec<Card>>,
+}
+
+impl<I: Input> BettingRound<'_, I> {
+    fn number_of_players_all_in(&self) -> usize {
+        return self.players.iter().filter(|player| player.balance() == 0).count();
+    }
+
+    fn number_of_players_folded(&self) -> usize {
+        return self.players.iter().filter(|player| player.has_folded()).count();
+    }
+
+    /// take back the last turn in the pot, which the player at player_index took in this phase,
+    /// returning what the player bet in that turn to them
+    fn undo_last_turn(&mut self, player_index: usize, phase_number: usize) {
+        let player = &mut self.players[player_index];
+        let undone_action = self.pot.undo_last_turn(&player.account_id(), phase_number).expect("Expected the player's turn in this phase to be undoable");
+        player.win(undone_action.refund as usize);
+        if undone_action.action == Action::Fold {
+            player.set_folded(false);
+        }
+        self.input.on_event(GameEvent::TurnUndone { player_id: player.account_id(), action: undone_action.action, phase: phase_number });
+        if undone_action.refund > 0 {
+            self.input.on_event(GameEvent::Refund { player_id: player.account_id(), amount: undone_action.refund as usize });
+        }
+    }
+
+    /// true if another raise can be made, when there have already been raise_count raises in this betting round
+    fn can_raise(&self, raise_count: u32) -> bool {
+        return self.max_raises_per_round.map_or(true, |max_raises| raise_count < max_raises);
+    }
+
+    /// play one betting phase, starting with the player at start_player_index,
+    /// until every player still in the round has matched the last raise.
+    /// players who act before anyone has bet in this phase may bet rather than raise,
+    /// except in the first phase, where the blinds (or bring in) are the opening bet.
+    /// Once max_raises_per_round raises have been made, nobody is offered a raise for the rest of the phase.
+    /// returns the index of the player the betting stopped at
+    pub fn play(&mut self, phase_number: usize, start_player_index: usize) -> usize {
+        let mut current_player_index = start_player_index;
+        let mut last_raise_player_index = current_player_index;
+        let mut raise_has_occurred = false;
+        let mut raise_count = 0;
+        loop {
+            if self.number_of_players_folded()+1 == self.players.len() {
+                // all players have folded but one, remaining player automatically wins
+                break;
+            }
+            let player_matched_call = self.pot.get_call_amount() == self.pot.get_player_stake(&self.players.get(current_player_index).unwrap().account_id());
+            if self.number_of_players_all_in()+1 == self.players.len() && player_matched_call {
+                // all players are all in but one, remaining player doesn't need to bet
+                break;
+            }
+
+            let player: &Player = &self.players.get(current_player_index).expect("Expected a player at this index, but there was None");
+
+            if player.is_sitting_out() && !player.has_folded() {
+                // players who are sitting out but were dealt in fold without being asked for an action
+                let player: &mut Player = &mut self.players.get_mut(current_player_index).expect("Expected a player at this index, but there was None");
+                player.set_folded(true);
+                self.pot.add_turn(&player.account_id(), Action::Fold, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+                self.input.on_event(GameEvent::Fold { player_id: player.account_id() });
+            }
+            else if !(player.has_folded() || player.balance() == 0) {
+                display_pot(self.players, self.pot, self.input);
+                if self.show_balances {
+                    self.input.display_player_balances(self.players.iter().collect());
+                }
+                self.input.display_current_player(player);
+                if let Some(community_cards) = self.community_cards {
+                    self.input.display_community_cards_to_player(community_cards.iter().collect(), player);
+                }
+                self.input.display_player_cards_to_player(player);
+                let can_raise = self.can_raise(raise_count);
+
+                let player: &mut Player = &mut self.players.get_mut(current_player_index).expect("Expected a player at this index, but there was None");
+                // the state of the betting before this turn, in case the player takes it back
+                let betting_before_turn = (last_raise_player_index, raise_has_occurred, raise_count);
+
+                let action = if !raise_has_occurred && self.pot.get_call_amount() == self.pot.get_player_stake(&player.account_id()) {
+                    // the big blind can check because they already paid a full bet, and on the second round, everyone can check if nobody raises
+                    let facing_forced_bet = phase_number == 1 && self.pot.get_call_amount() > 0;
+                    let mut action_options = match facing_forced_bet {
+                        true => vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold],
+                        false => vec![ActionOption::Check, ActionOption::Bet, ActionOption::Fold],
+                    };
+                    if !can_raise {
+                        action_options.retain(|action_option| *action_option != ActionOption::Raise);
+                    }
+                    action_options.sort_by_key(|action_option| action_option.display_priority());
+                    let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
+
+                    let player_raise_limit = min(self.raise_limit, player.balance() as u32);
+                    let chip_size = self.pot.chip_size();
+
+                    let action = match chosen_action_option {
+                        ActionOption::Check => Action::Check,
+                        ActionOption::Bet => {
+                            let min_bet = min(round_up_to_chip_size(self.minimum_bet as usize, chip_size) as u32, player_raise_limit);
+                            let bet_amount = snap_to_chip_size(self.input.request_bet_amount(min_bet, player_raise_limit, &player), chip_size, min_bet, player_raise_limit);
+                            Action::Bet(self.pot.get_call_amount() as usize + bet_amount as usize)
+                        },
+                        ActionOption::Raise => {
+                            let raise_amount = snap_to_chip_size(self.input.request_raise_amount(player_raise_limit, &player), chip_size, min(chip_size, player_raise_limit), player_raise_limit);
+                            Action::Raise(self.pot.get_call_amount() as usize + raise_amount as usize)
+                        },
+                        ActionOption::Fold => Action::Fold,
+                        _ => panic!("Player managed to select an impossible Action!")
+                    };
+
+                    match action {
+                        Action::Check => {},
+                        Action::Bet(raise_amount) | Action::Raise(raise_amount) => {
+                            last_raise_player_index = current_player_index;
+                            raise_has_occurred = true;
+                            if matches!(action, Action::Raise(_)) {
+                                raise_count += 1;
+                            }
+                            let bet_amount = raise_amount - self.pot.get_player_stake(&player.account_id()) as usize;
+                            player.bet(bet_amount as usize).expect("Expected the bet to be covered by the player's balance, the raise limit is capped at it");
+                            self.input.on_event(GameEvent::Bet { player_id: player.account_id(), amount: bet_amount });
+                        },
+                        Action::Fold => {
+                            player.set_folded(true);
+                            self.input.on_event(GameEvent::Fold { player_id: player.account_id() });
+                        },
+                        _ => panic!("Player managed to perform an impossible Action!")
+                    }
+
+                    self.pot.add_turn(&player.account_id(), action.clone(), phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+                    action
+                }
+                else {
+                    let current_bet_amount = self.pot.get_call_amount() as u32;
+                    if player.balance() as u32 > current_bet_amount {
+                        let mut action_options = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
+                        if !can_raise {
+                            action_options.retain(|action_option| *action_option != ActionOption::Raise);
+                        }
+                        action_options.sort_by_key(|action_option| action_option.display_priority());
+                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
+
+                        let player_raise_limit = min(self.raise_limit, player.balance() as u32 - current_bet_amount);
+                        let action = match chosen_action_option {
+                            ActionOption::Call => Action::Call,
+                            ActionOption::Raise => {
+                                let chip_size = self.pot.chip_size();
+                                let raise_amount = snap_to_chip_size(self.input.request_raise_amount(player_raise_limit, &player), chip_size, min(chip_size, player_raise_limit), player_raise_limit);
+                                Action::Raise(<i64 as TryInto<usize>>::try_into(self.pot.get_call_amount()).unwrap() + raise_amount as usize)
+                            },
+                            ActionOption::Fold => Action::Fold,
+                            _ => panic!("Player managed to select an impossible Action!")
+                        };
+
+                        match action {
+                            Action::Call => {
+                                let bet_amount = self.pot.get_call_amount() - self.pot.get_player_stake(&player.account_id());
+                                player.bet(bet_amount as usize).expect("Expected the call to be covered by the player's balance, a player who can't cover it can only go all in");
+                                self.input.on_event(GameEvent::Bet { player_id: player.account_id(), amount: bet_amount as usize });
+                            },
+                            Action::Raise(raise_amount) => {
+                                last_raise_player_index = current_player_index;
+                                raise_has_occurred = true;
+                                raise_count += 1;
+                                let bet_amount = raise_amount - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
+                                player.bet(bet_amount).expect("Expected the raise to be covered by the player's balance, the raise limit is capped at what is left after calling");
+                                self.input.on_event(GameEvent::Bet { player_id: player.account_id(), amount: bet_amount });
+                            },
+                            Action::Fold => {
+                                player.set_folded(true);
+                                self.input.on_event(GameEvent::Fold { player_id: player.account_id() });
+                            },
+                            _ => panic!("Player managed to perform an impossible Action!")
+                        }
+                        self.pot.add_turn(&player.account_id(), action.clone(), phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+                        action
+                    } else {
+                        let mut action_options = vec![ActionOption::AllIn, ActionOption::Fold];
+                        action_options.sort_by_key(|action_option| action_option.display_priority());
+                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
+
+                        // player does not have enough money for a full call, nevermind a raise
+                        let action = match chosen_action_option {
+                            ActionOption::AllIn => Action::AllIn(<i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap() + player.balance()),
+                            ActionOption::Fold => Action::Fold,
+                            _ => panic!("Player managed to select an impossible Action!")
+                        };
+
+                        match action {
+                            Action::AllIn(total_stake) => {
+                                let bet_amount = total_stake - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
+                                assert_eq!(bet_amount, player.balance());
+                                player.bet(bet_amount).expect("Expected an all in to be exactly the player's balance");
+                                self.input.on_event(GameEvent::Bet { player_id: player.account_id(), amount: bet_amount });
+                            },
+                            Action::Fold => {
+                                player.set_folded(true);
+                                self.input.on_event(GameEvent::Fold { player_id: player.account_id() });
+                            },
+                            _ => panic!("Player managed to perform an impossible Action!")
+                        }
+                        self.pot.add_turn(&player.account_id(), action.clone(), phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+                        action
+                    }
+                };
+
+                // the player can take back their turn before the next player acts, to correct a mistake,
+                // after which the betting goes back to how it was and they take their turn again
+                if self.input.request_undo_turn(&self.players[current_player_index], &action) {
+                    (last_raise_player_index, raise_has_occurred, raise_count) = betting_before_turn;
+                    self.undo_last_turn(current_player_index, phase_number);
+                    continue;
+                }
+            }
+
+            current_player_index += 1;
+            // wrap the player index around
+            if current_player_index == self.players.len() {
+                current_player_index = 0;
+            }
+
+            if current_player_index == last_raise_player_index {
+                // the next player is the player who last raised,
+                // which means that all bets have been matched,
+                // and it is time to move on to the next phase
+                break;
+            }
+        }
+        let turns: Vec<(Uuid, Action, usize, Vec<Card>)> = self.pot.get_phase_history(phase_number).into_iter().cloned().collect();
+        self.input.display_turn_summary(&turns, &self.players.iter().collect::<Vec<&Player>>());
+        #[cfg(debug_assertions)]
+        self.pot.assert_consistency();
+        return current_player_index;
+    }
+}
@@ -0,0 +1,533 @@
+use uuid::Uuid;
+
+use crate::card::Card;
+use crate::database::db_handler::DbHandler;
+use crate::deck::{Deck, DeckError, DECK_SIZE};
+use crate::hand_rank::{Hand, HandRank};
+use crate::input::Input;
+use crate::player::Player;
+use crate::pot::{Pot, PotError};
+use super::betting::{display_pot, post_blind, post_kill_blind, proportional_small_blind, refund_stakes, BettingRound};
+use super::drawing::request_cards_to_replace;
+use super::showdown::{award_winnings, kill_pot_winner, rank_hands, return_cards_to_deck, reveal_hands, seat_order, winning_order};
+use super::{Kill, KillNotSupported, Rules, RoundError};
+use crate::action_option::ActionOption;
+use crate::action::Action;
+use crate::game_event::GameEvent;
+use crate::game_type::GameType;
+
+/// Community Card Draw Rules
+/// 
+/// This struct keeps track of all information relevant to a game of community card draw,
+/// a draw variant where each player is dealt four hole cards that they may draw to replace,
+/// and shared community cards are dealt face up, like an Omaha board.
+/// At showdown, each hand is made of exactly two hole cards and three community cards.
+/// This struct has methods for each of the phases of the game,
+/// as well as some helper methods for commonly used operations.
+/// The only methods that are used by external code, however, are the constructor (new)
+/// and the play_round method which uses the rest of the methods to run a whole
+/// round of community card draw. Those two methods are an implementation of the Rules trait.
+pub struct CommunityDraw<I: Input> {
+    players: Vec<Player>,
+    deck: Deck,
+    dealer_position: usize,
+    current_player_index: usize,
+    raise_limit: u32,
+    /// the most raises in each betting round, None for no cap (see Rules::set_max_raises_per_round)
+    max_raises_per_round: Option<u32>,
+    big_blind_amount: u32,
+    small_blind_amount: u32,
+    /// the (small blind, big blind) that the rules were created with, which the small blind stays in proportion to
+    starting_blinds: (u32, u32),
+    kill: Option<Kill>,
+    /// the winner of the last kill pot, who has to post a kill blind this round
+    killer: Option<Uuid>,
+    input: I,
+    pot: Pot,
+    game_id: Uuid,
+    /// the number of this round within the game, which is saved with the round
+    round_number: u32,
+    community_cards: Vec<Card>
+}
+
+impl<I: Input> CommunityDraw<I> {
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return &self.input;
+    }
+
+    /// the input, to lend another one for a round (see MixedGame, which shares one input between its variants)
+    pub(super) fn input_mut(&mut self) -> &mut I {
+        return &mut self.input;
+    }
+
+    /// the seat of the dealer in the last round, the button moves on one seat at the start of each round
+    pub(super) fn dealer_position(&self) -> usize {
+        return self.dealer_position;
+    }
+
+    /// move the dealer button to a seat, to carry it on from a round played with other rules (see MixedGame)
+    pub(super) fn set_dealer_position(&mut self, dealer_position: usize) {
+        self.dealer_position = dealer_position;
+    }
+
+    fn increment_dealer_position(&mut self) {
+        self.dealer_position += 1;
+        if self.dealer_position >= self.players.len() {
+            self.dealer_position = 0;
+        }
+    }
+
+    fn increment_player_index(&mut self) {
+        self.current_player_index += 1;
+        // wrap the player index around
+        if self.current_player_index == self.players.len() {
+            self.current_player_index = 0;
+        }
+    }
+
+    fn play_blinds(&mut self) {
+        // the first and second players after the dealer must bet blind
+        let first_blind_player = self.players.get_mut(self.dealer_position).expect("Expected a player at the dealer position, but there was None");
+        post_blind(first_blind_player, &mut self.pot, &mut self.input, self.small_blind_amount as usize);
+        self.increment_player_index();
+
+        let second_blind_player = match self.players.get_mut(self.dealer_position+1) {
+            Some(player) => player,
+            None => {
+                self.players.get_mut(0).expect("Expected a non-zero number of players")
+            }
+        };
+        post_blind(second_blind_player, &mut self.pot, &mut self.input, self.big_blind_amount as usize);
+        self.increment_player_index();
+
+        match post_kill_blind(&mut self.players, &mut self.pot, &mut self.input, self.kill, self.killer, self.big_blind_amount) {
+            // the killer acts last in the first betting phase, like a big blind
+            Some(killer_index) => self.current_player_index = (killer_index + 1) % self.players.len(),
+            // the killer couldn't post the kill blind, so this round isn't a kill pot
+            None => self.killer = None,
+        }
+    }
+
+    fn play_bet_phase(&mut self, phase_number: usize) {
+        // betting starts with the first blind player (player at self.dealer_position),
+        // except in the first phase of a kill pot, where it starts after the killer (see play_blinds)
+        let start_player_index = match (phase_number, self.killer) {
+            (1, Some(_)) => self.current_player_index,
+            _ => self.dealer_position,
+        };
+        self.current_player_index = BettingRound {
+            players: &mut self.players,
+            pot: &mut self.pot,
+            input: &mut self.input,
+            raise_limit: self.raise_limit,
+            max_raises_per_round: self.max_raises_per_round,
+            minimum_bet: self.big_blind_amount,
+            show_balances: false,
+            community_cards: Some(&self.community_cards),
+        }.play(phase_number, start_player_index);
+    }
+
+    fn play_phase_one(&mut self) {
+        self.play_bet_phase(1);
+    }
+
+    /// play a draw phase, where each player who hasn't folded may replace some of their cards,
+    /// returns Err if the deck runs out of replacement cards
+    fn play_draw_phase(&mut self) -> Result<(), DeckError> {
+        // players may discard as many of their hole cards as they wish to draw new replacements,
+        // community cards cannot be replaced
+        let start_player_index = self.current_player_index;
+        loop {
+            if self.pot.number_of_players_folded()+1 == (self.players.len() as u32) {
+                // all players have folded but one, remaining player automatically wins
+                break;
+            }
+
+            let player: &Player = &self.players.get(self.current_player_index).expect("Expected a player at this index, but there was None");
+
+            if !self.pot.player_has_folded(&player.account_id()) {
+                display_pot(&self.players, &self.pot, &self.input);
+                self.input.display_player_balances(self.players.iter().collect());
+                self.input.display_current_player(player);
+                self.input.display_community_cards_to_player(self.community_cards.iter().collect(), player);
+                self.input.display_player_cards_to_player(player);
+
+                let player: &mut Player = self.players.get_mut(self.current_player_index).expect("Expected a player at this index, but there was None");
+
+                let mut action_options = vec![ActionOption::Replace, ActionOption::Check];
+                action_options.sort_by_key(|action_option| action_option.display_priority());
+                let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
+
+                let action = match chosen_action_option {
+                    ActionOption::Replace => Action::Replace(request_cards_to_replace(&mut self.input, player, player.peek_at_cards().len())),
+                    ActionOption::Check => Action::Check,
+                    _ => panic!("Player managed to select an impossible Action!")
+                };
+
+                match action {
+                    Action::Replace(ref cards_to_replace) => {
+                        if cards_to_replace.len() > 0 {
+                            // take all of the player's cards
+                            let mut cards = player.return_cards();
+                            // find which cards are to be kept
+                            let cards_to_remove: Vec<&Card> = cards.iter().filter(
+                                |card| cards_to_replace.iter().any(
+                                    |card_to_replace|  card_to_replace.as_ref() == *card
+                                )
+                            ).collect();
+                            // remove cards that were chosen for replacement
+                            let mut card_indices_to_remove = Vec::new();
+                            for (card_index, card) in cards.iter().enumerate() {
+                                if cards_to_remove.contains(&card) {
+                                    card_indices_to_remove.push(card_index);
+                                }
+                            }
+                            card_indices_to_remove.sort();
+                            card_indices_to_remove.reverse();
+                            card_indices_to_remove.into_iter().for_each(|card_index| self.deck.return_card(cards.remove(card_index)));
+                            // deal replacement cards
+                            for _ in 0..cards_to_replace.len() {
+                                match self.deck.deal(false) {
+                                    Ok(card) => cards.push(card),
+                                    Err(deck_error) => {
+                                        // give the player back the cards they kept, so they are returned to the deck with everyone else's
+                                        cards.into_iter().for_each(|card| player.obtain_card(card));
+                                        return Err(deck_error);
+                                    },
+                                }
+                            }
+                            // give the player back their new cards
+                            for card in cards {
+                                self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+                                player.obtain_card(card);
+                            }
+                        }
+                    },
+                    Action::Check => {
+                        // do nothing, Player has chosen not to Replace any Cards
+                    },
+                    _ => panic!("Player managed to perform an impossible Action!")
+                }
+
+                self.pot.add_turn(&player.account_id(), action, 2, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+            }
+
+            self.increment_player_index();
+
+            if self.current_player_index == start_player_index {
+                // one turn has been completed for each player,
+                // this marks the end of the draw phase
+                break;
+            }
+        }
+        return Ok(());
+    }
+
+    fn play_phase_two(&mut self) {
+        // betting on this phase starts with the player at the dealer position (or the next one that hasn't folded yet)
+        self.play_bet_phase(3);
+    }
+
+    fn play_phase_three(&mut self) {
+        self.play_bet_phase(4);
+    }
+
+    /// get the rank of a player's hand, the same way the winner(s) are decided at the showdown
+    fn rank_player_hand(&self, player: &Player) -> HandRank {
+        let hole_cards: Vec<Card> = player.peek_at_cards().iter().map(|&card| card.clone()).collect();
+        return Hand::rank_omaha_hand(&hole_cards, &self.community_cards);
+    }
+
+    /// deal the cards and play the bet and draw phases of a round, up to the showdown,
+    /// returns Err if the deck runs out of cards
+    fn deal_and_play_phases(&mut self) -> Result<(), DeckError> {
+        self.play_blinds();
+        self.deal_initial_cards()?;
+        self.play_phase_one();
+        self.deal_community_cards(3)?;
+        self.play_draw_phase()?;
+        self.play_phase_two();
+        self.deal_community_cards(2)?;
+        self.play_phase_three();
+        return Ok(());
+    }
+
+    /// abort the round that is being played, refunding everyone what they put into the pot,
+    /// and hand back the players along with the error that stopped the round
+    fn abort_round(&mut self, round_error: RoundError) -> (RoundError, Vec<Player>) {
+        println!("Error: {round_error}");
+        refund_stakes(&mut self.players, &self.pot, &mut self.input);
+        // nobody won this round, so nobody has to post a kill blind next round
+        self.killer = None;
+        self.return_cards();
+        return (round_error, self.players.drain(..).collect());
+    }
+
+    fn showdown(&mut self) -> Result<(), PotError> {
+        // show to each player everyone's cards (except folded)
+        display_pot(&self.players, &self.pot, &self.input);
+        let hand_ranks = rank_hands(&self.players, &self.pot, |player| self.rank_player_hand(player));
+        reveal_hands(&mut self.players, &mut self.pot, &mut self.input, self.current_player_index, &hand_ranks, |shown_hand_rank, hand_rank| shown_hand_rank > hand_rank);
+
+        let winning_order = winning_order(&self.players, hand_ranks, |left, right| left.cmp(right));
+        let player_winnings_map = self.pot.divide_winnings(winning_order, &seat_order(&self.players, self.dealer_position))?.to_map();
+        // a player who wins a big enough pot by themselves has to post a kill blind next round
+        self.killer = kill_pot_winner(self.kill, &player_winnings_map);
+        award_winnings(&mut self.players, &self.pot, &mut self.input, &player_winnings_map);
+        return Ok(());
+    }
+
+    fn deal_initial_cards(&mut self) -> Result<(), DeckError> {
+        for _ in 0..4 {
+            // each player gets 4 hole cards
+            for player in self.players.iter_mut() {
+                let card = self.deck.deal(false)?;
+                self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+                player.obtain_card(card);
+            }
+        }
+        return Ok(());
+    }
+
+    /// deal a number of community cards face up, these are shared by all players
+    fn deal_community_cards(&mut self, number_of_cards: usize) -> Result<(), DeckError> {
+        for _ in 0..number_of_cards {
+            let card = self.deck.deal(true)?;
+            self.input.on_event(GameEvent::CommunityCardDealt { card: card.clone() });
+            self.community_cards.push(card);
+        }
+        return Ok(());
+    }
+
+    /// return all of the cards dealt this round to the deck, and check that the whole deck is back
+    fn return_cards(&mut self) {
+        return_cards_to_deck(&mut self.deck, &mut self.players, self.community_cards.drain(..));
+    }
+}
+
+impl<I: Input> Rules for CommunityDraw<I> {
+    /// the most players that can be dealt into a round without the deck running out of cards,
+    /// each player is dealt 4 cards and 5 community cards are shared, discarded cards are returned to the deck before their replacements are dealt
+    const MAX_PLAYERS: usize = (DECK_SIZE - 5) / 4;
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        if let Err(round_error) = Self::check_player_count(players.len()) {
+            return Err((round_error, players));
+        }
+        self.input.set_game_type(GameType::CommunityDraw);
+        self.pot.clear(&players.iter().collect());
+        assert_eq!(self.community_cards.len(), 0);
+        assert_eq!(self.deck.size(), 52);
+        self.deck.shuffle();
+        self.deck.cut_random();
+        self.players = players;
+        for player in self.players.iter_mut() {
+            // nobody has folded yet in this round
+            player.set_folded(false);
+        }
+        self.increment_dealer_position();
+        assert!(self.dealer_position < self.players.len());
+        self.current_player_index = self.dealer_position;
+
+        if let Err(deck_error) = self.deal_and_play_phases() {
+            return Err(self.abort_round(RoundError::DeckExhausted(deck_error)));
+        }
+        if let Err(pot_error) = self.showdown() {
+            return Err(self.abort_round(RoundError::PotNotDivided(pot_error)));
+        }
+        self.pot.save(self.game_id, self.round_number).await;
+
+        self.return_cards();
+
+        return Ok(self.players.drain(..).collect());
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.input.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Community Card Draw: 4 down cards per player, 3 community cards, draw phase, 2 more community cards, three bet rounds";
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.pot.export_played_round_to_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.pot.round_events();
+    }
+
+    fn community_cards(&self) -> &[Card] {
+        return &self.community_cards;
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        self.kill = kill;
+        if kill.is_none() {
+            self.killer = None;
+        }
+        return Ok(());
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        self.round_number = round_number;
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.small_blind_amount = proportional_small_blind(self.starting_blinds, amount);
+        self.big_blind_amount = amount;
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        self.pot.set_chip_size(chip_size);
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.max_raises_per_round = max_raises;
+    }
+
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> CommunityDraw<I> {
+        let deck = Deck::new();
+        let dealer_position = 0_usize;
+        let current_player_index = 0_usize;
+        let players = Vec::new();
+        let pot = Pot::new(&Vec::new(), db_handler);
+        let community_cards = Vec::new();
+        return CommunityDraw {
+            players,
+            deck,
+            dealer_position,
+            current_player_index,
+            raise_limit,
+            max_raises_per_round: None,
+            big_blind_amount: minimum_bet,
+            small_blind_amount,
+            starting_blinds: (small_blind_amount, minimum_bet),
+            kill: None,
+            killer: None,
+            input: I::new(),
+            pot,
+            game_id,
+            round_number: 0,
+            community_cards
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::input::test_input::TestInput;
+
+    use super::*;
+
+    #[test]
+    fn new() {
+        let community_draw = CommunityDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+
+        assert_eq!(community_draw.deck.size(), 52);
+        assert_eq!(community_draw.dealer_position, 0);
+        assert_eq!(community_draw.current_player_index, 0);
+        assert_eq!(community_draw.pot.get_call_amount(), 0);
+        assert_eq!(community_draw.pot.get_player_ids().len(), 0);
+        assert_eq!(community_draw.players.len(), 0);
+        assert_eq!(community_draw.community_cards.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn try_play_round_one_player() {
+        let mut community_draw = CommunityDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+
+        assert!(matches!(community_draw.play_round(players).await, Err((RoundError::TooFewPlayers, _))));
+    }
+
+    #[test]
+    fn deal_initial_and_community_cards() {
+        let mut community_draw = CommunityDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        community_draw.players = players;
+        community_draw.deal_initial_cards().unwrap();
+        assert!(community_draw.community_cards().is_empty());
+        community_draw.deal_community_cards(3).unwrap();
+        assert_eq!(community_draw.community_cards().len(), 3);
+        for player in community_draw.players.iter() {
+            assert_eq!(player.peek_at_cards().len(), 4);
+            assert_eq!(player.peek_at_up_cards().len(), 0);
+        }
+        assert_eq!(community_draw.community_cards.len(), 3);
+        assert!(community_draw.community_cards.iter().all(|card| card.is_face_up()));
+        assert_eq!(community_draw.deck.size(), 52 - 3*4 - 3);
+        community_draw.return_cards();
+        assert_eq!(community_draw.deck.size(), 52);
+    }
+
+    #[test]
+    fn showdown_uses_hole_and_community_cards() {
+        let mut community_draw = CommunityDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        community_draw.players = players;
+        community_draw.play_blinds();
+        community_draw.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Check
+        ]);
+        community_draw.play_phase_one();
+
+        // player 0 has four spades in the hole, but only two of them may be used, so they have no flush,
+        // player 1 makes a straight using two hole cards and three community cards
+        for card in ["As", "Ks", "Qs", "3s"] {
+            community_draw.players[0].obtain_card(card.parse().unwrap());
+        }
+        for card in ["9h", "8c", "2h", "2c"] {
+            community_draw.players[1].obtain_card(card.parse().unwrap());
+        }
+        for card in ["7d", "6s", "5d", "Jc", "4h"] {
+            let mut card: Card = card.parse().unwrap();
+            card.set_face_up(true);
+            community_draw.community_cards.push(card);
+        }
+
+        community_draw.showdown().unwrap();
+        assert_eq!(community_draw.players[0].balance(), initial_balance - 2);
+        assert_eq!(community_draw.players[1].balance(), initial_balance + 2);
+    }
+
+    #[test]
+    fn deck_exhausted_aborts_round() {
+        let mut community_draw = CommunityDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        community_draw.pot.clear(&players.iter().collect());
+        community_draw.players = players;
+        // leave fewer cards in the deck than the players' hole cards need
+        let set_aside_cards: Vec<Card> = (0..47).map(|_| community_draw.deck.deal(false).unwrap()).collect();
+
+        assert_eq!(community_draw.deal_and_play_phases(), Err(DeckError::Empty));
+        for card in set_aside_cards {
+            community_draw.deck.return_card(card);
+        }
+        let (round_error, players) = community_draw.abort_round(RoundError::DeckExhausted(DeckError::Empty));
+
+        // the players get back their blinds and their cards
+        assert_eq!(round_error, RoundError::DeckExhausted(DeckError::Empty));
+        assert!(players.iter().all(|player| player.balance() == initial_balance && player.peek_at_cards().is_empty()));
+        community_draw.deck.assert_complete();
+    }
+}
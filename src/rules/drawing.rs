@@ -0,0 +1,27 @@
+use crate::card::Card;
+use crate::input::Input;
+use crate::player::Player;
+
+/// how many times a player is asked for the cards to replace before they are treated as keeping all of their cards,
+/// so that a player (or client) who keeps choosing invalid cards can't hold up the draw phase
+pub(crate) const MAX_REPLACE_CARD_REQUESTS: usize = 3;
+
+/// ask a player which of their cards to replace in a draw phase, at most max_draw of them.
+/// A player who chooses too many cards, or cards that they don't hold, is told why and asked again,
+/// after MAX_REPLACE_CARD_REQUESTS invalid choices none of their cards are replaced
+pub(crate) fn request_cards_to_replace<I: Input>(input: &mut I, player: &Player, max_draw: usize) -> Vec<Box<Card>> {
+    for _ in 0..MAX_REPLACE_CARD_REQUESTS {
+        let cards_to_replace = input.request_replace_cards(player);
+        if cards_to_replace.len() > max_draw {
+            println!("{} chose {} cards to replace, but at most {max_draw} can be replaced", player.name(), cards_to_replace.len());
+        }
+        else if !player.holds_cards(&cards_to_replace) {
+            println!("{} chose cards to replace that they don't hold, each of their own cards can be chosen once", player.name());
+        }
+        else {
+            return cards_to_replace.into_iter().map(|card| Box::new(card.clone())).collect();
+        }
+    }
+    println!("{} made too many invalid choices, so none of their cards are replaced", player.name());
+    return Vec::new();
+}
@@ -1,17 +1,21 @@
 use uuid::Uuid;
 
+use crate::badugi_hand::BadugiHand;
 use crate::card::Card;
 use crate::database::db_handler::DbHandler;
-use crate::deck::Deck;
-use crate::hand_rank::Hand;
+use crate::deck::{Deck, DeckError, DECK_SIZE};
+use crate::hand_rank::{Hand, HandRank};
 use crate::input::Input;
 use crate::player::Player;
-use crate::pot::Pot;
-use super::Rules;
+use crate::pot::{Pot, PotError};
+use super::betting::{display_pot, post_blind, post_kill_blind, proportional_small_blind, refund_stakes, BettingRound};
+use super::drawing::request_cards_to_replace;
+use super::showdown::{award_winnings, kill_pot_winner, rank_hands, return_cards_to_deck, reveal_hands, seat_order, winning_order};
+use super::{Kill, KillNotSupported, Rules, RoundError};
 use crate::action_option::ActionOption;
 use crate::action::Action;
-
-use std::cmp::min;
+use crate::game_event::GameEvent;
+use crate::game_type::GameType;
 
 /// Five Card Draw Rules
 /// 
@@ -27,15 +31,90 @@ pub struct FiveCardDraw<I: Input> {
     dealer_position: usize,
     current_player_index: usize,
     raise_limit: u32,
+    /// the most raises in each betting round, None for no cap (see Rules::set_max_raises_per_round)
+    max_raises_per_round: Option<u32>,
     big_blind_amount: u32,
+    small_blind_amount: u32,
+    /// the (small blind, big blind) that the rules were created with, which the small blind stays in proportion to
+    starting_blinds: (u32, u32),
+    kill: Option<Kill>,
+    /// the winner of the last kill pot, who has to post a kill blind this round
+    killer: Option<Uuid>,
+    max_draw: usize,
+    /// the card that is wild, as a house rule, if there is one (see set_wild_card)
+    wild_card: Option<Card>,
+    /// true if the rounds are played as badugi, with 4 cards, three draws and the lowest badugi winning (see new_badugi)
+    badugi: bool,
     input: I,
     pot: Pot,
-    game_id: Uuid
+    game_id: Uuid,
+    /// the number of this round within the game, which is saved with the round
+    round_number: u32
 }
 
 impl<I: Input> FiveCardDraw<I> {
-    fn number_of_players_all_in(&self) -> usize {
-        return self.players.iter().filter(|player| player.balance() == 0).count();
+    /// create a new instance of five card draw rules, with house rules that cap
+    /// the number of cards a player may replace during the draw phase at max_draw
+    pub fn new_with_max_draw(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, max_draw: usize, db_handler: DbHandler, game_id: Uuid) -> FiveCardDraw<I> {
+        let deck = Deck::new();
+        let dealer_position = 0_usize;
+        let current_player_index = 0_usize;
+        let players = Vec::new();
+        let pot = Pot::new(&Vec::new(), db_handler);
+        return FiveCardDraw {
+            players,
+            deck,
+            dealer_position,
+            current_player_index,
+            raise_limit,
+            max_raises_per_round: None,
+            big_blind_amount: minimum_bet,
+            small_blind_amount,
+            starting_blinds: (small_blind_amount, minimum_bet),
+            kill: None,
+            killer: None,
+            max_draw,
+            wild_card: None,
+            badugi: false,
+            input: I::new(),
+            pot,
+            game_id,
+            round_number: 0
+        };
+    }
+
+    /// create a new instance of draw rules that play badugi (see Badugi) rather than five card draw,
+    /// each player is dealt 4 cards and may replace all of them in each of the three draws
+    pub(super) fn new_badugi(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> FiveCardDraw<I> {
+        let mut badugi = FiveCardDraw::new_with_max_draw(raise_limit, minimum_bet, small_blind_amount, 4, db_handler, game_id);
+        badugi.badugi = true;
+        return badugi;
+    }
+
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return &self.input;
+    }
+
+    /// the input, to lend another one for a round (see MixedGame, which shares one input between its variants)
+    pub(super) fn input_mut(&mut self) -> &mut I {
+        return &mut self.input;
+    }
+
+    /// the seat of the dealer in the last round, the button moves on one seat at the start of each round
+    pub(super) fn dealer_position(&self) -> usize {
+        return self.dealer_position;
+    }
+
+    /// move the dealer button to a seat, to carry it on from a round played with other rules (see MixedGame)
+    pub(super) fn set_dealer_position(&mut self, dealer_position: usize) {
+        self.dealer_position = dealer_position;
+    }
+
+    /// make a card wild, as a house rule (like a joker), so that whoever holds it at the showdown
+    /// can count it as whichever card makes their best hand (see Hand::rank_hand_with_wilds)
+    pub fn set_wild_card(&mut self, card: Card) {
+        self.wild_card = Some(card);
     }
 
     fn increment_dealer_position(&mut self) {
@@ -56,8 +135,7 @@ impl<I: Input> FiveCardDraw<I> {
     fn play_blinds(&mut self) {
         // the first and second players after the dealer must bet blind
         let first_blind_player = self.players.get_mut(self.dealer_position).expect("Expected a player at the dealer position, but there was None");
-        self.pot.add_turn(&first_blind_player.account_id(), Action::Ante(<u32 as TryInto<usize>>::try_into(self.big_blind_amount).unwrap()/2), 0, first_blind_player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-        first_blind_player.bet(<u32 as TryInto<usize>>::try_into(self.big_blind_amount).unwrap()/2).unwrap();
+        post_blind(first_blind_player, &mut self.pot, &mut self.input, self.small_blind_amount as usize);
         self.increment_player_index();
 
         let second_blind_player = match self.players.get_mut(self.dealer_position+1) {
@@ -66,135 +144,60 @@ impl<I: Input> FiveCardDraw<I> {
                 self.players.get_mut(0).expect("Expected a non-zero number of players")
             }
         };
-        self.pot.add_turn(&second_blind_player.account_id(), Action::Ante(self.big_blind_amount as usize), 0, second_blind_player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-        second_blind_player.bet(self.big_blind_amount as usize).unwrap();
+        post_blind(second_blind_player, &mut self.pot, &mut self.input, self.big_blind_amount as usize);
         self.increment_player_index();
+
+        match post_kill_blind(&mut self.players, &mut self.pot, &mut self.input, self.kill, self.killer, self.big_blind_amount) {
+            // the killer acts last in the first betting phase, like a big blind
+            Some(killer_index) => self.current_player_index = (killer_index + 1) % self.players.len(),
+            // the killer couldn't post the kill blind, so this round isn't a kill pot
+            None => self.killer = None,
+        }
     }
 
     fn play_bet_phase(&mut self, phase_number: usize) {
-        // betting starts with the first blind player (player at self.dealer_position)
-        self.current_player_index = self.dealer_position;
-        let mut last_raise_player_index = self.current_player_index;
-        let mut raise_has_occurred = false;
-        loop {
-            if self.pot.number_of_players_folded()+1 == (self.players.len() as u32) {
-                // all players have folded but one, remaining player automatically wins
-                break;
-            }
-            let player_matched_call = self.pot.get_call_amount() == self.pot.get_player_stake(&self.players.get(self.current_player_index).unwrap().account_id());
-            if self.number_of_players_all_in()+1 == self.players.len() && player_matched_call {
-                // all players are all in but one, remaining player doesn't need to bet
-                break;
-            }
-
-            let player: &Player = &self.players.get(self.current_player_index).expect("Expected a player at this index, but there was None");
-
-            if !(self.pot.player_has_folded(&player.account_id()) || player.balance() == 0) {
-                self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
-                self.input.display_current_player(player);
-                self.input.display_player_cards_to_player(player);
-
-                let player: &mut Player = &mut self.players.get_mut(self.current_player_index).expect("Expected a player at this index, but there was None");
-
-                if !raise_has_occurred && self.pot.get_call_amount() == self.pot.get_player_stake(&player.account_id()) {
-                    // the big blind can check because they already paid a full bet, and on the second round, everyone can check if nobody raises
-                    let action_options = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
-                    let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                    let player_raise_limit = min(self.raise_limit, player.balance() as u32);
-
-                    let action = match chosen_action_option {
-                        ActionOption::Check => Action::Check,
-                        ActionOption::Raise => Action::Raise(self.pot.get_call_amount() as usize + self.input.request_raise_amount(player_raise_limit, &player) as usize),
-                        ActionOption::Fold => Action::Fold,
-                        _ => panic!("Player managed to select an impossible Action!")
-                    };
-
-                    match action {
-                        Action::Check => {},
-                        Action::Raise(raise_amount) => {
-                            last_raise_player_index = self.current_player_index;
-                            raise_has_occurred = true;
-                            let bet_amount = raise_amount - self.pot.get_player_stake(&player.account_id()) as usize;
-                            player.bet(bet_amount as usize).unwrap();
-                        },
-                        Action::Fold => {},
-                        _ => panic!("Player managed to perform an impossible Action!")
-                    }
-
-                    self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                }
-                else {
-                    let current_bet_amount = self.pot.get_call_amount() as u32;
-                    if player.balance() as u32 > current_bet_amount {
-                        let action_options = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
-                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                        let player_raise_limit = min(self.raise_limit, player.balance() as u32 - current_bet_amount);
-                        let action = match chosen_action_option {
-                            ActionOption::Call => Action::Call,
-                            ActionOption::Raise => Action::Raise(<i64 as TryInto<usize>>::try_into(self.pot.get_call_amount()).unwrap() + self.input.request_raise_amount(player_raise_limit, &player) as usize),
-                            ActionOption::Fold => Action::Fold,
-                            _ => panic!("Player managed to select an impossible Action!")
-                        };
-    
-                        match action {
-                            Action::Call => {
-                                let bet_amount = self.pot.get_call_amount() - self.pot.get_player_stake(&player.account_id());
-                                player.bet(bet_amount as usize).unwrap();
-                            },
-                            Action::Raise(raise_amount) => {
-                                last_raise_player_index = self.current_player_index;
-                                raise_has_occurred = true;
-                                let bet_amount = raise_amount - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
-                                player.bet(bet_amount).unwrap();
-                            },
-                            Action::Fold => {},
-                            _ => panic!("Player managed to perform an impossible Action!")
-                        }
-                        self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                    } else {
-                        let action_options = vec![ActionOption::AllIn, ActionOption::Fold];
-                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                        // player does not have enough money for a full call, nevermind a raise
-                        let action = match chosen_action_option {
-                            ActionOption::AllIn => Action::AllIn(<i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap() + player.balance()),
-                            ActionOption::Fold => Action::Fold,
-                            _ => panic!("Player managed to select an impossible Action!")
-                        };
-    
-                        match action {
-                            Action::AllIn(total_stake) => {
-                                let bet_amount = total_stake - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
-                                assert_eq!(bet_amount, player.balance());
-                                player.bet(bet_amount).unwrap();
-                            },
-                            Action::Fold => {},
-                            _ => panic!("Player managed to perform an impossible Action!")
-                        }
-                        self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                    };
-                }
-            }
-
-            self.increment_player_index();
-
-            if self.current_player_index == last_raise_player_index {
-                // the next player is the player who last raised,
-                // which means that all bets have been matched,
-                // and it is time to move on to the next phase
-                break;
-            }
-        }
+        // betting starts with the first blind player (player at self.dealer_position),
+        // except in the first phase of a kill pot, where it starts after the killer (see play_blinds)
+        let start_player_index = match (phase_number, self.killer) {
+            (1, Some(_)) => self.current_player_index,
+            _ => self.dealer_position,
+        };
+        self.current_player_index = BettingRound {
+            players: &mut self.players,
+            pot: &mut self.pot,
+            input: &mut self.input,
+            raise_limit: self.raise_limit,
+            max_raises_per_round: self.max_raises_per_round,
+            minimum_bet: self.big_blind_amount,
+            show_balances: false,
+            community_cards: None,
+        }.play(phase_number, start_player_index);
     }
 
     fn play_phase_one(&mut self) {
         self.play_bet_phase(1);
     }
 
-    fn play_draw_phase(&mut self) {
-        // house rules: players may discard as many cards as they wish to draw new replacements
+    /// the number of cards each player is dealt
+    fn hand_size(&self) -> usize {
+        return match self.badugi {
+            true => 4,
+            false => 5,
+        };
+    }
+
+    /// the number of draw phases in a round, each of them followed by a bet phase
+    fn number_of_draws(&self) -> usize {
+        return match self.badugi {
+            true => 3,
+            false => 1,
+        };
+    }
+
+    /// play a draw phase, where each player who hasn't folded may replace some of their cards,
+    /// returns Err if the deck runs out of replacement cards
+    fn play_draw_phase(&mut self, phase_number: usize) -> Result<(), DeckError> {
+        // house rules: players may discard as many cards as they wish to draw new replacements, up to max_draw cards
         let start_player_index = self.current_player_index;
         loop {
             if self.pot.number_of_players_folded()+1 == (self.players.len() as u32) {
@@ -205,24 +208,19 @@ impl<I: Input> FiveCardDraw<I> {
             let player: &Player = &self.players.get(self.current_player_index).expect("Expected a player at this index, but there was None");
 
             if !self.pot.player_has_folded(&player.account_id()) {
-                self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
+                display_pot(&self.players, &self.pot, &self.input);
                 self.input.display_player_balances(self.players.iter().collect());
                 self.input.display_current_player(player);
                 self.input.display_player_cards_to_player(player);
 
                 let player: &mut Player = self.players.get_mut(self.current_player_index).expect("Expected a player at this index, but there was None");
 
-                let action_options = vec![ActionOption::Replace, ActionOption::Check];
+                let mut action_options = vec![ActionOption::Replace, ActionOption::Check];
+                action_options.sort_by_key(|action_option| action_option.display_priority());
                 let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
 
                 let action = match chosen_action_option {
-                    ActionOption::Replace => Action::Replace(
-                        self.input.request_replace_cards(
-                            &player
-                        ).iter().map(
-                            |card| Box::new((*card).clone())
-                        ).collect()
-                    ),
+                    ActionOption::Replace => Action::Replace(request_cards_to_replace(&mut self.input, player, self.max_draw)),
                     ActionOption::Check => Action::Check,
                     _ => panic!("Player managed to select an impossible Action!")
                 };
@@ -250,10 +248,20 @@ impl<I: Input> FiveCardDraw<I> {
                             card_indices_to_remove.into_iter().for_each(|card_index| self.deck.return_card(cards.remove(card_index)));
                             // deal replacement cards
                             for _ in 0..cards_to_replace.len() {
-                                cards.push(self.deck.deal(false).unwrap());
+                                match self.deck.deal(false) {
+                                    Ok(card) => cards.push(card),
+                                    Err(deck_error) => {
+                                        // give the player back the cards they kept, so they are returned to the deck with everyone else's
+                                        cards.into_iter().for_each(|card| player.obtain_card(card));
+                                        return Err(deck_error);
+                                    },
+                                }
                             }
                             // give the player back their new cards
-                            cards.into_iter().for_each(|card| player.obtain_card(card));
+                            for card in cards {
+                                self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+                                player.obtain_card(card);
+                            }
                         }
                     },
                     Action::Check => {
@@ -262,7 +270,7 @@ impl<I: Input> FiveCardDraw<I> {
                     _ => panic!("Player managed to perform an impossible Action!")
                 }
 
-                self.pot.add_turn(&player.account_id(), action, 2, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+                self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
             }
 
             self.increment_player_index();
@@ -273,160 +281,178 @@ impl<I: Input> FiveCardDraw<I> {
                 break;
             }
         }
+        return Ok(());
     }
 
-    fn play_phase_two(&mut self) {
-        // betting on this phase starts with the player at the dealer position (or the next one that hasn't folded yet)
-        // this is identical to the first phase, in certain variations of five card draw, so it is in our rules
-        self.play_bet_phase(3);
+    /// get the rank of a player's hand, the same way the winner(s) are decided at the showdown
+    fn rank_player_hand(&self, player: &Player) -> HandRank {
+        let cards: Vec<Card> = player.peek_at_cards().iter().map(|&card| card.clone()).collect();
+        return match &self.wild_card {
+            Some(wild_card) => Hand::rank_hand_with_wilds(&cards, std::slice::from_ref(wild_card)),
+            None => Hand::rank_hand(&cards),
+        };
     }
 
-    /// take each non-folded player's cards, and make them all up cards (visible to everyone)
-    fn flip_non_folded_players_cards_up(&mut self) {
-        for player in self.players.iter_mut().filter(|player| !self.pot.player_has_folded(&player.account_id())) {
-            let mut cards = player.return_cards();
-            cards.iter_mut().for_each(|card| card.set_face_up(true));
-            for card in cards {
-                player.obtain_card(card);
-            }
-        }
+    /// get the badugi hand of a player, the same way the winner(s) are decided at a badugi showdown
+    fn rank_player_badugi_hand(&self, player: &Player) -> BadugiHand {
+        let cards: Vec<Card> = player.peek_at_cards().iter().map(|&card| card.clone()).collect();
+        return BadugiHand::new(&cards);
     }
 
-    fn showdown(&mut self) {
-        // show to each player everyone's cards (except folded)
-        let start_player_index = self.current_player_index;
-        let mut current_player_index = self.current_player_index;
-        self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
-        self.flip_non_folded_players_cards_up();
-        loop {
-            let player: &Player = self.players.get(current_player_index).expect("Expected a player at this index, but there was None");
-
-            if !self.pot.player_has_folded(&player.account_id()) {
-                let other_players: Vec<&Player> = self.players.iter()
-                    .filter(|&other_player| other_player != player)
-                    .map(|player| player as &Player)
-                    .collect();
-                self.input.display_other_player_up_cards_to_player(other_players, player);
-            }
+    fn showdown(&mut self) -> Result<(), PotError> {
+        return match self.badugi {
+            true => self.showdown_by(Self::rank_player_badugi_hand),
+            false => self.showdown_by(Self::rank_player_hand),
+        };
+    }
 
-            current_player_index += 1;
-            // wrap the player index around
-            if current_player_index == self.players.len() {
-                current_player_index = 0;
-            }
+    /// the showdown, where the best hand is the greatest rank given by rank_player_hand
+    fn showdown_by<R: Ord>(&mut self, rank_player_hand: fn(&Self, &Player) -> R) -> Result<(), PotError> {
+        // show to each player everyone's cards (except folded)
+        display_pot(&self.players, &self.pot, &self.input);
+        let hand_ranks = rank_hands(&self.players, &self.pot, |player| rank_player_hand(self, player));
+        reveal_hands(&mut self.players, &mut self.pot, &mut self.input, self.current_player_index, &hand_ranks, |shown_hand_rank, hand_rank| shown_hand_rank > hand_rank);
+
+        let winning_order = winning_order(&self.players, hand_ranks, |left, right| left.cmp(right));
+        let player_winnings_map = self.pot.divide_winnings(winning_order, &seat_order(&self.players, self.dealer_position))?.to_map();
+        // a player who wins a big enough pot by themselves has to post a kill blind next round
+        self.killer = kill_pot_winner(self.kill, &player_winnings_map);
+        award_winnings(&mut self.players, &self.pot, &mut self.input, &player_winnings_map);
+        return Ok(());
+    }
 
-            if current_player_index == start_player_index {
-                // one turn has been completed for each player,
-                // this marks the end of the draw phase
-                break;
+    fn deal_initial_cards(&mut self) -> Result<(), DeckError> {
+        for _ in 0..self.hand_size() {
+            // each player gets 5 cards (4 in badugi)
+            for player in self.players.iter_mut() {
+                let card = self.deck.deal(false)?;
+                self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+                player.obtain_card(card);
             }
         }
+        return Ok(());
+    }
 
-        let mut player_cards: Vec<(Uuid, Vec<&Card>)> = self.players.iter()
-            .filter(|player| !self.pot.player_has_folded(&player.account_id()))
-            .map(|player| (player.account_id(), player.peek_at_cards()))
-            .collect();
-        player_cards.sort_by(|left, right| Hand::new(right.1.iter().map(|&card| card.clone()).collect())
-            .cmp(&Hand::new(left.1.iter().map(|&card| card.clone())
-            .collect()))); // sort by best hand of cards first // FIXME: unsure if problematic if there's one or more ties
-        let mut winning_order: Vec<Vec<Uuid>> = vec![vec![player_cards[0].0]];
-        for player_cards_index in 1..player_cards.len() {
-            let this_players_hand = Hand::new(player_cards[player_cards_index].1.iter().map(|&card| card.clone()).collect());
-            let last_players_hand = Hand::new(player_cards[player_cards_index-1].1.iter().map(|&card| card.clone()).collect());
-            if this_players_hand == last_players_hand {
-                winning_order.last_mut().unwrap().push(player_cards[player_cards_index].0);
-            }
-            else {
-                assert!(this_players_hand < last_players_hand);
-                winning_order.push(vec![player_cards[player_cards_index].0]);
-            }
-        }
-        winning_order.push(self.players.iter()
-            .filter(|player| self.pot.player_has_folded(&player.account_id()))
-            .map(|player| player.account_id()).collect());
-        let player_winnings_map = self.pot.divide_winnings(winning_order);
-        let mut winner_uuids = Vec::new();
-        for (player_id, &winnings) in player_winnings_map.iter() {
-            assert!(winnings >= 0);
-            if winnings > 0 {
-                let mut player_matches: Vec<&mut Player> = self.players.iter_mut().filter(|player| player.account_id() == *player_id).collect();
-                assert_eq!(player_matches.len(), 1);
-                let player_match = &mut player_matches[0];
-                assert!(!self.pot.player_has_folded(&player_match.account_id()), "Player: {}, winning amount: {}", player_match.account_id(), winnings);
-                player_match.win(winnings as usize);
-                winner_uuids.push(player_id);
-            }
-        }
-        let winners: Vec<&Player> = self.players.iter().filter(|player| winner_uuids.iter().any(|&uuid| player.account_id() == *uuid)).map(|player| player as &Player).collect();
-        self.input.announce_winner(winners, self.players.iter().map(|player| player as &Player).collect());
-        self.input.display_player_balances(self.players.iter().collect());
+    /// return all of the cards dealt this round to the deck, and check that the whole deck is back
+    fn return_cards(&mut self) {
+        return_cards_to_deck(&mut self.deck, &mut self.players, []);
     }
 
-    fn deal_initial_cards(&mut self) -> Result<(), String> {
-        for _ in 0..5 {
-            // each player gets 5 cards
-            for player in self.players.iter_mut() {
-                player.obtain_card(self.deck.deal(false)?);
-            }
+    /// play a round with players whose count has already been checked against the MAX_PLAYERS of the game being played
+    /// deal the cards and play the bet and draw phases of a round, up to the showdown,
+    /// returns Err if the deck runs out of cards
+    fn deal_and_play_phases(&mut self) -> Result<(), DeckError> {
+        self.play_blinds();
+        self.deal_initial_cards()?;
+        self.play_phase_one();
+        for draw_phase_number in (0..self.number_of_draws()).map(|draw| 2 + 2*draw) {
+            self.play_draw_phase(draw_phase_number)?;
+            // betting after a draw starts with the player at the dealer position (or the next one that hasn't folded yet)
+            // this is identical to the first phase, in certain variations of five card draw, so it is in our rules
+            self.play_bet_phase(draw_phase_number + 1);
         }
         return Ok(());
     }
 
-    fn return_player_cards(&mut self) {
-        for player in self.players.iter_mut() {
-            let cards = player.return_cards();
-            for card in cards {
-                self.deck.return_card(card);
-            }
-        }
+    /// abort the round that is being played, refunding everyone what they put into the pot,
+    /// and hand back the players along with the error that stopped the round
+    fn abort_round(&mut self, round_error: RoundError) -> (RoundError, Vec<Player>) {
+        println!("Error: {round_error}");
+        refund_stakes(&mut self.players, &self.pot, &mut self.input);
+        // nobody won this round, so nobody has to post a kill blind next round
+        self.killer = None;
+        self.return_cards();
+        return (round_error, self.players.drain(..).collect());
     }
-}
 
-impl<I: Input> Rules for FiveCardDraw<I> {
-    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (&'static str, Vec<Player>)> {
-        if players.len() < 2 {
-            return Err(("Cannot start a game with less than 2 players", players));
-        }
-        if players.len() > 10 {
-            return Err(("Cannot start a game with more than 10 players, as the deck may run out of cards", players));
-        }
+    pub(super) async fn play_draw_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        self.input.set_game_type(match self.badugi {
+            true => GameType::Badugi,
+            false => GameType::FiveCardDraw,
+        });
         self.pot.clear(&players.iter().collect());
         assert_eq!(self.deck.size(), 52);
+        self.deck.shuffle();
+        self.deck.cut_random();
         self.players = players;
+        for player in self.players.iter_mut() {
+            // nobody has folded yet in this round
+            player.set_folded(false);
+        }
         self.increment_dealer_position();
         assert!(self.dealer_position < self.players.len());
         self.current_player_index = self.dealer_position;
 
-        self.play_blinds();
-        self.deal_initial_cards().unwrap();
-        self.play_phase_one();
-        self.play_draw_phase();
-        self.play_phase_two();
-        self.showdown();
-        self.pot.save(self.game_id).await;
+        if let Err(deck_error) = self.deal_and_play_phases() {
+            return Err(self.abort_round(RoundError::DeckExhausted(deck_error)));
+        }
+        if let Err(pot_error) = self.showdown() {
+            return Err(self.abort_round(RoundError::PotNotDivided(pot_error)));
+        }
+        self.pot.save(self.game_id, self.round_number).await;
 
-        self.return_player_cards();
+        self.return_cards();
 
         return Ok(self.players.drain(..).collect());
     }
+}
 
-    fn new(raise_limit: u32, minimum_bet: u32, db_handler: DbHandler, game_id: Uuid) -> FiveCardDraw<I> {
-        let deck = Deck::new();
-        let dealer_position = 0_usize;
-        let current_player_index = 0_usize;
-        let players = Vec::new();
-        let pot = Pot::new(&Vec::new(), db_handler);
-        return FiveCardDraw {
-            players,
-            deck,
-            dealer_position,
-            current_player_index,
-            raise_limit,
-            big_blind_amount: minimum_bet,
-            input: I::new(),
-            pot,
-            game_id
-        };
+impl<I: Input> Rules for FiveCardDraw<I> {
+    /// the most players that can be dealt into a round without the deck running out of cards,
+    /// each player is dealt 5 cards, and discarded cards are returned to the deck before their replacements are dealt
+    const MAX_PLAYERS: usize = DECK_SIZE / 5;
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        if let Err(round_error) = Self::check_player_count(players.len()) {
+            return Err((round_error, players));
+        }
+        return self.play_draw_round(players).await;
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.input.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Five Card Draw: 5 cards per player, draw phase, two bet rounds";
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.pot.export_played_round_to_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.pot.round_events();
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        self.kill = kill;
+        if kill.is_none() {
+            self.killer = None;
+        }
+        return Ok(());
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        self.round_number = round_number;
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.small_blind_amount = proportional_small_blind(self.starting_blinds, amount);
+        self.big_blind_amount = amount;
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        self.pot.set_chip_size(chip_size);
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.max_raises_per_round = max_raises;
+    }
+
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> FiveCardDraw<I> {
+        // by default, players may replace all 5 of their cards
+        return FiveCardDraw::new_with_max_draw(raise_limit, minimum_bet, small_blind_amount, 5, db_handler, game_id);
     }
 }
 
@@ -434,13 +460,16 @@ impl<I: Input> Rules for FiveCardDraw<I> {
 mod tests {
     use uuid::Uuid;
 
+    use crate::input::bot_input::BotInput;
     use crate::input::test_input::TestInput;
+    use crate::rules::KillType;
+    use crate::rules::drawing::MAX_REPLACE_CARD_REQUESTS;
 
     use super::*;
 
     #[test]
     fn new() {
-        let five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
 
         assert_eq!(five_card_draw.deck.size(), 52);
         assert_eq!(five_card_draw.dealer_position, 0);
@@ -452,17 +481,17 @@ mod tests {
 
     #[tokio::test]
     async fn try_play_round_one_player() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
         ];
 
-        assert!(five_card_draw.play_round(players).await.is_err_and(|err| err.0 == "Cannot start a game with less than 2 players"));
+        assert!(matches!(five_card_draw.play_round(players).await, Err((RoundError::TooFewPlayers, _))));
     }
 
     #[test]
     fn increment_dealer_position() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
@@ -480,7 +509,7 @@ mod tests {
 
     #[test]
     fn increment_player_index() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
@@ -498,7 +527,7 @@ mod tests {
 
     #[test]
     fn play_blinds() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -511,11 +540,133 @@ mod tests {
         assert_eq!(five_card_draw.current_player_index, 2);
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-1);
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-2);
+        assert_eq!(five_card_draw.input.events(), &vec![
+            GameEvent::Bet { player_id: five_card_draw.players[0].account_id(), amount: 1 },
+            GameEvent::Bet { player_id: five_card_draw.players[1].account_id(), amount: 2 },
+        ]);
+    }
+
+    #[test]
+    fn play_blinds_two_five() {
+        // a small blind that isn't half of the big blind
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 5, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.play_blinds();
+        assert_eq!(five_card_draw.pot.get_call_amount(), 5);
+        assert_eq!(five_card_draw.pot.get_total_stake(), 7);
+        assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-2);
+        assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-5);
+        assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance);
+
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Call, // betting starts with the small blind, who pays the 3 that they are short
+            ActionOption::Check,
+            ActionOption::Call
+        ]);
+        five_card_draw.play_phase_one();
+        five_card_draw.input.assert_all_actions_consumed();
+        assert_eq!(five_card_draw.pot.get_total_stake(), 15);
+        for player in five_card_draw.players.into_iter() {
+            assert_eq!(player.balance(), initial_balance-5);
+        }
+    }
+
+    #[test]
+    fn play_blinds_in_kill_pot() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.set_kill(Some(Kill { kill_type: KillType::FullKill, qualifying_pot: 20 })).unwrap();
+        five_card_draw.killer = Some(five_card_draw.players[2].account_id());
+        five_card_draw.play_blinds();
+        // the killer posts a kill blind of twice the big blind, as well as the usual blinds being posted
+        assert_eq!(five_card_draw.pot.get_call_amount(), 4);
+        assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-1);
+        assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-2);
+        assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance-4);
+
+        // the killer acts last in the first betting phase, like a big blind
+        assert_eq!(five_card_draw.current_player_index, 0);
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Call,
+            ActionOption::Check
+        ]);
+        five_card_draw.play_phase_one();
+        five_card_draw.input.assert_all_actions_consumed();
+        assert_eq!(five_card_draw.pot.get_total_stake(), 12);
+    }
+
+    #[test]
+    fn play_blinds_in_kill_pot_killer_is_big_blind() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 4, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.set_kill(Some(Kill { kill_type: KillType::HalfKill, qualifying_pot: 20 })).unwrap();
+        five_card_draw.killer = Some(five_card_draw.players[1].account_id());
+        five_card_draw.play_blinds();
+        // the kill blind replaces the big blind
+        assert_eq!(five_card_draw.pot.get_call_amount(), 6);
+        assert_eq!(five_card_draw.pot.get_total_stake(), 2 + 6);
+        assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-2);
+        assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-6);
+        assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance);
+    }
+
+    #[test]
+    fn play_blinds_in_kill_pot_rounded_to_chip_size() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 4, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.set_chip_size(4);
+        five_card_draw.set_kill(Some(Kill { kill_type: KillType::HalfKill, qualifying_pot: 20 })).unwrap();
+        five_card_draw.killer = Some(five_card_draw.players[2].account_id());
+        five_card_draw.play_blinds();
+        // a half kill of 6 is rounded up to the next chip, like the blinds are
+        assert_eq!(five_card_draw.pot.get_call_amount(), 8);
+        assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance-8);
+    }
+
+    #[test]
+    fn play_blinds_killer_not_dealt_in() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.set_kill(Some(Kill { kill_type: KillType::FullKill, qualifying_pot: 20 })).unwrap();
+        five_card_draw.killer = Some(Uuid::now_v7());
+        five_card_draw.play_blinds();
+        assert_eq!(five_card_draw.pot.get_call_amount(), 2);
+        assert_eq!(five_card_draw.pot.get_total_stake(), 3);
     }
 
     #[test]
     fn deal_initial_cards() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -523,6 +674,8 @@ mod tests {
         ];
         five_card_draw.players = players;
         five_card_draw.deal_initial_cards().unwrap();
+        // five card draw has no community cards
+        assert!(five_card_draw.community_cards().is_empty());
         let mut cards = Vec::new();
         for mut player in five_card_draw.players {
             assert_eq!(player.peek_at_cards().len(), 5);
@@ -533,11 +686,14 @@ mod tests {
             }
             cards.extend(temp_cards);
         }
+        // face down cards are dealt without revealing them
+        assert_eq!(five_card_draw.input.events().len(), 15);
+        assert!(five_card_draw.input.events().iter().all(|event| matches!(event, GameEvent::CardDealt { card: None, .. })));
     }
 
     #[test]
     fn play_phase_one_check_only() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -573,7 +729,7 @@ mod tests {
 
     #[test]
     fn play_phase_one_with_raises() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -612,9 +768,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn play_phase_one_max_raises_per_round() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.set_max_raises_per_round(Some(4));
+        five_card_draw.players = (0..3).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Raise,
+            ActionOption::Raise,
+            ActionOption::Raise,
+            ActionOption::Raise,
+            ActionOption::Call,
+            ActionOption::Call
+        ]);
+        five_card_draw.input.set_raise_amounts(vec![2, 2, 2, 2]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.play_phase_one();
+
+        // the fourth raise closes the raising, so the other two players can only call or fold
+        let offered_action_options = five_card_draw.input.offered_action_options();
+        assert_eq!(offered_action_options.len(), 6);
+        assert!(offered_action_options[..4].iter().all(|action_options| action_options.contains(&ActionOption::Raise)));
+        for action_options in offered_action_options[4..].iter() {
+            assert!(action_options.contains(&ActionOption::Call) && action_options.contains(&ActionOption::Fold));
+            assert!(!action_options.contains(&ActionOption::Raise));
+        }
+        assert_eq!(five_card_draw.pot.get_call_amount(), 10);
+        for player in five_card_draw.players.iter() {
+            assert_eq!(player.balance(), 990);
+        }
+        five_card_draw.input.assert_all_actions_consumed();
+    }
+
     #[test]
     fn play_phase_one_with_folds() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -651,17 +840,222 @@ mod tests {
     }
 
     #[test]
-    fn play_all_folds_auto_win() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
-        let initial_balance = 1000;
-        let players = vec![
-            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
-            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
-            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+    fn folded_flag_agrees_with_pot_history() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
         ];
-        five_card_draw.players = players;
-
-        five_card_draw.input.set_player_names(vec!["p1".to_string(), "p2".to_string(), "p3".to_string()]);
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Fold, // player 0 folds
+            ActionOption::Check,
+            ActionOption::Call,
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.play_phase_one();
+
+        assert!(five_card_draw.players[0].has_folded());
+        for player in five_card_draw.players.iter() {
+            assert_eq!(player.has_folded(), five_card_draw.pot.player_has_folded(&player.account_id()));
+        }
+    }
+
+    #[test]
+    fn play_phase_one_with_undo() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Raise, // player 1 raises by mistake
+            ActionOption::Check, // player 1 checks instead
+            ActionOption::Call
+        ]);
+        five_card_draw.input.set_raise_amounts(vec![
+            10
+        ]);
+        five_card_draw.input.set_undo_selections(vec![
+            false,
+            true, // player 1 takes back their raise before player 2 acts
+            false,
+            false
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.play_phase_one();
+        five_card_draw.input.assert_all_actions_consumed();
+
+        assert_eq!(five_card_draw.pot.get_call_amount(), 2);
+        assert_eq!(five_card_draw.pot.get_total_stake(), 6);
+        // the client is told that the raise was taken back, and that its chips were given back
+        let undoing_player_id = five_card_draw.players[1].account_id();
+        let events = five_card_draw.input.events();
+        let undone_index = events.iter().position(|event| matches!(event,
+            GameEvent::TurnUndone { player_id, action: Action::Raise(_), phase: 1 } if *player_id == undoing_player_id
+        )).expect("Expected the undone raise to be reported");
+        assert!(matches!(events[undone_index + 1], GameEvent::Refund { player_id, amount: 10 } if player_id == undoing_player_id));
+        for player in five_card_draw.players.into_iter() {
+            assert_eq!(player.balance(), initial_balance-2);
+        }
+    }
+
+    #[test]
+    fn refund_stakes_reports_refunds() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.play_blinds();
+
+        refund_stakes(&mut five_card_draw.players, &five_card_draw.pot, &mut five_card_draw.input);
+        for player in five_card_draw.players.iter() {
+            assert_eq!(player.balance(), 1000);
+        }
+        // only the blinds had anything to give back
+        let refunds: Vec<usize> = five_card_draw.input.events().iter().filter_map(|event| match event {
+            GameEvent::Refund { amount, .. } => Some(*amount),
+            _ => None,
+        }).collect();
+        assert_eq!(refunds, vec![1, 2]);
+    }
+
+    #[test]
+    fn play_phase_two_opening_bet() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Check,
+            ActionOption::Call,
+            // nobody has bet in the second phase yet, so the first player opens with a bet
+            ActionOption::Bet,
+            ActionOption::Call,
+            ActionOption::Call
+        ]);
+        five_card_draw.input.set_bet_amounts(vec![
+            10
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.play_phase_one();
+        five_card_draw.play_bet_phase(3);
+        five_card_draw.input.assert_all_actions_consumed();
+
+        assert_eq!(five_card_draw.pot.get_call_amount(), 12);
+        assert_eq!(five_card_draw.pot.get_total_stake(), 36);
+        for player in five_card_draw.players.into_iter() {
+            assert_eq!(player.balance(), initial_balance-12);
+        }
+    }
+
+    #[test]
+    fn play_chip_size_snaps_bets_and_raises() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 10, 5, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.set_chip_size(5);
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Raise,
+            ActionOption::Call,
+            ActionOption::Call,
+            ActionOption::Bet,
+            ActionOption::Call,
+            ActionOption::Call
+        ]);
+        // a raise of 7 is snapped down to one chip, and a bet of 13 is snapped up to three chips
+        five_card_draw.input.set_raise_amounts(vec![7]);
+        five_card_draw.input.set_bet_amounts(vec![13]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.play_phase_one();
+        assert_eq!(five_card_draw.pot.get_call_amount(), 15);
+        five_card_draw.play_bet_phase(3);
+        five_card_draw.input.assert_all_actions_consumed();
+
+        assert_eq!(five_card_draw.pot.get_call_amount(), 30);
+        for player in five_card_draw.players.into_iter() {
+            assert_eq!(player.balance(), initial_balance-30);
+        }
+    }
+
+    #[test]
+    fn play_blinds_rounded_up_to_chip_size() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.set_chip_size(5);
+        five_card_draw.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.play_blinds();
+        assert_eq!(five_card_draw.pot.get_call_amount(), 5);
+        assert_eq!(five_card_draw.pot.get_total_stake(), 10);
+    }
+
+    #[test]
+    fn play_phase_one_sitting_out_player_is_not_prompted() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let mut players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        players[0].set_sit_out(true);
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            // player 0 is sitting out, so they are never asked for an action
+            ActionOption::Check,
+            ActionOption::Call,
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.play_phase_one();
+
+        let sitting_out_player_id = five_card_draw.players.get(0).unwrap().account_id();
+        assert!(five_card_draw.pot.player_has_folded(&sitting_out_player_id));
+        assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-1); // posted the small blind, then folded
+        assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-2);
+        assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance-2);
+    }
+
+    #[test]
+    fn play_all_folds_auto_win() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_player_names(vec!["p1".to_string(), "p2".to_string(), "p3".to_string()]);
         five_card_draw.input.set_game_variation(crate::game_type::GameType::FiveCardDraw);
         five_card_draw.input.set_action_option_selections(vec![
             ActionOption::Fold,
@@ -678,21 +1072,49 @@ mod tests {
         five_card_draw.play_blinds();
         five_card_draw.deal_initial_cards().unwrap();
         five_card_draw.play_phase_one();
-        five_card_draw.play_draw_phase();
-        five_card_draw.play_phase_two();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.play_bet_phase(3);
         assert_eq!(five_card_draw.pot.get_call_amount(), 2);
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-1); // small blind and fold
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-2); // big blind and fold
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance); // should not have the opportunity to raise due to auto-winning
-        five_card_draw.showdown();
+        five_card_draw.showdown().unwrap();
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-1); // small blind and fold
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-2); // big blind and fold
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance+3); // automatically wins due to other players folding, gets 3$
     }
 
+    #[tokio::test]
+    async fn play_round_with_bots() {
+        let mut five_card_draw = FiveCardDraw::<BotInput<TestInput>>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new_bot(Uuid::now_v7(), "bot 1".to_string(), initial_balance),
+            Player::new_bot(Uuid::now_v7(), "bot 2".to_string(), initial_balance)
+        ];
+        // the bots are the blinds, and the person folds as soon as it is their turn,
+        // so the bots play out the rest of the round on their own
+        five_card_draw.input.input().set_action_option_selections(vec![ActionOption::Fold]);
+
+        let players = five_card_draw.play_round(players).await.unwrap();
+        assert!(players[0].has_folded());
+        for player in players.iter() {
+            assert_eq!(player.has_folded(), five_card_draw.pot.player_has_folded(&player.account_id()));
+        }
+        assert_eq!(players.len(), 3);
+        assert_eq!(players[0].balance(), initial_balance);
+        assert!(players.iter().all(|player| player.peek_at_cards().is_empty()));
+
+        // the person folded last round, but they are asked for an action again in the next round
+        five_card_draw.input.input().set_action_option_selections(vec![ActionOption::Fold]);
+        five_card_draw.play_round(players).await.unwrap();
+        five_card_draw.input.input().assert_all_actions_consumed();
+    }
+
     #[test]
     fn play_full_game_auto_win() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -731,13 +1153,13 @@ mod tests {
         five_card_draw.play_blinds();
         five_card_draw.deal_initial_cards().unwrap();
         five_card_draw.play_phase_one();
-        five_card_draw.play_draw_phase();
-        five_card_draw.play_phase_two();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.play_bet_phase(3);
         assert_eq!(five_card_draw.pot.get_call_amount(), 400);
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-400); // small blind, call to 2, call to 100, raise to 200, raise to 400, auto-wins
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-300); // big blind, call to 100, raise to 300, and fold
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance-100); // raise to 100, and fold
-        five_card_draw.showdown();
+        five_card_draw.showdown().unwrap();
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance+400);
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-300);
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance-100);
@@ -745,7 +1167,7 @@ mod tests {
 
     #[test]
     fn play_draw_phase_draw_various_amounts_of_cards() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -783,7 +1205,9 @@ mod tests {
         }
 
         five_card_draw.play_phase_one();
-        five_card_draw.play_draw_phase();
+        // the third player's replacements are the next 5 cards in the deck, their discards go to the bottom
+        let replacement_cards: Vec<Card> = five_card_draw.deck.peek(5).to_vec();
+        five_card_draw.play_draw_phase(2).unwrap();
 
         assert_eq!(five_card_draw.pot.get_call_amount(), 2);
         assert_eq!(five_card_draw.dealer_position, 0);
@@ -799,19 +1223,333 @@ mod tests {
             assert_eq!(*card, initial_player_cards.get(1).unwrap().get(card_index).unwrap());
         }
         for (card_index, card) in five_card_draw.players.get(2).unwrap().peek_at_cards().iter().enumerate() {
-            if *card != initial_player_cards.get(2).unwrap().get(card_index).unwrap() {
-                break;
+            assert_eq!(*card, replacement_cards.get(card_index).unwrap());
+            assert!(!initial_player_cards.get(2).unwrap().contains(card));
+        }
+    }
+
+    #[test]
+    fn play_draw_phase_max_draw_reprompts() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new_with_max_draw(1000, 2, 1, 3, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            // phase 1
+            ActionOption::Call,
+            ActionOption::Check,
+            // draw phase
+            ActionOption::Replace,
+            ActionOption::Check
+        ]);
+        five_card_draw.input.set_card_replace_selections(vec![
+            vec![0, 1, 2, 3, 4], // attempt to replace all cards, which is more than the cap of 3
+            vec![0, 1, 2] // after being asked again, replace only 3 cards
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.deal_initial_cards().unwrap();
+        let kept_cards: Vec<Card> = five_card_draw.players[0].peek_at_cards()[3..].iter().map(|&card| card.clone()).collect();
+
+        five_card_draw.play_phase_one();
+        five_card_draw.play_draw_phase(2).unwrap();
+
+        let cards: Vec<Card> = five_card_draw.players[0].peek_at_cards().iter().map(|&card| card.clone()).collect();
+        assert_eq!(cards.len(), 5);
+        for kept_card in kept_cards.iter() {
+            assert!(cards.contains(kept_card));
+        }
+    }
+
+    #[test]
+    fn play_draw_phase_rejects_cards_not_held() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            // phase 1
+            ActionOption::Call,
+            ActionOption::Check,
+            // draw phase
+            ActionOption::Replace,
+            ActionOption::Check
+        ]);
+        five_card_draw.input.set_card_replace_selections(vec![
+            vec![0, 0], // the same card twice, which would be one card more than the player holds
+            vec![0] // after being asked again, replace only the first card
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.deal_initial_cards().unwrap();
+        let kept_cards: Vec<Card> = five_card_draw.players[0].peek_at_cards()[1..].iter().map(|&card| card.clone()).collect();
+
+        five_card_draw.play_phase_one();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.input.assert_all_actions_consumed();
+
+        let cards: Vec<Card> = five_card_draw.players[0].peek_at_cards().iter().map(|&card| card.clone()).collect();
+        assert_eq!(cards.len(), 5);
+        for kept_card in kept_cards.iter() {
+            assert!(cards.contains(kept_card));
+        }
+    }
+
+    #[test]
+    fn play_draw_phase_stops_asking_after_invalid_choices() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new_with_max_draw(1000, 2, 1, 3, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            // phase 1
+            ActionOption::Call,
+            ActionOption::Check,
+            // draw phase
+            ActionOption::Replace,
+            ActionOption::Check
+        ]);
+        // too many cards, then the same card twice, after which the player isn't asked again
+        five_card_draw.input.set_card_replace_selections(vec![vec![0, 1, 2, 3, 4]; MAX_REPLACE_CARD_REQUESTS - 1]
+            .into_iter().chain([vec![0, 0]]).collect());
+
+        five_card_draw.play_blinds();
+        five_card_draw.deal_initial_cards().unwrap();
+        let cards_before: Vec<Card> = five_card_draw.players[0].peek_at_cards().iter().map(|&card| card.clone()).collect();
+
+        five_card_draw.play_phase_one();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.input.assert_all_actions_consumed();
+
+        // none of the player's cards were replaced
+        let cards: Vec<Card> = five_card_draw.players[0].peek_at_cards().iter().map(|&card| card.clone()).collect();
+        assert_eq!(cards, cards_before);
+    }
+
+    #[test]
+    #[should_panic]
+    fn play_draw_phase_max_draw_rejects_too_many() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new_with_max_draw(1000, 2, 1, 3, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+
+        five_card_draw.input.set_action_option_selections(vec![
+            // phase 1
+            ActionOption::Call,
+            ActionOption::Check,
+            // draw phase
+            ActionOption::Replace,
+            ActionOption::Check
+        ]);
+        five_card_draw.input.set_card_replace_selections(vec![
+            vec![0, 1, 2, 3, 4] // attempt to replace all cards, which is more than the cap of 3
+            // the player is asked again, but there are no more selections, so this should panic
+        ]);
+
+        five_card_draw.play_blinds();
+        five_card_draw.deal_initial_cards().unwrap();
+        five_card_draw.play_phase_one();
+        five_card_draw.play_draw_phase(2).unwrap();
+    }
+
+    #[test]
+    fn showdown_reveals_last_aggressor_first() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+        let player_ids: Vec<Uuid> = five_card_draw.players.iter().map(|player| player.account_id()).collect();
+
+        // player 1 is the last aggressor, so they show first even though the current player is player 0
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Ante(1), 0, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[1], Action::Ante(2), 0, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[2], Action::Call, 1, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Call, 1, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[1], Action::Bet(10), 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[2], Action::Call, 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Call, 3, Vec::new());
+        assert_eq!(five_card_draw.current_player_index, 0);
+
+        for (player, cards) in five_card_draw.players.iter_mut().zip(["Qh Qd 9c 8s 6h", "As Ad Ac 2h 3d", "Kh Jd 4c 5s 7h"]) {
+            for card in cards.split_whitespace() {
+                player.obtain_card(card.parse().unwrap());
+            }
+        }
+        // player 2 is asked first (and shows), then player 0 is asked (and mucks),
+        // if the reveal started at player 0, they would have shown without being asked
+        five_card_draw.input.set_show_or_muck_selections(vec![true, false]);
+
+        five_card_draw.showdown().unwrap();
+        assert!(five_card_draw.players[1].peek_at_cards().iter().all(|card| card.is_face_up()));
+        assert!(five_card_draw.players[2].peek_at_cards().iter().all(|card| card.is_face_up()));
+        assert!(five_card_draw.players[0].peek_at_cards().iter().all(|card| !card.is_face_up()));
+    }
+
+    #[test]
+    fn showdown_mucks_recorded_in_pot() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.players = (0..3).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        let player_ids: Vec<Uuid> = five_card_draw.players.iter().map(|player| player.account_id()).collect();
+        five_card_draw.pot.add_turn(&player_ids[1], Action::Bet(10), 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[2], Action::Call, 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Call, 3, Vec::new());
+        for (player, cards) in five_card_draw.players.iter_mut().zip(["Qh Qd 9c 8s 6h", "As Ad Ac 2h 3d", "Kh Jd 4c 5s 7h"]) {
+            for card in cards.split_whitespace() {
+                player.obtain_card(card.parse().unwrap());
+            }
+        }
+        // player 1 shows the winning hand, and both of the other players muck
+        five_card_draw.input.set_show_or_muck_selections(vec![false, false]);
+
+        five_card_draw.showdown().unwrap();
+        for mucked_index in [0, 2] {
+            assert!(five_card_draw.players[mucked_index].peek_at_cards().iter().all(|card| !card.is_face_up()));
+            assert!(five_card_draw.pot.get_phase_history(3).iter()
+                .any(|(player_id, action, _, hand)| *player_id == player_ids[mucked_index] && *action == Action::Muck && hand.is_empty()));
+            // their stake stayed in the pot, and was lost to the winner
+            assert!(!five_card_draw.pot.player_has_folded(&player_ids[mucked_index]));
+            assert_eq!(five_card_draw.players[mucked_index].balance(), 1000);
+        }
+        assert_eq!(five_card_draw.players[1].balance(), 1030);
+        five_card_draw.pot.assert_consistency();
+    }
+
+    #[test]
+    fn showdown_with_wild_card() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.set_wild_card("2c".parse().unwrap());
+        five_card_draw.players = (0..2).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        let player_ids: Vec<Uuid> = five_card_draw.players.iter().map(|player| player.account_id()).collect();
+        five_card_draw.pot.add_turn(&player_ids[1], Action::Bet(10), 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Call, 3, Vec::new());
+        // the wild two makes three kings, which beats three queens
+        for (player, cards) in five_card_draw.players.iter_mut().zip(["Kh Kd 2c 7s 9d", "Qh Qd Qs 3c 4d"]) {
+            for card in cards.split_whitespace() {
+                player.obtain_card(card.parse().unwrap());
             }
-            if card_index == 4 {
-                // last card and they have all matched so far, something is wrong or we got insanely unlucky...
-                panic!();
+        }
+        five_card_draw.input.set_show_or_muck_selections(vec![true, true]);
+
+        five_card_draw.showdown().unwrap();
+        assert_eq!(five_card_draw.players[0].balance(), 1020);
+        assert_eq!(five_card_draw.players[1].balance(), 1000);
+    }
+
+    #[test]
+    fn showdown_many_players_with_tied_hands() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let hands = [
+            "2c 3d 4h 5s 7c", "2d 3h 4s 6c 8d", "2h 3s 5c 6d 9h", "2s 4c 5d 7h Th", "3c 4d 6h 7s Jc",
+            "5h 6s 8c 9s Qc", "7d 8h Td Jd Kc",
+            // the last two players tie with the same ranks in different suits
+            "Ac Ks Qh Js 9c", "Ad Kd Qs Jh 9d",
+        ];
+        five_card_draw.players = hands.iter().map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        let player_ids: Vec<Uuid> = five_card_draw.players.iter().map(|player| player.account_id()).collect();
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Bet(10), 1, Vec::new());
+        for player_id in player_ids[1..].iter() {
+            five_card_draw.pot.add_turn(player_id, Action::Call, 1, Vec::new());
+        }
+        for (player, cards) in five_card_draw.players.iter_mut().zip(hands) {
+            for card in cards.split_whitespace() {
+                player.obtain_card(card.parse().unwrap());
             }
         }
+
+        five_card_draw.showdown().unwrap();
+        let balances: Vec<usize> = five_card_draw.players.iter().map(|player| player.balance()).collect();
+        assert_eq!(balances, vec![1000, 1000, 1000, 1000, 1000, 1000, 1000, 1045, 1045]);
+    }
+
+    #[test]
+    fn winning_qualifying_pot_makes_next_round_a_kill_pot() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.set_kill(Some(Kill { kill_type: KillType::FullKill, qualifying_pot: 30 })).unwrap();
+        let player_ids: Vec<Uuid> = five_card_draw.players.iter().map(|player| player.account_id()).collect();
+
+        // player 1 wins a pot of 30 by themselves
+        five_card_draw.play_blinds();
+        five_card_draw.pot.add_turn(&player_ids[2], Action::Call, 1, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Call, 1, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[1], Action::Bet(10), 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[2], Action::Call, 3, Vec::new());
+        five_card_draw.pot.add_turn(&player_ids[0], Action::Call, 3, Vec::new());
+        five_card_draw.players[0].bet(9).unwrap();
+        five_card_draw.players[1].bet(8).unwrap();
+        five_card_draw.players[2].bet(10).unwrap();
+        for (player, cards) in five_card_draw.players.iter_mut().zip(["Qh Qd 9c 8s 6h", "As Ad Ac 2h 3d", "Kh Jd 4c 5s 7h"]) {
+            for card in cards.split_whitespace() {
+                player.obtain_card(card.parse().unwrap());
+            }
+        }
+        five_card_draw.input.set_show_or_muck_selections(vec![false, false]);
+        five_card_draw.showdown().unwrap();
+        assert_eq!(five_card_draw.killer, Some(player_ids[1]));
+        assert_eq!(five_card_draw.players[1].balance(), 1020);
+
+        // in the next round, the killer posts the kill blind
+        for player in five_card_draw.players.iter_mut() {
+            player.return_cards();
+        }
+        five_card_draw.pot.clear(&five_card_draw.players.iter().collect());
+        five_card_draw.increment_dealer_position();
+        five_card_draw.current_player_index = five_card_draw.dealer_position;
+        five_card_draw.play_blinds();
+        assert_eq!(five_card_draw.pot.get_call_amount(), 4);
+        // the killer is the small blind this round, so their kill blind replaces it
+        assert_eq!(five_card_draw.pot.get_player_stake(&player_ids[1]), 4);
+        assert_eq!(five_card_draw.pot.get_player_stake(&player_ids[2]), 2);
+        assert_eq!(five_card_draw.pot.get_player_stake(&player_ids[0]), 0);
+        assert_eq!(five_card_draw.players[1].balance(), 1020 - 4);
+    }
+
+    #[test]
+    fn winning_small_pot_is_not_a_kill() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        five_card_draw.players = players;
+        five_card_draw.set_kill(Some(Kill { kill_type: KillType::FullKill, qualifying_pot: 30 })).unwrap();
+        five_card_draw.killer = Some(five_card_draw.players[0].account_id());
+
+        five_card_draw.play_blinds();
+        for (player, cards) in five_card_draw.players.iter_mut().zip(["Qh Qd 9c 8s 6h", "As Ad Ac 2h 3d"]) {
+            for card in cards.split_whitespace() {
+                player.obtain_card(card.parse().unwrap());
+            }
+        }
+        five_card_draw.input.set_show_or_muck_selections(vec![false]);
+        five_card_draw.showdown().unwrap();
+        assert_eq!(five_card_draw.killer, None);
     }
 
     #[test]
     fn play_full_round_all_checks_and_calls() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -843,18 +1581,18 @@ mod tests {
         five_card_draw.play_blinds();
         five_card_draw.deal_initial_cards().unwrap();
         five_card_draw.play_phase_one();
-        five_card_draw.play_draw_phase();
-        five_card_draw.play_phase_two();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.play_bet_phase(3);
         assert_eq!(five_card_draw.pot.get_call_amount(), 2);
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), initial_balance-2); // call to 2 and check the rest
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), initial_balance-2); // big blind 2 and check the rest
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), initial_balance-2); // call to 2 and check the rest
-        five_card_draw.showdown();
+        five_card_draw.showdown().unwrap();
     }
 
     #[test]
     fn play_phase_one_with_all_ins() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 100;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -891,7 +1629,7 @@ mod tests {
 
     #[test]
     fn play_phase_one_with_all_ins_not_enough_further_raise() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 100),
@@ -924,7 +1662,7 @@ mod tests {
 
     #[test]
     fn play_full_round_with_all_ins_not_enough() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 100),
@@ -952,20 +1690,20 @@ mod tests {
         five_card_draw.play_blinds();
         five_card_draw.deal_initial_cards().unwrap();
         five_card_draw.play_phase_one();
-        five_card_draw.play_draw_phase();
-        five_card_draw.play_phase_two();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.play_bet_phase(3);
         assert_eq!(five_card_draw.pot.get_call_amount(), 500);
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), 500);
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), 0);
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), 0);
-        five_card_draw.showdown();
+        five_card_draw.showdown().unwrap();
         let total_balance: usize = five_card_draw.players.iter().map(|player| player.balance()).sum();
         assert_eq!(total_balance, 1110);
     }
 
     #[test]
     fn play_full_round_with_all_ins_not_enough_further_raise() {
-        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 100),
@@ -996,14 +1734,147 @@ mod tests {
         five_card_draw.play_blinds();
         five_card_draw.deal_initial_cards().unwrap();
         five_card_draw.play_phase_one();
-        five_card_draw.play_draw_phase();
-        five_card_draw.play_phase_two();
+        five_card_draw.play_draw_phase(2).unwrap();
+        five_card_draw.play_bet_phase(3);
         assert_eq!(five_card_draw.pot.get_call_amount(), 200);
         assert_eq!(five_card_draw.players.get(0).unwrap().balance(), 800);
         assert_eq!(five_card_draw.players.get(1).unwrap().balance(), 0);
         assert_eq!(five_card_draw.players.get(2).unwrap().balance(), 0);
-        five_card_draw.showdown();
+        five_card_draw.showdown().unwrap();
         let total_balance: usize = five_card_draw.players.iter().map(|player| player.balance()).sum();
         assert_eq!(total_balance, 1110);
     }
+
+    #[test]
+    fn play_phase_one_turn_summary() {
+        let mut five_card_draw = FiveCardDraw::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        five_card_draw.players = (0..3).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        let player_ids: Vec<Uuid> = five_card_draw.players.iter().map(|player| player.account_id()).collect();
+        five_card_draw.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Check,
+            ActionOption::Call
+        ]);
+
+        five_card_draw.play_blinds();
+        assert!(five_card_draw.input.turn_summaries().is_empty());
+        five_card_draw.play_phase_one();
+
+        // the blinds are posted before the phase, so only the turns taken in the phase are summarized
+        let turn_summaries = five_card_draw.input.turn_summaries();
+        assert_eq!(turn_summaries.len(), 1);
+        let turns: Vec<(Uuid, Action)> = turn_summaries[0].iter().map(|(player_id, action, _, _)| (*player_id, action.clone())).collect();
+        assert_eq!(turns, vec![
+            (player_ids[0], Action::Call),
+            (player_ids[1], Action::Check),
+            (player_ids[2], Action::Call)
+        ]);
+        assert!(turn_summaries[0].iter().all(|(_, _, phase_num, _)| *phase_num == 1));
+        five_card_draw.input.assert_all_actions_consumed();
+    }
+
+    #[tokio::test]
+    async fn play_rounds_keeps_dealer_position() {
+        let mut five_card_draw = FiveCardDraw::<BotInput<TestInput>>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut players: Vec<Player> = (0..3)
+            .map(|bot_number| Player::new_bot(Uuid::now_v7(), format!("bot {bot_number}"), 1000))
+            .collect();
+        // the button moves on one seat each round, and wraps around to the first seat
+        for dealer_position in [1, 2, 0] {
+            players = five_card_draw.play_round(players).await.unwrap();
+            assert_eq!(five_card_draw.dealer_position, dealer_position);
+            assert_eq!(players.iter().map(|player| player.balance()).sum::<usize>(), 3000);
+        }
+    }
+
+    /// give a player cards from space separated poker notation, like "Ah Kd 2c"
+    fn give_cards(player: &mut Player, notation: &str) {
+        for card in notation.split_whitespace() {
+            player.obtain_card(card.parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn badugi_deal_initial_cards() {
+        let mut badugi = FiveCardDraw::<TestInput>::new_badugi(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        badugi.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        badugi.deal_initial_cards().unwrap();
+        for player in badugi.players.iter() {
+            assert_eq!(player.peek_at_cards().len(), 4);
+        }
+        assert_eq!(badugi.deck.remaining(), 52 - 12);
+    }
+
+    #[test]
+    fn badugi_play_draw_phase_replaces_chosen_cards() {
+        let mut badugi = FiveCardDraw::<TestInput>::new_badugi(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        badugi.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        badugi.deal_initial_cards().unwrap();
+        let initial_player_cards: Vec<Vec<Card>> = badugi.players.iter()
+            .map(|player| player.peek_at_cards().iter().map(|&card| card.clone()).collect())
+            .collect();
+
+        badugi.input.set_action_option_selections(vec![ActionOption::Replace, ActionOption::Check]);
+        badugi.input.set_card_replace_selections(vec![vec![0, 3]]);
+        badugi.play_draw_phase(2).unwrap();
+        badugi.input.assert_all_actions_consumed();
+
+        // the first player kept their middle two cards, and was dealt two replacements
+        // (which could be the cards they discarded, as discards go back into the deck first)
+        let cards = badugi.players[0].peek_at_cards();
+        assert_eq!(cards.len(), 4);
+        assert!(cards.contains(&&initial_player_cards[0][1]));
+        assert!(cards.contains(&&initial_player_cards[0][2]));
+        assert_eq!(badugi.deck.remaining(), 52 - 8);
+        // the second player kept all of their cards
+        for (card, initial_card) in badugi.players[1].peek_at_cards().iter().zip(initial_player_cards[1].iter()) {
+            assert_eq!(*card, initial_card);
+        }
+    }
+
+    #[test]
+    fn badugi_showdown_four_card_badugi_beats_three_card_badugi() {
+        let mut badugi = FiveCardDraw::<TestInput>::new_badugi(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        badugi.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        badugi.pot.clear(&badugi.players.iter().collect());
+        badugi.play_blinds();
+        // a king high badugi beats a three card hand, however low it is
+        give_cards(&mut badugi.players[0], "Ac 2d 3h 4h");
+        give_cards(&mut badugi.players[1], "Kc Qd Jh Ts");
+        badugi.input.set_show_or_muck_selections(vec![true]);
+
+        badugi.showdown().unwrap();
+        assert_eq!(badugi.players[0].balance(), 1000 - 1);
+        assert_eq!(badugi.players[1].balance(), 1000 + 1);
+    }
+
+    #[test]
+    fn badugi_showdown_split_pot_with_equal_badugis() {
+        let mut badugi = FiveCardDraw::<TestInput>::new_badugi(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        badugi.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        let player_ids: Vec<Uuid> = badugi.players.iter().map(|player| player.account_id()).collect();
+        badugi.pot.clear(&badugi.players.iter().collect());
+        badugi.play_blinds();
+        badugi.pot.add_turn(&player_ids[0], Action::Call, 1, Vec::new());
+        badugi.players[0].bet(1).unwrap();
+        give_cards(&mut badugi.players[0], "4s 3h 2d Ac");
+        give_cards(&mut badugi.players[1], "4h 3s 2c Ad");
+
+        badugi.showdown().unwrap();
+        assert_eq!(badugi.players[0].balance(), 1000);
+        assert_eq!(badugi.players[1].balance(), 1000);
+    }
 }
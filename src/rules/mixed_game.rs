@@ -0,0 +1,300 @@
+use strum::IntoEnumIterator;
+use uuid::Uuid;
+
+use crate::card::Card;
+use crate::database::db_handler::DbHandler;
+use crate::game_event::GameEvent;
+use crate::game_type::GameType;
+use crate::input::Input;
+use crate::player::Player;
+use super::badugi::Badugi;
+use super::community_draw::CommunityDraw;
+use super::five_card_draw::FiveCardDraw;
+use super::seven_card_stud::SevenCardStud;
+use super::short_deck_holdem::ShortDeckHoldem;
+use super::texas_holdem::TexasHoldem;
+use super::{Kill, KillNotSupported, Rules, RulesEnum, RoundError};
+
+/// how long each variant is played for before a mixed game moves on to the next one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RotationLength {
+    /// switch to the next variant after every round
+    Round,
+    /// switch to the next variant after an orbit, once every player has had the dealer button
+    Orbit,
+}
+
+/// Mixed Game Rules
+///
+/// A mixed game (like H.O.R.S.E.) cycles through an ordered list of variants,
+/// playing each one for a round or an orbit (see RotationLength) before moving on to the next,
+/// and going back to the first variant after the last one.
+/// Each variant keeps its own rules, and rounds are passed on to the rules of the variant currently being played,
+/// so the house rules (kill, chip size, minimum bet) are set on all of them.
+/// The dealer button and the input are the mixed game's own, and are lent to the variant for each round,
+/// so the button moves on one seat every round whichever variant is played, and every round is played through one input.
+pub struct MixedGame<I: Input> {
+    rotation: Vec<RulesEnum<I>>,
+    /// the input that players are asked for their actions through, in every variant
+    input: I,
+    /// the seat of the dealer in the last round, whichever variant it was played with
+    dealer_position: usize,
+    rotation_length: RotationLength,
+    /// the index in the rotation of the variant currently being played
+    current_variant: usize,
+    /// the number of rounds played of the current variant
+    rounds_played: usize,
+    /// the index in the rotation of the variant that the last round was played with
+    last_variant: usize,
+}
+
+impl<I: Input> MixedGame<I> {
+    /// create a mixed game that plays through the game types in order, with the same raise limit, minimum bet and small blind,
+    /// returns Err(message) if there are no game types to play
+    pub fn new_rotation(game_types: Vec<GameType>, rotation_length: RotationLength, raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> Result<MixedGame<I>, String> {
+        if game_types.is_empty() {
+            return Err("A mixed game needs at least one game type to play".to_string());
+        }
+        return Ok(MixedGame {
+            rotation: game_types.into_iter()
+                .map(|game_type| RulesEnum::new(game_type, raise_limit, minimum_bet, small_blind_amount, db_handler.clone(), game_id))
+                .collect(),
+            input: I::new(),
+            dealer_position: 0,
+            rotation_length,
+            current_variant: 0,
+            rounds_played: 0,
+            last_variant: 0,
+        });
+    }
+
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return &self.input;
+    }
+
+    /// the most players that can be dealt into a round of every variant in the rotation
+    pub fn max_players(&self) -> usize {
+        return self.rotation.iter().map(|rules| rules.max_players()).min().expect("Expected at least one variant in the rotation");
+    }
+
+    /// the game type that the next round will be played with
+    pub fn current_game_type(&self) -> GameType {
+        return self.rotation[self.current_variant].to_game_type();
+    }
+
+    /// count a round of the current variant, and move on to the next variant once it has been played for long enough
+    fn advance_rotation(&mut self, player_count: usize) {
+        self.rounds_played += 1;
+        let rounds_per_variant = match self.rotation_length {
+            RotationLength::Round => 1,
+            RotationLength::Orbit => player_count,
+        };
+        if self.rounds_played >= rounds_per_variant {
+            self.current_variant = (self.current_variant + 1) % self.rotation.len();
+            self.rounds_played = 0;
+        }
+    }
+}
+
+impl<I: Input> Rules for MixedGame<I> {
+    /// the most players that any variant can deal, the rotation can only be played with as many players
+    /// as the variant that deals the fewest, which play_round checks (see MixedGame::max_players)
+    const MAX_PLAYERS: usize = {
+        let variant_max_players = [
+            FiveCardDraw::<I>::MAX_PLAYERS,
+            SevenCardStud::<I>::MAX_PLAYERS,
+            TexasHoldem::<I>::MAX_PLAYERS,
+            CommunityDraw::<I>::MAX_PLAYERS,
+            Badugi::<I>::MAX_PLAYERS,
+            ShortDeckHoldem::<I>::MAX_PLAYERS,
+        ];
+        let mut max_players = 0;
+        let mut variant = 0;
+        while variant < variant_max_players.len() {
+            if variant_max_players[variant] > max_players {
+                max_players = variant_max_players[variant];
+            }
+            variant += 1;
+        }
+        max_players
+    };
+
+    /// create a mixed game of every game type, switching variant every round (see MixedGame::new_rotation)
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> MixedGame<I> {
+        return MixedGame::new_rotation(GameType::iter().collect(), RotationLength::Round, raise_limit, minimum_bet, small_blind_amount, db_handler, game_id)
+            .expect("Expected there to be at least one game type");
+    }
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        let player_count = players.len();
+        if player_count > self.max_players() {
+            // a later variant in the rotation couldn't deal this many players
+            return Err((RoundError::TooManyPlayers, players));
+        }
+        self.last_variant = self.current_variant;
+        let rules = &mut self.rotation[self.current_variant];
+        // lend the variant the button and the input for this round
+        rules.set_dealer_position(self.dealer_position);
+        std::mem::swap(&mut self.input, rules.input_mut());
+        let result = rules.play_round(players).await;
+        std::mem::swap(&mut self.input, rules.input_mut());
+        self.dealer_position = rules.dealer_position();
+        match &result {
+            // the round wasn't played, so the same variant is played next time
+            Err((RoundError::TooFewPlayers | RoundError::TooManyPlayers, _)) => {},
+            _ => self.advance_rotation(player_count),
+        }
+        return result;
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.input.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Mixed Game: the variant changes each round or orbit, cycling through a list of variants";
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        // kills are played in every variant of the rotation or in none of them
+        let result: Result<Vec<()>, KillNotSupported> = self.rotation.iter_mut().map(|rules| rules.set_kill(kill)).collect();
+        if result.is_err() {
+            self.rotation.iter_mut().for_each(|rules| rules.set_kill(None).unwrap());
+        }
+        return result.map(|_| ());
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.rotation[self.last_variant].export_last_round_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.rotation[self.last_variant].last_round_events();
+    }
+
+    fn community_cards(&self) -> &[Card] {
+        return self.rotation[self.last_variant].community_cards();
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        for rules in self.rotation.iter_mut() {
+            rules.set_round_number(round_number);
+        }
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        for rules in self.rotation.iter_mut() {
+            rules.set_minimum_bet(amount);
+        }
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        for rules in self.rotation.iter_mut() {
+            rules.set_chip_size(chip_size);
+        }
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        for rules in self.rotation.iter_mut() {
+            rules.set_max_raises_per_round(max_raises);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::bot_input::BotInput;
+    use crate::input::test_input::TestInput;
+    use crate::rules::KillType;
+
+    use super::*;
+
+    fn bots(count: usize) -> Vec<Player> {
+        return (0..count).map(|bot_number| Player::new_bot(Uuid::now_v7(), format!("bot {bot_number}"), 1000)).collect();
+    }
+
+    #[tokio::test]
+    async fn rotates_variant_each_round() {
+        let game_types = vec![GameType::TexasHoldem, GameType::FiveCardDraw, GameType::SevenCardStud];
+        let mut mixed_game = MixedGame::<BotInput<TestInput>>::new_rotation(game_types, RotationLength::Round, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).unwrap();
+        let mut players = bots(3);
+
+        assert!(matches!(mixed_game.current_game_type(), GameType::TexasHoldem));
+        players = mixed_game.play_round(players).await.unwrap();
+        assert!(matches!(mixed_game.current_game_type(), GameType::FiveCardDraw));
+        players = mixed_game.play_round(players).await.unwrap();
+        assert!(matches!(mixed_game.current_game_type(), GameType::SevenCardStud));
+        players = mixed_game.play_round(players).await.unwrap();
+        // back to the start of the rotation
+        assert!(matches!(mixed_game.current_game_type(), GameType::TexasHoldem));
+        assert_eq!(players.iter().map(|player| player.balance()).sum::<usize>(), 3000);
+    }
+
+    #[tokio::test]
+    async fn rotates_variant_each_orbit() {
+        let game_types = vec![GameType::TexasHoldem, GameType::Badugi];
+        let mut mixed_game = MixedGame::<BotInput<TestInput>>::new_rotation(game_types, RotationLength::Orbit, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).unwrap();
+        let mut players = bots(2);
+
+        players = mixed_game.play_round(players).await.unwrap();
+        assert!(matches!(mixed_game.current_game_type(), GameType::TexasHoldem));
+        players = mixed_game.play_round(players).await.unwrap();
+        assert!(matches!(mixed_game.current_game_type(), GameType::Badugi));
+
+        // a round that can't be played doesn't count towards the orbit
+        let (_, players) = mixed_game.play_round(players.drain(..1).collect()).await.unwrap_err();
+        assert_eq!(players.len(), 1);
+        assert!(matches!(mixed_game.current_game_type(), GameType::Badugi));
+    }
+
+    #[tokio::test]
+    async fn shares_dealer_position_and_input() {
+        let game_types = vec![GameType::TexasHoldem, GameType::FiveCardDraw, GameType::SevenCardStud];
+        let mut mixed_game = MixedGame::<BotInput<TestInput>>::new_rotation(game_types, RotationLength::Round, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).unwrap();
+        let mut players = bots(3);
+
+        let mut event_counts = Vec::new();
+        for expected_dealer_position in [1, 2, 0] {
+            players = mixed_game.play_round(players).await.unwrap();
+            // the button moves on one seat every round, although each round is played with a different variant
+            assert_eq!(mixed_game.dealer_position, expected_dealer_position);
+            event_counts.push(mixed_game.input.input().events().len());
+        }
+        // the events of every round go to the same input
+        assert!(event_counts.windows(2).all(|counts| counts[0] < counts[1]), "event counts {:?}", event_counts);
+    }
+
+    #[tokio::test]
+    async fn max_players_from_rotation() {
+        let game_types = vec![GameType::TexasHoldem, GameType::Badugi];
+        let mut mixed_game = MixedGame::<BotInput<TestInput>>::new_rotation(game_types, RotationLength::Round, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).unwrap();
+        // more players than seven card stud deals, but no more than badugi does
+        assert_eq!(mixed_game.max_players(), Badugi::<TestInput>::MAX_PLAYERS);
+        let players = mixed_game.play_round(bots(Badugi::<TestInput>::MAX_PLAYERS)).await.unwrap();
+        assert!(matches!(mixed_game.current_game_type(), GameType::Badugi));
+
+        // too many players for badugi can't play the Texas Hold'em round either
+        let (round_error, _) = mixed_game.play_round([players, bots(1)].concat()).await.unwrap_err();
+        assert!(matches!(round_error, RoundError::TooManyPlayers));
+        assert!(matches!(mixed_game.current_game_type(), GameType::Badugi));
+        assert_eq!(MixedGame::<TestInput>::MAX_PLAYERS, TexasHoldem::<TestInput>::MAX_PLAYERS);
+    }
+
+    #[test]
+    fn set_kill_needs_blinds_in_every_variant() {
+        let kill = Kill { kill_type: KillType::HalfKill, qualifying_pot: 20 };
+        let game_types = vec![GameType::TexasHoldem, GameType::FiveCardDraw];
+        let mut mixed_game = MixedGame::<TestInput>::new_rotation(game_types, RotationLength::Round, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).unwrap();
+        assert_eq!(mixed_game.set_kill(Some(kill)), Ok(()));
+
+        let game_types = vec![GameType::TexasHoldem, GameType::SevenCardStud];
+        let mut mixed_game = MixedGame::<TestInput>::new_rotation(game_types, RotationLength::Round, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).unwrap();
+        assert_eq!(mixed_game.set_kill(Some(kill)), Err(KillNotSupported));
+    }
+
+    #[test]
+    fn empty_rotation() {
+        assert!(MixedGame::<TestInput>::new_rotation(Vec::new(), RotationLength::Round, 1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7()).is_err());
+    }
+}
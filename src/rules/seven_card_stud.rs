@@ -2,16 +2,18 @@ use uuid::Uuid;
 
 use crate::card::Card;
 use crate::database::db_handler::DbHandler;
-use crate::deck::Deck;
-use crate::hand_rank::Hand;
+use crate::deck::{Deck, DeckError, DECK_SIZE};
+use crate::hand_rank::{Hand, HandRank};
 use crate::input::Input;
 use crate::player::Player;
-use crate::pot::Pot;
-use super::Rules;
-use crate::action_option::ActionOption;
-use crate::action::Action;
+use crate::pot::{Pot, PotError};
+use super::betting::{display_pot, post_blind, proportional_small_blind, refund_stakes, BettingRound};
+use super::showdown::{award_winnings, rank_hands, return_cards_to_deck, reveal_hands, seat_order, winning_order};
+use super::{Kill, KillNotSupported, Rules, RoundError};
+use crate::game_event::GameEvent;
+use crate::game_type::GameType;
 
-use std::cmp::min;
+use std::cmp::Ordering;
 
 /// Seven Card Stud Rules
 /// 
@@ -27,15 +29,47 @@ pub struct SevenCardStud<I: Input> {
     dealer_position: usize,
     current_player_index: usize,
     raise_limit: u32,
+    /// the most raises in each betting round, None for no cap (see Rules::set_max_raises_per_round)
+    max_raises_per_round: Option<u32>,
+    /// the smallest opening bet
+    minimum_bet: u32,
+    /// the forced bet of the player showing the lowest card, which starts the first betting phase
     bring_in: u32,
+    /// the (bring in, minimum bet) that the rules were created with, which the bring in stays in proportion to
+    starting_bets: (u32, u32),
     input: I,
     pot: Pot,
-    game_id: Uuid
+    game_id: Uuid,
+    /// the number of this round within the game, which is saved with the round
+    round_number: u32
 }
 
 impl<I: Input> SevenCardStud<I> {
-    fn number_of_players_all_in(&self) -> usize {
-        return self.players.iter().filter(|player| player.balance() == 0).count();
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return &self.input;
+    }
+
+    /// the input, to lend another one for a round (see MixedGame, which shares one input between its variants)
+    pub(super) fn input_mut(&mut self) -> &mut I {
+        return &mut self.input;
+    }
+
+    /// the seat of the dealer in the last round, the button moves on one seat at the start of each round
+    pub(super) fn dealer_position(&self) -> usize {
+        return self.dealer_position;
+    }
+
+    /// move the dealer button to a seat, to carry it on from a round played with other rules (see MixedGame)
+    pub(super) fn set_dealer_position(&mut self, dealer_position: usize) {
+        self.dealer_position = dealer_position;
+    }
+
+    /// set the bring in, which is usually less than the minimum bet (which it starts out equal to),
+    /// as the minimum bet goes up the bring in keeps the same proportion of it (see Rules::set_minimum_bet)
+    pub fn set_bring_in(&mut self, amount: u32) {
+        self.bring_in = amount;
+        self.starting_bets = (amount, self.minimum_bet);
     }
 
     fn increment_dealer_position(&mut self) {
@@ -67,19 +101,16 @@ impl<I: Input> SevenCardStud<I> {
                 player_index = 0;
             }
             let player = self.players.get(player_index).unwrap();
-            let player_up_cards: Vec<&Card> = player.peek_at_cards().iter()
-                .filter(|card| card.is_face_up())
-                .map(|card| *card)
-                .collect();
+            let player_up_cards: Vec<&Card> = player.peek_at_up_cards();
             assert_eq!(player_up_cards.len(), 1);
             let player_up_card = player_up_cards[0];
             match bring_in_player_card {
                 Some(card) => {
+                    // cards of equal rank are ordered by suit, so the lowest suit pays the bring in
                     if player_up_card < card {
                         bring_in_player_card = Some(player_up_card);
                         bring_in_player_index = player_index;
                     }
-                    // if the cards are equal in rank, the previously found player has precedence as they are closer to the dealer
                 },
                 None => {
                     bring_in_player_card = Some(player_up_card);
@@ -88,9 +119,9 @@ impl<I: Input> SevenCardStud<I> {
             }
         }
         let bring_in_player_index = bring_in_player_index;
+        // the bring in is posted like a blind, so a player who can't cover it goes all in for what they have left
         let bring_in_player = self.players.get_mut(bring_in_player_index).unwrap();
-        self.pot.add_turn(&bring_in_player.account_id(), Action::Ante(self.bring_in as usize), 0, bring_in_player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-        bring_in_player.bet(self.bring_in as usize).unwrap();
+        post_blind(bring_in_player, &mut self.pot, &mut self.input, self.bring_in as usize);
         self.current_player_index = bring_in_player_index;
         self.increment_player_index();
     }
@@ -99,27 +130,30 @@ impl<I: Input> SevenCardStud<I> {
     /// and returns the index of that player
     fn find_player_with_best_up_card_hand(&self) -> usize {
         let mut best_up_card_hand_player_index = 0;
-        let mut best_up_card_hand: Option<Hand> = None;
+        let mut best_up_card_hand: Option<(Hand, Card)> = None;
         // find player with lowest ranking up-card
         for (player_index, player) in self.players.iter().enumerate() {
             if self.pot.player_has_folded(&player.account_id()) {
                 continue;
             }
-            let player_up_cards: Vec<&Card> = player.peek_at_cards().iter()
-                .filter(|card| card.is_face_up())
-                .map(|card| *card)
-                .collect();
+            let player_up_cards: Vec<&Card> = player.peek_at_up_cards();
             let player_up_card_hand = Hand::new(player_up_cards.iter().map(|&card| card.clone()).collect());
+            let player_highest_up_card = SevenCardStud::<I>::highest_card(&player_up_cards);
             match best_up_card_hand {
-                Some(ref hand) => {
-                    assert!(player_up_card_hand != *hand);
-                    if player_up_card_hand > *hand {
-                        best_up_card_hand = Some(player_up_card_hand);
+                Some((ref hand, ref highest_up_card)) => {
+                    // ties between equally ranked up card hands are broken by the suit of the highest card
+                    let is_better = match player_up_card_hand.cmp(hand) {
+                        Ordering::Greater => true,
+                        Ordering::Equal => player_highest_up_card > *highest_up_card,
+                        Ordering::Less => false,
+                    };
+                    if is_better {
+                        best_up_card_hand = Some((player_up_card_hand, player_highest_up_card));
                         best_up_card_hand_player_index = player_index;
                     }
                 },
                 None => {
-                    best_up_card_hand = Some(player_up_card_hand);
+                    best_up_card_hand = Some((player_up_card_hand, player_highest_up_card));
                     best_up_card_hand_player_index = player_index;
                 }
             }
@@ -128,126 +162,31 @@ impl<I: Input> SevenCardStud<I> {
         return best_up_card_hand_player_index;
     }
 
+    /// finds the card with the highest rank, using the suit to break ties between cards of the same rank
+    fn highest_card(cards: &Vec<&Card>) -> Card {
+        let highest_card = cards.iter()
+            .max()
+            .expect("Expected at least one card");
+        return (*highest_card).clone();
+    }
+
     fn play_bet_phase(&mut self, phase_number: usize) {
         // for the first bet phase, the correct player to start at has been set by the bring in method.
         // for subsequent bet phases, the starting player is the one with the up cards that make the best poker hand.
-        if phase_number != 1 {
-            self.current_player_index = self.find_player_with_best_up_card_hand();
-        }
-        let mut last_raise_player_index = self.current_player_index;
-        let mut raise_has_occurred = false;
-        loop {
-            if self.pot.number_of_players_folded()+1 == (self.players.len() as u32) {
-                // all players have folded but one, remaining player automatically wins
-                break;
-            }
-            let player_matched_call = self.pot.get_call_amount() == self.pot.get_player_stake(&self.players.get(self.current_player_index).unwrap().account_id());
-            if self.number_of_players_all_in()+1 == self.players.len() && player_matched_call {
-                // all players are all in but one, remaining player doesn't need to bet
-                break;
-            }
-
-            let player: &Player = &self.players.get(self.current_player_index).expect("Expected a player at this index, but there was None");
-
-            if !(self.pot.player_has_folded(&player.account_id()) || player.balance() == 0) {
-                self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
-                self.input.display_player_balances(self.players.iter().collect());
-                self.input.display_current_player(player);
-                self.input.display_player_cards_to_player(player);
-
-                let player: &mut Player = &mut self.players.get_mut(self.current_player_index).expect("Expected a player at this index, but there was None");
-
-                if !raise_has_occurred && self.pot.get_call_amount() == self.pot.get_player_stake(&player.account_id()) {
-                    // the big blind can check because they already paid a full bet, and on the second round, everyone can check if nobody raises
-                    let action_options = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
-                    let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                    let player_raise_limit = min(self.raise_limit, player.balance() as u32);
-
-                    let action = match chosen_action_option {
-                        ActionOption::Check => Action::Check,
-                        ActionOption::Raise => Action::Raise(self.pot.get_call_amount() as usize + self.input.request_raise_amount(player_raise_limit, &player) as usize),
-                        ActionOption::Fold => Action::Fold,
-                        _ => panic!("Player managed to select an impossible Action!")
-                    };
-
-                    match action {
-                        Action::Check => {},
-                        Action::Raise(raise_amount) => {
-                            last_raise_player_index = self.current_player_index;
-                            raise_has_occurred = true;
-                            let bet_amount = raise_amount - self.pot.get_player_stake(&player.account_id()) as usize;
-                            player.bet(bet_amount as usize).unwrap();
-                        },
-                        Action::Fold => {},
-                        _ => panic!("Player managed to perform an impossible Action!")
-                    }
-
-                    self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                }
-                else {
-                    let current_bet_amount = self.pot.get_call_amount() as u32;
-                    if player.balance() as u32 > current_bet_amount {
-                        let action_options = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
-                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                        let player_raise_limit = min(self.raise_limit, player.balance() as u32 - current_bet_amount);
-                        let action = match chosen_action_option {
-                            ActionOption::Call => Action::Call,
-                            ActionOption::Raise => Action::Raise(<i64 as TryInto<usize>>::try_into(self.pot.get_call_amount()).unwrap() + self.input.request_raise_amount(player_raise_limit, &player) as usize),
-                            ActionOption::Fold => Action::Fold,
-                            _ => panic!("Player managed to select an impossible Action!")
-                        };
-    
-                        match action {
-                            Action::Call => {
-                                let bet_amount = self.pot.get_call_amount() - self.pot.get_player_stake(&player.account_id());
-                                player.bet(bet_amount as usize).unwrap();
-                            },
-                            Action::Raise(raise_amount) => {
-                                last_raise_player_index = self.current_player_index;
-                                raise_has_occurred = true;
-                                let bet_amount = raise_amount - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
-                                player.bet(bet_amount).unwrap();
-                            },
-                            Action::Fold => {},
-                            _ => panic!("Player managed to perform an impossible Action!")
-                        }
-                        self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                    } else {
-                        let action_options = vec![ActionOption::AllIn, ActionOption::Fold];
-                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                        // player does not have enough money for a full call, nevermind a raise
-                        let action = match chosen_action_option {
-                            ActionOption::AllIn => Action::AllIn(<i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap() + player.balance()),
-                            ActionOption::Fold => Action::Fold,
-                            _ => panic!("Player managed to select an impossible Action!")
-                        };
-    
-                        match action {
-                            Action::AllIn(total_stake) => {
-                                let bet_amount = total_stake - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
-                                assert_eq!(bet_amount, player.balance());
-                                player.bet(bet_amount).unwrap();
-                            },
-                            Action::Fold => {},
-                            _ => panic!("Player managed to perform an impossible Action!")
-                        }
-                        self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                    };
-                }
-            }
-
-            self.increment_player_index();
-
-            if self.current_player_index == last_raise_player_index {
-                // the next player is the player who last raised,
-                // which means that all bets have been matched,
-                // and it is time to move on to the next phase
-                break;
-            }
-        }
+        let start_player_index = match phase_number {
+            1 => self.current_player_index,
+            _ => self.find_player_with_best_up_card_hand(),
+        };
+        self.current_player_index = BettingRound {
+            players: &mut self.players,
+            pot: &mut self.pot,
+            input: &mut self.input,
+            raise_limit: self.raise_limit,
+            max_raises_per_round: self.max_raises_per_round,
+            minimum_bet: self.minimum_bet,
+            show_balances: true,
+            community_cards: None,
+        }.play(phase_number, start_player_index);
     }
 
     fn play_phase_one(&mut self) {
@@ -270,88 +209,51 @@ impl<I: Input> SevenCardStud<I> {
         self.play_bet_phase(5);
     }
 
-    /// take each non-folded player's cards, and make them all up cards (visible to everyone)
-    fn flip_non_folded_players_cards_up(&mut self) {
-        for player in self.players.iter_mut().filter(|player| !self.pot.player_has_folded(&player.account_id())) {
-            let mut cards = player.return_cards();
-            cards.iter_mut().for_each(|card| card.set_face_up(true));
-            for card in cards {
-                player.obtain_card(card);
-            }
-        }
+    /// get the rank of a player's hand, the same way the winner(s) are decided at the showdown
+    fn rank_player_hand(&self, player: &Player) -> HandRank {
+        let cards: Vec<Card> = player.peek_at_cards().iter().map(|&card| card.clone()).collect();
+        return Hand::rank_hand(&cards);
     }
 
-    fn showdown(&mut self) {
-        // show to each player everyone's cards (except folded)
-        let start_player_index = self.current_player_index;
-        let mut current_player_index = self.current_player_index;
-        self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
-        self.flip_non_folded_players_cards_up();
-        loop {
-            let player: &Player = self.players.get(current_player_index).expect("Expected a player at this index, but there was None");
-
-            if !self.pot.player_has_folded(&player.account_id()) {
-                let other_players: Vec<&Player> = self.players.iter()
-                    .filter(|&other_player| other_player != player)
-                    .map(|player| player as &Player)
-                    .collect();
-                self.input.display_other_player_up_cards_to_player(other_players, player);
-            }
+    /// deal the cards and play the bet phases of a round, up to the showdown,
+    /// returns Err if the deck runs out of cards
+    fn deal_and_play_phases(&mut self) -> Result<(), DeckError> {
+        self.deal_initial_cards()?;
+        self.play_bring_in();
+        self.play_phase_one();
+        self.deal_up_cards()?;
+        self.play_phase_two();
+        self.deal_up_cards()?;
+        self.play_phase_three();
+        self.deal_up_cards()?;
+        self.play_phase_four();
+        self.deal_down_cards()?;
+        self.play_phase_five();
+        return Ok(());
+    }
 
-            current_player_index += 1;
-            // wrap the player index around
-            if current_player_index == self.players.len() {
-                current_player_index = 0;
-            }
+    /// abort the round that is being played, refunding everyone what they put into the pot,
+    /// and hand back the players along with the error that stopped the round
+    fn abort_round(&mut self, round_error: RoundError) -> (RoundError, Vec<Player>) {
+        println!("Error: {round_error}");
+        refund_stakes(&mut self.players, &self.pot, &mut self.input);
+        self.return_cards();
+        return (round_error, self.players.drain(..).collect());
+    }
 
-            if current_player_index == start_player_index {
-                // one turn has been completed for each player,
-                // this marks the end of the draw phase
-                break;
-            }
-        }
+    fn showdown(&mut self) -> Result<(), PotError> {
+        // show to each player everyone's cards (except folded)
+        display_pot(&self.players, &self.pot, &self.input);
+        let hand_ranks = rank_hands(&self.players, &self.pot, |player| self.rank_player_hand(player));
+        reveal_hands(&mut self.players, &mut self.pot, &mut self.input, self.current_player_index, &hand_ranks, |shown_hand_rank, hand_rank| shown_hand_rank > hand_rank);
 
-        let mut player_cards: Vec<(Uuid, Vec<&Card>)> = self.players.iter()
-            .filter(|player| !self.pot.player_has_folded(&player.account_id()))
-            .map(|player| (player.account_id(), player.peek_at_cards()))
-            .collect();
-        player_cards.sort_by(|left, right| Hand::new(right.1.iter().map(|&card| card.clone()).collect())
-            .cmp(&Hand::new(left.1.iter().map(|&card| card.clone())
-            .collect()))); // sort by best hand of cards first // FIXME: unsure if problematic if there's one or more ties
-        let mut winning_order: Vec<Vec<Uuid>> = vec![vec![player_cards[0].0]];
-        for player_cards_index in 1..player_cards.len() {
-            let this_players_hand = Hand::new(player_cards[player_cards_index].1.iter().map(|&card| card.clone()).collect());
-            let last_players_hand = Hand::new(player_cards[player_cards_index-1].1.iter().map(|&card| card.clone()).collect());
-            if this_players_hand == last_players_hand {
-                winning_order.last_mut().unwrap().push(player_cards[player_cards_index].0);
-            }
-            else {
-                assert!(this_players_hand < last_players_hand);
-                winning_order.push(vec![player_cards[player_cards_index].0]);
-            }
-        }
-        winning_order.push(self.players.iter()
-            .filter(|player| self.pot.player_has_folded(&player.account_id()))
-            .map(|player| player.account_id()).collect());
-        let player_winnings_map = self.pot.divide_winnings(winning_order);
-        let mut winner_uuids = Vec::new();
-        for (player_id, &winnings) in player_winnings_map.iter() {
-            assert!(winnings >= 0);
-            if winnings > 0 {
-                let mut player_matches: Vec<&mut Player> = self.players.iter_mut().filter(|player| player.account_id() == *player_id).collect();
-                assert_eq!(player_matches.len(), 1);
-                let player_match = &mut player_matches[0];
-                assert!(!self.pot.player_has_folded(&player_match.account_id()), "Player: {}, winning amount: {}", player_match.account_id(), winnings);
-                player_match.win(winnings as usize);
-                winner_uuids.push(player_id);
-            }
-        }
-        let winners: Vec<&Player> = self.players.iter().filter(|player| winner_uuids.iter().any(|&uuid| player.account_id() == *uuid)).map(|player| player as &Player).collect();
-        self.input.announce_winner(winners, self.players.iter().map(|player| player as &Player).collect());
-        self.input.display_player_balances(self.players.iter().collect());
+        let winning_order = winning_order(&self.players, hand_ranks, |left, right| left.cmp(right));
+        let player_winnings_map = self.pot.divide_winnings(winning_order, &seat_order(&self.players, self.dealer_position))?.to_map();
+        award_winnings(&mut self.players, &self.pot, &mut self.input, &player_winnings_map);
+        return Ok(());
     }
 
-    fn deal_initial_cards(&mut self) -> Result<(), String> {
+    fn deal_initial_cards(&mut self) -> Result<(), DeckError> {
         // each player is dealt two cards face down and one card face up
         for _ in 0..2 {
             self.deal_down_cards()?;
@@ -361,70 +263,115 @@ impl<I: Input> SevenCardStud<I> {
     }
 
     /// each non-folded player is dealt one card face up
-    fn deal_up_cards(&mut self) -> Result<(), String> {
+    fn deal_up_cards(&mut self) -> Result<(), DeckError> {
         let remaining_players = self.players.iter_mut()
             .filter(|player| !self.pot.player_has_folded(&player.account_id()));
         for player in remaining_players {
-            player.obtain_card(self.deck.deal(true)?);
+            let card = self.deck.deal(true)?;
+            self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+            player.obtain_card(card);
         }
         return Ok(());
     }
 
     /// each non-folded player is dealt one card face down
-    fn deal_down_cards(&mut self) -> Result<(), String> {
+    fn deal_down_cards(&mut self) -> Result<(), DeckError> {
         let remaining_players = self.players.iter_mut()
             .filter(|player| !self.pot.player_has_folded(&player.account_id()));
         for player in remaining_players {
-            player.obtain_card(self.deck.deal(false)?);
+            let card = self.deck.deal(false)?;
+            self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+            player.obtain_card(card);
         }
         return Ok(());
     }
 
-    fn return_player_cards(&mut self) {
-        for player in self.players.iter_mut() {
-            let cards = player.return_cards();
-            for card in cards {
-                self.deck.return_card(card);
-            }
-        }
+    /// return all of the cards dealt this round to the deck, and check that the whole deck is back
+    fn return_cards(&mut self) {
+        return_cards_to_deck(&mut self.deck, &mut self.players, []);
     }
 }
 
 impl<I: Input> Rules for SevenCardStud<I> {
-    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (&'static str, Vec<Player>)> {
-        if players.len() < 2 {
-            return Err(("Cannot start a game with less than 2 players", players));
-        }
-        if players.len() > 7 {
-            return Err(("Cannot start a game with more than 7 players, as the deck may run out of cards", players));
+    /// the most players that can be dealt into a round without the deck running out of cards,
+    /// each player is dealt up to 7 cards
+    const MAX_PLAYERS: usize = DECK_SIZE / 7;
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        if let Err(round_error) = Self::check_player_count(players.len()) {
+            return Err((round_error, players));
         }
+        self.input.set_game_type(GameType::SevenCardStud);
         self.pot.clear(&players.iter().collect());
         assert_eq!(self.deck.size(), 52);
+        self.deck.shuffle();
+        self.deck.cut_random();
         self.players = players;
+        for player in self.players.iter_mut() {
+            // nobody has folded yet in this round
+            player.set_folded(false);
+        }
         self.increment_dealer_position();
         assert!(self.dealer_position < self.players.len());
         self.current_player_index = self.dealer_position;
 
-        self.deal_initial_cards().unwrap();
-        self.play_bring_in();
-        self.play_phase_one();
-        self.deal_up_cards().unwrap();
-        self.play_phase_two();
-        self.deal_up_cards().unwrap();
-        self.play_phase_three();
-        self.deal_up_cards().unwrap();
-        self.play_phase_four();
-        self.deal_down_cards().unwrap();
-        self.play_phase_five();
-        self.showdown();
-        self.pot.save(self.game_id).await;
+        if let Err(deck_error) = self.deal_and_play_phases() {
+            return Err(self.abort_round(RoundError::DeckExhausted(deck_error)));
+        }
+        if let Err(pot_error) = self.showdown() {
+            return Err(self.abort_round(RoundError::PotNotDivided(pot_error)));
+        }
+        self.pot.save(self.game_id, self.round_number).await;
 
-        self.return_player_cards();
+        self.return_cards();
 
         return Ok(self.players.drain(..).collect());
     }
 
-    fn new(raise_limit: u32, minimum_bet: u32, db_handler: DbHandler, game_id: Uuid) -> SevenCardStud<I> {
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.input.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Seven Card Stud: 2 down cards and 1 up card per player, then 3 more up cards and a final down card, five bet rounds";
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.pot.export_played_round_to_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.pot.round_events();
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        // seven card stud is played with antes and a bring in rather than blinds,
+        // so there is no big blind for a kill blind to replace
+        return match kill {
+            Some(_) => Err(KillNotSupported),
+            None => Ok(()),
+        };
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        self.round_number = round_number;
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.bring_in = proportional_small_blind(self.starting_bets, amount);
+        self.minimum_bet = amount;
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        self.pot.set_chip_size(chip_size);
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.max_raises_per_round = max_raises;
+    }
+
+    fn new(raise_limit: u32, minimum_bet: u32, _small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> SevenCardStud<I> {
+        // stud has a bring in instead of blinds
         let deck = Deck::new();
         let dealer_position = 0_usize;
         let current_player_index = 0_usize;
@@ -436,10 +383,14 @@ impl<I: Input> Rules for SevenCardStud<I> {
             dealer_position,
             current_player_index,
             raise_limit,
+            max_raises_per_round: None,
+            minimum_bet,
             bring_in: minimum_bet,
+            starting_bets: (minimum_bet, minimum_bet),
             input: I::new(),
             pot,
-            game_id
+            game_id,
+            round_number: 0
         };
     }
 }
@@ -448,14 +399,22 @@ impl<I: Input> Rules for SevenCardStud<I> {
 mod tests {
     use uuid::Uuid;
 
+    use crate::action_option::ActionOption;
     use crate::input::test_input::TestInput;
-    use crate::card::{Rank, Suit};
+    use crate::rules::KillType;
 
     use super::*;
 
+    /// parse a face up card from poker notation, like "Ah"
+    fn face_up(notation: &str) -> Card {
+        let mut card: Card = notation.parse().unwrap();
+        card.set_face_up(true);
+        return card;
+    }
+
     #[test]
     fn new() {
-        let seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
 
         assert_eq!(seven_card_stud.deck.size(), 52);
         assert_eq!(seven_card_stud.dealer_position, 0);
@@ -465,19 +424,58 @@ mod tests {
         assert_eq!(seven_card_stud.players.len(), 0);
     }
 
+    #[test]
+    fn set_minimum_bet_keeps_bring_in_separate() {
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 4, 0, DbHandler::new_dummy(), Uuid::now_v7());
+        seven_card_stud.set_bring_in(1);
+        seven_card_stud.set_minimum_bet(8);
+        assert_eq!((seven_card_stud.bring_in, seven_card_stud.minimum_bet), (2, 8));
+        seven_card_stud.set_minimum_bet(12);
+        assert_eq!((seven_card_stud.bring_in, seven_card_stud.minimum_bet), (3, 12));
+    }
+
+    #[test]
+    fn set_kill_rejected() {
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
+        let kill = Kill { kill_type: KillType::FullKill, qualifying_pot: 20 };
+        assert_eq!(seven_card_stud.set_kill(Some(kill)), Err(KillNotSupported));
+        assert_eq!(seven_card_stud.set_kill(None), Ok(()));
+    }
+
     #[tokio::test]
     async fn try_play_round_one_player() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
         ];
 
-        assert!(seven_card_stud.play_round(players).await.is_err_and(|err| err.0 == "Cannot start a game with less than 2 players"));
+        assert!(matches!(seven_card_stud.play_round(players).await, Err((RoundError::TooFewPlayers, _))));
+    }
+
+    #[tokio::test]
+    async fn play_round_returns_players_for_next_round() {
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
+        // every player checks or folds whenever they are asked
+        seven_card_stud.input.set_strict(false);
+        let initial_balance = 1000;
+        let mut players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        // the players (and their balances) from one round are played in the next round
+        for _ in 0..2 {
+            players = seven_card_stud.play_round(players).await.unwrap();
+            assert_eq!(players.len(), 3);
+            assert_eq!(players.iter().map(|player| player.balance()).sum::<usize>(), 3 * initial_balance);
+            assert!(players.iter().all(|player| player.peek_at_cards().is_empty()));
+        }
+        assert_eq!(seven_card_stud.players.len(), 0);
     }
 
     #[test]
     fn increment_dealer_position() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
@@ -495,7 +493,7 @@ mod tests {
 
     #[test]
     fn increment_player_index() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
@@ -513,7 +511,7 @@ mod tests {
 
     #[test]
     fn deal_initial_cards() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -524,8 +522,8 @@ mod tests {
         let mut cards = Vec::new();
         for mut player in seven_card_stud.players {
             assert_eq!(player.peek_at_cards().len(), 3);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| card.is_face_up()).count(), 1);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| !card.is_face_up()).count(), 2);
+            assert_eq!(player.peek_at_up_cards().len(), 1);
+            assert_eq!(player.peek_at_down_cards().len(), 2);
             let temp_cards = player.return_cards();
             // make sure that cards didn't somehow get duplicated, that cards are in fact unique
             for card in temp_cards.iter() {
@@ -537,7 +535,7 @@ mod tests {
 
     #[test]
     fn deal_up_cards() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -548,8 +546,8 @@ mod tests {
         let mut cards = Vec::new();
         for mut player in seven_card_stud.players {
             assert_eq!(player.peek_at_cards().len(), 1);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| card.is_face_up()).count(), 1);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| !card.is_face_up()).count(), 0);
+            assert_eq!(player.peek_at_up_cards().len(), 1);
+            assert_eq!(player.peek_at_down_cards().len(), 0);
             let temp_cards = player.return_cards();
             // make sure that cards didn't somehow get duplicated, that cards are in fact unique
             for card in temp_cards.iter() {
@@ -561,7 +559,7 @@ mod tests {
 
     #[test]
     fn deal_down_cards() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -572,8 +570,8 @@ mod tests {
         let mut cards = Vec::new();
         for mut player in seven_card_stud.players {
             assert_eq!(player.peek_at_cards().len(), 1);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| card.is_face_up()).count(), 0);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| !card.is_face_up()).count(), 1);
+            assert_eq!(player.peek_at_up_cards().len(), 0);
+            assert_eq!(player.peek_at_down_cards().len(), 1);
             let temp_cards = player.return_cards();
             // make sure that cards didn't somehow get duplicated, that cards are in fact unique
             for card in temp_cards.iter() {
@@ -586,7 +584,7 @@ mod tests {
 
     #[test]
     fn deal_initial_cards_up_cards_and_down_cards() {
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -601,8 +599,8 @@ mod tests {
         let mut cards = Vec::new();
         for mut player in seven_card_stud.players {
             assert_eq!(player.peek_at_cards().len(), 7);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| card.is_face_up()).count(), 4);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| !card.is_face_up()).count(), 3);
+            assert_eq!(player.peek_at_up_cards().len(), 4);
+            assert_eq!(player.peek_at_down_cards().len(), 3);
             let temp_cards = player.return_cards();
             // make sure that cards didn't somehow get duplicated, that cards are in fact unique
             for card in temp_cards.iter() {
@@ -615,7 +613,7 @@ mod tests {
     #[test]
     fn play_bring_in() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -630,10 +628,29 @@ mod tests {
         assert_eq!(seven_card_stud.players.iter().filter(|player| player.balance() == initial_balance).count(), 2);
     }
 
+    #[test]
+    fn play_bring_in_short_stack() {
+        let bring_in_amount = 10;
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
+        seven_card_stud.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 4),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        seven_card_stud.players[0].obtain_card(face_up("5s"));
+        seven_card_stud.players[1].obtain_card(face_up("2d")); // this player pays bring in, but only has 4 left
+        seven_card_stud.players[2].obtain_card(face_up("4s"));
+        seven_card_stud.play_bring_in();
+        // they go all in, and the others still have to call the full bring in
+        assert_eq!(seven_card_stud.players[1].balance(), 0);
+        assert_eq!(seven_card_stud.pot.get_player_stake(&seven_card_stud.players[1].account_id()), 4);
+        assert_eq!(seven_card_stud.pot.get_call_amount() as u32, bring_in_amount);
+    }
+
     #[test]
     fn play_bring_in_equal_card_rank() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -642,9 +659,9 @@ mod tests {
         ];
         seven_card_stud.players = players;
 
-        seven_card_stud.players[0].obtain_card(Card::new(Rank::Two, Suit::Spades, true)); // this is the last player from the dealer
-        seven_card_stud.players[1].obtain_card(Card::new(Rank::Two, Suit::Diamonds, true)); // this player pays bring in, as they are closer to the dealer
-        seven_card_stud.players[2].obtain_card(Card::new(Rank::Four, Suit::Spades, true));
+        seven_card_stud.players[0].obtain_card(face_up("2s"));
+        seven_card_stud.players[1].obtain_card(face_up("2d")); // this player pays bring in, as diamonds are lower than spades
+        seven_card_stud.players[2].obtain_card(face_up("4s"));
         assert_eq!(seven_card_stud.dealer_position, 0);
         seven_card_stud.play_bring_in();
         assert_eq!(seven_card_stud.pot.get_call_amount() as u32, bring_in_amount);
@@ -653,10 +670,34 @@ mod tests {
         assert_eq!(seven_card_stud.players.get(2).unwrap().balance(), initial_balance);
     }
 
+    #[test]
+    fn find_player_with_best_up_card_hand_equal_rank() {
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        seven_card_stud.players = players;
+        seven_card_stud.pot.clear(&seven_card_stud.players.iter().collect());
+
+        // both player 0 and player 2 are showing a pair of threes, but player 2 has the three of spades
+        for card in ["3d", "3c"] {
+            seven_card_stud.players[0].obtain_card(face_up(card));
+        }
+        for card in ["2c", "8s"] {
+            seven_card_stud.players[1].obtain_card(face_up(card));
+        }
+        for card in ["3h", "3s"] {
+            seven_card_stud.players[2].obtain_card(face_up(card));
+        }
+        assert_eq!(seven_card_stud.find_player_with_best_up_card_hand(), 2);
+    }
+
     #[test]
     fn play_phase_one_check_only() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -680,9 +721,9 @@ mod tests {
         ]);
 
         // manually deal initial (up) cards so we know which player pays bring in
-        seven_card_stud.players[0].obtain_card(Card::new(Rank::Two, Suit::Spades, true)); // this player pays bring in
-        seven_card_stud.players[1].obtain_card(Card::new(Rank::Three, Suit::Spades, true)); // phase one starts on this player
-        seven_card_stud.players[2].obtain_card(Card::new(Rank::Four, Suit::Spades, true));
+        seven_card_stud.players[0].obtain_card(face_up("2s")); // this player pays bring in
+        seven_card_stud.players[1].obtain_card(face_up("3s")); // phase one starts on this player
+        seven_card_stud.players[2].obtain_card(face_up("4s"));
         seven_card_stud.play_bring_in();
         seven_card_stud.play_phase_one();
 
@@ -696,7 +737,7 @@ mod tests {
     #[test]
     fn play_phase_one_with_raises() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -725,9 +766,9 @@ mod tests {
         ]);
 
         // manually deal initial (up) cards so we know which player pays bring in
-        seven_card_stud.players[0].obtain_card(Card::new(Rank::Two, Suit::Spades, true)); // this player pays bring in
-        seven_card_stud.players[1].obtain_card(Card::new(Rank::Three, Suit::Spades, true)); // phase one starts on this player
-        seven_card_stud.players[2].obtain_card(Card::new(Rank::Four, Suit::Spades, true));
+        seven_card_stud.players[0].obtain_card(face_up("2s")); // this player pays bring in
+        seven_card_stud.players[1].obtain_card(face_up("3s")); // phase one starts on this player
+        seven_card_stud.players[2].obtain_card(face_up("4s"));
         seven_card_stud.play_bring_in();
         seven_card_stud.play_phase_one();
 
@@ -741,7 +782,7 @@ mod tests {
     #[test]
     fn play_phase_one_with_folds() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -768,9 +809,9 @@ mod tests {
         ]);
 
         // manually deal initial (up) cards so we know which player pays bring in
-        seven_card_stud.players[0].obtain_card(Card::new(Rank::Two, Suit::Spades, true)); // this player pays bring in
-        seven_card_stud.players[1].obtain_card(Card::new(Rank::Three, Suit::Spades, true)); // phase one starts on this player
-        seven_card_stud.players[2].obtain_card(Card::new(Rank::Four, Suit::Spades, true));
+        seven_card_stud.players[0].obtain_card(face_up("2s")); // this player pays bring in
+        seven_card_stud.players[1].obtain_card(face_up("3s")); // phase one starts on this player
+        seven_card_stud.players[2].obtain_card(face_up("4s"));
         seven_card_stud.play_bring_in();
         seven_card_stud.play_phase_one();
 
@@ -783,7 +824,7 @@ mod tests {
     #[test]
     fn play_all_folds_auto_win() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -807,9 +848,9 @@ mod tests {
         ]);
 
         // manually deal initial (up) cards so we know which player pays bring in
-        seven_card_stud.players[0].obtain_card(Card::new(Rank::Two, Suit::Spades, true)); // this player pays bring in
-        seven_card_stud.players[1].obtain_card(Card::new(Rank::Three, Suit::Spades, true)); // phase one starts on this player
-        seven_card_stud.players[2].obtain_card(Card::new(Rank::Four, Suit::Spades, true));
+        seven_card_stud.players[0].obtain_card(face_up("2s")); // this player pays bring in
+        seven_card_stud.players[1].obtain_card(face_up("3s")); // phase one starts on this player
+        seven_card_stud.players[2].obtain_card(face_up("4s"));
         seven_card_stud.play_bring_in();
         seven_card_stud.play_phase_one();
 
@@ -822,7 +863,7 @@ mod tests {
     #[test]
     fn play_full_round_all_checks_and_calls() {
         let bring_in_amount = 1;
-        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut seven_card_stud = SevenCardStud::<TestInput>::new(1000, bring_in_amount, bring_in_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -873,6 +914,6 @@ mod tests {
         assert_eq!(seven_card_stud.players.get(0).unwrap().balance(), initial_balance - bring_in_amount as usize);
         assert_eq!(seven_card_stud.players.get(1).unwrap().balance(), initial_balance - bring_in_amount as usize);
         assert_eq!(seven_card_stud.players.get(2).unwrap().balance(), initial_balance - bring_in_amount as usize);
-        seven_card_stud.showdown();
+        seven_card_stud.showdown().unwrap();
     }
 }
@@ -0,0 +1,143 @@
+use uuid::Uuid;
+
+use crate::card::Card;
+use crate::database::db_handler::DbHandler;
+use crate::deck::SHORT_DECK_SIZE;
+use crate::game_event::GameEvent;
+use crate::input::Input;
+use crate::player::Player;
+use super::texas_holdem::TexasHoldem;
+use super::{AnteMode, Kill, KillNotSupported, Rules, RoundError};
+
+/// Short Deck Hold'em Rules
+///
+/// Short deck (or 6+) hold'em is played like Texas Hold'em, and its rounds are played by the Texas Hold'em rules,
+/// but the twos through to the fives are taken out of the deck, leaving 36 cards (see Deck::new_short).
+/// With only nine cards of each suit, a flush is harder to make than a full house, so a flush beats a full house,
+/// and the ace connects to the six as well as the king, so A-6-7-8-9 is the lowest straight (see Hand::rank_short_deck_hand).
+pub struct ShortDeckHoldem<I: Input> {
+    texas_holdem: TexasHoldem<I>,
+}
+
+impl<I: Input> ShortDeckHoldem<I> {
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return self.texas_holdem.input();
+    }
+
+    /// see TexasHoldem::input_mut
+    pub(super) fn input_mut(&mut self) -> &mut I {
+        return self.texas_holdem.input_mut();
+    }
+
+    /// see TexasHoldem::dealer_position
+    pub(super) fn dealer_position(&self) -> usize {
+        return self.texas_holdem.dealer_position();
+    }
+
+    /// see TexasHoldem::set_dealer_position
+    pub(super) fn set_dealer_position(&mut self, dealer_position: usize) {
+        self.texas_holdem.set_dealer_position(dealer_position);
+    }
+
+    /// see TexasHoldem::set_ante
+    pub fn set_ante(&mut self, ante_amount: u32, ante_mode: AnteMode) {
+        self.texas_holdem.set_ante(ante_amount, ante_mode);
+    }
+
+    /// see TexasHoldem::set_max_straddles
+    pub fn set_max_straddles(&mut self, max_straddles: u32) {
+        self.texas_holdem.set_max_straddles(max_straddles);
+    }
+}
+
+impl<I: Input> Rules for ShortDeckHoldem<I> {
+    /// the most players that can be dealt into a round without the short deck running out of cards,
+    /// each player is dealt 2 cards and 5 community cards are shared
+    const MAX_PLAYERS: usize = (SHORT_DECK_SIZE - 5) / 2;
+
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> ShortDeckHoldem<I> {
+        let mut texas_holdem = TexasHoldem::new(raise_limit, minimum_bet, small_blind_amount, db_handler, game_id);
+        texas_holdem.set_short_deck(true);
+        return ShortDeckHoldem {
+            texas_holdem,
+        };
+    }
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        // the short deck deals fewer players than Texas Hold'em does
+        if let Err(round_error) = Self::check_player_count(players.len()) {
+            return Err((round_error, players));
+        }
+        return self.texas_holdem.play_round(players).await;
+    }
+
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.texas_holdem.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Short Deck Hold'em: Texas Hold'em with the twos to fives removed, a flush beats a full house, and A-6-7-8-9 is a straight";
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        return self.texas_holdem.set_kill(kill);
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.texas_holdem.export_last_round_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.texas_holdem.last_round_events();
+    }
+
+    fn community_cards(&self) -> &[Card] {
+        return self.texas_holdem.community_cards();
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        self.texas_holdem.set_round_number(round_number);
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.texas_holdem.set_minimum_bet(amount);
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        self.texas_holdem.set_chip_size(chip_size);
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.texas_holdem.set_max_raises_per_round(max_raises);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::bot_input::BotInput;
+    use crate::input::test_input::TestInput;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn play_rounds_with_bots() {
+        let mut short_deck_holdem = ShortDeckHoldem::<BotInput<TestInput>>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut players: Vec<Player> = (0..ShortDeckHoldem::<TestInput>::MAX_PLAYERS)
+            .map(|bot_number| Player::new_bot(Uuid::now_v7(), format!("bot {bot_number}"), 1000))
+            .collect();
+        for _ in 0..3 {
+            players = short_deck_holdem.play_round(players).await.unwrap();
+        }
+        assert_eq!(players.iter().map(|player| player.balance()).sum::<usize>(), 1000 * ShortDeckHoldem::<TestInput>::MAX_PLAYERS);
+    }
+
+    #[tokio::test]
+    async fn try_play_round_too_many_players() {
+        let mut short_deck_holdem = ShortDeckHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let players: Vec<Player> = (0..ShortDeckHoldem::<TestInput>::MAX_PLAYERS+1)
+            .map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000))
+            .collect();
+        assert!(matches!(short_deck_holdem.play_round(players).await, Err((RoundError::TooManyPlayers, _))));
+    }
+}
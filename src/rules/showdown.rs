@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::card::Card;
+use crate::deck::Deck;
+use crate::game_event::GameEvent;
+use crate::input::Input;
+use crate::player::Player;
+use crate::pot::Pot;
+
+use super::Kill;
+
+/// get the players' ids in seat order, starting with the player to the left of the dealer (the player at dealer_position),
+/// which is the order that the odd chips of a split pot are given out in (see Pot::divide_winnings)
+pub(crate) fn seat_order(players: &[Player], dealer_position: usize) -> Vec<Uuid> {
+    return (0..players.len())
+        .map(|offset| players[(dealer_position + offset) % players.len()].account_id())
+        .collect();
+}
+
+/// rank the hand of each player, in the same order as the players (None for players who have folded),
+/// each hand is ranked once up front, rather than every time two hands are compared
+pub(crate) fn rank_hands<R>(players: &[Player], pot: &Pot, rank_player_hand: impl Fn(&Player) -> R) -> Vec<Option<R>> {
+    return players.iter()
+        .map(|player| (!pot.player_has_folded(&player.account_id())).then(|| rank_player_hand(player)))
+        .collect();
+}
+
+/// get the players' ids ordered from the best hand to the worst, with tied players grouped together,
+/// followed by the players who have folded (whose hand rank is None, see rank_hands).
+/// compare orders two hand ranks, with the better hand being the greater
+pub(crate) fn winning_order<R>(players: &[Player], hand_ranks: Vec<Option<R>>, compare: impl Fn(&R, &R) -> Ordering) -> Vec<Vec<Uuid>> {
+    let mut player_hand_ranks: Vec<(Uuid, R)> = Vec::new();
+    let mut folded_player_ids: Vec<Uuid> = Vec::new();
+    for (player, hand_rank) in players.iter().zip(hand_ranks) {
+        match hand_rank {
+            Some(hand_rank) => player_hand_ranks.push((player.account_id(), hand_rank)),
+            None => folded_player_ids.push(player.account_id()),
+        }
+    }
+    // sort by best hand of cards first
+    player_hand_ranks.sort_by(|left, right| compare(&right.1, &left.1));
+    let mut winning_order: Vec<Vec<Uuid>> = Vec::new();
+    for (player_index, (player_id, hand_rank)) in player_hand_ranks.iter().enumerate() {
+        if player_index > 0 && compare(hand_rank, &player_hand_ranks[player_index-1].1) == Ordering::Equal {
+            winning_order.last_mut().unwrap().push(*player_id);
+        }
+        else {
+            winning_order.push(vec![*player_id]);
+        }
+    }
+    winning_order.push(folded_player_ids);
+    return winning_order;
+}
+
+/// reveal the cards of each non-folded player, one player at a time, starting with
+/// the last aggressor (the last player to bet or raise in the final betting phase),
+/// or the player at current_player_index if nobody bet or raised, and going around the table from there.
+/// A player whose hand is beaten by a hand that has already been shown (by a player with
+/// at least as much in the pot) may muck instead, in which case their cards stay face down.
+/// hand_ranks are the ranks of the players' hands (see rank_hands),
+/// and beats(shown_hand_rank, hand_rank) is true when a shown hand beats a player's hand.
+/// Afterwards, each player who hasn't folded is shown the other players' face up cards
+pub(crate) fn reveal_hands<I: Input, R>(players: &mut [Player], pot: &mut Pot, input: &mut I, current_player_index: usize, hand_ranks: &[Option<R>], beats: impl Fn(&R, &R) -> bool) {
+    let start_player_index = match pot.get_last_aggressor() {
+        Some(aggressor_id) => players.iter()
+            .position(|player| player.account_id() == aggressor_id)
+            .unwrap_or(current_player_index),
+        None => current_player_index,
+    };
+    let mut shown_hands: Vec<(i64, &R)> = Vec::new();
+    for offset in 0..players.len() {
+        let player_index = (start_player_index + offset) % players.len();
+        let player: &Player = &players[player_index];
+        let hand_rank = match &hand_ranks[player_index] {
+            Some(hand_rank) if !pot.player_has_folded(&player.account_id()) => hand_rank,
+            _ => continue,
+        };
+
+        let player_stake = pot.get_player_stake(&player.account_id());
+        let is_beaten = shown_hands.iter()
+            .any(|(shown_stake, shown_hand_rank)| *shown_stake >= player_stake && beats(shown_hand_rank, hand_rank));
+        if is_beaten && !input.request_show_or_muck(player) {
+            // the player mucks, their cards are not shown to anyone
+            pot.add_muck(&player.account_id());
+            continue;
+        }
+
+        let player: &mut Player = &mut players[player_index];
+        let mut cards = player.return_cards();
+        cards.iter_mut().for_each(|card| card.set_face_up(true));
+        for card in cards {
+            player.obtain_card(card);
+        }
+        shown_hands.push((player_stake, hand_rank));
+    }
+
+    for offset in 0..players.len() {
+        let player: &Player = &players[(start_player_index + offset) % players.len()];
+        if !pot.player_has_folded(&player.account_id()) {
+            let other_players: Vec<&Player> = players.iter()
+                .filter(|&other_player| other_player != player)
+                .collect();
+            input.display_other_player_up_cards_to_player(other_players, player);
+        }
+    }
+}
+
+/// the player who has to post a kill blind next round, which is a player who won a big enough pot by themselves,
+/// or None if the game has no kill pots (see Kill)
+pub(crate) fn kill_pot_winner(kill: Option<Kill>, player_winnings: &HashMap<Uuid, i64>) -> Option<Uuid> {
+    let winner_ids: Vec<&Uuid> = player_winnings.iter()
+        .filter(|(_, &winnings)| winnings > 0)
+        .map(|(player_id, _)| player_id)
+        .collect();
+    let pot_total: i64 = player_winnings.values().sum();
+    return match (kill, winner_ids.as_slice()) {
+        (Some(kill), [winner_id]) if pot_total as usize >= kill.qualifying_pot => Some(**winner_id),
+        _ => None,
+    };
+}
+
+/// give each winner their winnings from the pot (see Pot::divide_winnings), and announce the winners to everyone
+pub(crate) fn award_winnings<I: Input>(players: &mut [Player], pot: &Pot, input: &mut I, player_winnings: &HashMap<Uuid, i64>) {
+    let mut winner_ids = Vec::new();
+    for (player_id, &winnings) in player_winnings.iter() {
+        assert!(winnings >= 0);
+        if winnings > 0 {
+            let player = players.iter_mut()
+                .find(|player| player.account_id() == *player_id)
+                .expect("Expected the winner to be one of the players");
+            assert!(!pot.player_has_folded(&player.account_id()), "Player: {}, winning amount: {}", player.account_id(), winnings);
+            player.win(winnings as usize);
+            input.on_event(GameEvent::PotAwarded { player_id: *player_id, amount: winnings as usize });
+            winner_ids.push(*player_id);
+        }
+    }
+    let winners: Vec<&Player> = players.iter().filter(|player| winner_ids.contains(&player.account_id())).collect();
+    input.announce_winner(winners, players.iter().collect());
+    input.display_player_balances(players.iter().collect());
+}
+
+/// return every player's cards and any other cards still out of the deck (e.g. the community cards) to the deck
+/// at the end of a round, and check that the whole deck is back (see Deck::assert_complete)
+pub(crate) fn return_cards_to_deck(deck: &mut Deck, players: &mut [Player], other_cards: impl IntoIterator<Item = Card>) {
+    for player in players.iter_mut() {
+        for card in player.return_cards() {
+            deck.return_card(card);
+        }
+    }
+    for card in other_cards {
+        deck.return_card(card);
+    }
+    deck.assert_complete();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn winning_order_groups_ties_and_folded_players_last() {
+        let players: Vec<Player> = (0..4).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        let player_ids: Vec<Uuid> = players.iter().map(|player| player.account_id()).collect();
+        // player 1 has folded, and players 0 and 3 tie for the best hand
+        let hand_ranks = vec![Some(5), None, Some(2), Some(5)];
+        assert_eq!(
+            winning_order(&players, hand_ranks, |left: &i32, right: &i32| left.cmp(right)),
+            vec![vec![player_ids[0], player_ids[3]], vec![player_ids[2]], vec![player_ids[1]]]
+        );
+    }
+
+    #[test]
+    fn seat_order_starts_at_dealer_position() {
+        let players: Vec<Player> = (0..3).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+        let player_ids: Vec<Uuid> = players.iter().map(|player| player.account_id()).collect();
+        assert_eq!(seat_order(&players, 2), vec![player_ids[2], player_ids[0], player_ids[1]]);
+    }
+}
@@ -2,16 +2,18 @@ use uuid::Uuid;
 
 use crate::card::Card;
 use crate::database::db_handler::DbHandler;
-use crate::deck::Deck;
-use crate::hand_rank::Hand;
+use crate::deck::{Deck, DeckError, DECK_SIZE, SHORT_DECK_SIZE};
+use crate::hand_rank::{Hand, HandRank};
 use crate::input::Input;
 use crate::player::Player;
-use crate::pot::Pot;
-use super::Rules;
-use crate::action_option::ActionOption;
-use crate::action::Action;
+use crate::pot::{Pot, PotError};
+use super::betting::{display_pot, post_blind, post_kill_blind, proportional_small_blind, refund_stakes, round_up_to_chip_size, BettingRound};
+use super::showdown::{award_winnings, kill_pot_winner, rank_hands, return_cards_to_deck, reveal_hands, seat_order, winning_order};
+use super::{AnteMode, Kill, KillNotSupported, Rules, RoundError};
+use crate::game_event::GameEvent;
+use crate::game_type::GameType;
 
-use std::cmp::min;
+use std::cmp::{min, Ordering};
 
 /// Texas Holdem Rules
 /// 
@@ -27,16 +29,52 @@ pub struct TexasHoldem<I: Input> {
     dealer_position: usize,
     current_player_index: usize,
     raise_limit: u32,
+    /// the most raises in each betting round, None for no cap (see Rules::set_max_raises_per_round)
+    max_raises_per_round: Option<u32>,
     big_blind_amount: u32,
+    small_blind_amount: u32,
+    /// the (small blind, big blind) that the rules were created with, which the small blind stays in proportion to
+    starting_blinds: (u32, u32),
+    kill: Option<Kill>,
+    /// the winner of the last kill pot, who has to post a kill blind this round
+    killer: Option<Uuid>,
+    ante_amount: u32,
+    ante_mode: AnteMode,
+    /// the most straddles that can be posted before the flop, 0 for no straddles,
+    /// more than 1 allows players to re-straddle after the first straddle
+    max_straddles: u32,
+    /// true when playing short deck hold'em, with a short deck (see Deck::new_short) and the short deck hand rankings
+    short_deck: bool,
     input: I,
     pot: Pot,
     game_id: Uuid,
-    community_cards: Vec<Card>
+    /// the number of this round within the game, which is saved with the round
+    round_number: u32,
+    community_cards: Vec<Card>,
+    /// when the remaining cards are run twice, community_cards holds the first runout,
+    /// and this holds the cards of the second runout that replace the end of it
+    second_runout: Vec<Card>
 }
 
 impl<I: Input> TexasHoldem<I> {
-    fn number_of_players_all_in(&self) -> usize {
-        return self.players.iter().filter(|player| player.balance() == 0).count();
+    /// the input that players are asked for their actions through
+    pub fn input(&self) -> &I {
+        return &self.input;
+    }
+
+    /// the input, to lend another one for a round (see MixedGame, which shares one input between its variants)
+    pub(super) fn input_mut(&mut self) -> &mut I {
+        return &mut self.input;
+    }
+
+    /// the seat of the dealer in the last round, the button moves on one seat at the start of each round
+    pub(super) fn dealer_position(&self) -> usize {
+        return self.dealer_position;
+    }
+
+    /// move the dealer button to a seat, to carry it on from a round played with other rules (see MixedGame)
+    pub(super) fn set_dealer_position(&mut self, dealer_position: usize) {
+        self.dealer_position = dealer_position;
     }
 
     fn increment_dealer_position(&mut self) {
@@ -54,11 +92,67 @@ impl<I: Input> TexasHoldem<I> {
         }
     }
 
+    /// set the ante that is charged at the start of each round (0 for no ante),
+    /// and whether every player pays it or the big blind pays it for the whole table
+    pub fn set_ante(&mut self, ante_amount: u32, ante_mode: AnteMode) {
+        self.ante_amount = ante_amount;
+        self.ante_mode = ante_mode;
+    }
+
+    /// set the most straddles that can be posted before the flop (0 for no straddles),
+    /// each straddle is twice the previous blind or straddle, and is offered to the next player in turn
+    pub fn set_max_straddles(&mut self, max_straddles: u32) {
+        self.max_straddles = max_straddles;
+    }
+
+    /// play with a short deck (sixes to aces) and the short deck hand rankings, or with the full deck,
+    /// this swaps the deck, so it can only be done between rounds
+    pub(super) fn set_short_deck(&mut self, short_deck: bool) {
+        self.short_deck = short_deck;
+        self.deck = match short_deck {
+            true => Deck::new_short(),
+            false => Deck::new(),
+        };
+    }
+
+    /// the number of cards in the deck when none of them have been dealt
+    fn full_deck_size(&self) -> usize {
+        return match self.short_deck {
+            true => SHORT_DECK_SIZE,
+            false => DECK_SIZE,
+        };
+    }
+
+    fn charge_antes(&mut self) {
+        if self.ante_amount == 0 {
+            return;
+        }
+        // the big blind is the second player after the dealer, see play_blinds
+        let big_blind_position = (self.dealer_position + 1) % self.players.len();
+        let ante_amount = round_up_to_chip_size(self.ante_amount as usize, self.pot.chip_size());
+        let ante_payments: Vec<(usize, usize)> = match self.ante_mode {
+            AnteMode::EveryoneAntes => (0..self.players.len())
+                .map(|player_index| (player_index, ante_amount))
+                .collect(),
+            AnteMode::BigBlindAnte => vec![(big_blind_position, ante_amount * self.players.len())],
+        };
+        for (player_index, ante_amount) in ante_payments {
+            let player = self.players.get_mut(player_index).expect("Expected a player at this index, but there was None");
+            // a player who cannot cover the whole ante pays what they have left
+            let ante_amount = min(ante_amount, player.balance());
+            if ante_amount == 0 {
+                continue;
+            }
+            self.pot.add_ante(&player.account_id(), ante_amount, 0, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
+            player.bet(ante_amount).expect("Expected the ante to be covered by the player's balance, it is capped at it");
+            self.input.on_event(GameEvent::Bet { player_id: player.account_id(), amount: ante_amount });
+        }
+    }
+
     fn play_blinds(&mut self) {
         // the first and second players after the dealer must bet blind
         let first_blind_player = self.players.get_mut(self.dealer_position).expect("Expected a player at the dealer position, but there was None");
-        self.pot.add_turn(&first_blind_player.account_id(), Action::Ante(<u32 as TryInto<usize>>::try_into(self.big_blind_amount).unwrap()/2), 0, first_blind_player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-        first_blind_player.bet(<u32 as TryInto<usize>>::try_into(self.big_blind_amount).unwrap()/2).unwrap();
+        post_blind(first_blind_player, &mut self.pot, &mut self.input, self.small_blind_amount as usize);
         self.increment_player_index();
 
         let second_blind_player = match self.players.get_mut(self.dealer_position+1) {
@@ -67,134 +161,60 @@ impl<I: Input> TexasHoldem<I> {
                 self.players.get_mut(0).expect("Expected a non-zero number of players")
             }
         };
-        self.pot.add_turn(&second_blind_player.account_id(), Action::Ante(self.big_blind_amount as usize), 0, second_blind_player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-        second_blind_player.bet(self.big_blind_amount as usize).unwrap();
+        post_blind(second_blind_player, &mut self.pot, &mut self.input, self.big_blind_amount as usize);
         self.increment_player_index();
-    }
 
-    fn play_bet_phase(&mut self, phase_number: usize) {
-        // for every betting phase except the first, betting starts with the first blind player (player at self.dealer_position)
-        if phase_number != 1 {
-            self.current_player_index = self.dealer_position;
+        match post_kill_blind(&mut self.players, &mut self.pot, &mut self.input, self.kill, self.killer, self.big_blind_amount) {
+            // the killer acts last in the first betting phase, like a big blind
+            Some(killer_index) => self.current_player_index = (killer_index + 1) % self.players.len(),
+            // the killer couldn't post the kill blind, so this round isn't a kill pot
+            None => self.killer = None,
         }
-        // otherwise (so, for the first betting phase) betting starts with the player after the big blind
-        let mut last_raise_player_index = self.current_player_index;
-        let mut raise_has_occurred = false;
-        loop {
-            if self.pot.number_of_players_folded()+1 == (self.players.len() as u32) {
-                // all players have folded but one, remaining player automatically wins
-                break;
-            }
-            let player_matched_call = self.pot.get_call_amount() == self.pot.get_player_stake(&self.players.get(self.current_player_index).unwrap().account_id());
-            if self.number_of_players_all_in()+1 == self.players.len() && player_matched_call {
-                // all players are all in but one, remaining player doesn't need to bet
-                break;
-            }
+        self.play_straddles();
+    }
 
-            let player: &Player = &self.players.get(self.current_player_index).expect("Expected a player at this index, but there was None");
-
-            if !(self.pot.player_has_folded(&player.account_id()) || player.balance() == 0) {
-                self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
-                self.input.display_player_balances(self.players.iter().collect());
-                self.input.display_current_player(player);
-                self.input.display_community_cards_to_player(self.community_cards.iter().collect(), player);
-                self.input.display_player_cards_to_player(player);
-
-                let player: &mut Player = &mut self.players.get_mut(self.current_player_index).expect("Expected a player at this index, but there was None");
-
-                if !raise_has_occurred && self.pot.get_call_amount() == self.pot.get_player_stake(&player.account_id()) {
-                    // the big blind can check because they already paid a full bet, and on the second round, everyone can check if nobody raises
-                    let action_options = vec![ActionOption::Check, ActionOption::Raise, ActionOption::Fold];
-                    let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                    let player_raise_limit = min(self.raise_limit, player.balance() as u32);
-
-                    let action = match chosen_action_option {
-                        ActionOption::Check => Action::Check,
-                        ActionOption::Raise => Action::Raise(self.pot.get_call_amount() as usize + self.input.request_raise_amount(player_raise_limit, &player) as usize),
-                        ActionOption::Fold => Action::Fold,
-                        _ => panic!("Player managed to select an impossible Action!")
-                    };
-
-                    match action {
-                        Action::Check => {},
-                        Action::Raise(raise_amount) => {
-                            last_raise_player_index = self.current_player_index;
-                            raise_has_occurred = true;
-                            let bet_amount = raise_amount - self.pot.get_player_stake(&player.account_id()) as usize;
-                            player.bet(bet_amount as usize).unwrap();
-                        },
-                        Action::Fold => {},
-                        _ => panic!("Player managed to perform an impossible Action!")
-                    }
-
-                    self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                }
-                else {
-                    let current_bet_amount = self.pot.get_call_amount() as u32;
-                    if player.balance() as u32 > current_bet_amount {
-                        let action_options = vec![ActionOption::Call, ActionOption::Raise, ActionOption::Fold];
-                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                        let player_raise_limit = min(self.raise_limit, player.balance() as u32 - current_bet_amount);
-                        let action = match chosen_action_option {
-                            ActionOption::Call => Action::Call,
-                            ActionOption::Raise => Action::Raise(<i64 as TryInto<usize>>::try_into(self.pot.get_call_amount()).unwrap() + self.input.request_raise_amount(player_raise_limit, &player) as usize),
-                            ActionOption::Fold => Action::Fold,
-                            _ => panic!("Player managed to select an impossible Action!")
-                        };
-    
-                        match action {
-                            Action::Call => {
-                                let bet_amount = self.pot.get_call_amount() - self.pot.get_player_stake(&player.account_id());
-                                player.bet(bet_amount as usize).unwrap();
-                            },
-                            Action::Raise(raise_amount) => {
-                                last_raise_player_index = self.current_player_index;
-                                raise_has_occurred = true;
-                                let bet_amount = raise_amount - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
-                                player.bet(bet_amount).unwrap();
-                            },
-                            Action::Fold => {},
-                            _ => panic!("Player managed to perform an impossible Action!")
-                        }
-                        self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                    } else {
-                        let action_options = vec![ActionOption::AllIn, ActionOption::Fold];
-                        let chosen_action_option: ActionOption = self.input.input_action_options(action_options, &player);
-
-                        // player does not have enough money for a full call, nevermind a raise
-                        let action = match chosen_action_option {
-                            ActionOption::AllIn => Action::AllIn(<i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap() + player.balance()),
-                            ActionOption::Fold => Action::Fold,
-                            _ => panic!("Player managed to select an impossible Action!")
-                        };
-    
-                        match action {
-                            Action::AllIn(total_stake) => {
-                                let bet_amount = total_stake - <i64 as TryInto<usize>>::try_into(self.pot.get_player_stake(&player.account_id())).unwrap();
-                                assert_eq!(bet_amount, player.balance());
-                                player.bet(bet_amount).unwrap();
-                            },
-                            Action::Fold => {},
-                            _ => panic!("Player managed to perform an impossible Action!")
-                        }
-                        self.pot.add_turn(&player.account_id(), action, phase_number, player.peek_at_cards().iter().map(|&card| card.clone()).collect());
-                    };
-                }
+    /// offer the player after the big blind a straddle of twice the big blind, then (when re-straddling is allowed)
+    /// the player after them twice that straddle, and so on, until a player declines.
+    /// each straddle is posted as a blind, so the player after the last straddle acts first before the flop,
+    /// and the last straddler acts last. There are no straddles in a kill pot, or once the offer gets back to a blind
+    fn play_straddles(&mut self) {
+        if self.pot.get_call_amount() > round_up_to_chip_size(self.big_blind_amount as usize, self.pot.chip_size()) as i64 {
+            // the kill blind already raised the stakes
+            return;
+        }
+        for _ in 0..self.max_straddles {
+            let straddle_amount = self.pot.get_call_amount() as u32 * 2;
+            let player = self.players.get_mut(self.current_player_index).expect("Expected a player at the current player index, but there was None");
+            let can_straddle = self.pot.get_player_stake(&player.account_id()) == 0
+                && !player.is_sitting_out()
+                && player.balance() >= straddle_amount as usize;
+            if !can_straddle || !self.input.request_straddle(player, straddle_amount) {
+                return;
             }
-
+            post_blind(player, &mut self.pot, &mut self.input, straddle_amount as usize);
             self.increment_player_index();
-
-            if self.current_player_index == last_raise_player_index {
-                // the next player is the player who last raised,
-                // which means that all bets have been matched,
-                // and it is time to move on to the next phase
-                break;
-            }
         }
     }
 
+    fn play_bet_phase(&mut self, phase_number: usize) {
+        // for every betting phase except the first, betting starts with the first blind player (player at self.dealer_position),
+        // otherwise (so, for the first betting phase) betting starts with the player after the big blind
+        let start_player_index = match phase_number {
+            1 => self.current_player_index,
+            _ => self.dealer_position,
+        };
+        self.current_player_index = BettingRound {
+            players: &mut self.players,
+            pot: &mut self.pot,
+            input: &mut self.input,
+            raise_limit: self.raise_limit,
+            max_raises_per_round: self.max_raises_per_round,
+            minimum_bet: self.big_blind_amount,
+            show_balances: true,
+            community_cards: Some(&self.community_cards),
+        }.play(phase_number, start_player_index);
+    }
+
     fn play_phase_one(&mut self) {
         self.play_bet_phase(1);
     }
@@ -211,88 +231,152 @@ impl<I: Input> TexasHoldem<I> {
         self.play_bet_phase(4);
     }
 
-    /// take each non-folded player's cards, and make them all up cards (visible to everyone)
-    fn flip_non_folded_players_cards_up(&mut self) {
-        for player in self.players.iter_mut().filter(|player| !self.pot.player_has_folded(&player.account_id())) {
-            let mut cards = player.return_cards();
-            cards.iter_mut().for_each(|card| card.set_face_up(true));
-            for card in cards {
-                player.obtain_card(card);
-            }
-        }
+    /// get the rank of a player's hand, which is the best five cards out of their own cards and the community cards,
+    /// the same way the winner(s) are decided at the showdown
+    fn rank_player_hand(&self, player: &Player) -> HandRank {
+        let cards: Vec<Card> = player.peek_at_cards().into_iter().chain(self.community_cards.iter()).cloned().collect();
+        return self.rank_cards(&cards);
     }
 
-    fn showdown(&mut self) {
-        // show to each player everyone's cards (except folded)
-        let start_player_index = self.current_player_index;
-        let mut current_player_index = self.current_player_index;
-        self.input.display_pot(self.pot.get_total_stake(), self.players.iter().map(|player| player as &Player).collect());
-        self.flip_non_folded_players_cards_up();
-        loop {
-            let player: &Player = self.players.get(current_player_index).expect("Expected a player at this index, but there was None");
-
-            if !self.pot.player_has_folded(&player.account_id()) {
-                let other_players: Vec<&Player> = self.players.iter()
-                    .filter(|&other_player| other_player != player)
-                    .map(|player| player as &Player)
-                    .collect();
-                self.input.display_other_player_up_cards_to_player(other_players, player);
-            }
+    /// rank the best five of the cards, with the short deck rankings when playing short deck hold'em
+    fn rank_cards(&self, cards: &[Card]) -> HandRank {
+        return match self.short_deck {
+            true => Hand::rank_short_deck_hand(cards),
+            false => Hand::rank_hand(&Hand::best_five(cards)),
+        };
+    }
 
-            current_player_index += 1;
-            // wrap the player index around
-            if current_player_index == self.players.len() {
-                current_player_index = 0;
-            }
+    /// compare two hand ranks, with the short deck rankings when playing short deck hold'em (where a flush beats a full house)
+    fn compare_hand_ranks(short_deck: bool, left: &HandRank, right: &HandRank) -> Ordering {
+        return match short_deck {
+            true => left.cmp_short_deck(right),
+            false => left.cmp(right),
+        };
+    }
 
-            if current_player_index == start_player_index {
-                // one turn has been completed for each player,
-                // this marks the end of the draw phase
-                break;
-            }
-        }
+    /// get the players' ids ordered from the best hand to the worst, with tied players grouped together,
+    /// followed by the players who have folded
+    fn winning_order(&self) -> Vec<Vec<Uuid>> {
+        return self.runout_winning_order(&self.community_cards);
+    }
 
-        let mut player_cards: Vec<(Uuid, Vec<&Card>)> = self.players.iter()
-            .filter(|player| !self.pot.player_has_folded(&player.account_id()))
-            .map(|player| (player.account_id(), player.peek_at_cards()))
+    /// get the winning order for one runout of the board when the remaining cards are run twice,
+    /// each player's hand is the best five cards out of their own cards and that runout's board
+    fn runout_winning_order(&self, board: &Vec<Card>) -> Vec<Vec<Uuid>> {
+        let hand_ranks = rank_hands(&self.players, &self.pot, |player| {
+            let cards: Vec<Card> = player.peek_at_cards().into_iter().chain(board.iter()).cloned().collect();
+            self.rank_cards(&cards)
+        });
+        return winning_order(&self.players, hand_ranks, |left, right| Self::compare_hand_ranks(self.short_deck, left, right));
+    }
+
+    /// when every player who hasn't folded is all in (or has nobody left to bet against) before the river,
+    /// ask each of them whether to run the remaining community cards twice.
+    /// If they all agree, the remaining cards are dealt twice and shown to everyone,
+    /// and true is returned, as there is no more betting in this round
+    fn offer_run_it_twice(&mut self) -> Result<bool, DeckError> {
+        let remaining_players: Vec<usize> = (0..self.players.len())
+            .filter(|&player_index| !self.pot.player_has_folded(&self.players[player_index].account_id()))
             .collect();
-        player_cards.sort_by(|left, right| Hand::new(right.1.iter().map(|&card| card.clone()).collect())
-            .cmp(&Hand::new(left.1.iter().map(|&card| card.clone())
-            .collect()))); // sort by best hand of cards first // FIXME: unsure if problematic if there's one or more ties
-        let mut winning_order: Vec<Vec<Uuid>> = vec![vec![player_cards[0].0]];
-        for player_cards_index in 1..player_cards.len() {
-            let this_players_hand = Hand::new(player_cards[player_cards_index].1.iter().map(|&card| card.clone()).collect());
-            let last_players_hand = Hand::new(player_cards[player_cards_index-1].1.iter().map(|&card| card.clone()).collect());
-            if this_players_hand == last_players_hand {
-                winning_order.last_mut().unwrap().push(player_cards[player_cards_index].0);
-            }
-            else {
-                assert!(this_players_hand < last_players_hand);
-                winning_order.push(vec![player_cards[player_cards_index].0]);
-            }
+        let players_who_can_bet = remaining_players.iter()
+            .filter(|&&player_index| self.players[player_index].balance() > 0)
+            .count();
+        let remaining_card_count = 5 - self.community_cards.len().min(5);
+        if remaining_players.len() < 2 || players_who_can_bet > 1 || remaining_card_count == 0 {
+            return Ok(false);
         }
-        winning_order.push(self.players.iter()
-            .filter(|player| self.pot.player_has_folded(&player.account_id()))
-            .map(|player| player.account_id()).collect());
-        let player_winnings_map = self.pot.divide_winnings(winning_order);
-        let mut winner_uuids = Vec::new();
-        for (player_id, &winnings) in player_winnings_map.iter() {
-            assert!(winnings >= 0);
-            if winnings > 0 {
-                let mut player_matches: Vec<&mut Player> = self.players.iter_mut().filter(|player| player.account_id() == *player_id).collect();
-                assert_eq!(player_matches.len(), 1);
-                let player_match = &mut player_matches[0];
-                assert!(!self.pot.player_has_folded(&player_match.account_id()), "Player: {}, winning amount: {}", player_match.account_id(), winnings);
-                player_match.win(winnings as usize);
-                winner_uuids.push(player_id);
+        if self.deck.remaining() < remaining_card_count * 2 {
+            // with a full table there may not be enough cards left to deal the board twice
+            return Ok(false);
+        }
+        for &player_index in remaining_players.iter() {
+            if !self.input.request_run_it_twice(&self.players[player_index]) {
+                return Ok(false);
             }
         }
-        let winners: Vec<&Player> = self.players.iter().filter(|player| winner_uuids.iter().any(|&uuid| player.account_id() == *uuid)).map(|player| player as &Player).collect();
-        self.input.announce_winner(winners, self.players.iter().map(|player| player as &Player).collect());
-        self.input.display_player_balances(self.players.iter().collect());
+
+        for _ in 0..remaining_card_count {
+            let card = self.deck.deal(true)?;
+            self.input.on_event(GameEvent::CommunityCardDealt { card: card.clone() });
+            self.community_cards.push(card);
+        }
+        for _ in 0..remaining_card_count {
+            self.second_runout.push(self.deck.deal(true)?);
+        }
+        let shared_card_count = self.community_cards.len() - remaining_card_count;
+        for player in self.players.iter() {
+            self.input.display_community_cards_to_player(self.community_cards.iter().collect(), player);
+            self.input.display_community_cards_to_player(self.community_cards[..shared_card_count].iter().chain(self.second_runout.iter()).collect(), player);
+        }
+        return Ok(true);
+    }
+
+    /// deal the cards and play the bet phases of a round, up to the showdown,
+    /// returns Err if the deck runs out of cards
+    fn deal_and_play_phases(&mut self) -> Result<(), DeckError> {
+        self.deal_initial_cards()?;
+        self.charge_antes();
+        self.play_blinds();
+        self.play_phase_one();
+        // once nobody can bet any more, the players may choose to run the rest of the board twice
+        let mut ran_it_twice = self.offer_run_it_twice()?;
+        if !ran_it_twice {
+            self.deal_flop_cards()?;
+            self.play_phase_two();
+            ran_it_twice = self.offer_run_it_twice()?;
+        }
+        if !ran_it_twice {
+            self.deal_community_card()?;
+            self.play_phase_three();
+            ran_it_twice = self.offer_run_it_twice()?;
+        }
+        if !ran_it_twice {
+            self.deal_community_card()?;
+            self.play_phase_four();
+        }
+        return Ok(());
+    }
+
+    /// abort the round that is being played, refunding everyone what they put into the pot,
+    /// and hand back the players along with the error that stopped the round
+    fn abort_round(&mut self, round_error: RoundError) -> (RoundError, Vec<Player>) {
+        println!("Error: {round_error}");
+        refund_stakes(&mut self.players, &self.pot, &mut self.input);
+        // nobody won this round, so nobody has to post a kill blind next round
+        self.killer = None;
+        self.return_cards();
+        return (round_error, self.players.drain(..).collect());
     }
 
-    fn deal_initial_cards(&mut self) -> Result<(), String> {
+    fn showdown(&mut self) -> Result<(), PotError> {
+        // show to each player everyone's cards (except folded)
+        display_pot(&self.players, &self.pot, &self.input);
+        let hand_ranks = rank_hands(&self.players, &self.pot, |player| self.rank_player_hand(player));
+        let short_deck = self.short_deck;
+        reveal_hands(&mut self.players, &mut self.pot, &mut self.input, self.current_player_index, &hand_ranks, |shown_hand_rank, hand_rank| Self::compare_hand_ranks(short_deck, shown_hand_rank, hand_rank) == Ordering::Greater);
+
+        let seat_order = seat_order(&self.players, self.dealer_position);
+        let player_winnings_map = match self.second_runout.is_empty() {
+            true => self.pot.divide_winnings(self.winning_order(), &seat_order)?,
+            false => {
+                // each runout's board is made of the cards dealt before everyone went all in, followed by that runout's cards
+                let shared_card_count = self.community_cards.len() - self.second_runout.len();
+                let second_board: Vec<Card> = self.community_cards[..shared_card_count].iter()
+                    .chain(self.second_runout.iter())
+                    .cloned()
+                    .collect();
+                let first_winning_order = self.runout_winning_order(&self.community_cards);
+                let second_winning_order = self.runout_winning_order(&second_board);
+                self.pot.divide_winnings_twice(first_winning_order, second_winning_order, &seat_order)?
+            },
+        }.to_map();
+        // a player who wins a big enough pot by themselves has to post a kill blind next round
+        self.killer = kill_pot_winner(self.kill, &player_winnings_map);
+        award_winnings(&mut self.players, &self.pot, &mut self.input, &player_winnings_map);
+        return Ok(());
+    }
+
+    fn deal_initial_cards(&mut self) -> Result<(), DeckError> {
         // each player is dealt two cards face down
         for _ in 0..2 {
             self.deal_down_cards()?;
@@ -301,89 +385,131 @@ impl<I: Input> TexasHoldem<I> {
     }
 
     /// Deal 3 community cards
-    fn deal_flop_cards(&mut self) -> Result<(), String> {
+    fn deal_flop_cards(&mut self) -> Result<(), DeckError> {
         for _ in 0..3 {
             self.deal_community_card()?;
         }
         return Ok(());
     }
 
-    /// deals a community card, iff there are at least two players who can still take bet actions (haven't folded or gone all in)
-    fn deal_community_card(&mut self) -> Result<(), String> {
-        if self.pot.number_of_players_folded()+1 == (self.players.len() as u32) {
-            // all players have folded but one
+    /// deals a community card, iff there are at least two players contesting the pot (who haven't folded).
+    /// Players who are all in still need the whole board to make their hands,
+    /// the betting phases are skipped when nobody can bet, but the cards are still dealt
+    fn deal_community_card(&mut self) -> Result<(), DeckError> {
+        if self.pot.number_of_players_folded()+1 >= (self.players.len() as u32) {
+            // all players have folded but one, who wins without a showdown
             return Ok(());
         }
-        if self.number_of_players_all_in()+1 == self.players.len() {
-            // all players are all in but one
-            return Ok(());
-        }
-        self.community_cards.push(self.deck.deal(true)?);
+        let card = self.deck.deal(true)?;
+        self.input.on_event(GameEvent::CommunityCardDealt { card: card.clone() });
+        self.community_cards.push(card);
         return Ok(());
     }
 
     /// each non-folded player is dealt one card face down
-    fn deal_down_cards(&mut self) -> Result<(), String> {
+    fn deal_down_cards(&mut self) -> Result<(), DeckError> {
         let remaining_players = self.players.iter_mut()
             .filter(|player| !self.pot.player_has_folded(&player.account_id()));
         for player in remaining_players {
-            player.obtain_card(self.deck.deal(false)?);
+            let card = self.deck.deal(false)?;
+            self.input.on_event(GameEvent::card_dealt(player.account_id(), &card));
+            player.obtain_card(card);
         }
         return Ok(());
     }
 
-    fn return_player_cards(&mut self) {
-        for player in self.players.iter_mut() {
-            let cards = player.return_cards();
-            for card in cards {
-                self.deck.return_card(card);
-            }
-        }
-    }
-
-    fn return_community_cards(&mut self) {
-        while let Some(card) = self.community_cards.pop() {
-            self.deck.return_card(card);
-        }
-        assert_eq!(self.community_cards.len(), 0);
+    /// return all of the cards dealt this round to the deck, and check that the whole deck is back
+    fn return_cards(&mut self) {
+        return_cards_to_deck(&mut self.deck, &mut self.players, self.community_cards.drain(..).chain(self.second_runout.drain(..)));
     }
 }
 
 impl<I: Input> Rules for TexasHoldem<I> {
-    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (&'static str, Vec<Player>)> {
-        if players.len() < 2 {
-            return Err(("Cannot start a game with less than 2 players", players));
-        }
-        if players.len() > 23 {
-            return Err(("Cannot start a game with more than 23 players, as the deck may run out of cards", players));
+    /// the most players that can be dealt into a round without the deck running out of cards,
+    /// each player is dealt 2 cards and 5 community cards are shared
+    const MAX_PLAYERS: usize = (DECK_SIZE - 5) / 2;
+
+    async fn play_round(&mut self, players: Vec<Player>) -> Result<Vec<Player>, (RoundError, Vec<Player>)> {
+        if let Err(round_error) = Self::check_player_count(players.len()) {
+            return Err((round_error, players));
         }
+        self.input.set_game_type(match self.short_deck {
+            true => GameType::ShortDeckHoldem,
+            false => GameType::TexasHoldem,
+        });
         self.pot.clear(&players.iter().collect());
         assert_eq!(self.community_cards.len(), 0);
-        assert_eq!(self.deck.size(), 52);
+        assert_eq!(self.deck.size(), self.full_deck_size());
+        self.deck.shuffle();
+        self.deck.cut_random();
         self.players = players;
+        for player in self.players.iter_mut() {
+            // nobody has folded yet in this round
+            player.set_folded(false);
+        }
         self.increment_dealer_position();
         assert!(self.dealer_position < self.players.len());
         self.current_player_index = self.dealer_position;
 
-        self.deal_initial_cards().unwrap();
-        self.play_blinds();
-        self.play_phase_one();
-        self.deal_flop_cards().unwrap();
-        self.play_phase_two();
-        self.deal_community_card().unwrap();
-        self.play_phase_three();
-        self.deal_community_card().unwrap();
-        self.play_phase_four();
-        self.showdown();
-        self.pot.save(self.game_id).await;
-
-        self.return_player_cards();
-        self.return_community_cards();
+        if let Err(deck_error) = self.deal_and_play_phases() {
+            return Err(self.abort_round(RoundError::DeckExhausted(deck_error)));
+        }
+        if let Err(pot_error) = self.showdown() {
+            return Err(self.abort_round(RoundError::PotNotDivided(pot_error)));
+        }
+        self.pot.save(self.game_id, self.round_number).await;
+
+        self.return_cards();
 
         return Ok(self.players.drain(..).collect());
     }
 
-    fn new(raise_limit: u32, minimum_bet: u32, db_handler: DbHandler, game_id: Uuid) -> TexasHoldem<I> {
+    fn announce_player_eliminated(&self, player: &Player) {
+        self.input.announce_player_eliminated(player);
+    }
+
+    fn deal_order_description() -> &'static str {
+        return "Texas Hold'em: 2 down cards per player, 5 community cards dealt as the flop, turn and river, four bet rounds";
+    }
+
+    fn export_last_round_json(&self) -> Option<String> {
+        return self.pot.export_played_round_to_json();
+    }
+
+    fn last_round_events(&self) -> Vec<GameEvent> {
+        return self.pot.round_events();
+    }
+
+    fn community_cards(&self) -> &[Card] {
+        return &self.community_cards;
+    }
+
+    fn set_kill(&mut self, kill: Option<Kill>) -> Result<(), KillNotSupported> {
+        self.kill = kill;
+        if kill.is_none() {
+            self.killer = None;
+        }
+        return Ok(());
+    }
+
+    fn set_round_number(&mut self, round_number: u32) {
+        self.round_number = round_number;
+    }
+
+    fn set_minimum_bet(&mut self, amount: u32) {
+        self.small_blind_amount = proportional_small_blind(self.starting_blinds, amount);
+        self.big_blind_amount = amount;
+    }
+
+    fn set_chip_size(&mut self, chip_size: u32) {
+        self.pot.set_chip_size(chip_size);
+    }
+
+    fn set_max_raises_per_round(&mut self, max_raises: Option<u32>) {
+        self.max_raises_per_round = max_raises;
+    }
+
+    fn new(raise_limit: u32, minimum_bet: u32, small_blind_amount: u32, db_handler: DbHandler, game_id: Uuid) -> TexasHoldem<I> {
         let deck = Deck::new();
         let dealer_position = 0_usize;
         let current_player_index = 0_usize;
@@ -396,11 +522,22 @@ impl<I: Input> Rules for TexasHoldem<I> {
             dealer_position,
             current_player_index,
             raise_limit,
+            max_raises_per_round: None,
             big_blind_amount: minimum_bet,
+            small_blind_amount,
+            starting_blinds: (small_blind_amount, minimum_bet),
+            kill: None,
+            killer: None,
+            ante_amount: 0,
+            ante_mode: AnteMode::EveryoneAntes,
+            max_straddles: 0,
+            short_deck: false,
             input: I::new(),
             pot,
             game_id,
-            community_cards
+            round_number: 0,
+            community_cards,
+            second_runout: Vec::new()
         };
     }
 }
@@ -409,13 +546,16 @@ impl<I: Input> Rules for TexasHoldem<I> {
 mod tests {
     use uuid::Uuid;
 
+    use crate::action::Action;
+    use crate::action_option::ActionOption;
+    use crate::card::cards;
     use crate::input::test_input::TestInput;
 
     use super::*;
 
     #[test]
     fn new() {
-        let texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
 
         assert_eq!(texas_holdem.deck.size(), 52);
         assert_eq!(texas_holdem.dealer_position, 0);
@@ -427,17 +567,27 @@ mod tests {
 
     #[tokio::test]
     async fn try_play_round_one_player() {
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
         ];
 
-        assert!(texas_holdem.play_round(players).await.is_err_and(|err| err.0 == "Cannot start a game with less than 2 players"));
+        assert!(matches!(texas_holdem.play_round(players).await, Err((RoundError::TooFewPlayers, _))));
+    }
+
+    #[tokio::test]
+    async fn try_play_round_too_many_players() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
+        // 23 players use 46 cards, leaving 6 for the 5 community cards
+        assert_eq!(TexasHoldem::<TestInput>::MAX_PLAYERS, 23);
+        let players = (0..24).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), 1000)).collect();
+
+        assert!(matches!(texas_holdem.play_round(players).await, Err((RoundError::TooManyPlayers, _))));
     }
 
     #[test]
     fn increment_dealer_position() {
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
@@ -455,7 +605,7 @@ mod tests {
 
     #[test]
     fn increment_player_index() {
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000)
@@ -473,7 +623,7 @@ mod tests {
 
     #[test]
     fn deal_initial_cards() {
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -484,8 +634,8 @@ mod tests {
         let mut cards = Vec::new();
         for mut player in texas_holdem.players {
             assert_eq!(player.peek_at_cards().len(), 2);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| card.is_face_up()).count(), 0);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| !card.is_face_up()).count(), 2);
+            assert_eq!(player.peek_at_up_cards().len(), 0);
+            assert_eq!(player.peek_at_down_cards().len(), 2);
             let temp_cards = player.return_cards();
             // make sure that cards didn't somehow get duplicated, that cards are in fact unique
             for card in temp_cards.iter() {
@@ -495,9 +645,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn community_cards_after_flop() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        texas_holdem.deal_initial_cards().unwrap();
+        assert!(texas_holdem.community_cards().is_empty());
+        texas_holdem.deal_flop_cards().unwrap();
+        assert_eq!(texas_holdem.community_cards().len(), 3);
+        assert!(texas_holdem.community_cards().iter().all(|card| card.is_face_up()));
+        texas_holdem.return_cards();
+        assert!(texas_holdem.community_cards().is_empty());
+    }
+
     #[test]
     fn deal_down_cards() {
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 1, 0, DbHandler::new_dummy(), Uuid::now_v7());
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
             Player::new(Uuid::now_v7(), "player".to_string(), 1000),
@@ -508,8 +674,8 @@ mod tests {
         let mut cards = Vec::new();
         for mut player in texas_holdem.players {
             assert_eq!(player.peek_at_cards().len(), 1);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| card.is_face_up()).count(), 0);
-            assert_eq!(player.peek_at_cards().iter().filter(|card| !card.is_face_up()).count(), 1);
+            assert_eq!(player.peek_at_up_cards().len(), 0);
+            assert_eq!(player.peek_at_down_cards().len(), 1);
             let temp_cards = player.return_cards();
             // make sure that cards didn't somehow get duplicated, that cards are in fact unique
             for card in temp_cards.iter() {
@@ -521,7 +687,7 @@ mod tests {
 
     #[test]
     fn play_blinds() {
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -536,10 +702,233 @@ mod tests {
         assert_eq!(texas_holdem.players.get(1).unwrap().balance(), initial_balance-2);
     }
 
+    fn four_players(initial_balance: usize) -> Vec<Player> {
+        return (0..4).map(|_| Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)).collect();
+    }
+
+    #[test]
+    fn play_blinds_straddle() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_max_straddles(1);
+        let initial_balance = 1000;
+        texas_holdem.players = four_players(initial_balance);
+        let player_ids: Vec<Uuid> = texas_holdem.players.iter().map(|player| player.account_id()).collect();
+        texas_holdem.input.set_straddle_selections(vec![true]);
+
+        texas_holdem.play_blinds();
+        // the player after the big blind straddled for twice the big blind, and the action moves past them
+        assert_eq!(texas_holdem.pot.get_call_amount(), 4);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance-4);
+        assert_eq!(texas_holdem.current_player_index, 3);
+
+        texas_holdem.input.set_action_option_selections(vec![
+            ActionOption::Call, // the player after the straddle acts first
+            ActionOption::Call,
+            ActionOption::Call,
+            ActionOption::Check // the straddler acts last, like the big blind
+        ]);
+        texas_holdem.play_phase_one();
+        texas_holdem.input.assert_all_actions_consumed();
+        let turns = texas_holdem.pot.get_phase_history(1);
+        let first_to_act = turns.iter().find(|(_, action, _, _)| !matches!(action, Action::Ante(_) | Action::Blind(_))).unwrap();
+        assert_eq!(first_to_act.0, player_ids[3]);
+        assert_eq!(turns.last().unwrap().0, player_ids[2]);
+        assert_eq!(texas_holdem.pot.get_total_stake(), 16);
+    }
+
+    #[test]
+    fn play_blinds_re_straddle() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_max_straddles(2);
+        let initial_balance = 1000;
+        texas_holdem.players = four_players(initial_balance);
+        texas_holdem.input.set_straddle_selections(vec![true, true]);
+
+        texas_holdem.play_blinds();
+        assert_eq!(texas_holdem.pot.get_call_amount(), 8);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance-4);
+        assert_eq!(texas_holdem.players.get(3).unwrap().balance(), initial_balance-8);
+        // the offer got back around to the small blind, so the action starts with them
+        assert_eq!(texas_holdem.current_player_index, 0);
+    }
+
+    #[test]
+    fn play_blinds_straddle_declined_or_not_allowed() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_max_straddles(1);
+        texas_holdem.players = four_players(1000);
+        texas_holdem.input.set_straddle_selections(vec![false]);
+        texas_holdem.play_blinds();
+        assert_eq!(texas_holdem.pot.get_call_amount(), 2);
+        assert_eq!(texas_holdem.current_player_index, 2);
+
+        // without straddles allowed, nobody is asked
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.players = four_players(1000);
+        texas_holdem.input.set_straddle_selections(vec![true]);
+        texas_holdem.play_blinds();
+        assert_eq!(texas_holdem.pot.get_call_amount(), 2);
+        assert_eq!(texas_holdem.current_player_index, 2);
+    }
+
+    #[test]
+    fn set_minimum_bet_raises_both_blinds() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_minimum_bet(8);
+        texas_holdem.players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000)
+        ];
+        texas_holdem.play_blinds();
+        assert_eq!(texas_holdem.pot.get_call_amount(), 8);
+        assert_eq!(texas_holdem.pot.get_total_stake(), 12);
+    }
+
+    #[test]
+    fn set_minimum_bet_keeps_starting_proportion() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 5, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_minimum_bet(7);
+        assert_eq!(texas_holdem.small_blind_amount, 2);
+        // worked out from the starting blinds of 2 and 5, rather than from the rounded down blinds of 2 and 7
+        texas_holdem.set_minimum_bet(10);
+        assert_eq!(texas_holdem.small_blind_amount, 4);
+    }
+
+    #[test]
+    fn play_blinds_two_five() {
+        // a small blind that isn't half of the big blind
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 5, 2, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        texas_holdem.players = players;
+        texas_holdem.play_blinds();
+        assert_eq!(texas_holdem.pot.get_call_amount(), 5);
+        assert_eq!(texas_holdem.pot.get_total_stake(), 7);
+        assert_eq!(texas_holdem.players.get(0).unwrap().balance(), initial_balance-2);
+        assert_eq!(texas_holdem.players.get(1).unwrap().balance(), initial_balance-5);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance);
+
+        texas_holdem.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Call, // the small blind pays the 3 that they are short
+            ActionOption::Check
+        ]);
+        texas_holdem.play_phase_one();
+        texas_holdem.input.assert_all_actions_consumed();
+        assert_eq!(texas_holdem.pot.get_total_stake(), 15);
+        for player in texas_holdem.players.into_iter() {
+            assert_eq!(player.balance(), initial_balance-5);
+        }
+    }
+
+    #[test]
+    fn play_blinds_big_blind_short() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 10, 5, DbHandler::new_dummy(), Uuid::now_v7());
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "small blind".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "short big blind".to_string(), 4),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        let player_ids: Vec<Uuid> = players.iter().map(|player| player.account_id()).collect();
+        texas_holdem.players = players;
+        texas_holdem.play_blinds();
+        // the big blind is all in for less, but the other players still have to call the full big blind
+        assert_eq!(texas_holdem.players.get(1).unwrap().balance(), 0);
+        assert_eq!(texas_holdem.pot.get_player_stake(&player_ids[1]), 4);
+        assert_eq!(texas_holdem.pot.get_call_amount(), 10);
+
+        texas_holdem.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Call
+        ]);
+        texas_holdem.play_phase_one();
+        texas_holdem.input.assert_all_actions_consumed();
+        assert_eq!(texas_holdem.players.get(0).unwrap().balance(), initial_balance-10);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance-10);
+
+        // the short big blind can only win the main pot, the rest is a side pot between the other two players
+        let mut side_pot_player_ids = vec![player_ids[0], player_ids[2]];
+        side_pot_player_ids.sort();
+        let mut all_player_ids = player_ids.clone();
+        all_player_ids.sort();
+        assert_eq!(texas_holdem.pot.compute_pots(), vec![(12, all_player_ids), (12, side_pot_player_ids)]);
+        let winnings = texas_holdem.pot.divide_winnings(vec![vec![player_ids[1]], vec![player_ids[2]], vec![player_ids[0]]], &player_ids).unwrap();
+        assert_eq!(winnings.get(&player_ids[1]), 12);
+        assert_eq!(winnings.get(&player_ids[2]), 12);
+        assert_eq!(winnings.get(&player_ids[0]), 0);
+    }
+
+    #[test]
+    fn charge_antes_everyone_antes() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_ante(1, AnteMode::EveryoneAntes);
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        texas_holdem.players = players;
+        texas_holdem.charge_antes();
+        texas_holdem.play_blinds();
+        assert_eq!(texas_holdem.pot.get_total_stake(), 6);
+        assert_eq!(texas_holdem.pot.get_call_amount(), 2, "Antes should not count towards the call amount!");
+        assert_eq!(texas_holdem.players.get(0).unwrap().balance(), initial_balance-2);
+        assert_eq!(texas_holdem.players.get(1).unwrap().balance(), initial_balance-3);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance-1);
+
+        texas_holdem.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Call,
+            ActionOption::Check
+        ]);
+        texas_holdem.play_phase_one();
+        assert_eq!(texas_holdem.pot.get_total_stake(), 9);
+        assert!(texas_holdem.players.iter().all(|player| player.balance() == initial_balance-3));
+    }
+
+    #[test]
+    fn charge_antes_big_blind_ante() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        texas_holdem.set_ante(1, AnteMode::BigBlindAnte);
+        let initial_balance = 1000;
+        let players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
+            Player::new(Uuid::now_v7(), "player".to_string(), initial_balance)
+        ];
+        texas_holdem.players = players;
+        texas_holdem.charge_antes();
+        texas_holdem.play_blinds();
+        // the big blind pays the ante for all 3 players
+        assert_eq!(texas_holdem.pot.get_total_stake(), 6);
+        assert_eq!(texas_holdem.pot.get_call_amount(), 2, "Antes should not count towards the call amount!");
+        assert_eq!(texas_holdem.players.get(0).unwrap().balance(), initial_balance-1);
+        assert_eq!(texas_holdem.players.get(1).unwrap().balance(), initial_balance-5);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance);
+
+        texas_holdem.input.set_action_option_selections(vec![
+            ActionOption::Call,
+            ActionOption::Call,
+            ActionOption::Check
+        ]);
+        texas_holdem.play_phase_one();
+        assert_eq!(texas_holdem.pot.get_total_stake(), 9);
+        assert_eq!(texas_holdem.players.get(0).unwrap().balance(), initial_balance-2);
+        assert_eq!(texas_holdem.players.get(1).unwrap().balance(), initial_balance-5);
+        assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance-2);
+    }
+
     #[test]
     fn play_phase_one_check_only() {
         let big_blind_amount = 2;
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, big_blind_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -575,7 +964,7 @@ mod tests {
     #[test]
     fn play_phase_one_with_raises() {
         let big_blind_amount = 2;
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, big_blind_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -616,7 +1005,7 @@ mod tests {
     #[test]
     fn play_phase_one_with_folds() {
         let big_blind_amount = 2;
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, big_blind_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -654,7 +1043,7 @@ mod tests {
     #[test]
     fn play_all_folds_auto_win() {
         let big_blind_amount = 2;
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, big_blind_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -689,7 +1078,7 @@ mod tests {
     #[test]
     fn play_full_round_all_checks_and_calls() {
         let big_blind_amount = 2;
-        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, big_blind_amount, big_blind_amount / 2, DbHandler::new_dummy(), Uuid::now_v7());
         let initial_balance = 1000;
         let players = vec![
             Player::new(Uuid::now_v7(), "player".to_string(), initial_balance),
@@ -735,6 +1124,129 @@ mod tests {
         assert_eq!(texas_holdem.players.get(0).unwrap().balance(), initial_balance - big_blind_amount as usize);
         assert_eq!(texas_holdem.players.get(1).unwrap().balance(), initial_balance - big_blind_amount as usize);
         assert_eq!(texas_holdem.players.get(2).unwrap().balance(), initial_balance - big_blind_amount as usize);
-        texas_holdem.showdown();
+        texas_holdem.showdown().unwrap();
+    }
+
+    /// two players who are both all in for 100 before the flop
+    fn all_in_heads_up() -> TexasHoldem<TestInput> {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        let mut players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 100),
+            Player::new(Uuid::now_v7(), "player".to_string(), 100)
+        ];
+        texas_holdem.pot.clear(&players.iter().collect());
+        texas_holdem.pot.add_turn(&players[0].account_id(), Action::AllIn(100), 1, Vec::new());
+        texas_holdem.pot.add_turn(&players[1].account_id(), Action::Call, 1, Vec::new());
+        for player in players.iter_mut() {
+            player.bet(100).unwrap();
+        }
+        texas_holdem.players = players;
+        return texas_holdem;
+    }
+
+    #[test]
+    fn showdown_ranks_the_hole_cards_with_the_board() {
+        let mut texas_holdem = all_in_heads_up();
+        // the first player has the higher hole cards, but the second player makes a flush with the board
+        for (player, hole_cards) in texas_holdem.players.iter_mut().zip(["Ac 2d", "8h 7h"]) {
+            cards(hole_cards).into_iter().for_each(|card| player.obtain_card(card));
+        }
+        texas_holdem.community_cards = cards("Kh Kd 6h Jh 9c");
+        assert_eq!(texas_holdem.rank_player_hand(&texas_holdem.players[1]), Hand::rank_hand(&cards("Kh 6h Jh 8h 7h")));
+        let player_ids: Vec<Uuid> = texas_holdem.players.iter().map(|player| player.account_id()).collect();
+        assert_eq!(texas_holdem.winning_order(), vec![vec![player_ids[1]], vec![player_ids[0]], vec![]]);
+    }
+
+    #[test]
+    fn winning_order_uses_the_board() {
+        let mut texas_holdem = all_in_heads_up();
+        // the first player makes a full house with the board, the second player makes a flush
+        for (player, hole_cards) in texas_holdem.players.iter_mut().zip(["Kc 6d", "8h 7h"]) {
+            cards(hole_cards).into_iter().for_each(|card| player.obtain_card(card));
+        }
+        texas_holdem.community_cards = cards("Kh Kd 6h Jh 9c");
+        let player_ids: Vec<Uuid> = texas_holdem.players.iter().map(|player| player.account_id()).collect();
+        assert_eq!(texas_holdem.winning_order(), vec![vec![player_ids[0]], vec![player_ids[1]], vec![]]);
+
+        // in short deck hold'em, the flush beats the full house
+        texas_holdem.set_short_deck(true);
+        assert_eq!(texas_holdem.winning_order(), vec![vec![player_ids[1]], vec![player_ids[0]], vec![]]);
+    }
+
+    #[test]
+    fn offer_run_it_twice_all_players_agree() {
+        let mut texas_holdem = all_in_heads_up();
+        texas_holdem.input.set_run_it_twice_selections(vec![true, true]);
+        assert!(texas_holdem.offer_run_it_twice().unwrap());
+        assert_eq!(texas_holdem.community_cards.len(), 5);
+        assert_eq!(texas_holdem.second_runout.len(), 5);
+        assert_eq!(texas_holdem.deck.size(), 42);
+    }
+
+    #[test]
+    fn offer_run_it_twice_a_player_declines() {
+        let mut texas_holdem = all_in_heads_up();
+        texas_holdem.input.set_run_it_twice_selections(vec![true, false]);
+        assert!(!texas_holdem.offer_run_it_twice().unwrap());
+        assert_eq!(texas_holdem.community_cards.len(), 0);
+        assert_eq!(texas_holdem.second_runout.len(), 0);
+    }
+
+    #[test]
+    fn showdown_run_it_twice_each_player_wins_one_runout() {
+        let mut texas_holdem = all_in_heads_up();
+        for card in cards("Ah Ad") {
+            texas_holdem.players[0].obtain_card(card);
+        }
+        for card in cards("Kh Kd") {
+            texas_holdem.players[1].obtain_card(card);
+        }
+        // the aces hold on the first runout, but the kings make three of a kind on the second
+        texas_holdem.community_cards = cards("2c 7s 9d 3h 4s");
+        texas_holdem.second_runout = cards("Kc 5h");
+
+        texas_holdem.showdown().unwrap();
+        assert_eq!(texas_holdem.players[0].balance(), 100);
+        assert_eq!(texas_holdem.players[1].balance(), 100);
+    }
+
+    #[test]
+    fn all_in_pre_flop_deals_whole_board() {
+        let mut texas_holdem = TexasHoldem::<TestInput>::new(1000, 2, 1, DbHandler::new_dummy(), Uuid::now_v7());
+        // two players go all in with different stacks, and the third player covers them both
+        let mut players = vec![
+            Player::new(Uuid::now_v7(), "player".to_string(), 100),
+            Player::new(Uuid::now_v7(), "player".to_string(), 300),
+            Player::new(Uuid::now_v7(), "player".to_string(), 1000),
+        ];
+        texas_holdem.pot.clear(&players.iter().collect());
+        texas_holdem.pot.add_turn(&players[0].account_id(), Action::AllIn(100), 1, Vec::new());
+        texas_holdem.pot.add_turn(&players[1].account_id(), Action::AllIn(300), 1, Vec::new());
+        texas_holdem.pot.add_turn(&players[2].account_id(), Action::Call, 1, Vec::new());
+        for (player, stake) in players.iter_mut().zip([100, 300, 300]) {
+            player.bet(stake).unwrap();
+        }
+        texas_holdem.players = players;
+
+        texas_holdem.deal_initial_cards().unwrap();
+        texas_holdem.deal_flop_cards().unwrap();
+        texas_holdem.play_phase_two();
+        texas_holdem.deal_community_card().unwrap();
+        texas_holdem.play_phase_three();
+        texas_holdem.deal_community_card().unwrap();
+        texas_holdem.play_phase_four();
+
+        // nobody could bet, but the board is still completed for the showdown
+        assert_eq!(texas_holdem.community_cards.len(), 5);
+        // the main pot is contested by all three players, the side pot only by the two bigger stacks
+        let side_pots = texas_holdem.pot.get_side_pots();
+        assert_eq!(side_pots.iter().map(|side_pot| side_pot.amount).collect::<Vec<u32>>(), vec![300, 400]);
+        assert_eq!(side_pots[1].eligible_player_ids.len(), 2);
+        assert!(!side_pots[1].eligible_player_ids.contains(&texas_holdem.players[0].account_id()));
+
+        texas_holdem.showdown().unwrap();
+        assert_eq!(texas_holdem.players.iter().map(|player| player.balance()).sum::<usize>(), 1400);
+        // the shortest stack can win no more than the main pot
+        assert!(texas_holdem.players[0].balance() <= 300);
     }
 }
@@ -1,24 +1,31 @@
+use std::any::Any;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use warp::filters::reply::WithHeader;
 use warp::reply::Reply;
-use warp::{Filter, http::Method};
+use warp::{Filter, http::{Method, StatusCode}};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::json;
 use bson::doc;
 use uuid::Uuid;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
+use futures::{Stream, StreamExt, TryStreamExt};
+use strum::IntoEnumIterator;
 
-mod http_requests;
+pub(crate) mod http_requests;
 use http_requests::*;
 use crate::database::db_handler::DbHandler;
 use crate::input::server_input::ServerInput;
 use crate::input::Input;
-use crate::lobby::{self, Lobby};
-use crate::database::db_structs::Account;
+use crate::lobby::{self, Lobby, SessionTokens};
+use crate::database::db_structs::{self, Account};
 use crate::game_type::GameType;
+use crate::rules::RulesEnum;
+use crate::config::{Config, DEFAULT_STARTING_BALANCE};
 
 
 fn json_body<'a, T>() -> impl Filter<Extract = (T,), Error = warp::Rejection> + Clone 
@@ -30,10 +37,58 @@ where T: DeserializeOwned + Serialize + Clone + Send
 }
 
 
-#[derive(Clone)]
+// Number of lobby events kept for clients that fall behind, before the oldest are dropped.
+// Every lobby shares the channel, and each hand sends an event for each card dealt and each action taken.
+const LOBBY_EVENT_CAPACITY: usize = 1024;
+
+// Most requests that a client can make in each window before the rest are rejected.
+const RATE_LIMIT_REQUESTS: u32 = 60;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+// How long a lobby created by a user can stay empty before it is closed,
+// and how often the lobbies are checked for ones to close.
+const EMPTY_LOBBY_TIMEOUT: Duration = Duration::from_secs(300);
+const LOBBY_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+// Number of players listed on the leaderboard when the client doesn't ask for a number,
+// and the most that a client can ask for.
+const DEFAULT_LEADERBOARD_SIZE: u32 = 10;
+const MAX_LEADERBOARD_SIZE: u32 = 100;
+
+
 pub struct ServerState<I: Input + Send> {
     db_handler: DbHandler,
     lobbies: Arc<RwLock<HashMap<u32, Arc<RwLock<Lobby<I>>>>>>,
+    // Inputs of the lobbies whose games are played through the server,
+    // which can be reached while a hand is being played and the lobby is locked.
+    server_inputs: Arc<RwLock<HashMap<u32, ServerInput>>>,
+    // Session tokens of each lobby's users, which can also be checked while a hand is being played and the lobby is locked.
+    session_tokens: Arc<RwLock<HashMap<u32, SessionTokens>>>,
+    // Lobbies that are playing a hand, with the users who left them during it.
+    hands_in_play: Arc<RwLock<HashMap<u32, HashSet<Uuid>>>>,
+    // Number of requests made by each client in their current rate limit window, and when that window started.
+    request_counts: Arc<RwLock<HashMap<IpAddr, (u32, Instant)>>>,
+    // Changes to every lobby, each client watching a lobby subscribes and picks out that lobby's events.
+    lobby_events: broadcast::Sender<LobbyEvent>,
+    // Balance that new accounts start with.
+    starting_balance: u32,
+}
+
+
+// Not derived, as that would need the input to be Clone too, when only the shared state is cloned.
+impl<I: Input + Send> Clone for ServerState<I> {
+    fn clone(&self) -> Self {
+        Self {
+            db_handler: self.db_handler.clone(),
+            lobbies: self.lobbies.clone(),
+            server_inputs: self.server_inputs.clone(),
+            session_tokens: self.session_tokens.clone(),
+            hands_in_play: self.hands_in_play.clone(),
+            request_counts: self.request_counts.clone(),
+            lobby_events: self.lobby_events.clone(),
+            starting_balance: self.starting_balance,
+        }
+    }
 }
 
 
@@ -42,11 +97,93 @@ impl<I: Input + Send + Sync + 'static> ServerState<I> {
         Self {
             db_handler: db_handler,
             lobbies: Arc::new(RwLock::new(HashMap::new())),
+            server_inputs: Arc::new(RwLock::new(HashMap::new())),
+            session_tokens: Arc::new(RwLock::new(HashMap::new())),
+            hands_in_play: Arc::new(RwLock::new(HashMap::new())),
+            request_counts: Arc::new(RwLock::new(HashMap::new())),
+            lobby_events: broadcast::channel(LOBBY_EVENT_CAPACITY).0,
+            starting_balance: DEFAULT_STARTING_BALANCE,
+        }
+    }
+
+    // Sets the balance that new accounts start with.
+    pub fn set_starting_balance(&mut self, starting_balance: u32) {
+        self.starting_balance = starting_balance;
+    }
+
+    // Tells the clients watching a lobby about a change to it.
+    fn broadcast_lobby_event(&self, lobby_id: u32, update: LobbyUpdate) {
+        // sending only fails when nobody is watching, which is fine
+        let _ = self.lobby_events.send(LobbyEvent { lobby_id, update });
+    }
+
+    // Receives the changes to every lobby from now on.
+    pub fn subscribe_lobby_events(&self) -> broadcast::Receiver<LobbyEvent> {
+        self.lobby_events.subscribe()
+    }
+
+    // Counts a request made by a client.
+    // Returns false if they have made too many requests in the current window, and the request should be rejected.
+    pub async fn count_request(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut request_counts = self.request_counts.write().await;
+        let (count, window_start) = request_counts.entry(ip).or_insert((0, now));
+        if now.duration_since(*window_start) >= RATE_LIMIT_WINDOW {
+            *count = 0;
+            *window_start = now;
+        }
+        *count += 1;
+        *count <= RATE_LIMIT_REQUESTS
+    }
+
+    // Forgets the request counts of clients whose window is over, so that they don't build up.
+    pub async fn purge_request_counts(&self) {
+        let now = Instant::now();
+        self.request_counts.write().await.retain(|_, (_, window_start)| now.duration_since(*window_start) < RATE_LIMIT_WINDOW);
+    }
+
+    // Saves a lobby to the database, so that it can be restored if the server restarts.
+    async fn save_lobby(db_handler: &DbHandler, lobby: &Lobby<I>) {
+        match db_handler.replace_document_by_id(lobby.game_id(), lobby.to_document(), "Lobbies").await {
+            Some(Err(e)) => println!("Error when saving Lobby #{}: {:?}", lobby.id(), e),
+            _ => (),
+        }
+    }
+
+    // Restores the lobbies that were saved to the database before the server stopped.
+    // Returns the number of lobbies that were restored.
+    pub async fn load_lobbies(&self) -> usize {
+        let lobby_documents: Vec<db_structs::Lobby> = match self.db_handler.get_documents(doc! {}, "Lobbies").await {
+            Some(Ok(cursor)) => match cursor.try_collect().await {
+                Ok(lobby_documents) => lobby_documents,
+                Err(e) => {
+                    println!("Error when loading saved lobbies: {:?}", e);
+                    return 0;
+                },
+            },
+            Some(Err(e)) => {
+                println!("Error when loading saved lobbies: {:?}", e);
+                return 0;
+            },
+            None => return 0,
+        };
+        let lobby_count = lobby_documents.len();
+        for lobby_document in lobby_documents {
+            println!("Restoring Lobby #{}", lobby_document.lobby_id);
+            self.add_lobby(Lobby::from_document(lobby_document).await).await;
         }
+        lobby_count
     }
 
     // Adds a lobby to server state.
     pub async fn add_lobby(&self, new_lobby: Lobby<I>) {
+        // a game played through the server counts down each player's turn to the clients watching the lobby
+        if let Some(server_input) = (new_lobby.rules().input() as &dyn Any).downcast_ref::<ServerInput>() {
+            server_input.set_lobby_events(new_lobby.id(), self.lobby_events.clone());
+            self.server_inputs.write().await.insert(new_lobby.id(), server_input.clone());
+        }
+        self.session_tokens.write().await.insert(new_lobby.id(), new_lobby.session_tokens());
+        Self::save_lobby(&self.db_handler, &new_lobby).await;
         let mut lobbies = self.lobbies.write().await;
         lobbies.insert(new_lobby.id(), Arc::new(RwLock::new(new_lobby)));
     }
@@ -68,8 +205,10 @@ impl<I: Input + Send + Sync + 'static> ServerState<I> {
         next_lobby_id
     }
 
-    // Adds user to a specific lobby.
-    pub async fn join_user(&self, user_id: Uuid, join_lobby_id: u32) -> Result<(), ()> {
+    // Adds user to a specific lobby, with the stack they are bringing,
+    // and the password if the lobby is private.
+    // Returns the user's session token for reconnecting to the lobby.
+    pub async fn join_user(&self, user_id: Uuid, join_lobby_id: u32, buy_in: u32, password: Option<&str>) -> Result<Uuid, ()> {
         let lobbies = self.lobbies.read().await;
         for lobby_arc in lobbies.values() {
             let lobby = lobby_arc.read().await;
@@ -83,41 +222,227 @@ impl<I: Input + Send + Sync + 'static> ServerState<I> {
             None => Err(()),
             Some(join_lobby_arc) => {
                 let mut join_lobby = join_lobby_arc.write().await;
-                join_lobby.join_user(user_id)
+                let session_token = join_lobby.join_user(user_id, buy_in, password)?;
+                Self::save_lobby(&self.db_handler, &join_lobby).await;
+                self.broadcast_lobby_event(join_lobby_id, LobbyUpdate::UserJoined { user_id: user_id.simple().to_string() });
+                Ok(session_token)
             },
         }
     }
 
     // Removes user from a specific lobby.
+    // If the lobby is left empty, it is closed.
+    // A user who leaves while a hand is being played is folded at their turns for the rest of it,
+    // so the chips they have already bet stay in the pot, and is removed once the hand is over.
     pub async fn leave_user(&self, user_id: Uuid, leave_lobby_id: u32) -> Result<(), ()> {
-        let lobbies = self.lobbies.read().await;
-        return match lobbies.get(&leave_lobby_id) {
-            None => {
-                println!("User {} cannot leave Lobby #{} because the lobby doesn't exist", user_id, leave_lobby_id);
-                Err(())
+        if let Some(left_users) = self.hands_in_play.write().await.get_mut(&leave_lobby_id) {
+            if let Some(server_input) = self.server_inputs.read().await.get(&leave_lobby_id) {
+                server_input.fold_left_player(user_id);
+            }
+            left_users.insert(user_id);
+            return Ok(());
+        }
+        {
+            let lobbies = self.lobbies.read().await;
+            match lobbies.get(&leave_lobby_id) {
+                None => {
+                    println!("User {} cannot leave Lobby #{} because the lobby doesn't exist", user_id, leave_lobby_id);
+                    return Err(());
+                }
+                Some(leave_lobby_arc) => {
+                    let mut leave_lobby = leave_lobby_arc.write().await;
+                    leave_lobby.leave_user(user_id)?;
+                    Self::save_lobby(&self.db_handler, &leave_lobby).await;
+                    self.broadcast_lobby_event(leave_lobby_id, LobbyUpdate::UserLeft { user_id: user_id.simple().to_string() });
+                },
             }
-            Some(leave_lobby_arc) => {
-                let mut leave_lobby = leave_lobby_arc.write().await;
-                leave_lobby.leave_user(user_id)
+        }
+        // the lobbies have to be unlocked before the lobby can be removed from them,
+        // so whether it is empty is checked again once they are locked to remove it
+        self.close_lobby_if_abandoned(leave_lobby_id, |_| true).await;
+        Ok(())
+    }
+
+    // Removes a lobby from the server, telling the clients watching it that it was closed.
+    pub async fn close_lobby(&self, lobby_id: u32) -> Result<(), ()> {
+        let closed_lobby_arc = match self.lobbies.write().await.remove(&lobby_id) {
+            None => {
+                println!("Cannot close Lobby #{} because the lobby doesn't exist", lobby_id);
+                return Err(());
             },
+            Some(closed_lobby_arc) => closed_lobby_arc,
         };
+        self.clean_up_closed_lobby(lobby_id, closed_lobby_arc).await;
+        Ok(())
     }
 
-    // Starts running the game-type for lobby.
-    // This method is WIP and its functionality is not verified.
-    pub async fn start_game(&self, lobby_id: u32) -> Result<(), ()> {
+    // Closes a lobby if it is abandoned: nobody is in it, no hand is being played in it, and is_abandoned holds for it.
+    // This is checked while the lobbies are locked to remove it, so that a user who joins the lobby
+    // in the meantime doesn't have it closed under them.
+    // Returns true if the lobby was closed.
+    async fn close_lobby_if_abandoned<F: Fn(&Lobby<I>) -> bool>(&self, lobby_id: u32, is_abandoned: F) -> bool {
+        let closed_lobby_arc = {
+            // the lobbies are always locked before the hands in play
+            let mut lobbies = self.lobbies.write().await;
+            if self.hands_in_play.read().await.contains_key(&lobby_id) {
+                return false;
+            }
+            let lobby_is_abandoned = match lobbies.get(&lobby_id) {
+                None => false,
+                // a lobby that is locked is busy, so it isn't abandoned
+                Some(lobby_arc) => match lobby_arc.try_read() {
+                    Ok(lobby) => lobby.count_users() == 0 && is_abandoned(&lobby),
+                    Err(_) => false,
+                },
+            };
+            if !lobby_is_abandoned {
+                return false;
+            }
+            lobbies.remove(&lobby_id).expect("Expected the abandoned lobby to still be in the lobbies")
+        };
+        self.clean_up_closed_lobby(lobby_id, closed_lobby_arc).await;
+        true
+    }
+
+    // Tells the clients watching a lobby that was removed from the server that it was closed.
+    // If a game was played in it, its rounds and turns are deleted from the database,
+    // as nobody is left to look at the lobby's history.
+    async fn clean_up_closed_lobby(&self, lobby_id: u32, closed_lobby_arc: Arc<RwLock<Lobby<I>>>) {
+        self.server_inputs.write().await.remove(&lobby_id);
+        self.session_tokens.write().await.remove(&lobby_id);
+        let closed_lobby = closed_lobby_arc.read().await;
+        match self.db_handler.delete_document_by_id::<db_structs::Lobby>(closed_lobby.game_id(), "Lobbies").await {
+            Some(Err(e)) => println!("Error when deleting saved Lobby #{}: {:?}", lobby_id, e),
+            _ => (),
+        }
+        if closed_lobby.current_round() > 0 {
+            match self.db_handler.delete_lobby_data(closed_lobby.game_id()).await {
+                Some(Ok(res)) => println!("Deleted {} rounds of closed Lobby #{}", res.deleted_count, lobby_id),
+                Some(Err(e)) => println!("Error when deleting data of closed Lobby #{}: {:?}", lobby_id, e),
+                None => (),
+            }
+        }
+        self.broadcast_lobby_event(lobby_id, LobbyUpdate::LobbyClosed);
+    }
+
+    // Closes the lobbies that have been abandoned, lobbies without a hand in play are closed if
+    // their game has finished and all their users have left, or if they were created by a user
+    // and have been empty for at least empty_timeout. The server's own lobbies are kept while nobody has played in them.
+    // Returns the number of lobbies that were closed.
+    pub async fn prune_lobbies(&self, empty_timeout: Duration) -> usize {
+        let lobby_ids: Vec<u32> = self.lobbies.read().await.keys().copied().collect();
+        let mut closed_lobby_count = 0;
+        for lobby_id in lobby_ids {
+            let closed = self.close_lobby_if_abandoned(lobby_id, |lobby| {
+                let empty_past_timeout = lobby.empty_since().is_some_and(|empty_since| empty_since.elapsed() >= empty_timeout);
+                lobby.current_round() > 0 || (empty_past_timeout && lobby.creator_id().is_some())
+            }).await;
+            if closed {
+                println!("Closed abandoned Lobby #{}", lobby_id);
+                closed_lobby_count += 1;
+            }
+        }
+        closed_lobby_count
+    }
+
+    // Renames a specific lobby.
+    // Only the user who created the lobby is allowed to rename it.
+    pub async fn rename_lobby(&self, user_id: Uuid, lobby_id: u32, new_name: String) -> Result<(), ()> {
         let lobbies = self.lobbies.read().await;
-        match lobbies.get(&lobby_id) {
+        return match lobbies.get(&lobby_id) {
             None => {
-                println!("Start Lobby #{} because the lobby doesn't exist", lobby_id);
+                println!("User {} cannot rename Lobby #{} because the lobby doesn't exist", user_id, lobby_id);
                 Err(())
             },
+            Some(rename_lobby_arc) => {
+                let mut rename_lobby = rename_lobby_arc.write().await;
+                if rename_lobby.creator_id() != Some(user_id) {
+                    println!("User {} cannot rename Lobby #{} because they did not create it", user_id, lobby_id);
+                    return Err(());
+                }
+                rename_lobby.rename(new_name)?;
+                Self::save_lobby(&self.db_handler, &rename_lobby).await;
+                Ok(())
+            },
+        };
+    }
+
+    // Whether a session token is the one that was issued to a user when they joined one of their lobbies.
+    pub async fn check_session_token(&self, user_id: Uuid, session_token: Uuid) -> bool {
+        self.session_tokens.read().await.values().any(|session_tokens| session_tokens.check(user_id, session_token))
+    }
+
+    // Changes the name a user is shown as in every lobby they are in.
+    pub async fn set_display_name(&self, user_id: Uuid, display_name: String) -> Result<(), ()> {
+        // the lobbies are unlocked before each lobby is locked, so that renaming doesn't hold up
+        // the whole server while it waits for a lobby that is playing a hand
+        let lobby_arcs: Vec<Arc<RwLock<Lobby<I>>>> = self.lobbies.read().await.values().cloned().collect();
+        let mut renamed = false;
+        for lobby_arc in lobby_arcs.iter() {
+            let mut lobby = lobby_arc.write().await;
+            if lobby.get_user(user_id).is_some() {
+                lobby.set_display_name(user_id, display_name.clone())?;
+                Self::save_lobby(&self.db_handler, &lobby).await;
+                renamed = true;
+            }
+        }
+        if !renamed {
+            println!("User {} cannot change their display name because they are not in a lobby", user_id);
+            return Err(());
+        }
+        Ok(())
+    }
+
+    // Starts running the game-type for lobby, at the request of a user who is in the lobby or created it.
+    // Returns the reason if the game can't be started, e.g. a hand is already being played in the lobby.
+    pub async fn start_game(&self, user_id: Uuid, lobby_id: u32) -> Result<(), String> {
+        let lobbies = self.lobbies.read().await;
+        match lobbies.get(&lobby_id) {
+            None => Err(format!("Lobby #{} doesn't exist", lobby_id)),
             Some(start_lobby_arc) => {
+                {
+                    // checked and marked as in play under one lock, so that only one of two requests to start the lobby starts a hand
+                    let mut hands_in_play = self.hands_in_play.write().await;
+                    if hands_in_play.contains_key(&lobby_id) {
+                        return Err("A game is already being played in the lobby".to_string());
+                    }
+                    let start_lobby = start_lobby_arc.read().await;
+                    if start_lobby.get_user(user_id).is_none() && start_lobby.creator_id() != Some(user_id) {
+                        return Err("Only the lobby's users and its creator can start a game".to_string());
+                    }
+                    // the reason is given along with how many players the game type needs
+                    start_lobby.can_start_game().map_err(|message| format!("{}, {} needs between {} and {} players",
+                        message, start_lobby.game_type(), start_lobby.rules().min_players(), start_lobby.rules().max_players()))?;
+                    hands_in_play.insert(lobby_id, HashSet::new());
+                }
                 let start_lobby_arc_clone = start_lobby_arc.clone();
+                let state = self.clone();
                 println!("Before start_game thread spawn");
                 tokio::spawn(async move {
-                    let mut start_lobby = start_lobby_arc_clone.write().await;
-                    start_lobby.start_game().await;
+                    // the hand is played on a blocking thread of its own, so that the lobby is still marked as no longer in play if it panics,
+                    // and so that the input waiting for the players' actions never blocks a thread of the runtime that receives them,
+                    // which would stall every request on a runtime with only one thread
+                    let hand_state = state.clone();
+                    let runtime = tokio::runtime::Handle::current();
+                    let hand = tokio::task::spawn_blocking(move || runtime.block_on(async move {
+                        let mut start_lobby = start_lobby_arc_clone.write().await;
+                        hand_state.broadcast_lobby_event(lobby_id, LobbyUpdate::RoundStarted);
+                        start_lobby.start_game().await;
+                        // saved after the round, so that the round number carries on if the server restarts
+                        Self::save_lobby(&hand_state.db_handler, &start_lobby).await;
+                        hand_state.broadcast_lobby_event(lobby_id, LobbyUpdate::RoundFinished);
+                    }));
+                    if let Err(error) = hand.await {
+                        println!("The hand in Lobby #{} failed: {}", lobby_id, error);
+                    }
+                    // now that the hand is over and the lobby is unlocked, the users who left during it can be removed
+                    let left_users = state.hands_in_play.write().await.remove(&lobby_id).unwrap_or_default();
+                    for user_id in left_users {
+                        let _ = state.leave_user(user_id, lobby_id).await;
+                    }
+                    if let Some(server_input) = state.server_inputs.read().await.get(&lobby_id) {
+                        server_input.clear_left_players();
+                    }
                 });
                 Ok(())
             }
@@ -125,6 +450,75 @@ impl<I: Input + Send + Sync + 'static> ServerState<I> {
     }
 }
 
+impl ServerState<ServerInput> {
+    // Reattaches a user whose connection dropped to their seat in a lobby, if their session token is valid.
+    // Returns the action options they were being offered as JSON, so the client can show them again,
+    // or None if they aren't being asked for an action, in which case they play from the next hand.
+    // The lobby stays locked for the whole of a hand, so its session tokens and input are reached without locking it.
+    pub async fn reconnect_user(&self, user_id: Uuid, lobby_id: u32, session_token: Uuid) -> Result<Option<String>, ()> {
+        self.check_lobby_session_token(user_id, lobby_id, session_token, "reconnect to").await?;
+        // reconnecting clears the user's disconnected flag, so they are asked for their actions again
+        Ok(self.server_inputs.read().await.get(&lobby_id).and_then(|server_input| server_input.reconnect(user_id)))
+    }
+
+    // Gets the action options a user is being offered in a lobby as JSON, or None if it isn't their turn.
+    pub async fn offered_actions(&self, user_id: Uuid, lobby_id: u32, session_token: Uuid) -> Result<Option<String>, ()> {
+        self.check_lobby_session_token(user_id, lobby_id, session_token, "get the action options of").await?;
+        Ok(self.server_inputs.read().await.get(&lobby_id).and_then(|server_input| server_input.offered_actions(user_id)))
+    }
+
+    // Takes the messages waiting for a user in a lobby as a JSON array, e.g. their cards and the bet amounts they can choose from.
+    // Each message is only given out once, so the client should collect them regularly while it is in the lobby.
+    pub async fn take_messages(&self, user_id: Uuid, lobby_id: u32, session_token: Uuid) -> Result<String, ()> {
+        self.check_lobby_session_token(user_id, lobby_id, session_token, "get the messages of").await?;
+        match self.server_inputs.read().await.get(&lobby_id) {
+            None => Err(()),
+            Some(server_input) => Ok(server_input.take_messages_json(user_id)),
+        }
+    }
+
+    // Passes a user's chosen action option (as JSON, e.g. "call"), bet amount and/or yes or no answer on to the game in a lobby.
+    // They are picked up the next time the user is asked for an action, an amount or a yes or no question.
+    pub async fn submit_action(&self, user_id: Uuid, lobby_id: u32, session_token: Uuid, action: Option<&str>, amount: Option<u32>, choice: Option<bool>) -> Result<(), ()> {
+        self.check_lobby_session_token(user_id, lobby_id, session_token, "submit an action to").await?;
+        let server_inputs = self.server_inputs.read().await;
+        let server_input = match server_inputs.get(&lobby_id) {
+            None => return Err(()),
+            Some(server_input) => server_input,
+        };
+        if let Some(action) = action {
+            if let Err(error) = server_input.submit_action_json(user_id, action) {
+                println!("User {} submitted an invalid action to Lobby #{}: {}", user_id, lobby_id, error);
+                return Err(());
+            }
+        }
+        if let Some(amount) = amount {
+            server_input.submit_amount(user_id, amount);
+        }
+        if let Some(choice) = choice {
+            server_input.submit_choice(user_id, choice);
+        }
+        Ok(())
+    }
+
+    // Checks the session token a user was given when they joined a lobby.
+    // The lobby stays locked for the whole of a hand, so its session tokens are reached without locking it.
+    async fn check_lobby_session_token(&self, user_id: Uuid, lobby_id: u32, session_token: Uuid, attempted: &str) -> Result<(), ()> {
+        let session_token_is_valid = match self.session_tokens.read().await.get(&lobby_id) {
+            None => {
+                println!("User {} cannot {} Lobby #{} because the lobby doesn't exist", user_id, attempted, lobby_id);
+                return Err(());
+            },
+            Some(session_tokens) => session_tokens.check(user_id, session_token),
+        };
+        if !session_token_is_valid {
+            println!("User {} cannot {} Lobby #{} because their session token is wrong", user_id, attempted, lobby_id);
+            return Err(());
+        }
+        Ok(())
+    }
+}
+
 // Add headers to reply to allow for CORS.
 // This enables the client to communicate this server.
 fn add_allow_cors<R: Reply>(reply: R) -> warp::reply::WithHeader<R> {
@@ -136,7 +530,8 @@ async fn create_new_account<I: Input + Send + Sync>(state: ServerState<I>) -> Re
     println!("Serving create-account request...");
     let new_account_id = Uuid::now_v7().simple().to_string();
     match state.db_handler.add_document(doc! {
-        "_id": new_account_id.clone()
+        "_id": new_account_id.clone(),
+        "starting_balance": state.starting_balance
     }, "Accounts").await {
         None => Ok(add_allow_cors(warp::reply::json(&json!({ "new_account_id": new_account_id })))),
         Some(res) => {
@@ -154,10 +549,66 @@ async fn create_new_account<I: Input + Send + Sync>(state: ServerState<I>) -> Re
     }
 }
 
+// Generates new account with a username.
+// Usernames must be unique, so registering with a username that is already taken is rejected.
+async fn register_account<I: Input + Send + Sync>(state: ServerState<I>, attempt: RegisterAttempt) -> Result<impl warp::Reply, warp::Rejection> {
+    println!("Serving create-account request for username {}...", attempt.username);
+    let username = attempt.username.trim().to_string();
+    if username.is_empty() {
+        return Ok(add_allow_cors(warp::reply::with_status(warp::reply::json(&json!({
+            "error": "Username cannot be blank"
+        })), StatusCode::BAD_REQUEST)));
+    }
+    let username_taken = warp::reply::json(&json!({
+        "error": format!("Username {} is already taken", username)
+    }));
+    if let Some(Ok(Some(_))) = state.db_handler.get_document::<Account>(doc! { "username": username.clone() }, "Accounts").await {
+        return Ok(add_allow_cors(warp::reply::with_status(username_taken, StatusCode::CONFLICT)));
+    }
+
+    let new_account_id = Uuid::now_v7();
+    let new_account_reply = warp::reply::json(&json!({ "new_account_id": new_account_id.simple().to_string() }));
+    match state.db_handler.add_document(Account {
+        _id: new_account_id,
+        username: Some(username),
+        starting_balance: state.starting_balance,
+    }, "Accounts").await {
+        None => Ok(add_allow_cors(warp::reply::with_status(new_account_reply, StatusCode::OK))),
+        Some(res) => {
+            match res {
+                Ok(_) => {
+                    println!("Successfully created new account {}", new_account_id);
+                    Ok(add_allow_cors(warp::reply::with_status(new_account_reply, StatusCode::OK)))
+                },
+                // the username was taken after it was checked
+                Err(e) if DbHandler::is_duplicate_key_error(&e) => {
+                    Ok(add_allow_cors(warp::reply::with_status(username_taken, StatusCode::CONFLICT)))
+                },
+                Err(e) => {
+                    println!("Error while create new account: {}", e);
+                    Err(warp::reject())
+                }
+            }
+        },
+    }
+}
+
 // Checks database if account matches credientials and attempts to login as a user.
-// Current login process only checks if there is an existing account with a uuid.
+// Current login process only checks if there is an existing account with a uuid or username.
 async fn try_login<I: Input + Send + Sync>(state: ServerState<I>, creds: LoginAttempt) -> Result<impl warp::Reply, warp::Rejection> {
     println!("{:?}", creds);
+    if let Some(username) = creds.username {
+        return match state.db_handler.get_document::<Account>(doc! { "username": username }, "Accounts").await {
+            // there are no usernames to look up without a database
+            None => Err(warp::reject()),
+            Some(Ok(None)) => Err(warp::reject()),
+            Some(Ok(Some(account))) => Ok(add_allow_cors(warp::reply::json(&json!({ "login_account_id": account._id.simple().to_string() })))),
+            Some(Err(e)) => {
+                println!("Error while attempting login: {}", e);
+                Err(warp::reject())
+            }
+        };
+    }
     match state.db_handler.get_document::<Account>(doc! { "_id": creds.uuid.clone() }, "Accounts").await {
         None => Ok(add_allow_cors(warp::reply::json(&json!({ "login_account_id": creds.uuid })))),
         Some(res) => match res {
@@ -182,14 +633,36 @@ async fn get_all_lobbies<I: Input + Send + Sync>(state: ServerState<I>) -> Resul
         let lobby = lobby_ptr.read().await;
         lobby_list_items.push(LobbyListItem {
             lobby_id: *lobby_id,
+            name: lobby.name().to_string(),
             status: lobby.status(),
             user_count: lobby.count_users(),
             game_type: lobby.rules().to_game_type(),
+            is_private: lobby.is_private(),
         })
     }
     Ok(add_allow_cors(warp::reply::json(&lobby_list_items)))
 }
 
+// Gets the game types that lobbies can be created with, along with the limits and defaults of each,
+// so that the client can build its lobby creation form from them.
+async fn get_game_types() -> Result<impl warp::Reply, warp::Rejection> {
+    let game_type_infos: Vec<GameTypeInfo> = GameType::iter().map(|game_type| {
+        let rules = RulesEnum::<ServerInput>::new(game_type.clone(), lobby::DEFAULT_RAISE_LIMIT, lobby::DEFAULT_BIG_BLIND, lobby::DEFAULT_SMALL_BLIND, DbHandler::new_dummy(), Uuid::now_v7());
+        GameTypeInfo {
+            name: game_type.to_string(),
+            game_type,
+            deal_order_description: rules.deal_order_description().to_string(),
+            min_players: rules.min_players(),
+            max_players: rules.max_players(),
+            uses_blinds: rules.uses_blinds(),
+            default_raise_limit: lobby::DEFAULT_RAISE_LIMIT,
+            default_minimum_bet: lobby::DEFAULT_BIG_BLIND,
+            default_small_blind: lobby::DEFAULT_SMALL_BLIND,
+        }
+    }).collect();
+    Ok(add_allow_cors(warp::reply::json(&game_type_infos)))
+}
+
 // Get information for a specific lobby and return it to the client.
 async fn get_lobby_info<I: Input + Send + Sync>(state: ServerState<I>, lobby_id: u32) -> Result<impl warp::Reply, warp::Rejection> {
     println!("Retrieving lobby #{}'s info...", lobby_id);
@@ -198,6 +671,8 @@ async fn get_lobby_info<I: Input + Send + Sync>(state: ServerState<I>, lobby_id:
         Some(lobby_arc) => {
             let lobby = lobby_arc.read().await;
             let active_users = lobby.active_players();
+            let server_inputs = state.server_inputs.read().await;
+            let server_input = server_inputs.get(&lobby_id);
             let mut user_infos = Vec::new();
             for user in lobby.users().iter() {
                 let mut is_active = false;
@@ -208,22 +683,29 @@ async fn get_lobby_info<I: Input + Send + Sync>(state: ServerState<I>, lobby_id:
                 }
                 user_infos.push(LobbyUserInfo {
                     user_id: user.simple().to_string(),
+                    display_name: lobby.display_name(*user),
                     is_active,
+                    time_bank_seconds: server_input.map(|server_input| server_input.time_bank(*user).as_secs()),
                 })
             }
 
             Ok(add_allow_cors(warp::reply::json(&LobbyInfo {
                 lobby_id,
+                name: lobby.name().to_string(),
                 status: lobby.status(),
                 users: user_infos,
                 game_type: lobby.game_type(),
+                deal_order_description: lobby.deal_order_description().to_string(),
+                min_buy_in: lobby.min_buy_in(),
+                max_buy_in: lobby.max_buy_in(),
+                starting_balance: lobby.starting_balance(),
             })))
         },
         None => Err(warp::reject())
     }
 }
 
-// Handle processing lobby action like creating lobbies, users joining lobbies, and users leaving lobbies.
+// Handle processing lobby action like creating lobbies, users joining lobbies, users leaving lobbies, and renaming lobbies.
 async fn process_lobby_action<I: Input + Send + Sync + 'static>(state: ServerState<I>, action: LobbyAction) -> Result<impl warp::Reply, warp::Rejection> {
     println!("Lobby action: {:?}", action);
     if let Ok(user_id) = Uuid::parse_str(&action.user_id) {
@@ -231,22 +713,48 @@ async fn process_lobby_action<I: Input + Send + Sync + 'static>(state: ServerSta
             LobbyActionType::Create => {
                 let next_lobby_id = state.get_new_lobby_id().await;
                 println!("Creating lobby #{}", next_lobby_id);
-                state.add_lobby(Lobby::new(next_lobby_id, action.game_type).await).await;
+                let small_blind = action.small_blind.unwrap_or(lobby::DEFAULT_SMALL_BLIND);
+                let big_blind = action.big_blind.unwrap_or(lobby::DEFAULT_BIG_BLIND);
+                if big_blind == 0 || small_blind > big_blind {
+                    println!("Cannot create lobby with blinds of {}/{}", small_blind, big_blind);
+                    return Err(warp::reject());
+                }
+                let mut new_lobby = Lobby::new_with_creator(next_lobby_id, action.game_type, user_id, action.name, small_blind, big_blind).await;
+                let min_buy_in = action.min_buy_in.unwrap_or(new_lobby.min_buy_in());
+                let max_buy_in = action.max_buy_in.unwrap_or(new_lobby.max_buy_in());
+                if new_lobby.set_buy_in_limits(min_buy_in, max_buy_in).is_err() {
+                    println!("Cannot create lobby with buy-in limits of {} to {}", min_buy_in, max_buy_in);
+                    return Err(warp::reject());
+                }
+                if new_lobby.set_starting_balance(action.starting_balance).is_err() {
+                    println!("Cannot create lobby with a starting balance of {:?} outside of its buy-in limits", action.starting_balance);
+                    return Err(warp::reject());
+                }
+                new_lobby.set_password(action.password);
+                state.add_lobby(new_lobby).await;
                 Ok(add_allow_cors(warp::reply::json(&json!({
                     "new_lobby_id": next_lobby_id
                 }))))
             },
             LobbyActionType::Join => {
                 println!("User {} is joinning lobby #{}", user_id, action.lobby_id);
-                match state.join_user(user_id, action.lobby_id).await {
-                    Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
-                        "joinned_lobby_id": action.lobby_id
+                // users who don't choose a stack bring the lobby's starting balance, if it has one
+                let buy_in = match action.buy_in {
+                    Some(buy_in) => buy_in,
+                    None => match state.lobbies.read().await.get(&action.lobby_id) {
+                        Some(lobby_arc) => lobby_arc.read().await.starting_balance().unwrap_or(lobby::DEFAULT_BUY_IN),
+                        None => lobby::DEFAULT_BUY_IN,
+                    },
+                };
+                match state.join_user(user_id, action.lobby_id, buy_in, action.password.as_deref()).await {
+                    Ok(session_token) => Ok(add_allow_cors(warp::reply::json(&json!({
+                        "joinned_lobby_id": action.lobby_id,
+                        "session_token": session_token.simple().to_string()
                     })))),
                     Err(()) => Err(warp::reject()),
                 }
             },
             LobbyActionType::Leave => {
-                //TODO: Clean up lobbies with zero users.
                 match state.leave_user(user_id, action.lobby_id).await {
                     Err(()) => Err(warp::reject()),
                     Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
@@ -254,14 +762,28 @@ async fn process_lobby_action<I: Input + Send + Sync + 'static>(state: ServerSta
                     })))),
                 }
             },
+            LobbyActionType::Rename => {
+                let new_name = match action.name {
+                    Some(name) => name,
+                    None => return Err(warp::reject()),
+                };
+                match state.rename_lobby(user_id, action.lobby_id, new_name).await {
+                    Err(()) => Err(warp::reject()),
+                    Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
+                        "renamed_lobby_id": action.lobby_id
+                    })))),
+                }
+            },
             LobbyActionType::Start => {
-                Err(warp::reject())
-                // match state.start_game(action.lobby_id).await {
-                //     Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
-                //         "start_lobby_id": action.lobby_id,
-                //     })))),
-                //     Err(()) => Err(warp::reject()),
-                // }
+                match state.start_game(user_id, action.lobby_id).await {
+                    Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
+                        "start_lobby_id": action.lobby_id,
+                    })))),
+                    Err(message) => {
+                        println!("Cannot start Lobby #{}: {}", action.lobby_id, message);
+                        Err(warp::reject::custom(CannotStartGame(message)))
+                    },
+                }
             }
         }
     } else {
@@ -270,25 +792,384 @@ async fn process_lobby_action<I: Input + Send + Sync + 'static>(state: ServerSta
     }
 }
 
-// Sets up routing and starts up a warp server.
-pub async fn run_server() {
-    let db_handler = match DbHandler::new("mongodb://localhost:27017/".to_string(), "test".to_string()).await {
-        Ok(handler) => handler,
-        Err(e) => {
-            println!("Server initializing dummy due to error while initializing database: {}", e);
-            DbHandler::new_dummy()
+// Reconnects a user to a lobby with their session token (see ServerState::reconnect_user),
+// and re-sends the action options they are being offered, if any.
+async fn reconnect(state: ServerState<ServerInput>, attempt: ReconnectAttempt) -> Result<impl warp::Reply, warp::Rejection> {
+    let (user_id, session_token) = match (Uuid::parse_str(&attempt.user_id), Uuid::parse_str(&attempt.session_token)) {
+        (Ok(user_id), Ok(session_token)) => (user_id, session_token),
+        _ => {
+            println!("Error parsing uuid while processing reconnect.");
+            return Err(warp::reject());
+        },
+    };
+    match state.reconnect_user(user_id, attempt.lobby_id, session_token).await {
+        Err(()) => Err(warp::reject()),
+        Ok(pending_action_options) => {
+            let pending_action_options: Option<serde_json::Value> = pending_action_options
+                .map(|action_options| serde_json::from_str(&action_options).expect("Offered action options should be valid JSON"));
+            Ok(add_allow_cors(warp::reply::json(&json!({
+                "reconnected_lobby_id": attempt.lobby_id,
+                "pending_action_options": pending_action_options
+            }))))
+        },
+    }
+}
+
+// Gets the action options a user is being offered, so the client knows when it is their turn and what they can do.
+async fn get_offered_actions(state: ServerState<ServerInput>, request: SessionRequest) -> Result<impl warp::Reply, warp::Rejection> {
+    let (user_id, session_token) = match (Uuid::parse_str(&request.user_id), Uuid::parse_str(&request.session_token)) {
+        (Ok(user_id), Ok(session_token)) => (user_id, session_token),
+        _ => {
+            println!("Error parsing uuid while getting offered actions.");
+            return Err(warp::reject());
+        },
+    };
+    match state.offered_actions(user_id, request.lobby_id, session_token).await {
+        Err(()) => Err(warp::reject()),
+        Ok(action_options) => {
+            let action_options: Option<serde_json::Value> = action_options
+                .map(|action_options| serde_json::from_str(&action_options).expect("Offered action options should be valid JSON"));
+            Ok(add_allow_cors(warp::reply::json(&json!({
+                "lobby_id": request.lobby_id,
+                "action_options": action_options
+            }))))
+        },
+    }
+}
+
+// Gets the messages the game has sent a user since they last collected them (see ServerState::take_messages).
+async fn get_messages(state: ServerState<ServerInput>, request: SessionRequest) -> Result<impl warp::Reply, warp::Rejection> {
+    let (user_id, session_token) = match (Uuid::parse_str(&request.user_id), Uuid::parse_str(&request.session_token)) {
+        (Ok(user_id), Ok(session_token)) => (user_id, session_token),
+        _ => {
+            println!("Error parsing uuid while getting messages.");
+            return Err(warp::reject());
+        },
+    };
+    match state.take_messages(user_id, request.lobby_id, session_token).await {
+        Err(()) => Err(warp::reject()),
+        Ok(messages) => {
+            let messages: serde_json::Value = serde_json::from_str(&messages).expect("Messages should be valid JSON");
+            Ok(add_allow_cors(warp::reply::json(&json!({
+                "lobby_id": request.lobby_id,
+                "messages": messages
+            }))))
+        },
+    }
+}
+
+// Submits a user's response to the action options they were offered (see ServerState::submit_action).
+async fn submit_action(state: ServerState<ServerInput>, submission: ActionSubmission) -> Result<impl warp::Reply, warp::Rejection> {
+    let (user_id, session_token) = match (Uuid::parse_str(&submission.user_id), Uuid::parse_str(&submission.session_token)) {
+        (Ok(user_id), Ok(session_token)) => (user_id, session_token),
+        _ => {
+            println!("Error parsing uuid while submitting an action.");
+            return Err(warp::reject());
+        },
+    };
+    let action = submission.action.as_ref().map(|action| action.to_string());
+    match state.submit_action(user_id, submission.lobby_id, session_token, action.as_deref(), submission.amount, submission.choice).await {
+        Err(()) => Err(warp::reject()),
+        Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
+            "submitted_lobby_id": submission.lobby_id
+        })))),
+    }
+}
+
+// Turns the changes to every lobby into a stream of the changes to one lobby.
+// Events that were dropped because the client fell behind are skipped,
+// the client can get the lobby's current state from lobby-info if it needs to.
+// The stream ends once the lobby is closed.
+fn lobby_update_stream(receiver: broadcast::Receiver<LobbyEvent>, lobby_id: u32) -> impl Stream<Item = LobbyUpdate> {
+    futures::stream::unfold(Some(receiver), move |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.lobby_id == lobby_id && event.update == LobbyUpdate::LobbyClosed => return Some((event.update, None)),
+                Ok(event) if event.lobby_id == lobby_id => return Some((event.update, Some(receiver))),
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
         }
+    })
+}
+
+// Pushes the changes to a lobby to the client as server-sent events, as they happen.
+// The receiver is dropped along with the stream when the client disconnects.
+async fn get_lobby_events<I: Input + Send + Sync + 'static>(state: ServerState<I>, lobby_id: u32) -> Result<impl warp::Reply, warp::Rejection> {
+    if !state.lobbies.read().await.contains_key(&lobby_id) {
+        return Err(warp::reject());
+    }
+    let events = lobby_update_stream(state.subscribe_lobby_events(), lobby_id)
+        .map(|update| warp::sse::Event::default().json_data(update));
+    Ok(add_allow_cors(warp::sse::reply(warp::sse::keep_alive().stream(events))))
+}
+
+// Changes the name a user is shown as in the lobbies they are in.
+async fn update_player_info<I: Input + Send + Sync + 'static>(state: ServerState<I>, player_info: PlayerInfo) -> Result<impl warp::Reply, warp::Rejection> {
+    let (user_id, session_token) = match (Uuid::parse_str(&player_info.user_id), Uuid::parse_str(&player_info.session_token)) {
+        (Ok(user_id), Ok(session_token)) => (user_id, session_token),
+        _ => {
+            println!("Error parsing uuid while processing player info.");
+            return Err(warp::reject());
+        },
     };
+    if !state.check_session_token(user_id, session_token).await {
+        println!("User {} cannot change their display name because their session token is wrong", user_id);
+        return Err(warp::reject());
+    }
+    let display_name = player_info.display_name.trim().to_string();
+    match state.set_display_name(user_id, display_name.clone()).await {
+        Err(()) => Err(warp::reject()),
+        Ok(()) => Ok(add_allow_cors(warp::reply::json(&json!({
+            "user_id": user_id.simple().to_string(),
+            "display_name": display_name
+        })))),
+    }
+}
+
+// Gets the players with the most winnings across all saved rounds (see DbHandler::get_leaderboard).
+async fn get_leaderboard<I: Input + Send + Sync>(state: ServerState<I>, query: LeaderboardQuery) -> Result<impl warp::Reply, warp::Rejection> {
+    let top_n = query.top.unwrap_or(DEFAULT_LEADERBOARD_SIZE);
+    if top_n == 0 || top_n > MAX_LEADERBOARD_SIZE {
+        return Ok(add_allow_cors(warp::reply::with_status(warp::reply::json(&json!({
+            "error": format!("The number of players listed must be between 1 and {}", MAX_LEADERBOARD_SIZE)
+        })), StatusCode::BAD_REQUEST)));
+    }
+    println!("Retrieving the top {} players...", top_n);
+    match state.db_handler.get_leaderboard(top_n).await {
+        Ok(leaderboard) => Ok(add_allow_cors(warp::reply::with_status(warp::reply::json(&leaderboard), StatusCode::OK))),
+        Err(e) => {
+            println!("Error while retrieving the leaderboard: {}", e);
+            Err(warp::reject())
+        }
+    }
+}
+
+// Rejection for a client that has made too many requests (see ServerState::count_request).
+#[derive(Debug)]
+struct TooManyRequests;
+
+impl warp::reject::Reject for TooManyRequests {}
+
+// Filter that rejects requests from clients that have made too many requests in the last minute.
+// Requests whose address isn't known are let through.
+fn rate_limit<I: Input + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |remote_addr: Option<SocketAddr>| {
+            let state = state.clone();
+            async move {
+                match remote_addr {
+                    Some(remote_addr) if !state.count_request(remote_addr.ip()).await => {
+                        println!("Rejecting request from {} because it has made too many requests", remote_addr.ip());
+                        Err(warp::reject::custom(TooManyRequests))
+                    },
+                    _ => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+// Rejection for starting a game that can't be played in the lobby (see ServerState::start_game).
+#[derive(Debug)]
+struct CannotStartGame(String);
+
+impl warp::reject::Reject for CannotStartGame {}
+
+// Replies to requests to start a game that can't be played with 400 Bad Request and the reason why,
+// other rejections are left for warp to handle.
+async fn handle_cannot_start_game(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    if let Some(CannotStartGame(message)) = rejection.find::<CannotStartGame>() {
+        return Ok(add_allow_cors(warp::reply::with_status(warp::reply::json(&json!({
+            "error": message
+        })), StatusCode::BAD_REQUEST)));
+    }
+    Err(rejection)
+}
+
+// Replies to requests that were rejected for being rate limited with 429 Too Many Requests,
+// other rejections are left for warp to handle.
+async fn handle_too_many_requests(rejection: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    if rejection.find::<TooManyRequests>().is_some() {
+        return Ok(add_allow_cors(warp::reply::with_status(warp::reply::json(&json!({
+            "error": "too many requests"
+        })), StatusCode::TOO_MANY_REQUESTS)));
+    }
+    Err(rejection)
+}
+
+// Route for registering accounts with a username (see register_account).
+fn register_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("create-account"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<RegisterAttempt>())
+        .and_then(register_account)
+}
+
+// Route for lobby actions (see process_lobby_action).
+fn lobby_action_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("lobby-action"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<LobbyAction>())
+        .and_then(process_lobby_action)
+        .recover(handle_cannot_start_game)
+}
+
+// Route for reconnecting to a lobby (see reconnect).
+fn reconnect_route(state: ServerState<ServerInput>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("reconnect"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<ReconnectAttempt>())
+        .and_then(reconnect)
+}
+
+// Route for getting the action options offered to a user (see get_offered_actions).
+fn offered_actions_route(state: ServerState<ServerInput>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("offered-actions"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<SessionRequest>())
+        .and_then(get_offered_actions)
+}
+
+// Route for collecting the messages sent to a user (see get_messages).
+fn messages_route(state: ServerState<ServerInput>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("messages"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<SessionRequest>())
+        .and_then(get_messages)
+}
+
+// Route for submitting an action (see submit_action).
+fn submit_action_route(state: ServerState<ServerInput>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("submit-action"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<ActionSubmission>())
+        .and_then(submit_action)
+}
+
+// Route for getting information about a lobby (see get_lobby_info).
+fn lobby_info_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::get()
+        .map(move || state.clone())
+        .and(warp::path("lobby-info"))
+        .and(warp::path::param::<u32>())
+        .and(warp::path::end())
+        .and(limit)
+        .and_then(get_lobby_info)
+}
+
+// Route for watching the changes to a lobby (see get_lobby_events).
+fn lobby_events_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::get()
+        .map(move || state.clone())
+        .and(warp::path!("lobby" / u32 / "events"))
+        .and(limit)
+        .and_then(get_lobby_events)
+}
+
+// Route for changing a user's display name (see update_player_info).
+fn player_info_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::post()
+        .map(move || state.clone())
+        .and(warp::path("player-info"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(json_body::<PlayerInfo>())
+        .and_then(update_player_info)
+}
+
+// Route for listing the game types (see get_game_types).
+fn game_types_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::get()
+        .and(warp::path("game-types"))
+        .and(warp::path::end())
+        .and(limit)
+        .and_then(get_game_types)
+}
+
+// Route for the leaderboard (see get_leaderboard).
+fn leaderboard_route<I: Input + Clone + Send + Sync + 'static>(state: ServerState<I>) -> impl Filter<Extract = (impl Reply,), Error = warp::Rejection> + Clone {
+    let limit = rate_limit(state.clone());
+    warp::get()
+        .map(move || state.clone())
+        .and(warp::path("leaderboard"))
+        .and(warp::path::end())
+        .and(limit)
+        .and(warp::query::<LeaderboardQuery>())
+        .and_then(get_leaderboard)
+}
+
+// Sets up routing and starts up a warp server.
+pub async fn run_server(config: &Config) {
+    let db_handler = config.connect_db_handler().await;
+
+    match db_handler.create_unique_index::<Account>("username", "Accounts").await {
+        Some(Err(e)) => println!("Error while making usernames unique: {}", e),
+        _ => (),
+    }
 
     let cors = warp::cors()
         .allow_any_origin()
         .allow_headers(vec!["Access-Control-Allow-Origin", "Origin", "Accept", "X-Requested-With", "Content-Type"])
         .allow_methods(&[Method::GET, Method::POST]); 
-    let state = ServerState::<ServerInput>::new(db_handler);
-    state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
-    state.add_lobby(Lobby::new(2, GameType::FiveCardDraw).await).await;
-    state.add_lobby(Lobby::new(3, GameType::FiveCardDraw).await).await;
-    state.add_lobby(Lobby::new(4, GameType::FiveCardDraw).await).await;
+    let mut state = ServerState::<ServerInput>::new(db_handler);
+    state.set_starting_balance(config.starting_balance);
+    // lobbies that were open when the server stopped are restored, otherwise the server starts with the default lobbies
+    if state.load_lobbies().await == 0 {
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        state.add_lobby(Lobby::new(2, GameType::FiveCardDraw).await).await;
+        state.add_lobby(Lobby::new(3, GameType::FiveCardDraw).await).await;
+        state.add_lobby(Lobby::new(4, GameType::FiveCardDraw).await).await;
+    }
+
+    // the request counts of clients that haven't made a request in the last minute are forgotten every minute
+    let purge_state = state.clone();
+    tokio::spawn(async move {
+        let mut purge_interval = tokio::time::interval(RATE_LIMIT_WINDOW);
+        loop {
+            purge_interval.tick().await;
+            purge_state.purge_request_counts().await;
+        }
+    });
+
+    // lobbies that were left empty are closed in the background, see prune_lobbies
+    let prune_state = state.clone();
+    tokio::spawn(async move {
+        let mut prune_interval = tokio::time::interval(LOBBY_PRUNE_INTERVAL);
+        loop {
+            prune_interval.tick().await;
+            prune_state.prune_lobbies(EMPTY_LOBBY_TIMEOUT).await;
+        }
+    });
 
     let clone_state = {
         let state_clone = state.clone();
@@ -298,6 +1179,7 @@ pub async fn run_server() {
         .map(clone_state.clone())
         .and(warp::path("login"))
         .and(warp::path::end())
+        .and(rate_limit(state.clone()))
         .and(json_body::<LoginAttempt>())
         .and_then(try_login).with(&cors);
 
@@ -306,32 +1188,981 @@ pub async fn run_server() {
         .map(clone_state.clone())
         .and(warp::path("create-account"))
         .and(warp::path::end())
+        .and(rate_limit(state.clone()))
         .and_then(create_new_account).with(&cors);
 
+    let register = register_route(state.clone()).with(&cors);
+
     let lobby_list = warp::get()
         .map(clone_state.clone())
         .and(warp::path("list-all-lobbies"))
         .and(warp::path::end())
+        .and(rate_limit(state.clone()))
         .and_then(get_all_lobbies).with(&cors);
 
-    let lobby_info= warp::get()
-        .map(clone_state.clone())
-        .and(warp::path("lobby-info"))
-        .and(warp::path::param::<u32>())
-        .and(warp::path::end())
-        .and_then(get_lobby_info).with(&cors);
+    let lobby_info = lobby_info_route(state.clone()).with(&cors);
 
-    let lobby_action = warp::post()
-        .map(clone_state.clone())
-        .and(warp::path("lobby-action"))
-        .and(warp::path::end())
-        .and(json_body::<LobbyAction>())
-        .and_then(process_lobby_action).with(&cors);
+    let lobby_action = lobby_action_route(state.clone()).with(&cors);
+
+    let reconnect = reconnect_route(state.clone()).with(&cors);
+
+    let offered_actions = offered_actions_route(state.clone()).with(&cors);
+
+    let messages = messages_route(state.clone()).with(&cors);
+
+    let submit_action = submit_action_route(state.clone()).with(&cors);
+
+    let game_types = game_types_route(state.clone()).with(&cors);
+
+    let player_info = player_info_route(state.clone()).with(&cors);
+
+    let lobby_events = lobby_events_route(state.clone()).with(&cors);
+
+    let leaderboard = leaderboard_route(state.clone()).with(&cors);
 
     warp::serve(lobby_action
         .or(login)
         .or(create_account)
+        .or(register)
         .or(lobby_list)
         .or(lobby_info)
+        .or(reconnect)
+        .or(offered_actions)
+        .or(messages)
+        .or(submit_action)
+        .or(game_types)
+        .or(player_info)
+        .or(lobby_events)
+        .or(leaderboard)
+        .recover(handle_too_many_requests)
     ).run(([127, 0, 0, 1], 5050)).await;
 }
+
+
+#[cfg(test)]
+mod tests {
+    use crate::action::Action;
+    use crate::game_event::GameEvent;
+    use crate::player::Player;
+    use crate::rules::{texas_holdem::TexasHoldem, Rules};
+
+    use super::*;
+
+    fn rename_action(lobby_id: u32, user_id: Uuid, name: &str) -> LobbyAction {
+        LobbyAction {
+            lobby_id,
+            action_type: LobbyActionType::Rename,
+            user_id: user_id.simple().to_string(),
+            game_type: GameType::FiveCardDraw,
+            name: Some(name.to_string()),
+            min_buy_in: None,
+            max_buy_in: None,
+            starting_balance: None,
+            small_blind: None,
+            big_blind: None,
+            buy_in: None,
+            password: None,
+        }
+    }
+
+    async fn lobby_name(state: &ServerState<ServerInput>, lobby_id: u32) -> String {
+        let lobbies = state.lobbies.read().await;
+        let lobby = lobbies.get(&lobby_id).unwrap().read().await;
+        lobby.name().to_string()
+    }
+
+    #[tokio::test]
+    async fn register_blank_username() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let response = warp::test::request()
+            .method("POST")
+            .path("/create-account")
+            .json(&RegisterAttempt { username: "  ".to_string() })
+            .reply(&register_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/create-account")
+            .json(&RegisterAttempt { username: "player".to_string() })
+            .reply(&register_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn join_lobby_buy_in_out_of_range() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let lobby_action = |action_type, user_id: Uuid, buy_in| LobbyAction {
+            lobby_id: 1,
+            action_type,
+            user_id: user_id.simple().to_string(),
+            game_type: GameType::FiveCardDraw,
+            name: None,
+            min_buy_in: Some(500),
+            max_buy_in: Some(800),
+            starting_balance: None,
+            small_blind: None,
+            big_blind: None,
+            buy_in,
+            password: None,
+        };
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Create, Uuid::now_v7(), None))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+
+        // the default stack is too large for this lobby
+        for buy_in in [Some(499), Some(801), None] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/lobby-action")
+                .json(&lobby_action(LobbyActionType::Join, Uuid::now_v7(), buy_in))
+                .reply(&lobby_action_route(state.clone()))
+                .await;
+            assert_ne!(response.status(), 200);
+        }
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Join, Uuid::now_v7(), Some(600)))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(state.lobbies.read().await.get(&1).unwrap().read().await.count_users(), 1);
+    }
+
+    #[tokio::test]
+    async fn join_lobby_with_starting_balance() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let lobby_action = |action_type, buy_in, starting_balance| LobbyAction {
+            lobby_id: 1,
+            action_type,
+            user_id: Uuid::now_v7().simple().to_string(),
+            game_type: GameType::FiveCardDraw,
+            name: None,
+            min_buy_in: Some(500),
+            max_buy_in: Some(800),
+            starting_balance,
+            small_blind: None,
+            big_blind: None,
+            buy_in,
+            password: None,
+        };
+        // the starting balance has to be within the buy-in limits
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Create, None, Some(900)))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Create, None, Some(600)))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+
+        // every stack but the starting balance is turned away, even within the buy-in limits
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Join, Some(700), None))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+        // users who don't choose a stack bring the starting balance
+        for buy_in in [Some(600), None] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/lobby-action")
+                .json(&lobby_action(LobbyActionType::Join, buy_in, None))
+                .reply(&lobby_action_route(state.clone()))
+                .await;
+            assert_eq!(response.status(), 200);
+        }
+        let lobbies = state.lobbies.read().await;
+        let lobby = lobbies.get(&1).unwrap().read().await;
+        assert_eq!(lobby.count_users(), 2);
+        assert_eq!(lobby.starting_balance(), Some(600));
+    }
+
+    #[tokio::test]
+    async fn lobby_info_deal_order_description() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::TexasHoldem).await).await;
+        let response = warp::test::request()
+            .method("GET")
+            .path("/lobby-info/1")
+            .reply(&lobby_info_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        let lobby_info: LobbyInfo = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(lobby_info.deal_order_description, TexasHoldem::<ServerInput>::deal_order_description());
+    }
+
+    #[tokio::test]
+    async fn rename_lobby_as_creator() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let creator_id = Uuid::now_v7();
+        state.add_lobby(Lobby::new_with_creator(1, GameType::FiveCardDraw, creator_id, None, lobby::DEFAULT_SMALL_BLIND, lobby::DEFAULT_BIG_BLIND).await).await;
+        assert_eq!(lobby_name(&state, 1).await, "Lobby #1");
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&rename_action(1, creator_id, "High Rollers"))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(lobby_name(&state, 1).await, "High Rollers");
+    }
+
+    #[tokio::test]
+    async fn rename_lobby_not_creator() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new_with_creator(1, GameType::FiveCardDraw, Uuid::now_v7(), Some("Friday Night".to_string()), lobby::DEFAULT_SMALL_BLIND, lobby::DEFAULT_BIG_BLIND).await).await;
+        // lobbies created by the server have no creator, so nobody can rename them
+        state.add_lobby(Lobby::new(2, GameType::FiveCardDraw).await).await;
+
+        for lobby_id in [1, 2] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/lobby-action")
+                .json(&rename_action(lobby_id, Uuid::now_v7(), "High Rollers"))
+                .reply(&lobby_action_route(state.clone()))
+                .await;
+            assert_ne!(response.status(), 200);
+        }
+        assert_eq!(lobby_name(&state, 1).await, "Friday Night");
+        assert_eq!(lobby_name(&state, 2).await, "Lobby #2");
+    }
+
+    #[tokio::test]
+    async fn rename_lobby_blank_name() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let creator_id = Uuid::now_v7();
+        state.add_lobby(Lobby::new_with_creator(1, GameType::FiveCardDraw, creator_id, None, lobby::DEFAULT_SMALL_BLIND, lobby::DEFAULT_BIG_BLIND).await).await;
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&rename_action(1, creator_id, "   "))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+        assert_eq!(lobby_name(&state, 1).await, "Lobby #1");
+    }
+
+    #[tokio::test]
+    async fn join_private_lobby_password() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let lobby_action = |action_type, password: Option<&str>| LobbyAction {
+            lobby_id: 1,
+            action_type,
+            user_id: Uuid::now_v7().simple().to_string(),
+            game_type: GameType::FiveCardDraw,
+            name: None,
+            min_buy_in: None,
+            max_buy_in: None,
+            starting_balance: None,
+            small_blind: None,
+            big_blind: None,
+            buy_in: None,
+            password: password.map(|password| password.to_string()),
+        };
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Create, Some("hunter2")))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        assert!(state.lobbies.read().await.get(&1).unwrap().read().await.is_private());
+
+        for password in [Some("hunter3"), Some(""), None] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/lobby-action")
+                .json(&lobby_action(LobbyActionType::Join, password))
+                .reply(&lobby_action_route(state.clone()))
+                .await;
+            assert_ne!(response.status(), 200);
+        }
+        assert_eq!(state.lobbies.read().await.get(&1).unwrap().read().await.count_users(), 0);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&lobby_action(LobbyActionType::Join, Some("hunter2")))
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(state.lobbies.read().await.get(&1).unwrap().read().await.count_users(), 1);
+    }
+
+    #[tokio::test]
+    async fn join_public_lobby_ignores_password() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        assert!(state.join_user(Uuid::now_v7(), 1, lobby::DEFAULT_BUY_IN, None).await.is_ok());
+        assert!(state.join_user(Uuid::now_v7(), 1, lobby::DEFAULT_BUY_IN, Some("anything")).await.is_ok());
+
+        let response = warp::test::request()
+            .method("GET")
+            .path("/list-all-lobbies")
+            .reply(&warp::get()
+                .map(move || state.clone())
+                .and(warp::path("list-all-lobbies"))
+                .and(warp::path::end())
+                .and_then(get_all_lobbies))
+            .await;
+        let lobby_list_items: Vec<LobbyListItem> = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(lobby_list_items.len(), 1);
+        assert!(!lobby_list_items[0].is_private);
+    }
+
+    #[tokio::test]
+    async fn change_display_name() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        let user_id = Uuid::now_v7();
+        let player_info = |display_name: &str, session_token: Uuid| PlayerInfo {
+            user_id: user_id.simple().to_string(),
+            session_token: session_token.simple().to_string(),
+            display_name: display_name.to_string(),
+        };
+
+        // users have to be in a lobby to be shown with a display name
+        let response = warp::test::request()
+            .method("POST")
+            .path("/player-info")
+            .json(&player_info("Alice", Uuid::new_v4()))
+            .reply(&player_info_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+
+        let session_token = state.join_user(user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        // and have to prove who they are with their session token
+        let response = warp::test::request()
+            .method("POST")
+            .path("/player-info")
+            .json(&player_info("Mallory", Uuid::new_v4()))
+            .reply(&player_info_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+        let response = warp::test::request()
+            .method("GET")
+            .path("/lobby-info/1")
+            .reply(&lobby_info_route(state.clone()))
+            .await;
+        let lobby_info: LobbyInfo = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(lobby_info.users[0].display_name, user_id.simple().to_string());
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/player-info")
+            .json(&player_info("  ", session_token))
+            .reply(&player_info_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/player-info")
+            .json(&player_info(" Alice ", session_token))
+            .reply(&player_info_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        let response = warp::test::request()
+            .method("GET")
+            .path("/lobby-info/1")
+            .reply(&lobby_info_route(state.clone()))
+            .await;
+        let lobby_info: LobbyInfo = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(lobby_info.users[0].display_name, "Alice");
+    }
+
+    #[tokio::test]
+    async fn lobby_events_for_joining_and_leaving() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        state.add_lobby(Lobby::new(2, GameType::FiveCardDraw).await).await;
+        let mut updates = Box::pin(lobby_update_stream(state.subscribe_lobby_events(), 1));
+
+        let user_id = Uuid::now_v7();
+        // events of other lobbies are left out
+        state.join_user(Uuid::now_v7(), 2, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        state.join_user(user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        state.leave_user(user_id, 1).await.unwrap();
+        assert_eq!(updates.next().await, Some(LobbyUpdate::UserJoined { user_id: user_id.simple().to_string() }));
+        assert_eq!(updates.next().await, Some(LobbyUpdate::UserLeft { user_id: user_id.simple().to_string() }));
+        // the lobby was left empty, so it was closed
+        assert_eq!(updates.next().await, Some(LobbyUpdate::LobbyClosed));
+        assert_eq!(updates.next().await, None);
+
+        // failed joins aren't broadcast
+        let mut updates = Box::pin(lobby_update_stream(state.subscribe_lobby_events(), 2));
+        assert!(state.join_user(user_id, 3, lobby::DEFAULT_BUY_IN, None).await.is_err());
+        drop(state);
+        assert_eq!(updates.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn lobby_closed_when_last_user_leaves() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        state.add_lobby(Lobby::new(2, GameType::FiveCardDraw).await).await;
+        let (first_user_id, second_user_id) = (Uuid::now_v7(), Uuid::now_v7());
+        state.join_user(first_user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        state.join_user(second_user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+
+        state.leave_user(first_user_id, 1).await.unwrap();
+        assert_eq!(state.lobbies.read().await.len(), 2);
+        state.leave_user(second_user_id, 1).await.unwrap();
+        assert_eq!(state.lobbies.read().await.len(), 1);
+        assert!(!state.lobbies.read().await.contains_key(&1));
+
+        assert_eq!(state.close_lobby(1).await, Err(()));
+        assert_eq!(state.close_lobby(2).await, Ok(()));
+        assert!(state.lobbies.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn abandoned_lobbies_pruned() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let (creator_id, user_id) = (Uuid::now_v7(), Uuid::now_v7());
+        // lobby 1 is the server's own, lobbies 2 and 3 were created by a user
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        for lobby_id in [2, 3] {
+            state.add_lobby(Lobby::new_with_creator(lobby_id, GameType::FiveCardDraw, creator_id, None, 1, 2).await).await;
+        }
+        state.join_user(user_id, 3, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+
+        // nothing has been empty for long enough yet
+        assert_eq!(state.prune_lobbies(Duration::from_secs(60)).await, 0);
+        assert_eq!(state.lobbies.read().await.len(), 3);
+
+        // a lobby with a hand in play isn't abandoned
+        state.hands_in_play.write().await.insert(2, HashSet::new());
+        assert_eq!(state.prune_lobbies(Duration::ZERO).await, 0);
+        assert_eq!(state.lobbies.read().await.len(), 3);
+        state.hands_in_play.write().await.remove(&2);
+
+        // the emptied lobby was created by a user, and the server's own lobby is kept
+        state.lobbies.read().await[&3].write().await.leave_user(user_id).unwrap();
+        assert_eq!(state.prune_lobbies(Duration::ZERO).await, 2);
+        let lobbies = state.lobbies.read().await;
+        assert_eq!(lobbies.len(), 1);
+        assert!(lobbies.contains_key(&1));
+    }
+
+    #[tokio::test]
+    async fn lobby_events_for_action_timer() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        let mut updates = Box::pin(lobby_update_stream(state.subscribe_lobby_events(), 1));
+        let player = Player::new(Uuid::now_v7(), "player".to_string(), 1000);
+
+        let lobbies = state.lobbies.read().await;
+        lobbies.get(&1).unwrap().read().await.rules().input().notify_action_timeout_seconds(&player, 30);
+        assert_eq!(updates.next().await, Some(LobbyUpdate::ActionTimerTick { player_id: player.account_id().to_string(), seconds_remaining: 30 }));
+    }
+
+    #[tokio::test]
+    async fn lobby_events_of_missing_lobby() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let response = warp::test::request()
+            .method("GET")
+            .path("/lobby/1/events")
+            .reply(&lobby_events_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn list_game_types() {
+        let response = warp::test::request()
+            .method("GET")
+            .path("/game-types")
+            .reply(&game_types_route(ServerState::<ServerInput>::new(DbHandler::new_dummy())))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let game_type_infos: Vec<GameTypeInfo> = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(game_type_infos.len(), GameType::iter().count());
+
+        let texas_holdem = game_type_infos.iter().find(|info| matches!(info.game_type, GameType::TexasHoldem)).unwrap();
+        assert_eq!(texas_holdem.name, "Texas Hold'em");
+        assert_eq!(texas_holdem.deal_order_description, TexasHoldem::<ServerInput>::deal_order_description());
+        assert_eq!(texas_holdem.min_players, 2);
+        // 2 cards for each player, and 5 community cards
+        assert_eq!(texas_holdem.max_players, 23);
+        assert!(texas_holdem.uses_blinds);
+        assert_eq!(texas_holdem.default_minimum_bet, lobby::DEFAULT_BIG_BLIND);
+
+        let seven_card_stud = game_type_infos.iter().find(|info| matches!(info.game_type, GameType::SevenCardStud)).unwrap();
+        assert_eq!(seven_card_stud.max_players, 7);
+        assert!(!seven_card_stud.uses_blinds);
+    }
+
+    #[tokio::test]
+    async fn reconnect_with_session_token() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        let user_id = Uuid::now_v7();
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&LobbyAction {
+                lobby_id: 1,
+                action_type: LobbyActionType::Join,
+                user_id: user_id.simple().to_string(),
+                game_type: GameType::FiveCardDraw,
+                name: None,
+                min_buy_in: None,
+                max_buy_in: None,
+                starting_balance: None,
+                small_blind: None,
+                big_blind: None,
+                buy_in: None,
+                password: None,
+            })
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        let joined: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        let session_token = joined["session_token"].as_str().unwrap().to_string();
+
+        let reconnect_attempt = |user_id: Uuid, session_token: &str| ReconnectAttempt {
+            lobby_id: 1,
+            user_id: user_id.simple().to_string(),
+            session_token: session_token.to_string(),
+        };
+        for attempt in [
+            reconnect_attempt(user_id, &Uuid::new_v4().simple().to_string()),
+            reconnect_attempt(user_id, "not a token"),
+            reconnect_attempt(Uuid::now_v7(), &session_token),
+        ] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/reconnect")
+                .json(&attempt)
+                .reply(&reconnect_route(state.clone()))
+                .await;
+            assert_ne!(response.status(), 200);
+        }
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/reconnect")
+            .json(&reconnect_attempt(user_id, &session_token))
+            .reply(&reconnect_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        let reconnected: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(reconnected["reconnected_lobby_id"], 1);
+        assert!(reconnected["pending_action_options"].is_null());
+
+        // reconnecting doesn't wait for a hand in play, which keeps the lobby locked until it is over
+        {
+            let _locked_lobby = state.lobbies.read().await[&1].clone().write_owned().await;
+            let reconnected = tokio::time::timeout(Duration::from_secs(1), state.reconnect_user(user_id, 1, Uuid::parse_str(&session_token).unwrap())).await;
+            assert_eq!(reconnected, Ok(Ok(None)));
+        }
+
+        // the token stops working once the user leaves
+        state.leave_user(user_id, 1).await.unwrap();
+        assert_eq!(state.reconnect_user(user_id, 1, Uuid::parse_str(&session_token).unwrap()).await, Err(()));
+    }
+
+    #[tokio::test]
+    async fn submit_action_with_session_token() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        let user_id = Uuid::now_v7();
+        let session_token = state.join_user(user_id, 1, DEFAULT_STARTING_BALANCE, None).await.unwrap();
+
+        let submission = |session_token: Uuid, action: Option<serde_json::Value>, amount: Option<u32>| ActionSubmission {
+            lobby_id: 1,
+            user_id: user_id.simple().to_string(),
+            session_token: session_token.simple().to_string(),
+            action,
+            amount,
+            choice: None,
+        };
+        for (submission, status) in [
+            (submission(session_token, Some(json!("call")), None), 200),
+            (submission(session_token, None, Some(20)), 200),
+            (submission(session_token, Some(json!("not an action")), None), 404),
+            (submission(Uuid::new_v4(), Some(json!("call")), None), 404),
+        ] {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/submit-action")
+                .json(&submission)
+                .reply(&submit_action_route(state.clone()))
+                .await;
+            assert_eq!(response.status(), status);
+        }
+
+        let offered_actions_request = |session_token: Uuid| SessionRequest {
+            lobby_id: 1,
+            user_id: user_id.simple().to_string(),
+            session_token: session_token.simple().to_string(),
+        };
+        let response = warp::test::request()
+            .method("POST")
+            .path("/offered-actions")
+            .json(&offered_actions_request(Uuid::new_v4()))
+            .reply(&offered_actions_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/offered-actions")
+            .json(&offered_actions_request(session_token))
+            .reply(&offered_actions_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        let offered: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(offered["action_options"].is_null());
+    }
+
+    #[tokio::test]
+    async fn messages_with_session_token() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new(1, GameType::FiveCardDraw).await).await;
+        let user_id = Uuid::now_v7();
+        let session_token = state.join_user(user_id, 1, DEFAULT_STARTING_BALANCE, None).await.unwrap();
+        let player = Player::new(user_id, "player".to_string(), 1000);
+        state.server_inputs.read().await[&1].announce_player_eliminated(&player);
+
+        let messages_request = |session_token: Uuid| SessionRequest {
+            lobby_id: 1,
+            user_id: user_id.simple().to_string(),
+            session_token: session_token.simple().to_string(),
+        };
+        let response = warp::test::request()
+            .method("POST")
+            .path("/messages")
+            .json(&messages_request(Uuid::new_v4()))
+            .reply(&messages_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), 200);
+        let response = warp::test::request()
+            .method("POST")
+            .path("/messages")
+            .json(&messages_request(session_token))
+            .reply(&messages_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+        let messages: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(messages["messages"], json!([{ "eliminated": user_id }]));
+
+        // the messages are only given out once
+        assert_eq!(state.take_messages(user_id, 1, session_token).await, Ok("[]".to_string()));
+    }
+
+    #[tokio::test]
+    async fn lobby_document_round_trip() {
+        let creator_id = Uuid::now_v7();
+        let mut lobby = Lobby::<ServerInput>::new_with_creator(3, GameType::TexasHoldem, creator_id, Some("Friday Night".to_string()), 5, 10).await;
+        lobby.set_buy_in_limits(100, 2000).unwrap();
+        lobby.set_password(Some("hunter2".to_string()));
+        let user_id = Uuid::now_v7();
+        let session_token = lobby.join_user(user_id, 500, Some("hunter2")).unwrap();
+        lobby.set_display_name(user_id, "Alice".to_string()).unwrap();
+        lobby.start_game().await;
+
+        // saved and loaded through BSON, as it would be through the database
+        let bson_bytes = bson::to_vec(&lobby.to_document()).unwrap();
+        let mut restored = Lobby::<ServerInput>::from_document(bson::from_slice(&bson_bytes).unwrap()).await;
+        assert_eq!(restored.id(), 3);
+        assert_eq!(restored.name(), "Friday Night");
+        assert_eq!(restored.creator_id(), Some(creator_id));
+        assert_eq!(restored.game_id(), lobby.game_id());
+        assert!(matches!(restored.game_type(), GameType::TexasHoldem));
+        assert_eq!((restored.min_buy_in(), restored.max_buy_in()), (100, 2000));
+        assert_eq!(restored.current_round(), 1);
+        // the game that was being played is abandoned
+        assert!(matches!(restored.status(), lobby::LobbyStatus::InLobby));
+        assert!(restored.active_players().is_empty());
+
+        assert_eq!(restored.count_users(), 1);
+        assert_eq!(restored.display_name(user_id), "Alice");
+        assert!(restored.check_session_token(user_id, session_token));
+        let restored_document = restored.to_document();
+        assert_eq!(restored_document.users[0].buy_in, 500);
+        // only the hash of the session token is saved
+        let saved_token_hash = restored_document.users[0].session_token_hash.clone().unwrap();
+        assert!(!saved_token_hash.contains(&session_token.simple().to_string()));
+        assert!(!String::from_utf8_lossy(&bson_bytes).contains(&session_token.simple().to_string()));
+        assert_eq!((restored_document.small_blind, restored_document.big_blind), (5, 10));
+        // the password still works, as the game ID it is salted with is kept
+        assert!(restored.join_user(Uuid::now_v7(), 500, Some("wrong")).is_err());
+        assert!(restored.join_user(Uuid::now_v7(), 500, Some("hunter2")).is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn restore_lobbies_after_restart() {
+        let db_handler = DbHandler::new("mongodb://localhost:27017/".to_string(), "ece421-poker-system-test".to_string()).await.unwrap();
+        let state = ServerState::<ServerInput>::new(db_handler.clone());
+        let lobby_id = 1000 + Uuid::now_v7().as_u128() as u32 % 1000;
+        state.add_lobby(Lobby::new(lobby_id, GameType::Badugi).await).await;
+        let user_id = Uuid::now_v7();
+        let session_token = state.join_user(user_id, lobby_id, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+
+        let restarted_state = ServerState::<ServerInput>::new(db_handler);
+        assert!(restarted_state.load_lobbies().await >= 1);
+        {
+            let lobbies = restarted_state.lobbies.read().await;
+            let restored = lobbies.get(&lobby_id).unwrap().read().await;
+            assert!(matches!(restored.game_type(), GameType::Badugi));
+            assert!(restored.check_session_token(user_id, session_token));
+        }
+        // a closed lobby is not restored again
+        restarted_state.close_lobby(lobby_id).await.unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn leave_during_hand_folds_player() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new_with_db_handler(1, GameType::TexasHoldem, DbHandler::new_dummy())).await;
+        let user_ids = [Uuid::now_v7(), Uuid::now_v7()];
+        for user_id in user_ids {
+            state.join_user(user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        }
+        let server_input = state.server_inputs.read().await.get(&1).unwrap().clone();
+        state.start_game(user_ids[0], 1).await.unwrap();
+
+        // the first player to act has already posted their blind when they leave
+        let leaver_id = loop {
+            if let Some(user_id) = user_ids.into_iter().find(|user_id| server_input.offered_actions(*user_id).is_some()) {
+                break user_id;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        };
+        let other_id = *user_ids.iter().find(|user_id| **user_id != leaver_id).unwrap();
+        state.leave_user(leaver_id, 1).await.unwrap();
+
+        // they are only removed from the lobby once the hand is over
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while state.lobbies.read().await.get(&1).unwrap().read().await.get_user(leaver_id).is_some() {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        }).await.unwrap();
+        assert!(!server_input.has_left(leaver_id));
+
+        let lobbies = state.lobbies.read().await;
+        let lobby = lobbies.get(&1).unwrap().read().await;
+        assert_eq!(lobby.count_users(), 1);
+        // the pot's history of the hand
+        let events = lobby.rules().last_round_events();
+        let total_bet = |bettor_id: Uuid| -> usize {
+            events.iter()
+                .filter_map(|event| match event {
+                    GameEvent::ActionTaken { player_id, action: Action::Blind(amount) | Action::Bet(amount), .. } if *player_id == bettor_id => Some(*amount),
+                    _ => None,
+                })
+                .sum()
+        };
+        assert!(total_bet(leaver_id) > 0);
+        assert!(events.iter().any(|event| matches!(event, GameEvent::ActionTaken { player_id, action: Action::Fold, .. } if *player_id == leaver_id)));
+        // the chips they bet stayed in the pot, which was won by the other player
+        assert!(events.iter().any(|event| matches!(event,
+            GameEvent::RoundEnded { winner_ids, pot_total } if *winner_ids == vec![other_id] && *pot_total as usize == total_bet(leaver_id) + total_bet(other_id)
+        )));
+    }
+
+    #[tokio::test]
+    async fn submit_action_while_polling_on_current_thread_runtime() {
+        // #[tokio::test] runs on a current thread runtime, which the hand mustn't block while it waits for an action
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new_with_db_handler(1, GameType::TexasHoldem, DbHandler::new_dummy())).await;
+        let user_ids = [Uuid::now_v7(), Uuid::now_v7()];
+        let mut session_tokens = HashMap::new();
+        for user_id in user_ids {
+            session_tokens.insert(user_id, state.join_user(user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap());
+        }
+        let server_input = state.server_inputs.read().await.get(&1).unwrap().clone();
+        state.start_game(user_ids[0], 1).await.unwrap();
+
+        // the input is polling for the first player's action once they are offered their action options
+        let folder_id = loop {
+            if let Some(user_id) = user_ids.into_iter().find(|user_id| server_input.offered_actions(*user_id).is_some()) {
+                break user_id;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+        let submission = ActionSubmission {
+            lobby_id: 1,
+            user_id: folder_id.simple().to_string(),
+            session_token: session_tokens[&folder_id].simple().to_string(),
+            action: Some(json!("fold")),
+            amount: None,
+            choice: None,
+        };
+        let response = warp::test::request()
+            .method("POST")
+            .path("/submit-action")
+            .json(&submission)
+            .reply(&submit_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), 200);
+
+        // the fold ends the hand well before the player would have been folded for timing out
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while state.hands_in_play.read().await.contains_key(&1) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }).await.unwrap();
+        assert!(!server_input.is_disconnected(folder_id));
+    }
+
+    #[tokio::test]
+    async fn rate_limit_lobby_actions() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new_with_db_handler(1, GameType::FiveCardDraw, DbHandler::new_dummy())).await;
+        let route = lobby_action_route(state.clone()).recover(handle_too_many_requests);
+        let flooder: SocketAddr = ([10, 0, 0, 1], 4000).into();
+        let mut statuses = Vec::new();
+        for _ in 0..70 {
+            let response = warp::test::request()
+                .method("POST")
+                .path("/lobby-action")
+                .remote_addr(flooder)
+                .json(&rename_action(1, Uuid::now_v7(), "Flood"))
+                .reply(&route)
+                .await;
+            statuses.push(response.status());
+        }
+        assert!(statuses[..60].iter().all(|status| *status != StatusCode::TOO_MANY_REQUESTS));
+        assert!(statuses[60..].iter().all(|status| *status == StatusCode::TOO_MANY_REQUESTS));
+
+        // other clients are still let through
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .remote_addr(([10, 0, 0, 2], 4000).into())
+            .json(&rename_action(1, Uuid::now_v7(), "Flood"))
+            .reply(&route)
+            .await;
+        assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // the counts are only purged once their window is over
+        state.purge_request_counts().await;
+        assert_eq!(state.request_counts.read().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn leaderboard_without_database() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        let response = warp::test::request()
+            .path("/leaderboard?top=5")
+            .reply(&leaderboard_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.body(), "[]");
+
+        let response = warp::test::request()
+            .path("/leaderboard?top=lots")
+            .reply(&leaderboard_route(state.clone()))
+            .await;
+        assert_ne!(response.status(), StatusCode::OK);
+
+        for top_n in [0, MAX_LEADERBOARD_SIZE + 1] {
+            let response = warp::test::request()
+                .path(&format!("/leaderboard?top={}", top_n))
+                .reply(&leaderboard_route(state.clone()))
+                .await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[tokio::test]
+    async fn start_game_with_wrong_player_count() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new_with_db_handler(1, GameType::SevenCardStud, DbHandler::new_dummy())).await;
+        let user_ids: Vec<Uuid> = (0..8).map(|_| Uuid::now_v7()).collect();
+        let start = LobbyAction {
+            lobby_id: 1,
+            action_type: LobbyActionType::Start,
+            user_id: user_ids[0].simple().to_string(),
+            game_type: GameType::SevenCardStud,
+            name: None,
+            min_buy_in: None,
+            max_buy_in: None,
+            starting_balance: None,
+            small_blind: None,
+            big_blind: None,
+            buy_in: None,
+            password: None,
+        };
+        state.join_user(user_ids[0], 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&start)
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(error["error"].as_str().unwrap().contains("not enough players"));
+
+        // seven card stud can only deal 7 players before the deck runs out
+        for user_id in user_ids[1..].iter() {
+            state.join_user(*user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        }
+        let response = warp::test::request()
+            .method("POST")
+            .path("/lobby-action")
+            .json(&start)
+            .reply(&lobby_action_route(state.clone()))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert!(error["error"].as_str().unwrap().contains("too many players"));
+        assert!(error["error"].as_str().unwrap().contains("between 2 and 7 players"));
+        assert!(state.hands_in_play.read().await.is_empty());
+
+        state.leave_user(user_ids[7], 1).await.unwrap();
+        assert!(state.lobbies.read().await.get(&1).unwrap().read().await.can_start_game().is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn start_game_only_once_by_lobby_user() {
+        let state = ServerState::<ServerInput>::new(DbHandler::new_dummy());
+        state.add_lobby(Lobby::new_with_db_handler(1, GameType::TexasHoldem, DbHandler::new_dummy())).await;
+        let user_ids = [Uuid::now_v7(), Uuid::now_v7()];
+        for user_id in user_ids {
+            state.join_user(user_id, 1, lobby::DEFAULT_BUY_IN, None).await.unwrap();
+        }
+
+        assert!(state.start_game(Uuid::now_v7(), 1).await.unwrap_err().contains("Only the lobby's users"));
+        assert!(state.hands_in_play.read().await.is_empty());
+
+        // of two requests to start the lobby at once, only one starts a hand
+        let (first_start, second_start) = tokio::join!(state.start_game(user_ids[0], 1), state.start_game(user_ids[1], 1));
+        assert!(first_start.is_ok() != second_start.is_ok());
+        assert!(state.hands_in_play.read().await.contains_key(&1));
+
+        // both users leave, so the hand is folded out rather than waiting for them to time out
+        for user_id in user_ids {
+            state.leave_user(user_id, 1).await.unwrap();
+        }
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while !state.hands_in_play.read().await.is_empty() {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            }
+        }).await.unwrap();
+    }
+}
@@ -4,13 +4,58 @@ use uuid::Uuid;
 use crate::card::Card;
 use crate::player::Player;
 use crate::action::Action;
+use crate::game_event::GameEvent;
 use crate::game_type::GameType;
 use crate::lobby::LobbyStatus;
 
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LoginAttempt {
-    pub uuid: String
+    // either the account's uuid or its username can be used to log in
+    #[serde(default)]
+    pub uuid: String,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RegisterAttempt {
+    pub username: String
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReconnectAttempt {
+    pub lobby_id: u32,
+    pub user_id: String,
+    // token that the user was given when they joined the lobby
+    pub session_token: String,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SessionRequest {
+    pub lobby_id: u32,
+    pub user_id: String,
+    pub session_token: String,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ActionSubmission {
+    pub lobby_id: u32,
+    pub user_id: String,
+    pub session_token: String,
+    // one of the offered action options, e.g. "call"
+    #[serde(default)]
+    pub action: Option<serde_json::Value>,
+    // the amount to bet, when the user is asked for one
+    #[serde(default)]
+    pub amount: Option<u32>,
+    // the answer to a yes or no question, e.g. true to show their hand when asked to show or muck
+    #[serde(default)]
+    pub choice: Option<bool>,
 }
 
 
@@ -35,25 +80,102 @@ pub struct PlayerAction {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LobbyListItem {
     pub lobby_id: u32,
+    pub name: String,
     pub status: LobbyStatus,
     pub user_count: u32,
     pub game_type: GameType,
+    // whether a password is needed to join the lobby
+    pub is_private: bool,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GameTypeInfo {
+    pub game_type: GameType,
+    pub name: String,
+    pub deal_order_description: String,
+    pub min_players: usize,
+    pub max_players: usize,
+    // whether the game starts the betting with blinds, or with a bring in (which is the minimum bet)
+    pub uses_blinds: bool,
+    pub default_raise_limit: u32,
+    pub default_minimum_bet: u32,
+    pub default_small_blind: u32,
 }
 
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LobbyUserInfo {
     pub user_id: String,
+    pub display_name: String,
     pub is_active: bool,
+    // seconds left in the user's time bank, for lobbies played through the server
+    pub time_bank_seconds: Option<u64>,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LeaderboardQuery {
+    // number of players to list, 10 if it isn't given
+    pub top: Option<u32>,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PlayerInfo {
+    pub user_id: String,
+    // token that the user was given when they joined any of their lobbies
+    pub session_token: String,
+    // name the user wants to be shown as in the lobbies they are in
+    pub display_name: String,
 }
 
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LobbyInfo{
     pub lobby_id: u32,
+    pub name: String,
     pub status: LobbyStatus,
     pub users: Vec<LobbyUserInfo>,
     pub game_type: GameType,
+    pub deal_order_description: String,
+    pub min_buy_in: u32,
+    pub max_buy_in: u32,
+    pub starting_balance: Option<u32>,
+}
+
+
+// A change to a lobby, which is pushed to the clients watching the lobby's event stream.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LobbyUpdate {
+    UserJoined { user_id: String },
+    UserLeft { user_id: String },
+    RoundStarted,
+    RoundFinished,
+    // the number of seconds a player has left to act before they are folded, sent every second while they decide
+    ActionTimerTick { player_id: String, seconds_remaining: u32 },
+    // every turn taken in a betting phase, sent once the phase has ended
+    PhaseSummary { turns: Vec<TurnSummary> },
+    // something that happened in the hand being played, as it happens, face down cards are never included
+    GameEvent { event: GameEvent },
+    // the lobby was closed, so no more events will come from it
+    LobbyClosed,
+}
+
+
+// A turn taken in a betting phase, with the action described as the other players see it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TurnSummary {
+    pub player_id: String,
+    pub action: String,
+}
+
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LobbyEvent {
+    pub lobby_id: u32,
+    pub update: LobbyUpdate,
 }
 
 
@@ -62,7 +184,8 @@ pub enum LobbyActionType {
     Create,
     Join,
     Leave,
-    Start
+    Start,
+    Rename
 }
 
 
@@ -72,4 +195,26 @@ pub struct LobbyAction {
     pub action_type: LobbyActionType,
     pub user_id: String,
     pub game_type: GameType,
+    // name of the lobby, used when creating or renaming a lobby
+    #[serde(default)]
+    pub name: Option<String>,
+    // buy-in limits of the lobby, used when creating a lobby
+    #[serde(default)]
+    pub min_buy_in: Option<u32>,
+    #[serde(default)]
+    pub max_buy_in: Option<u32>,
+    // stack every user must join with, used when creating a lobby
+    #[serde(default)]
+    pub starting_balance: Option<u32>,
+    // blinds of the lobby, used when creating a lobby
+    #[serde(default)]
+    pub small_blind: Option<u32>,
+    #[serde(default)]
+    pub big_blind: Option<u32>,
+    // stack the user brings when joining a lobby
+    #[serde(default)]
+    pub buy_in: Option<u32>,
+    // password of the lobby, set when creating a private lobby and needed to join one
+    #[serde(default)]
+    pub password: Option<String>,
 }
\ No newline at end of file